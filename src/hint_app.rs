@@ -0,0 +1,4127 @@
+use crate::hint_article::Article;
+use crate::hint_auth::HnSession;
+use crate::hint_bookmarks::{Bookmarks, SavedStory};
+use crate::hint_browser::{domain, expand_opener, on_site, opener, story_url};
+use crate::hint_cache::{CacheUsage, DiskCache, FrontPage, StoryCache};
+use crate::hint_command::{self, push_history};
+use crate::hint_comments::{Comment, CommentThread, TOP_LEVEL_BATCH};
+use crate::hint_export::{self, expand_home, ExportFormat, ExportedStory};
+use crate::hint_fetch::FETCH_AHEAD;
+use crate::hint_hackernews::{Feed, HnStory, HnStoryType};
+use crate::hint_hidden::HiddenStories;
+use crate::hint_history::{local_time, History};
+use crate::hint_hooks::{HookEvent, Hooks};
+use crate::hint_html;
+use crate::hint_ignored::{IgnoreMode, IgnoredUsers};
+use crate::hint_images::{Image, IMAGE_ROWS};
+use crate::hint_input::{Edit, TextInput};
+use crate::hint_keymap::{Action, Keymap, Mode};
+use crate::hint_metrics::Metrics;
+use crate::hint_positions::ReadingPositions;
+use crate::hint_prefetch::{Prefetch, Prefetched};
+use crate::hint_reader::Reader;
+use crate::hint_scores::{ScoreHistory, StoryCounts};
+use crate::hint_search::{self, SavedSearch, SeenResults};
+use crate::hint_seen::SeenComments;
+use crate::hint_session::Session;
+use crate::hint_snooze::{self, parse_duration, Snoozed, DEFAULT_SNOOZE};
+use crate::hint_terminal::{ImageProtocol, TitleMode};
+use crate::hint_theme::Theme;
+use crate::hnreader::User;
+use chrono::{Days, NaiveDate};
+use ratatui::{
+    crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::Rect,
+    widgets::ListState,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::{Range, RangeInclusive};
+use std::path::PathBuf;
+use unicode_width::UnicodeWidthStr;
+
+/// HN rejects longer story titles
+const MAX_TITLE_LEN: usize = 80;
+/// How many actions `u` can go back
+const MAX_UNDO: usize = 50;
+/// Share of the screen the story list keeps next to the details pane, in percent
+const DEFAULT_DETAILS_SPLIT: u16 = 50;
+const MIN_DETAILS_SPLIT: u16 = 20;
+const MAX_DETAILS_SPLIT: u16 = 80;
+/// How far `<` and `>` move the split
+const DETAILS_SPLIT_STEP: u16 = 5;
+/// Lines PageUp and PageDown scroll the details pane by
+const DETAILS_PAGE: u16 = 10;
+/// More top-level comments are fetched once the selection is this close to the last one
+const LOAD_AHEAD: usize = 5;
+/// The next stories past `stories_per_feed` are fetched once the selection is this close to the
+/// last row
+const LOAD_MORE_AHEAD: usize = 3;
+/// Stories fetched each time the list runs out
+const LOAD_MORE_STORIES: usize = 30;
+/// Related discussions the details pane lists, one for each of the number keys that open them
+const MAX_RELATED: usize = 5;
+/// Saved searches run again every this many ticks, five minutes
+const SEARCH_REFRESH_TICKS: u32 = 3000;
+/// Bookmarked stories' scores are sampled every this many ticks, fifteen minutes
+const SCORE_SAMPLE_TICKS: u32 = 9000;
+/// HN's list of changed items is asked for every this many ticks, half a minute
+const UPDATES_POLL_TICKS: u32 = 300;
+/// Stories a feed keeps in memory unless the config says otherwise
+const DEFAULT_MAX_STORIES: usize = 1000;
+/// Stories evicted past the cap at once, so the cache is not written again for each one arriving
+const EVICTION_BATCH: usize = 50;
+/// Ages such as "3m ago" go stale, so an idle screen is still redrawn every this many ticks, a minute
+const CLOCK_REDRAW_TICKS: u32 = 600;
+/// Rows from the top of the screen that quick open numbers, one for each number key
+const QUICK_OPEN_HINTS: usize = 9;
+
+/// This struct holds the current state of the app. In particular, it has the `storylist` field
+/// which is a wrapper around `ListState`. Keeping track of the state lets us render the
+/// associated widget with its state and have access to features such as natural scrolling.
+///
+/// The model is only ever changed by `update`, in response to a `Msg`. Rendering lives in
+/// `hint_view` and never mutates anything but the list's scroll state.
+#[derive(Default)]
+pub struct Model {
+    pub should_exit: bool,
+    pub show_details: bool,
+    /// The details pane takes the whole screen and the list is hidden, toggled with `f`
+    pub details_full_screen: bool,
+    /// Lines of the details pane scrolled past, back to 0 whenever the selection moves
+    pub details_scroll: u16,
+    /// The selected story's article, read in the details pane in place of its details; closed
+    /// when the selection moves
+    pub reader: Option<Reader>,
+    /// The article `a` is waiting on, shown in the reader rather than the pager once it is in
+    reader_pending: Option<String>,
+    /// Where reading stopped in the articles and threads left so far; kept across sessions
+    pub positions: ReadingPositions,
+    /// The list or the details pane, whichever Tab last switched to; `focused_pane` has the
+    /// final say, as prompts, the reply box and comments take the keys while they are open
+    pub focus: Pane,
+    /// Dead and deleted comments are greyed out in threads rather than left out, toggled with `d`
+    pub show_dead: bool,
+    pub feed: Feed,
+    pub storylist: DisplayList,
+    pub tick_count: u32,
+    /// Animation frames so far, which the spinner turns with
+    pub frame_count: u32,
+    /// Stories of the feed showing are still arriving, which keeps the spinner turning
+    pub loading: bool,
+    /// When the feed showing last finished loading, Unix time
+    pub refreshed_at: Option<i64>,
+    /// When the rows showing were fresh, while they are the front page cached at the last quit
+    pub cached_at: Option<i64>,
+    /// The logged-in HN account, if any
+    pub hn_session: Option<HnSession>,
+    /// Line being typed at the bottom of the screen, e.g. after `:`
+    pub prompt: Option<Prompt>,
+    /// One-off message shown in the footer until the next key press
+    pub notice: Option<String>,
+    /// The discussion of a story, shown instead of the list while open
+    pub comments: Option<CommentView>,
+    /// Reply being written, drawn as a box over everything else
+    pub compose: Option<Compose>,
+    /// Question asked before an action that is hard to take back, which takes every key until
+    /// it is answered
+    pub confirm: Option<Confirm>,
+    /// Stories hidden with `x`, left out of every feed
+    pub hidden: HiddenStories,
+    /// Commenters ignored with `I`, whose comments are folded or left out as `ignore_mode` says
+    pub ignored: IgnoredUsers,
+    pub ignore_mode: IgnoreMode,
+    /// Comments loaded in each thread opened before, to tell what is new on the next visit
+    pub seen: SeenComments,
+    /// Stories saved for later with `b`
+    pub bookmarks: Bookmarks,
+    /// Stories put away with `z` until a later time
+    pub snoozed: Snoozed,
+    /// Stories opened in the browser or the reader, listed by the History feed
+    pub history: History,
+    /// Scores of bookmarked stories over time, for the sparkline in the details pane
+    pub scores: ScoreHistory,
+    /// Read stories evicted from a list past `max_stories`, so they come back read
+    pub story_cache: StoryCache,
+    /// Most stories the list keeps in memory, from the config file, `None` for 1000; unread and
+    /// bookmarked stories stay regardless
+    pub max_stories: Option<usize>,
+    /// Words the History feed is narrowed to, set with `/` there or `:history`
+    pub history_query: Option<String>,
+    pub theme: Theme,
+    /// Show at most this many stories, set with `:limit`
+    pub limit: Option<usize>,
+    /// Text of the last `:search`, repeated with `n`
+    pub search: Option<String>,
+    /// Searches from the config file, shown as tabs above the list that `s` steps through
+    pub saved_searches: Vec<SavedSearch>,
+    /// Latest results of each saved search, by name
+    pub search_results: HashMap<String, Vec<HnStory>>,
+    /// Saved search results already shown, so each tab can count the new ones
+    pub seen_results: SeenResults,
+    /// Position in `saved_searches` of the search `Feed::Search` shows
+    pub active_search: Option<usize>,
+    /// Day whose front page `Feed::Past` shows, picked with `:past`
+    pub past_day: Option<NaiveDate>,
+    /// Unix times from and to which `Feed::Between` lists stories, picked with `:between`
+    pub between: Option<(i64, i64)>,
+    /// Earlier `:` command lines, oldest first
+    pub command_history: Vec<String>,
+    /// Shell commands run on story actions, from the config file
+    pub hooks: Hooks,
+    /// Commands that open some domains or file types instead of the browser, from the config file
+    pub open_with: BTreeMap<String, String>,
+    /// Read articles from the Wayback Machine instead of the live site, toggled with `A`
+    pub archived_articles: bool,
+    /// Address and numbered links of the last article read, for the number keys while its story
+    /// is selected
+    pub article_links: Option<(String, Vec<String>)>,
+    /// Make titles clickable with OSC 8 escapes, when the terminal supports them
+    pub hyperlinks: bool,
+    /// How to show the images of articles in the reader, if the config asks and the terminal can
+    pub inline_images: Option<ImageProtocol>,
+    /// The images of the article in the reader, by address, once downloaded
+    pub images: HashMap<String, Image>,
+    /// Where the view left room for each image in its last frame, for the main loop to draw them
+    pub image_slots: Vec<(String, Rect)>,
+    /// Titles too long for their row go on to a second line rather than being cut, from the config
+    /// file
+    pub wrap_titles: bool,
+    /// Fetch the thread and article of the story the selection rests on before they are asked
+    /// for, from the config file
+    pub prefetch: bool,
+    /// What was fetched ahead so far
+    prefetched: Prefetched,
+    /// Running with `--offline`: feeds, stories and comments come from the disk cache, as old as
+    /// they are
+    pub offline: bool,
+    /// Unread stories from the top of each feed loaded whose articles are saved for reading
+    /// offline, from the config file
+    pub prefetch_articles: usize,
+    /// Minutes each article fetched so far takes to read, by address
+    reading_times: HashMap<String, u32>,
+    /// The list shows the reading times next to the titles, from the config file
+    pub reading_time_column: bool,
+    /// Code in comments and articles stays in one colour rather than coloured by its syntax,
+    /// from the config file
+    pub plain_code: bool,
+    /// Stories take a line each, or two with their details; kept across sessions
+    pub density: Density,
+    /// Which key does what, from the config file
+    pub keymap: Keymap,
+    /// Figures of the API requests so far, shared with the client that records them
+    pub metrics: Metrics,
+    /// Whether the debug overlay with `metrics` is up
+    pub show_metrics: bool,
+    /// What the disk caches held when `:cache stats` last asked, shown in the debug overlay
+    pub cache_usage: Vec<(DiskCache, CacheUsage)>,
+    /// The top rows on screen are numbered and the number keys open them, see `Msg::StartQuickOpen`
+    pub quick_open: bool,
+    /// What to keep in the terminal title, from the config file
+    pub title_mode: TitleMode,
+    /// Where the details pane goes, from the config file
+    pub details_layout: DetailsLayout,
+    /// Percent of the screen the list keeps next to the details pane, `None` for half
+    pub details_split: Option<u16>,
+    /// Show only stories from this site, set with `d`
+    pub only_domain: Option<String>,
+    /// Leave out stories from these sites, added to with `D`
+    pub never_domains: Vec<String>,
+    /// Show only Ask HN posts, jobs or another kind of story, set with `t`
+    pub only_kind: Option<HnStoryType>,
+    /// Show only stories by this user, set with `w` in the details pane
+    pub only_author: Option<String>,
+    /// User whose latest stories `Feed::Submissions` lists, picked with `W` in the details pane
+    pub submitter: Option<String>,
+    /// The story `Feed::Discussions` was picked from with `P`, and its link
+    pub discussed: Option<(u64, String)>,
+    /// Stories of titles like those the details pane showed, by story id, numbered there for the
+    /// number keys to open their threads; `None` while on their way or if the search failed
+    pub related: HashMap<u64, Option<Vec<HnStory>>>,
+    /// Accounts of the authors the details pane showed, by name, for their karma and age; `None`
+    /// while on its way or if it failed, so each is asked for once a session
+    pub authors: HashMap<String, Option<User>>,
+    /// Leave out stories with fewer points, set with `:minscore`
+    pub min_score: Option<u32>,
+    /// Order of the list, set with `:sort`
+    pub sort: StorySort,
+    /// The order `:sort` picked for the feed showing, which the session keeps over the configured one
+    pub picked_sort: Option<StorySort>,
+    /// Stories of the same site or author together under a header, set with `:group`
+    pub group: Option<Grouping>,
+    /// Sort and filters each feed starts with, from the config file
+    pub feed_defaults: HashMap<Feed, FeedDefaults>,
+    /// Recent list changes, most recent last
+    undo: Vec<Undo>,
+    /// Story to select once it arrives, restored from the last session
+    pending_selection: Option<u64>,
+    /// Place of each story in the feed showing, so stories fetched out of order still line up
+    feed_order: HashMap<u64, usize>,
+    /// Ids of the feed past the stories it lists, fetched as the list is scrolled to its end
+    more_stories: Vec<u64>,
+    /// The screen shows the model as it is; every message but an idle tick clears it
+    drawn: bool,
+    /// First key of a two-key sequence, see `Msg::KeyPrefix`
+    key_prefix: Option<char>,
+    /// The tabs not showing, in order; the one showing lives in the model itself
+    tabs: Vec<Tab>,
+    /// Place of the tab showing among all of them, `tabs` going around it
+    tab: usize,
+    /// Where the list, the thread and the details pane were before a `g` moved them to the top,
+    /// put back when the `g` turns out to start `gt` or `gT`
+    before_g: Option<(ListState, Option<ListState>, u16)>,
+}
+
+/// What a tab shows over the shared list: a comment thread, or the details of a story. The
+/// tab showing keeps these in the model; switching parks them here and brings another's back.
+#[derive(Default)]
+pub struct Tab {
+    comments: Option<CommentView>,
+    show_details: bool,
+    details_full_screen: bool,
+    details_scroll: u16,
+    reader: Option<Reader>,
+    focus: Pane,
+    /// The story selected in the list
+    selected: Option<u64>,
+}
+
+pub struct CommentView {
+    pub story_id: u64,
+    /// `None` until the thread has been fetched
+    pub thread: Option<CommentThread>,
+    /// Row 0 is the story itself, the `visible` comments follow from row 1
+    pub state: ListState,
+    /// Comments whose replies are folded away
+    pub collapsed: HashSet<u64>,
+    /// Comments whose replies are being fetched, with how many
+    pub loading_replies: HashMap<u64, usize>,
+    /// Top-level comments being fetched past the end of the thread
+    pub loading_more: usize,
+    /// Dead and deleted comments are shown greyed out instead of left out, see `Model::show_dead`
+    pub show_dead: bool,
+    /// Authors from `Model::ignored`, kept here for `visible`
+    pub ignored: HashSet<String>,
+    pub ignore_mode: IgnoreMode,
+    /// Comments that were there when the thread was last opened; `None` on the first visit,
+    /// when nothing counts as new
+    pub seen_before: Option<HashSet<u64>>,
+    /// Text searched for with `/`, which `n` and `N` then move between
+    pub search: Option<String>,
+}
+
+impl CommentView {
+    fn new(story_id: u64) -> Self {
+        Self {
+            story_id,
+            thread: None,
+            state: ListState::default(),
+            collapsed: HashSet::new(),
+            loading_replies: HashMap::new(),
+            loading_more: 0,
+            show_dead: false,
+            ignored: HashSet::new(),
+            ignore_mode: IgnoreMode::default(),
+            seen_before: None,
+            search: None,
+        }
+    }
+
+    /// Select the next or previous comment on screen matching the search, wrapping around at
+    /// the ends; `false` if none matches
+    fn jump_match(&mut self, forward: bool) -> bool {
+        let (Some(thread), Some(search)) = (&self.thread, &self.search) else {
+            return false;
+        };
+        let needle = search.to_lowercase();
+        let rows = self.visible().into_iter().enumerate();
+        let rows: Vec<usize> = rows
+            .filter(|(_, i)| comment_matches(&thread.comments[*i], &needle))
+            .map(|(position, _)| position + 1)
+            .collect();
+        let row = self.state.selected().unwrap_or(0);
+        let target = if forward {
+            rows.iter().find(|r| **r > row).or(rows.first())
+        } else {
+            rows.iter().rev().find(|r| **r < row).or(rows.last())
+        };
+        if let Some(target) = target {
+            self.state.select(Some(*target));
+        }
+        target.is_some()
+    }
+
+    /// Whether the comment was posted since the thread was last opened
+    pub fn is_new(&self, comment: &Comment) -> bool {
+        self.seen_before.as_ref().is_some_and(|seen| !seen.contains(&comment.id))
+    }
+
+    /// How many of the loaded comments are new
+    pub fn new_count(&self) -> usize {
+        let comments = self.thread.iter().flat_map(|thread| &thread.comments);
+        comments.filter(|comment| self.is_new(comment)).count()
+    }
+
+    /// Select the next or previous new comment among those on screen, `false` if there is none
+    fn jump_new(&mut self, forward: bool) -> bool {
+        let Some(thread) = &self.thread else {
+            return false;
+        };
+        let rows = self.visible().into_iter().enumerate().filter(|(_, i)| self.is_new(&thread.comments[*i]));
+        let rows: Vec<usize> = rows.map(|(position, _)| position + 1).collect();
+        let row = self.state.selected().unwrap_or(0);
+        let target = if forward {
+            rows.into_iter().find(|r| *r > row)
+        } else {
+            rows.into_iter().rev().find(|r| *r < row)
+        };
+        if let Some(target) = target {
+            self.state.select(Some(target));
+        }
+        target.is_some()
+    }
+
+    /// Take in the fetched thread, folding the comments of ignored users
+    fn thread_loaded(&mut self, thread: CommentThread) {
+        let ignored = self.ignored_ids(&thread.comments);
+        self.collapsed.extend(ignored);
+        self.thread = Some(thread);
+    }
+
+    /// Ids of the `comments` that start out folded because their author is ignored
+    fn ignored_ids(&self, comments: &[Comment]) -> Vec<u64> {
+        if self.ignore_mode == IgnoreMode::Hide {
+            return Vec::new();
+        }
+        comments.iter().filter(|comment| self.is_ignored(comment)).map(|comment| comment.id).collect()
+    }
+
+    pub fn is_ignored(&self, comment: &Comment) -> bool {
+        !comment.deleted && self.ignored.contains(&comment.author)
+    }
+
+    /// Start or stop ignoring `author`, folding or unfolding their comments, and keep the
+    /// selection on the same comment or the one before it if it goes away
+    fn set_ignored(&mut self, author: &str, ignored: bool) {
+        let selected = self.selected_comment();
+        if ignored {
+            self.ignored.insert(author.to_string());
+        } else {
+            self.ignored.remove(author);
+        }
+        if let Some(thread) = &self.thread {
+            let by_author = thread.comments.iter().filter(|comment| comment.author == author).map(|comment| comment.id);
+            if !ignored {
+                for id in by_author {
+                    self.collapsed.remove(&id);
+                }
+            } else if self.ignore_mode == IgnoreMode::Collapse {
+                self.collapsed.extend(by_author);
+            }
+        }
+        if let Some(index) = selected {
+            self.select_comment(index);
+        }
+    }
+
+    /// Start fetching what the selection is about to need: the replies of the selected comment,
+    /// and the next top-level comments once the end of the thread is near
+    fn load_near_selection(&mut self) -> Option<Cmd> {
+        let selected = self.selected_comment();
+        let visible = self.visible().len();
+        let row = self.state.selected().unwrap_or(0);
+        let thread = self.thread.as_mut()?;
+        let mut cmds = Vec::new();
+        if let Some(comment) = selected.map(|i| &mut thread.comments[i]).filter(|c| !c.unloaded.is_empty()) {
+            let ids = std::mem::take(&mut comment.unloaded);
+            self.loading_replies.insert(comment.id, ids.len());
+            let (parent, depth) = (Some(comment.id), comment.depth + 1);
+            cmds.push(Cmd::LoadReplies { story_id: thread.story_id, parent, depth, ids });
+        }
+        if row.saturating_add(LOAD_AHEAD) >= visible && !thread.unloaded.is_empty() && self.loading_more == 0 {
+            let count = thread.unloaded.len().min(TOP_LEVEL_BATCH);
+            let ids: Vec<u64> = thread.unloaded.drain(..count).collect();
+            self.loading_more = ids.len();
+            cmds.push(Cmd::LoadReplies { story_id: thread.story_id, parent: None, depth: 0, ids });
+        }
+        match cmds.len() {
+            0 => None,
+            1 => cmds.pop(),
+            _ => Some(Cmd::Batch(cmds)),
+        }
+    }
+
+    /// Add fetched comments to the thread, keeping the same comment selected
+    fn replies_loaded(&mut self, parent: Option<u64>, replies: Vec<Comment>) {
+        match parent {
+            Some(id) => {
+                self.loading_replies.remove(&id);
+            }
+            None => self.loading_more = 0,
+        }
+        let selected = self.selected_comment();
+        let Some(thread) = self.thread.as_mut() else {
+            return;
+        };
+        let selected_id = selected.map(|i| thread.comments[i].id);
+        let ignored = match self.ignore_mode {
+            IgnoreMode::Collapse => replies.iter().filter(|c| self.ignored.contains(&c.author)).map(|c| c.id).collect(),
+            IgnoreMode::Hide => Vec::new(),
+        };
+        self.collapsed.extend(ignored);
+        thread.insert_replies(parent, replies);
+        if let Some(index) = selected_id.and_then(|id| thread.comments.iter().position(|c| c.id == id)) {
+            self.select_comment(index);
+        }
+    }
+
+    /// Indexes into the thread's comments of those not inside a collapsed subtree, in order
+    pub fn visible(&self) -> Vec<usize> {
+        let Some(thread) = &self.thread else {
+            return Vec::new();
+        };
+        let mut visible = Vec::new();
+        // Depth of the collapsed comment whose replies are being skipped
+        let mut folded_at: Option<usize> = None;
+        for (i, comment) in thread.comments.iter().enumerate() {
+            match folded_at {
+                Some(depth) if comment.depth > depth => continue,
+                _ => folded_at = None,
+            }
+            if !self.show_dead && self.is_dead_end(i) {
+                continue;
+            }
+            if self.ignore_mode == IgnoreMode::Hide && self.is_ignored(comment) {
+                folded_at = Some(comment.depth);
+                continue;
+            }
+            visible.push(i);
+            if self.collapsed.contains(&comment.id) {
+                folded_at = Some(comment.depth);
+            }
+        }
+        visible
+    }
+
+    /// Whether the comment at `index` is dead or deleted with nothing worth reading below it.
+    /// Dead comments that have live replies stay as a placeholder, so the replies keep their place.
+    fn is_dead_end(&self, index: usize) -> bool {
+        let Some(comments) = self.thread.as_ref().map(|thread| &thread.comments) else {
+            return false;
+        };
+        let subtree = &comments[index..=index + self.descendants(index)];
+        subtree.iter().all(|comment| (comment.dead || comment.deleted) && comment.unloaded.is_empty())
+    }
+
+    /// Show or leave out dead and deleted comments, keeping the selection on the same comment
+    /// or on the one before it when the selected comment goes away
+    fn set_show_dead(&mut self, show_dead: bool) {
+        let selected = self.selected_comment();
+        self.show_dead = show_dead;
+        if let Some(index) = selected {
+            self.select_comment(index);
+        }
+    }
+
+    /// How many replies, at any depth, the comment at `index` has
+    pub fn descendants(&self, index: usize) -> usize {
+        let Some(comments) = self.thread.as_ref().map(|thread| &thread.comments) else {
+            return 0;
+        };
+        let depth = comments[index].depth;
+        comments[index + 1..].iter().take_while(|comment| comment.depth > depth).count()
+    }
+
+    /// Index into the thread's comments of the selected row, `None` for the story header
+    fn selected_comment(&self) -> Option<usize> {
+        let row = self.state.selected().filter(|row| *row > 0)?;
+        self.visible().get(row - 1).copied()
+    }
+
+    /// Select the comment at `index`, or the nearest parent of it still on screen
+    fn select_comment(&mut self, index: usize) {
+        let visible = self.visible();
+        let row = visible.iter().rposition(|i| *i <= index).map_or(0, |position| position + 1);
+        self.state.select(Some(row));
+    }
+
+    /// The comment `id` of the thread, if loaded
+    fn comment_mut(&mut self, id: u64) -> Option<&mut Comment> {
+        self.thread.as_mut()?.comments.iter_mut().find(|comment| comment.id == id)
+    }
+
+    /// Id and author of the selected row, the story when nothing or the header is selected
+    fn selected_target(&self) -> Option<(u64, String)> {
+        let thread = self.thread.as_ref()?;
+        match self.selected_comment() {
+            Some(index) => {
+                let comment = &thread.comments[index];
+                Some((comment.id, comment.author.clone()))
+            }
+            None => Some((thread.story_id, thread.author.clone())),
+        }
+    }
+
+    fn toggle_collapsed(&mut self) {
+        let Some(index) = self.selected_comment() else {
+            return;
+        };
+        let id = self.thread.as_ref().map_or(0, |thread| thread.comments[index].id);
+        // Comments of ignored users fold away their text too, so they open even without replies
+        let ignored = self.thread.as_ref().is_some_and(|thread| self.is_ignored(&thread.comments[index]));
+        if !self.collapsed.remove(&id) && (self.descendants(index) > 0 || ignored) {
+            self.collapsed.insert(id);
+        }
+    }
+
+    /// Select the next or previous top-level comment, skipping over replies
+    fn jump_top_level(&mut self, forward: bool) {
+        let Some(thread) = &self.thread else {
+            return;
+        };
+        let top_level: Vec<usize> = self
+            .visible()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, i)| thread.comments[*i].depth == 0)
+            .map(|(position, _)| position + 1)
+            .collect();
+        let row = self.state.selected().unwrap_or(0);
+        let target = if forward {
+            top_level.into_iter().find(|r| *r > row)
+        } else {
+            top_level.into_iter().rev().find(|r| *r < row)
+        };
+        if let Some(target) = target {
+            self.state.select(Some(target));
+        }
+    }
+
+    /// Fold every comment that has replies, or unfold everything, keeping the selection in place
+    fn set_all_collapsed(&mut self, collapsed: bool) {
+        let selected = self.selected_comment();
+        self.collapsed.clear();
+        if collapsed {
+            let with_replies: Vec<u64> = match &self.thread {
+                Some(thread) => (0..thread.comments.len())
+                    .filter(|i| self.descendants(*i) > 0)
+                    .map(|i| thread.comments[i].id)
+                    .collect(),
+                None => Vec::new(),
+            };
+            self.collapsed.extend(with_replies);
+        }
+        if let Some(index) = selected {
+            self.select_comment(index);
+        }
+    }
+}
+
+/// What a compose box is going to post
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ComposeTarget {
+    Reply {
+        parent: u64,
+        /// Author of the story or comment being replied to
+        replying_to: String,
+    },
+    /// A new story; `text` is the body of an Ask HN style post
+    Submission { title: String, url: Option<String> },
+}
+
+/// A reply or new story in the making
+pub struct Compose {
+    pub target: ComposeTarget,
+    pub text: TextInput,
+    /// Showing the finished reply and waiting for confirmation
+    pub preview: bool,
+}
+
+/// A yes or no question, drawn as a box over everything else
+pub struct Confirm {
+    pub question: String,
+    /// What a yes goes on to do
+    yes: Msg,
+    /// What a no does besides closing the question, if anything
+    no: Option<Msg>,
+}
+
+pub struct Prompt {
+    pub label: String,
+    pub input: TextInput,
+    /// Mask the input, for passwords
+    pub secret: bool,
+    kind: PromptKind,
+}
+
+enum PromptKind {
+    Command,
+    LoginUsername,
+    LoginPassword { username: String },
+    SubmitTitle,
+    SubmitUrl { title: String },
+    Snooze { ids: Vec<u64> },
+    /// `input` is what the command will read, captured when `|` was pressed
+    Pipe { input: String },
+    /// Which of the numbered `links` to open
+    FollowLink { links: Vec<String> },
+    /// Text to search the open thread for; nothing clears the search
+    CommentSearch,
+    /// Words to narrow the History feed to; nothing lists it all again
+    HistorySearch,
+    /// Text to search the article in the reader for; nothing clears the search
+    ArticleSearch,
+    /// Rank of the story to select
+    Rank,
+}
+
+impl Prompt {
+    fn new(label: &str, kind: PromptKind) -> Self {
+        Self {
+            label: label.to_string(),
+            input: TextInput::default(),
+            secret: matches!(kind, PromptKind::LoginPassword { .. }),
+            kind,
+        }
+    }
+
+    /// What to draw for the typed text
+    pub fn display_input(&self) -> String {
+        if self.secret {
+            "*".repeat(self.input.text().chars().count())
+        } else {
+            self.input.to_string()
+        }
+    }
+
+    /// Column of the cursor, counting from the start of the label
+    pub fn cursor_column(&self) -> usize {
+        let typed = self.input.before_cursor();
+        self.label.width() + if self.secret { typed.chars().count() } else { typed.width() }
+    }
+}
+
+#[derive(Default)]
+pub struct DisplayList {
+    pub items: Vec<DisplayListItem>,
+    pub state: ListState,
+    /// Where visual mode (`V`) started; the marked range runs from here to the selection
+    pub visual_anchor: Option<usize>,
+    /// The stories of each folded group but its first, which stays in the list under the group's
+    /// header, by group
+    pub folded: BTreeMap<String, Vec<DisplayListItem>>,
+}
+
+/// A list change that `u` can take back, holding whatever is needed to restore the old state
+enum Undo {
+    /// Earlier status of each changed story
+    Status(Vec<(u64, Status)>),
+    /// Hidden stories with the rows they were on, in the feed they were hidden from
+    Hide { feed: Feed, rows: Vec<(usize, DisplayListItem)> },
+    Snooze { feed: Feed, rows: Vec<(usize, DisplayListItem)> },
+    Bookmarks(Bookmarks),
+}
+
+#[derive(Debug, Clone)]
+pub struct DisplayListItem {
+    pub story: HnStory,
+    pub status: Status,
+    /// Upvoted by the logged-in account
+    pub voted: bool,
+    /// In the logged-in account's HN favorites
+    pub favorite: bool,
+    /// Back from a snooze
+    pub resurfaced: bool,
+    /// When it was last opened, in the History feed
+    pub visited: Option<i64>,
+}
+
+/// The part of the screen keys go to, see `Model::focused_pane`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Pane {
+    #[default]
+    List,
+    Details,
+    Comments,
+    /// The `:` command line and the other prompts in the footer
+    Prompt,
+    /// The reply box
+    Compose,
+    /// A yes or no question, see `Confirm`
+    Confirm,
+}
+
+/// How much of each story the list shows, toggled with `i`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Density {
+    /// A line per story
+    #[default]
+    Compact,
+    /// The title, then a dim line with the score, author, age and site
+    Comfortable,
+}
+
+impl Density {
+    pub fn toggle(self) -> Self {
+        match self {
+            Density::Compact => Density::Comfortable,
+            Density::Comfortable => Density::Compact,
+        }
+    }
+}
+
+/// Where the details pane goes when it is open, set in the config file
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DetailsLayout {
+    /// Under the list
+    #[default]
+    Below,
+    /// Side by side, the list on the left
+    Right,
+}
+
+/// Order of the list
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorySort {
+    /// As the feed ranks its stories
+    #[default]
+    Rank,
+    /// Newest first
+    New,
+    /// Most points first
+    Score,
+    /// Most comments first
+    Comments,
+}
+
+impl StorySort {
+    pub const NAMES: [&'static str; 4] = ["rank", "new", "score", "comments"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "rank" => Some(StorySort::Rank),
+            "new" => Some(StorySort::New),
+            "score" => Some(StorySort::Score),
+            "comments" => Some(StorySort::Comments),
+            _ => None,
+        }
+    }
+
+    /// What the story is sorted by, the largest first; none for the feed's own order
+    fn key(self, story: &HnStory) -> Option<u64> {
+        match self {
+            StorySort::Rank => None,
+            StorySort::New => Some(story.time().unwrap_or(0)),
+            StorySort::Score => Some(story.score().unwrap_or(0).into()),
+            StorySort::Comments => Some(story.descendants().unwrap_or(0).into()),
+        }
+    }
+}
+
+/// What the list can be grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    /// The site each story links to; text posts go together
+    Domain,
+    Author,
+}
+
+impl Grouping {
+    pub const NAMES: [&'static str; 2] = ["domain", "author"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "domain" => Some(Grouping::Domain),
+            "author" => Some(Grouping::Author),
+            _ => None,
+        }
+    }
+
+    /// The group `story` goes in
+    pub fn key(self, story: &HnStory) -> String {
+        match self {
+            Grouping::Domain => story.url().as_deref().and_then(domain).unwrap_or_else(|| "text posts".to_string()),
+            Grouping::Author => story.author().to_string(),
+        }
+    }
+}
+
+/// Sort and filters a feed starts with, such as `{"ask": {"min_score": 20, "sort": "new"}}` in
+/// the config file's `feeds`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedDefaults {
+    pub sort: StorySort,
+    pub min_score: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Status {
+    Unread,
+    Read,
+}
+
+/// Everything that can happen to the app: raw input, results from background
+/// tasks, and the user-level actions that input is translated into.
+#[derive(Debug)]
+pub enum Msg {
+    Key(KeyEvent),
+    /// Text pasted into the terminal, in one piece rather than a key per character
+    Paste(String),
+    /// A story fetched for the given feed; stale ones from a previous feed are dropped
+    StoryLoaded(Feed, HnStory),
+    /// Every story id of a feed in its order, sent before its stories
+    FeedOrder(Feed, Vec<u64>),
+    /// Ids of a feed past the stories it lists, for scrolling to its end to fetch
+    FeedMore(Feed, Vec<u64>),
+    /// Unix time the feed's list was saved, when it comes from the disk cache
+    FeedSavedAt(Feed, i64),
+    /// Every story of a feed that could be fetched is in
+    FeedLoaded(Feed),
+    /// The stories `Cmd::LoadMore` asked for are in
+    MoreLoaded(Feed),
+    /// Every 100ms, for timers such as snoozes and saved searches
+    Tick,
+    /// Next frame of animations such as the spinner, at the configured pace
+    Frame,
+    /// Ctrl-Z: hand the terminal back to the shell until the job is resumed
+    Suspend,
+    /// The terminal changed size; the next frame lays everything out again
+    Resize { width: u16, height: u16 },
+    Quit,
+    SelectNone,
+    SelectNext,
+    SelectPrevious,
+    SelectFirst,
+    SelectLast,
+    ToggleStatus,
+    NextFeed,
+    PreviousFeed,
+    OpenCommandLine,
+    PromptInput(char),
+    PromptBackspace,
+    /// Any other editing key at the prompt, such as Ctrl-W or the arrows
+    PromptEdit(Edit),
+    PromptSubmit,
+    PromptCancel,
+    PromptComplete,
+    PromptHistoryPrevious,
+    PromptHistoryNext,
+    /// Jump to the next story matching the last `:search`
+    SearchNext,
+    /// Switch to the stories opened before
+    ShowHistory,
+    /// `/` in the History feed: ask for words to narrow it to
+    OpenHistorySearch,
+    /// Show the results of the next saved search
+    NextSavedSearch,
+    /// Fresh results of the saved search called `name`
+    SearchResultsLoaded { name: String, stories: Vec<HnStory> },
+    /// The stories that made the front page on `day`, most points first
+    PastFrontPageLoaded { day: NaiveDate, stories: Vec<HnStory> },
+    /// The stories posted from `start` to `end`, newest first
+    BetweenLoaded { start: i64, end: i64, stories: Vec<HnStory> },
+    /// The latest stories of `user`, for `Feed::Submissions`
+    SubmissionsLoaded { user: String, stories: Vec<HnStory> },
+    /// The submissions of `url` on HN Search, for `Feed::Discussions`
+    DiscussionsLoaded { url: String, stories: Vec<HnStory> },
+    /// The account of an author the details pane shows
+    AuthorLoaded(User),
+    /// Stories with titles like that of story `id`, from HN Search
+    RelatedLoaded { id: u64, stories: Vec<HnStory> },
+    /// Open the thread of the story listed `n`th under the details' related discussions
+    OpenRelated(usize),
+    /// A thread fetched before `c` asked for it
+    ThreadPrefetched(CommentThread),
+    /// An article fetched before `p` asked for it
+    ArticlePrefetched(Article),
+    /// Minutes the articles saved to disk take to read, by address
+    ReadingTimes(Vec<(String, u32)>),
+    /// Current scores of the bookmarked stories, by id
+    ScoresFetched(Vec<(u64, u32)>),
+    /// Ids of the items HN changed lately, from `updates.json`
+    UpdatesLoaded(Vec<u64>),
+    /// Fresh points and comment counts of stories in the list
+    CountsRefreshed(Vec<StoryCounts>),
+    LoggedIn(HnSession),
+    LoggedOut,
+    /// Something the user should know about, e.g. a failed background action
+    Notify(String),
+    ToggleVote,
+    VoteFailed { id: u64, up: bool, error: String },
+    ToggleFavorite,
+    FavoriteFailed { id: u64, add: bool, error: String },
+    OpenComments,
+    CloseComments,
+    CommentsLoaded(CommentThread),
+    CommentsFailed(String),
+    /// Comments fetched on demand: replies to `parent`, or more top-level comments if `None`
+    RepliesLoaded { story_id: u64, parent: Option<u64>, replies: Vec<Comment> },
+    /// Fold or unfold the replies to the selected comment
+    ToggleCollapsed,
+    /// `zM` folds every comment with replies, `zR` unfolds them all
+    CollapseAll(bool),
+    /// Show or leave out dead and deleted comments
+    ToggleDead,
+    /// Start or stop ignoring the author of the selected comment
+    ToggleIgnored,
+    /// `:ignore` and `:unignore`, for users whose comments are not on screen to press `I` on
+    SetIgnored { user: String, ignored: bool },
+    /// `]]` and `[[`: move to the next or previous top-level comment
+    JumpTopLevel { forward: bool },
+    /// Move to the next or previous comment posted since the last visit
+    JumpNewComment { forward: bool },
+    /// Copy the selected comment, or the story on the header row, as a Markdown quote
+    YankComment,
+    /// `/` in the comment viewer: ask what to search the thread for
+    OpenCommentSearch,
+    /// `n` and `N`: the next or previous search match while a search is on, else new comment
+    FindNext { forward: bool },
+    /// The first key of a two-key sequence such as `zM`, waiting for the second
+    KeyPrefix(char),
+    /// Open another tab on the list, keeping the view showing in its own
+    NewTab,
+    /// Show the next tab, or the previous one, going around at the ends
+    SwitchTab { forward: bool },
+    /// Close the tab showing and show the next one
+    CloseTab,
+    /// Start a reply to the selected comment, or to the story on the header row
+    Reply,
+    ComposeInput(char),
+    ComposeBackspace,
+    ComposeEdit(Edit),
+    ComposePreview,
+    /// Back from the preview to editing, or drop the reply while editing
+    ComposeCancel,
+    ComposeSubmit,
+    ReplyPosted,
+    /// A story we just submitted, with the id HN gave it
+    Submitted(HnStory),
+    PostFailed { target: ComposeTarget, text: String, error: String },
+    HideStory,
+    UnhideLast,
+    /// Start marking a range of stories, or stop
+    ToggleVisual,
+    MarkRead,
+    ToggleBookmark,
+    OpenInBrowser,
+    /// Take back the most recent status, hide or bookmark change
+    Undo,
+    /// Ask how long to snooze the selected or marked stories for
+    Snooze,
+    /// Reorder the Queue by moving the selected story
+    MoveInQueue { up: bool },
+    /// Move the focus between the list and the details pane, opening the details if needed
+    SwitchFocus,
+    /// Scroll the focused details pane by this many lines, up when negative
+    ScrollDetails(i32),
+    /// Blow the details pane up to the whole screen, opening it if needed, or shrink it back
+    ToggleFullScreen,
+    /// Give the list more or less of the screen next to the details pane, with `>` and `<`
+    ShiftDetailsSplit { grow_list: bool },
+    /// Number the top rows on screen for the number keys to open in the browser, like the link
+    /// hints of browser plugins
+    StartQuickOpen,
+    /// Leave quick open, opening the row shown with this number if there is one
+    QuickOpen(Option<usize>),
+    /// Switch the list between a line per story and two
+    ToggleDensity,
+    /// Show or hide the debug overlay with the API figures
+    ToggleMetrics,
+    /// Ask `question` and go on with `then` only on a yes
+    Confirm { question: String, then: Box<Msg> },
+    /// Yes or no to the question open
+    Answer(bool),
+    /// Mark every story in the list read, after `:readall`
+    MarkAllRead,
+    /// Forget every story opened, after `:clearhistory`
+    ClearHistory,
+    /// Delete what these disk caches hold, after `:cache clear`
+    ClearCache(Vec<DiskCache>),
+    /// What the disk caches hold now
+    CacheMeasured(Vec<(DiskCache, CacheUsage)>),
+    /// Load the feed showing again, keeping the stories already in the list
+    Refresh,
+    /// Ask for a shell command to feed the selected stories, or the open thread, to
+    Pipe,
+    /// Open the link numbered `n` (from 1) in the selected comment, or in the article last read
+    OpenLink(usize),
+    /// Ask which of the numbered links to open, for when there are more than the number keys reach
+    FollowLink,
+    /// A number key in the list with no links to open: ask for the rest of a rank, starting with it
+    OpenRankPrompt(char),
+    /// Select the story at this rank (from 1) of the feed, as HN numbers it, or once it loads
+    JumpToRank(usize),
+    /// Read the selected story's article, or the open thread, in `$PAGER` or with `editor` in `$EDITOR`
+    ViewText { editor: bool },
+    ArticleLoaded { article: Article, editor: bool },
+    ImageLoaded { url: String, image: Image },
+    /// Read the selected story's article in the details pane, or stop reading it
+    ToggleReader,
+    /// `/` in the reader: ask what to search the article for
+    OpenArticleSearch,
+    /// `n` and `N` in the reader: the next or previous row matching the search
+    FindInArticle { forward: bool },
+    /// Switch articles between the live site and the Wayback Machine
+    ToggleArchived,
+    /// Show only stories from the selected story's site, or everything again if already filtered
+    OnlyThisDomain,
+    /// Leave the selected story's site out of the list
+    NeverThisDomain,
+    /// Fold the selected story's group under its header, or unfold it, while the list is grouped
+    FoldGroup,
+    /// Show only stories of the selected story's kind, or every kind again if already filtered
+    OnlyThisKind,
+    /// Show only the loaded stories by the selected story's author, or everyone's again if already
+    /// filtered
+    OnlyThisAuthor,
+    /// List the latest stories of the selected story's author
+    ShowSubmissions,
+    /// List the other submissions of the selected story's link, see `Feed::Discussions`
+    ShowDiscussions,
+}
+
+/// Side effects requested by `update`, carried out by the runtime in `main`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cmd {
+    LoadFeed(Feed),
+    /// Fetch the feed's order again, but only the stories not among `known`
+    RefreshFeed { feed: Feed, known: HashSet<u64> },
+    Login { username: String, password: String },
+    Logout,
+    Vote { session: HnSession, id: u64, up: bool },
+    Favorite { session: HnSession, id: u64, add: bool },
+    /// Like `LoadFeed(Feed::Favorites)`, which needs the account to scrape them from
+    LoadFavorites(HnSession),
+    /// Fetch the next stories of the feed, `ids` in their order, for the end of the list
+    LoadMore { feed: Feed, ids: Vec<u64> },
+    LoadComments(u64),
+    /// Fetch the selected story's thread or article ahead, once the selection rests on it
+    Prefetch(Prefetch),
+    /// Fetch the comments `ids`, which sit at `depth`, with their direct replies
+    LoadReplies { story_id: u64, parent: Option<u64>, depth: usize, ids: Vec<u64> },
+    Post { session: HnSession, target: ComposeTarget, text: String },
+    SaveHidden(HiddenStories),
+    SaveIgnored(IgnoredUsers),
+    SaveSeen(SeenComments),
+    SaveSeenResults(SeenResults),
+    /// Ask HN Search for the stories matching a saved search
+    RunSearch { name: String, query: String },
+    /// Download these articles to the disk cache, those not in it already
+    SaveArticles(Vec<String>),
+    /// Download the images of the article in the reader
+    FetchImages(Vec<String>),
+    /// Find out how much the disk caches hold, for `Msg::CacheMeasured`
+    MeasureCache,
+    /// Delete what these disk caches hold
+    ClearCache(Vec<DiskCache>),
+    /// Ask HN Search for the front page of a past day
+    LoadPastFrontPage(NaiveDate),
+    /// Ask HN Search for the stories posted from `start` to `end`, unix times
+    LoadBetween { start: i64, end: i64 },
+    /// Fetch the latest stories of a user, from their submissions
+    LoadSubmissions(String),
+    /// Ask HN Search for the submissions of a link
+    LoadDiscussions(String),
+    /// Fetch a user's account, for `Msg::AuthorLoaded`
+    FetchAuthor(String),
+    /// Ask HN Search for stories with titles like that of story `id`
+    FindRelated { id: u64, title: String },
+    /// Fetch the current scores of these stories
+    FetchScores(Vec<u64>),
+    /// Ask HN which items changed lately, for `Msg::UpdatesLoaded`
+    PollUpdates,
+    /// Fetch the points and comment counts of these stories again
+    RefreshCounts(Vec<u64>),
+    SaveScores(ScoreHistory),
+    SaveStoryCache(StoryCache),
+    /// Mirror a local hide on the account, so it applies on the website too
+    SyncHide { session: HnSession, id: u64, hide: bool },
+    SaveBookmarks(Bookmarks),
+    SaveSnoozed(Snoozed),
+    OpenUrls(Vec<String>),
+    /// Write an exported list to a file
+    Export { path: PathBuf, contents: String },
+    /// Start shell commands from the config (hooks, openers), already expanded for their stories
+    RunCommands(Vec<String>),
+    /// Run a command in the foreground with `input` on its stdin, handing it the terminal
+    Pipe { command: String, input: String },
+    /// Download an article and extract its text, for `ViewText`; `archived` goes to the Wayback Machine
+    FetchArticle { url: String, editor: bool, archived: bool },
+    /// Show text in the pager or editor, which takes over the terminal like `Pipe`
+    ViewText { text: String, editor: bool },
+    /// Stop the process like the shell's Ctrl-Z would, giving the terminal back meanwhile
+    Suspend,
+    /// Put text on the clipboard; `what` names it for the notice that follows
+    Copy { text: String, what: String },
+    /// Several independent side effects at once
+    Batch(Vec<Cmd>),
+}
+
+impl Cmd {
+    /// Whether it takes the network beyond the HN API, which an offline client answers from the
+    /// disk cache instead
+    pub fn needs_network(&self) -> bool {
+        matches!(
+            self,
+            Cmd::Login { .. }
+                | Cmd::Vote { .. }
+                | Cmd::Favorite { .. }
+                | Cmd::LoadFavorites(_)
+                | Cmd::Post { .. }
+                | Cmd::RunSearch { .. }
+                | Cmd::SyncHide { .. }
+                | Cmd::LoadPastFrontPage(_)
+                | Cmd::LoadBetween { .. }
+                | Cmd::LoadDiscussions(_)
+                | Cmd::FindRelated { .. }
+                | Cmd::SaveArticles(_)
+                | Cmd::FetchImages(_)
+        )
+    }
+}
+
+impl Model {
+    /// Start from where the given session left off
+    pub fn restore(session: &Session) -> Self {
+        let mut model = Self {
+            feed: session.feed,
+            pending_selection: session.selected_story,
+            theme: session.theme.as_deref().and_then(Theme::by_name).unwrap_or_default(),
+            command_history: session.command_history.clone(),
+            details_split: session.details_split,
+            density: session.density,
+            picked_sort: session.sort,
+            ..Self::default()
+        };
+        *model.storylist.state.offset_mut() = session.scroll_offset;
+        model
+    }
+
+    /// Minutes the story's article takes to read, once it has been fetched
+    pub fn reading_minutes(&self, story: &HnStory) -> Option<u32> {
+        story.url().as_ref().and_then(|url| self.reading_times.get(url)).copied()
+    }
+
+    /// The related discussions of the selected story, once found
+    pub fn selected_related(&self) -> &[HnStory] {
+        let related = self.storylist.selected_item().and_then(|item| self.related.get(&item.id()));
+        related.and_then(Option::as_deref).unwrap_or_default()
+    }
+
+    /// Note where reading stands in the open articles and threads, of every tab, before quitting
+    pub fn remember_positions(&mut self) {
+        let readers = self.reader.iter().chain(self.tabs.iter().filter_map(|tab| tab.reader.as_ref()));
+        for reader in readers {
+            self.positions.set_article(&reader.url, reader.position().0);
+        }
+        let threads = self.comments.iter().chain(self.tabs.iter().filter_map(|tab| tab.comments.as_ref()));
+        for view in threads {
+            remember_thread(&mut self.positions, view);
+        }
+    }
+
+    /// Put the sort and filters the config gives the feed showing in place, unless `:sort` picked
+    /// another order for it
+    pub fn use_feed_defaults(&mut self) {
+        let defaults = self.feed_defaults.get(&self.feed).copied().unwrap_or_default();
+        self.sort = self.picked_sort.unwrap_or(defaults.sort);
+        self.min_score = defaults.min_score;
+    }
+
+    /// Fill the list with the front page cached at the last quit, if it is of the feed showing,
+    /// for the rows to come up at once; the feed's fresh order and stories then replace them
+    pub fn show_front_page(&mut self, page: &FrontPage) {
+        if page.feed != self.feed || page.stories.is_empty() || !self.storylist.items.is_empty() {
+            return;
+        }
+        self.feed_order = page.stories.iter().enumerate().map(|(rank, cached)| (cached.story.id, rank)).collect();
+        for cached in &page.stories {
+            story_loaded(self, page.feed, cached.to_story());
+        }
+        self.cached_at = Some(page.saved_at);
+    }
+
+    /// The top of the feed showing, for `show_front_page` at the next launch; none for the feeds
+    /// hint fills itself, or before the feed has loaded once
+    pub fn front_page(&self) -> Option<FrontPage> {
+        let filled_by_app = [Feed::Queue, Feed::History, Feed::Search, Feed::Past, Feed::Between, Feed::Submissions, Feed::Discussions];
+        if filled_by_app.contains(&self.feed) {
+            return None;
+        }
+        let saved_at = self.refreshed_at.or(self.cached_at)?;
+        Some(FrontPage::new(self.feed, saved_at, self.storylist.items.iter().map(|item| &item.story)))
+    }
+
+    /// How many tabs are open, the one showing included
+    pub fn tab_count(&self) -> usize {
+        self.tabs.len() + 1
+    }
+
+    /// Place of the tab showing, from 0
+    pub fn current_tab(&self) -> usize {
+        self.tab
+    }
+
+    /// Take the view showing out of the model, for another tab to take its place
+    fn park(&mut self) -> Tab {
+        Tab {
+            comments: self.comments.take(),
+            show_details: std::mem::take(&mut self.show_details),
+            details_full_screen: std::mem::take(&mut self.details_full_screen),
+            details_scroll: std::mem::take(&mut self.details_scroll),
+            reader: self.reader.take(),
+            focus: std::mem::take(&mut self.focus),
+            selected: self.storylist.selected_item().map(DisplayListItem::id),
+        }
+    }
+
+    fn unpark(&mut self, tab: Tab) {
+        self.comments = tab.comments;
+        self.show_details = tab.show_details;
+        self.details_full_screen = tab.details_full_screen;
+        self.details_scroll = tab.details_scroll;
+        self.reader = tab.reader;
+        self.focus = tab.focus;
+        // The story may have left the list meanwhile, which leaves the selection where it is
+        if let Some(row) = tab.selected.and_then(|id| self.storylist.items.iter().position(|item| item.id() == id)) {
+            self.storylist.state.select(Some(row));
+        }
+    }
+
+    /// Rebuild the list from the bookmarks while the Queue is showing, keeping the selected row
+    pub fn refresh_queue(&mut self) {
+        if self.feed != Feed::Queue {
+            return;
+        }
+        let items = self.bookmarks.iter().map(|bookmark| DisplayListItem::from_hnstory(bookmark.to_story())).collect();
+        self.replace_items(items);
+    }
+
+    /// Rebuild the list from the history while it is showing, narrowed to `history_query`
+    pub fn refresh_history(&mut self) {
+        if self.feed != Feed::History {
+            return;
+        }
+        let query = self.history_query.as_deref().unwrap_or("");
+        let items = self
+            .history
+            .search(query)
+            .into_iter()
+            .map(|visit| {
+                let mut item = DisplayListItem::from_hnstory(visit.story.to_story());
+                item.visited = Some(visit.at);
+                item
+            })
+            .collect();
+        self.replace_items(items);
+    }
+
+    /// Show a list kept locally rather than fetched, keeping the selected row
+    fn replace_items(&mut self, items: Vec<DisplayListItem>) {
+        let items: Vec<DisplayListItem> = items.into_iter().filter(|item| story_allowed(self, &item.story)).collect();
+        self.storylist.rearrange(|old| *old = items);
+        self.storylist.visual_anchor = None;
+        // A pending selection from the last session is resolved here, there is nothing to wait for
+        if let Some(id) = self.pending_selection.take() {
+            if let Some(i) = self.storylist.items.iter().position(|item| item.id() == id) {
+                self.storylist.state.select(Some(i));
+            }
+        }
+    }
+
+    /// The pane key presses go to: open prompts and overlays first, then the story screen
+    pub fn focused_pane(&self) -> Pane {
+        if self.confirm.is_some() {
+            Pane::Confirm
+        } else if self.prompt.is_some() {
+            Pane::Prompt
+        } else if self.compose.is_some() {
+            Pane::Compose
+        } else if self.comments.is_some() {
+            Pane::Comments
+        } else if self.show_details && self.focus == Pane::Details {
+            Pane::Details
+        } else {
+            Pane::List
+        }
+    }
+
+    /// Percent of the screen the list keeps while the details pane is open
+    pub fn details_split(&self) -> u16 {
+        self.details_split.unwrap_or(DEFAULT_DETAILS_SPLIT).clamp(MIN_DETAILS_SPLIT, MAX_DETAILS_SPLIT)
+    }
+
+    pub fn max_stories(&self) -> usize {
+        self.max_stories.unwrap_or(DEFAULT_MAX_STORIES).max(1)
+    }
+
+    /// The number quick open shows on `row`, if it is one of the top rows on screen
+    pub fn quick_open_hint(&self, row: usize) -> Option<usize> {
+        let offset = self.storylist.state.offset();
+        let hint = row.checked_sub(offset)? + 1;
+        (self.quick_open && hint <= QUICK_OPEN_HINTS && row < self.storylist.items.len()).then_some(hint)
+    }
+
+    /// The terminal title for the current view, `None` when hint leaves the title alone
+    pub fn window_title(&self) -> Option<String> {
+        let feed = format!("hint | {}", self.feed_title());
+        let story = match &self.comments {
+            Some(comments) => comments.thread.as_ref().map(|thread| thread.title.as_str()),
+            None => self.storylist.selected_item().map(|item| item.title()),
+        };
+        match (self.title_mode, story) {
+            (TitleMode::Off, _) => None,
+            (TitleMode::Story, Some(title)) => Some(format!("{} - {}", title, feed)),
+            (TitleMode::Feed | TitleMode::Story, _) => Some(feed),
+        }
+    }
+
+    /// The feed's name, with the saved search's or the History's words when there are some
+    pub fn feed_title(&self) -> String {
+        match (self.feed, self.active_search(), &self.history_query) {
+            (Feed::Search, Some(search), _) => format!("{}: {}", self.feed, search.name),
+            (Feed::History, _, Some(query)) => format!("{}: {}", self.feed, query),
+            (Feed::Past, _, _) => match self.past_day {
+                Some(day) => format!("{}: {}", self.feed, day.format("%a %Y-%m-%d")),
+                None => self.feed.to_string(),
+            },
+            (Feed::Between, _, _) => match self.between {
+                Some((start, end)) => {
+                    format!("{}: {} - {}", self.feed, local_time(start, "%a %H:%M"), local_time(end, "%a %H:%M"))
+                }
+                None => self.feed.to_string(),
+            },
+            (Feed::Submissions, _, _) => match &self.submitter {
+                Some(user) => format!("{}: {}", self.feed, user),
+                None => self.feed.to_string(),
+            },
+            (Feed::Discussions, _, _) => match &self.discussed {
+                Some((_, url)) => format!("{}: {}", self.feed, url.split_once("://").map_or(url.as_str(), |(_, rest)| rest)),
+                None => self.feed.to_string(),
+            },
+            _ => self.feed.to_string(),
+        }
+    }
+
+    pub fn active_search(&self) -> Option<&SavedSearch> {
+        self.saved_searches.get(self.active_search?)
+    }
+
+    /// How many results of the saved search at `index` were never shown
+    pub fn unseen_results(&self, index: usize) -> usize {
+        let Some(search) = self.saved_searches.get(index) else {
+            return 0;
+        };
+        let results = self.search_results.get(&search.name).into_iter().flatten();
+        self.seen_results.unseen(&search.name, results)
+    }
+
+    /// Whether anything changed since the last frame, so the screen needs drawing again
+    pub fn needs_redraw(&self) -> bool {
+        !self.drawn
+    }
+
+    pub fn mark_drawn(&mut self) {
+        self.drawn = true;
+    }
+
+    /// Draw the next frame even if the model has not changed, e.g. after another program had the
+    /// terminal
+    pub fn force_redraw(&mut self) {
+        self.drawn = false;
+    }
+
+    /// Ranks in the feed worth fetching first for a screen of `rows` rows: those from the top
+    /// row on, or around the story the last session left selected until it is in
+    pub fn fetch_window(&self, rows: usize) -> Range<usize> {
+        let rank = |id: u64| self.feed_order.get(&id).copied();
+        let start = match self.pending_selection.and_then(rank) {
+            Some(selected) => selected.saturating_sub(rows / 2),
+            None => {
+                let offset = self.storylist.state.offset();
+                let top = self.storylist.items.get(offset).or(self.storylist.items.last());
+                top.and_then(|item| rank(item.id())).unwrap_or(0)
+            }
+        };
+        start..start + rows + FETCH_AHEAD
+    }
+
+    /// Capture what `restore` needs to come back to the current view
+    pub fn session(&self) -> Session {
+        let selected_story = self
+            .storylist
+            .selected_item()
+            .map(|item| item.id())
+            .or(self.pending_selection);
+        Session {
+            // Saved search results, past stretches of time, a user's stories and a link's
+            // discussions are fetched again rather than kept, so start on a feed
+            feed: match self.feed {
+                Feed::Search | Feed::Past | Feed::Between | Feed::Submissions | Feed::Discussions => Feed::default(),
+                feed => feed,
+            },
+            selected_story,
+            scroll_offset: self.storylist.state.offset(),
+            theme: Some(self.theme.name.to_string()),
+            command_history: self.command_history.clone(),
+            details_split: self.details_split,
+            density: self.density,
+            sort: self.picked_sort,
+        }
+    }
+}
+
+impl DisplayList {
+    fn clear(&mut self) {
+        self.items.clear();
+        self.state = ListState::default();
+        self.visual_anchor = None;
+        self.folded.clear();
+    }
+
+    /// Rows marked in visual mode, limited to actual stories
+    pub fn marked_range(&self) -> Option<RangeInclusive<usize>> {
+        let anchor = self.visual_anchor?;
+        let selected = self.state.selected().unwrap_or(anchor);
+        let last = self.items.len().checked_sub(1)?;
+        Some(anchor.min(selected).min(last)..=anchor.max(selected).min(last))
+    }
+
+    /// Rows an action applies to: the marked range in visual mode, else the selected story
+    fn targets(&self) -> Vec<usize> {
+        match self.marked_range() {
+            Some(range) => range.collect(),
+            None => self.state.selected().filter(|i| *i < self.items.len()).into_iter().collect(),
+        }
+    }
+
+    pub fn append_item(&mut self, item: DisplayListItem) {
+        self.items.push(item);
+    }
+
+    /// Put `item` at row `i`, keeping the selected and marked stories the same
+    fn insert_item(&mut self, i: usize, item: DisplayListItem) {
+        if i >= self.items.len() {
+            return self.append_item(item);
+        }
+        self.rearrange(|items| items.insert(i, item));
+    }
+
+    /// Change the rows with `change`, keeping the same stories selected and marked. Scrolled down,
+    /// the same story stays at the top of the screen too; at the top, new stories come into view.
+    /// If the selected story goes, the row taking its place is selected.
+    fn rearrange(&mut self, change: impl FnOnce(&mut Vec<DisplayListItem>)) {
+        let rows = self.items.len();
+        let selected = self.state.selected();
+        let selected_id = self.selected_item().map(DisplayListItem::id);
+        let anchor_id = self.visual_anchor.and_then(|anchor| self.items.get(anchor)).map(DisplayListItem::id);
+        let offset = self.state.offset();
+        let top_id = self.items.get(offset).filter(|_| offset > 0).map(DisplayListItem::id);
+
+        change(&mut self.items);
+        let row = |id| self.items.iter().position(|item| item.id() == id);
+        if let Some(top) = top_id.and_then(row) {
+            *self.state.offset_mut() = top;
+        }
+        match (selected_id.and_then(row), selected) {
+            (Some(i), _) => self.state.select(Some(i)),
+            // The spinner row below the stories, or the end of the list, stays so
+            (None, Some(i)) if i >= rows => self.state.select(Some(i.max(self.items.len()))),
+            (None, Some(i)) => {
+                let last = self.items.len().checked_sub(1);
+                self.state.select(last.map(|last| i.min(last)));
+            }
+            (None, None) => {}
+        }
+        self.visual_anchor = anchor_id.and_then(row);
+    }
+
+    /// Put the stories `rank` knows in its order, in the rows they take up between them, keeping the
+    /// selected and marked stories the same
+    fn sort_ranked(&mut self, rank: impl Fn(u64) -> Option<usize>) {
+        self.rearrange(|items| {
+            let rows: Vec<usize> = (0..items.len()).filter(|i| rank(items[*i].id()).is_some()).collect();
+            let mut ranked: Vec<DisplayListItem> = rows.iter().map(|i| items[*i].clone()).collect();
+            ranked.sort_by_key(|item| rank(item.id()));
+            for (i, item) in rows.into_iter().zip(ranked) {
+                items[i] = item;
+            }
+        });
+    }
+
+    /// Put the stories of each group together, the biggest groups first and groups of the same size
+    /// in the order they come, each group's stories staying in their order. Keeps the selected and
+    /// marked stories the same.
+    fn group(&mut self, key: impl Fn(&HnStory) -> String) {
+        // How many stories each group has, folded ones too, and the row of its first
+        let mut groups: HashMap<String, (usize, usize)> = HashMap::new();
+        for (i, item) in self.items.iter().enumerate() {
+            let folded = |group: &String| self.folded.get(group).map_or(0, Vec::len);
+            let group = key(&item.story);
+            groups.entry(group).or_insert_with_key(|group| (folded(group), i)).0 += 1;
+        }
+        self.rearrange(|items| {
+            items.sort_by_cached_key(|item| {
+                let (size, first) = groups[&key(&item.story)];
+                (std::cmp::Reverse(size), first)
+            })
+        });
+    }
+
+    /// Take out up to `count` stories that `keep` lets go, the oldest (lowest ids) first, never the
+    /// selected or marked ones, keeping the selected and marked stories the same
+    fn evict(&mut self, count: usize, keep: impl Fn(&DisplayListItem) -> bool) -> Vec<DisplayListItem> {
+        let selected = self.state.selected();
+        let marked = self.marked_range();
+        let mut rows: Vec<usize> = (0..self.items.len())
+            .filter(|i| Some(*i) != selected && !marked.as_ref().is_some_and(|range| range.contains(i)))
+            .filter(|i| !keep(&self.items[*i]))
+            .collect();
+        rows.sort_by_key(|i| self.items[*i].id());
+        rows.truncate(count);
+        rows.sort_unstable();
+
+        let mut evicted = Vec::with_capacity(rows.len());
+        self.rearrange(|items| {
+            for (i, item) in std::mem::take(items).into_iter().enumerate() {
+                match rows.binary_search(&i) {
+                    Ok(_) => evicted.push(item),
+                    Err(_) => items.push(item),
+                }
+            }
+        });
+        evicted
+    }
+
+    /// The rows a `height` rows tall screen needs drawn, give or take `overscan` rows either side,
+    /// after scrolling just enough to keep the selection on screen as the List widget would. Row
+    /// `items.len()` is the spinner below the stories.
+    pub fn visible_rows(&mut self, height: usize, overscan: usize) -> Range<usize> {
+        let rows = self.items.len() + 1;
+        let height = height.max(1);
+        let mut offset = self.state.offset().min(rows.saturating_sub(height));
+        if let Some(selected) = self.state.selected().map(|selected| selected.min(rows - 1)) {
+            if selected < offset {
+                offset = selected;
+            } else if selected >= offset + height {
+                offset = selected + 1 - height;
+            }
+        }
+        *self.state.offset_mut() = offset;
+        offset.saturating_sub(overscan)..(offset + height + overscan).min(rows)
+    }
+
+    pub fn selected_item(&self) -> Option<&DisplayListItem> {
+        self.state.selected().and_then(|i| self.items.get(i))
+    }
+
+    /// The story with `id`, folded away or not
+    fn item_mut(&mut self, id: u64) -> Option<&mut DisplayListItem> {
+        self.items.iter_mut().chain(self.folded.values_mut().flatten()).find(|item| item.id() == id)
+    }
+}
+
+impl DisplayListItem {
+    pub fn from_hnstory(story: HnStory) -> Self {
+        Self {
+            story,
+            status: Status::Unread,
+            voted: false,
+            favorite: false,
+            resurfaced: false,
+            visited: None,
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.story.id()
+    }
+
+    pub fn title(&self) -> &str {
+        self.story.title()
+    }
+
+    /// Record our vote locally, ahead of HN confirming it
+    fn apply_vote(&mut self, up: bool) {
+        if self.voted == up {
+            return;
+        }
+        self.voted = up;
+        if let Some(score) = self.story.score() {
+            self.story.set_score(if up { score + 1 } else { score.saturating_sub(1) });
+        }
+    }
+}
+
+/// Apply a message to the model, returning the side effect it calls for, if any
+pub fn update(model: &mut Model, msg: Msg) -> Option<Cmd> {
+    // Only terminals with the kitty keyboard protocol tell when a key is let go, which changes nothing
+    if matches!(&msg, Msg::Key(key) if key.kind == KeyEventKind::Release) {
+        return None;
+    }
+    if !matches!(msg, Msg::Tick | Msg::Frame) {
+        model.drawn = false;
+    }
+    // The details pane follows the selection, so a new selection starts it from the top
+    if matches!(msg, Msg::SelectNone | Msg::SelectNext | Msg::SelectPrevious | Msg::SelectFirst | Msg::SelectLast) {
+        model.details_scroll = 0;
+        leave_reader(model);
+    }
+    match msg {
+        Msg::Key(key) => {
+            model.notice = None;
+            let mut prefix = model.key_prefix.take();
+            let before_g = model.before_g.take();
+            if prefix == Some('g') {
+                if let KeyCode::Char(c @ ('t' | 'T')) = key.code {
+                    // The `g` was not meant to move anything in the tab being left
+                    if let Some((list, thread, details_scroll)) = before_g {
+                        model.storylist.state = list;
+                        if let (Some(view), Some(thread)) = (model.comments.as_mut(), thread) {
+                            view.state = thread;
+                        }
+                        model.details_scroll = details_scroll;
+                    }
+                    return update(model, Msg::SwitchTab { forward: c == 't' });
+                }
+                prefix = None;
+            }
+            // `g` goes to the top at once, and may turn out to start `gt` or `gT`
+            let tab_keys = matches!(model.focused_pane(), Pane::List | Pane::Details | Pane::Comments);
+            if tab_keys && prefix.is_none() && key.code == KeyCode::Char('g') {
+                model.key_prefix = Some('g');
+                let thread = model.comments.as_ref().map(|view| view.state.clone());
+                model.before_g = Some((model.storylist.state.clone(), thread, model.details_scroll));
+            }
+            return handle_key(model, prefix, key).and_then(|msg| update(model, msg));
+        }
+        Msg::Paste(text) => return paste(model, text),
+        Msg::KeyPrefix(c) => model.key_prefix = Some(c),
+        Msg::NewTab => {
+            let current = model.park();
+            // The new tab starts on the list, at the story the other one was on
+            let selected = current.selected;
+            model.tabs.insert(model.tab, current);
+            model.tab += 1;
+            model.unpark(Tab { selected, ..Tab::default() });
+        }
+        Msg::SwitchTab { forward } => switch_tab(model, forward),
+        Msg::CloseTab => close_tab(model),
+        Msg::ToggleCollapsed => {
+            if let Some(view) = model.comments.as_mut() {
+                view.toggle_collapsed();
+            }
+        }
+        Msg::JumpNewComment { forward } => {
+            let view = model.comments.as_mut()?;
+            if view.jump_new(forward) {
+                return view.load_near_selection();
+            }
+            model.notice = Some(match view.new_count() {
+                0 => "No new comments since the last visit".to_string(),
+                _ if forward => "No more new comments below".to_string(),
+                _ => "No more new comments above".to_string(),
+            });
+        }
+        Msg::YankComment => {
+            let view = model.comments.as_ref()?;
+            let thread = view.thread.as_ref()?;
+            let (quote, what) = match view.selected_comment().map(|i| &thread.comments[i]) {
+                Some(comment) if comment.dead || comment.deleted => {
+                    model.notice = Some("Nothing to copy from a dead or deleted comment".to_string());
+                    return None;
+                }
+                Some(comment) => (
+                    hint_export::quote_markdown(&comment.author, &comment.text, comment.id),
+                    format!("{}'s comment", comment.author),
+                ),
+                None => {
+                    let text = thread.text.clone().unwrap_or_else(|| thread.title.clone());
+                    (hint_export::quote_markdown(&thread.author, &text, thread.story_id), "the story".to_string())
+                }
+            };
+            return Some(Cmd::Copy { text: quote, what });
+        }
+        Msg::OpenCommentSearch => model.prompt = Some(Prompt::new("/", PromptKind::CommentSearch)),
+        Msg::ShowHistory => return show_history(model, ""),
+        Msg::OpenHistorySearch => model.prompt = Some(Prompt::new("Search history: ", PromptKind::HistorySearch)),
+        Msg::FindNext { forward } => {
+            let view = model.comments.as_mut()?;
+            let Some(search) = view.search.clone() else {
+                return update(model, Msg::JumpNewComment { forward });
+            };
+            if view.jump_match(forward) {
+                return view.load_near_selection();
+            }
+            model.notice = Some(format!("No comment matches \"{}\"", search));
+        }
+        Msg::JumpTopLevel { forward } => {
+            if let Some(view) = model.comments.as_mut() {
+                view.jump_top_level(forward);
+                return view.load_near_selection();
+            }
+        }
+        Msg::CollapseAll(collapsed) => {
+            if let Some(view) = model.comments.as_mut() {
+                view.set_all_collapsed(collapsed);
+            }
+        }
+        Msg::ToggleIgnored => {
+            let view = model.comments.as_ref()?;
+            let thread = view.thread.as_ref()?;
+            let comment = &thread.comments[view.selected_comment()?];
+            if comment.deleted {
+                return None;
+            }
+            let (user, ignored) = (comment.author.clone(), !model.ignored.contains(&comment.author));
+            return set_ignored(model, user, ignored);
+        }
+        Msg::SetIgnored { user, ignored } => return set_ignored(model, user, ignored),
+        Msg::ToggleDead => {
+            model.show_dead = !model.show_dead;
+            if let Some(view) = model.comments.as_mut() {
+                view.set_show_dead(model.show_dead);
+            }
+            let what = if model.show_dead { "Showing" } else { "Hiding" };
+            model.notice = Some(format!("{} dead and deleted comments", what));
+        }
+        Msg::StoryLoaded(feed, story) => {
+            let cmd = local_search_match(model, &story);
+            return match (cmd, story_loaded(model, feed, story)) {
+                (Some(cmd), Some(evicted)) => Some(Cmd::Batch(vec![cmd, evicted])),
+                (cmd, evicted) => cmd.or(evicted),
+            };
+        }
+        Msg::FeedOrder(feed, ids) => {
+            if feed == model.feed {
+                model.feed_order = ids.into_iter().enumerate().map(|(rank, id)| (id, rank)).collect();
+                // Cached rows that fell off the feed since go, the others wait for their fresh copy
+                if model.cached_at.is_some() {
+                    let order = &model.feed_order;
+                    model.storylist.rearrange(|items| items.retain(|item| order.contains_key(&item.id())));
+                }
+                sort_list(model);
+            }
+        }
+        Msg::FeedMore(feed, ids) => {
+            if feed == model.feed {
+                model.more_stories = ids;
+            }
+        }
+        Msg::FeedSavedAt(feed, at) => {
+            if feed == model.feed {
+                model.cached_at = Some(at);
+            }
+        }
+        Msg::FeedLoaded(feed) if model.offline => {
+            if feed == model.feed {
+                model.loading = false;
+            }
+        }
+        Msg::FeedLoaded(feed) => {
+            if feed == model.feed {
+                model.loading = false;
+                // A refresh brings new scores and comment counts
+                if model.sort != StorySort::Rank {
+                    sort_list(model);
+                }
+                model.cached_at = None;
+                model.refreshed_at = Some(hint_snooze::now());
+                return articles_to_save(model);
+            }
+        }
+        Msg::MoreLoaded(feed) => {
+            if feed == model.feed {
+                model.loading = false;
+            }
+        }
+        Msg::Tick => {
+            model.tick_count = model.tick_count.wrapping_add(1);
+            let mut cmds: Vec<Cmd> = wake_snoozed(model).into_iter().collect();
+            // Resurfaced stories, the debug overlay's requests and the stories' ages all change
+            // without any message of their own
+            if !cmds.is_empty() || model.show_metrics || model.tick_count.is_multiple_of(CLOCK_REDRAW_TICKS) {
+                model.drawn = false;
+            }
+            // Searches and score samples start at the first tick, not a round later
+            let since_first = model.tick_count.wrapping_sub(1);
+            if since_first.is_multiple_of(SEARCH_REFRESH_TICKS) {
+                cmds.extend(run_saved_searches(model));
+            }
+            if since_first.is_multiple_of(SCORE_SAMPLE_TICKS) && !model.bookmarks.is_empty() {
+                cmds.push(Cmd::FetchScores(model.bookmarks.iter().map(|bookmark| bookmark.id).collect()));
+            }
+            // The disk cache has no updates to give
+            if model.tick_count.is_multiple_of(UPDATES_POLL_TICKS) && !model.offline && !model.storylist.items.is_empty() {
+                cmds.push(Cmd::PollUpdates);
+            }
+            return match cmds.len() {
+                0 | 1 => cmds.pop(),
+                _ => Some(Cmd::Batch(cmds)),
+            };
+        }
+        Msg::Frame => {
+            model.frame_count = model.frame_count.wrapping_add(1);
+            if model.loading {
+                model.drawn = false;
+            }
+        }
+        // Nothing to change, but the message gets the new size drawn right away
+        Msg::Resize { .. } => {}
+        Msg::Suspend => return Some(Cmd::Suspend),
+        Msg::Quit => model.should_exit = true,
+        Msg::SelectNone => return select_in(model, |state| state.select(None)),
+        Msg::SelectNext => return select_in(model, ListState::select_next),
+        Msg::SelectPrevious => return select_in(model, ListState::select_previous),
+        Msg::SelectFirst => return select_in(model, ListState::select_first),
+        Msg::SelectLast => return select_in(model, ListState::select_last),
+        Msg::ToggleStatus => return toggle_status(model),
+        // A past front page steps through the days instead
+        Msg::NextFeed if model.feed == Feed::Past => return step_past_day(model, true),
+        Msg::PreviousFeed if model.feed == Feed::Past => return step_past_day(model, false),
+        Msg::NextFeed => return switch_feed(model, model.feed.next()),
+        Msg::PreviousFeed => return switch_feed(model, model.feed.previous()),
+        Msg::OpenCommandLine => model.prompt = Some(Prompt::new(":", PromptKind::Command)),
+        Msg::PromptInput(c) => return update(model, Msg::PromptEdit(Edit::Insert(c))),
+        Msg::PromptBackspace => return update(model, Msg::PromptEdit(Edit::Backspace)),
+        Msg::PromptEdit(edit) => {
+            if let Some(prompt) = model.prompt.as_mut() {
+                prompt.input.apply(edit);
+            }
+        }
+        Msg::PromptSubmit => return submit_prompt(model),
+        Msg::PromptCancel => model.prompt = None,
+        Msg::PromptComplete => complete_prompt(model),
+        Msg::PromptHistoryPrevious => browse_history(model, true),
+        Msg::PromptHistoryNext => browse_history(model, false),
+        Msg::SearchNext => search_next(model),
+        Msg::NextSavedSearch => return next_saved_search(model),
+        Msg::ScoresFetched(scores) => {
+            let now = hint_snooze::now();
+            for (id, score) in scores {
+                model.scores.record(id, score, now);
+                if let Some(item) = model.storylist.item_mut(id) {
+                    item.story.set_score(score);
+                }
+            }
+            return Some(Cmd::SaveScores(model.scores.clone()));
+        }
+        Msg::UpdatesLoaded(ids) => {
+            // Only the stories in the list are fetched again, the rest get fresh copies when loaded
+            let loaded: Vec<u64> = ids.into_iter().filter(|id| model.storylist.item_mut(*id).is_some()).collect();
+            return (!loaded.is_empty()).then_some(Cmd::RefreshCounts(loaded));
+        }
+        Msg::CountsRefreshed(counts) => {
+            for StoryCounts { id, score, comments } in counts {
+                if let Some(item) = model.storylist.item_mut(id) {
+                    if let Some(score) = score {
+                        item.story.set_score(score);
+                    }
+                    if let Some(comments) = comments {
+                        item.story.set_descendants(comments);
+                    }
+                }
+            }
+        }
+        Msg::SearchResultsLoaded { name, stories } => {
+            model.search_results.insert(name.clone(), stories);
+            if model.feed == Feed::Search && model.active_search().is_some_and(|search| search.name == name) {
+                return show_search_results(model);
+            }
+        }
+        Msg::PastFrontPageLoaded { day, stories } => {
+            if model.feed == Feed::Past && model.past_day == Some(day) {
+                model.loading = false;
+                model.feed_order = stories.iter().enumerate().map(|(rank, story)| (story.id(), rank)).collect();
+                if stories.is_empty() {
+                    model.notice = Some(format!("Nothing from {} made the front page", day));
+                }
+                for story in stories {
+                    story_loaded(model, Feed::Past, story);
+                }
+            }
+        }
+        Msg::BetweenLoaded { start, end, stories } => {
+            if model.feed == Feed::Between && model.between == Some((start, end)) {
+                model.loading = false;
+                model.feed_order = stories.iter().enumerate().map(|(rank, story)| (story.id(), rank)).collect();
+                model.notice = Some(match stories.len() {
+                    0 => "Nothing was posted then".to_string(),
+                    count => format!("{} posted then", plural(count, "story", "stories")),
+                });
+                for story in stories {
+                    story_loaded(model, Feed::Between, story);
+                }
+            }
+        }
+        Msg::SubmissionsLoaded { user, stories } => {
+            if model.feed == Feed::Submissions && model.submitter.as_ref() == Some(&user) {
+                model.loading = false;
+                model.feed_order = stories.iter().enumerate().map(|(rank, story)| (story.id(), rank)).collect();
+                if stories.is_empty() {
+                    model.notice = Some(format!("{} has no stories lately", user));
+                }
+                for story in stories {
+                    story_loaded(model, Feed::Submissions, story);
+                }
+            }
+        }
+        Msg::LoggedIn(session) => {
+            model.notice = Some(format!("Logged in as {}", session.username));
+            model.hn_session = Some(session);
+        }
+        Msg::LoggedOut => {
+            model.hn_session = None;
+            model.notice = Some("Logged out".to_string());
+        }
+        Msg::Notify(message) => model.notice = Some(message),
+        Msg::ToggleVote => return toggle_vote(model),
+        Msg::VoteFailed { id, up, error } => {
+            if let Some(item) = model.storylist.item_mut(id) {
+                item.apply_vote(!up);
+            }
+            if let Some(comment) = model.comments.as_mut().and_then(|view| view.comment_mut(id)) {
+                comment.voted = !up;
+            }
+            model.notice = Some(format!("Vote failed: {}", error));
+        }
+        Msg::ToggleFavorite => return toggle_favorite(model),
+        Msg::FavoriteFailed { id, add, error } => {
+            if let Some(item) = model.storylist.item_mut(id) {
+                item.favorite = !add;
+            }
+            model.notice = Some(format!("Favorite failed: {}", error));
+        }
+        Msg::OpenComments => return open_comments(model),
+        Msg::CloseComments => {
+            leave_thread(model);
+            model.compose = None;
+        }
+        Msg::ThreadPrefetched(thread) => model.prefetched.add_thread(thread),
+        Msg::ArticlePrefetched(article) => {
+            model.reading_times.insert(article.url.clone(), article.reading_minutes());
+            model.prefetched.add_article(article);
+        }
+        Msg::ReadingTimes(times) => model.reading_times.extend(times),
+        Msg::DiscussionsLoaded { url, stories } => {
+            if let Some((from, _)) = model.discussed.clone().filter(|(_, discussed)| model.feed == Feed::Discussions && *discussed == url) {
+                model.loading = false;
+                // The story they were asked for from is on screen already
+                let stories: Vec<HnStory> = stories.into_iter().filter(|story| story.id() != from).collect();
+                model.feed_order = stories.iter().enumerate().map(|(rank, story)| (story.id(), rank)).collect();
+                if stories.is_empty() {
+                    model.notice = Some("No other discussions of this link".to_string());
+                }
+                for story in stories {
+                    story_loaded(model, Feed::Discussions, story);
+                }
+            }
+        }
+        Msg::AuthorLoaded(user) => {
+            model.authors.insert(user.id.clone(), Some(user));
+        }
+        Msg::RelatedLoaded { id, stories } => {
+            let mut stories: Vec<HnStory> = stories.into_iter().filter(|story| story.id() != id).collect();
+            stories.truncate(MAX_RELATED);
+            model.related.insert(id, Some(stories));
+        }
+        Msg::OpenRelated(n) => {
+            let story = n.checked_sub(1).and_then(|i| model.selected_related().get(i))?;
+            let story_id = story.id();
+            model.notice = Some(format!("Comments of {}", story.title()));
+            return open_thread_of(model, story_id);
+        }
+        Msg::ImageLoaded { url, image } => {
+            // Unless the reader moved on to another article meanwhile
+            if model.reader.as_ref().is_some_and(|reader| reader.image_urls().contains(&url)) {
+                model.images.insert(url, image);
+            }
+        }
+        Msg::CommentsLoaded(thread) => {
+            if let Some(view) = open_thread(&mut model.comments, &mut model.tabs, thread.story_id) {
+                view.seen_before = model.seen.get(thread.story_id);
+                model.seen.mark(thread.story_id, thread.comments.iter().map(|comment| comment.id));
+                // Back to where the last visit stopped, unless this is the thread loaded again
+                let stopped_at = view.thread.is_none().then(|| model.positions.thread(thread.story_id)).flatten();
+                let index = stopped_at.and_then(|id| thread.comments.iter().position(|comment| comment.id == id));
+                view.thread_loaded(thread);
+                if let Some(index) = index {
+                    view.select_comment(index);
+                }
+                return Some(Cmd::SaveSeen(model.seen.clone()));
+            }
+        }
+        Msg::RepliesLoaded { story_id, parent, replies } => {
+            if let Some(view) = open_thread(&mut model.comments, &mut model.tabs, story_id) {
+                model.seen.mark(story_id, replies.iter().map(|comment| comment.id));
+                view.replies_loaded(parent, replies);
+                return Some(Cmd::SaveSeen(model.seen.clone()));
+            }
+        }
+        Msg::CommentsFailed(error) => {
+            model.comments = None;
+            model.notice = Some(format!("Loading comments failed: {}", error));
+        }
+        Msg::Reply => start_reply(model),
+        Msg::ComposeInput(c) => return update(model, Msg::ComposeEdit(Edit::Insert(c))),
+        Msg::ComposeBackspace => return update(model, Msg::ComposeEdit(Edit::Backspace)),
+        Msg::ComposeEdit(edit) => {
+            if let Some(compose) = model.compose.as_mut().filter(|compose| !compose.preview) {
+                compose.text.apply(edit);
+            }
+        }
+        Msg::ComposePreview => {
+            if let Some(compose) = model.compose.as_mut() {
+                if compose.text.text().trim().is_empty() {
+                    model.notice = Some("Nothing to post yet".to_string());
+                } else {
+                    compose.preview = true;
+                    let question = match compose.target {
+                        ComposeTarget::Reply { .. } => "Post this reply?",
+                        ComposeTarget::Submission { .. } => "Submit this story?",
+                    };
+                    // A no goes back to editing rather than dropping the text
+                    let (yes, no) = (Msg::ComposeSubmit, Some(Msg::ComposeCancel));
+                    model.confirm = Some(Confirm { question: question.to_string(), yes, no });
+                }
+            }
+        }
+        Msg::ComposeCancel => match model.compose.as_mut() {
+            Some(compose) if compose.preview => compose.preview = false,
+            _ => model.compose = None,
+        },
+        Msg::ComposeSubmit => return submit_compose(model),
+        Msg::ReplyPosted => {
+            model.notice = Some("Reply posted".to_string());
+            return model.comments.as_ref().map(|view| Cmd::LoadComments(view.story_id));
+        }
+        Msg::Submitted(story) => {
+            model.notice = Some(format!("Submitted as item {}", story.id()));
+            model.comments = None;
+            model.show_details = false;
+            model.details_full_screen = false;
+            model.focus = Pane::List;
+            model.storylist.items.insert(0, DisplayListItem::from_hnstory(story));
+            model.storylist.state.select(Some(0));
+        }
+        Msg::PostFailed { target, text, error } => {
+            let what = match target {
+                ComposeTarget::Reply { .. } => "Reply",
+                ComposeTarget::Submission { .. } => "Submission",
+            };
+            // Hand the text back so nothing typed is lost, unless another post was started since
+            if model.compose.is_none() {
+                model.compose = Some(Compose { target, text: TextInput::new(&text), preview: false });
+            }
+            model.notice = Some(format!("{} failed: {}", what, error));
+        }
+        Msg::HideStory => return hide_stories(model),
+        Msg::UnhideLast => return unhide_last(model),
+        Msg::ToggleVisual => {
+            let list = &mut model.storylist;
+            list.visual_anchor = match list.visual_anchor {
+                Some(_) => None,
+                None => list.state.selected().filter(|i| *i < list.items.len()),
+            };
+        }
+        Msg::MarkRead if model.feed == Feed::Queue => {
+            let hooks = target_hooks(model, HookEvent::Read);
+            return with_hooks(pop_queue(model).map(Cmd::SaveBookmarks), hooks);
+        }
+        Msg::MarkRead => {
+            let targets = model.storylist.targets();
+            let hooks = target_hooks(model, HookEvent::Read);
+            let items = &mut model.storylist.items;
+            let previous = targets.iter().map(|i| (items[*i].id(), items[*i].status)).collect();
+            for i in targets {
+                items[i].status = Status::Read;
+            }
+            push_undo(model, Undo::Status(previous));
+            model.storylist.visual_anchor = None;
+            return with_hooks(None, hooks);
+        }
+        Msg::Undo => return undo(model),
+        Msg::Snooze => {
+            let ids: Vec<u64> =
+                model.storylist.targets().iter().map(|i| model.storylist.items[*i].id()).collect();
+            if !ids.is_empty() {
+                let label = format!("Snooze for (30m/4h/1d) [{}]: ", DEFAULT_SNOOZE);
+                model.prompt = Some(Prompt::new(&label, PromptKind::Snooze { ids }));
+            }
+        }
+        Msg::ToggleBookmark => return toggle_bookmarks(model),
+        Msg::OpenInBrowser => return open_stories(model),
+        Msg::MoveInQueue { up } => return move_in_queue(model, up),
+        Msg::SwitchFocus => {
+            model.focus = match model.focus {
+                Pane::Details => Pane::List,
+                _ => Pane::Details,
+            };
+            model.show_details |= model.focus == Pane::Details;
+            return batch(fetch_for_details(model));
+        }
+        Msg::ScrollDetails(lines) => {
+            if let Some(reader) = model.reader.as_mut() {
+                reader.scroll(lines);
+                return None;
+            }
+            // The view clamps the far end, as only it knows how long the text wraps to
+            model.details_scroll = (model.details_scroll as i32 + lines).clamp(0, u16::MAX as i32) as u16;
+        }
+        Msg::ToggleFullScreen => {
+            model.details_full_screen = !(model.show_details && model.details_full_screen);
+            model.show_details = true;
+            return batch(fetch_for_details(model));
+        }
+        Msg::ShiftDetailsSplit { grow_list } => {
+            let split = model.details_split();
+            model.details_split = Some(if grow_list {
+                (split + DETAILS_SPLIT_STEP).min(MAX_DETAILS_SPLIT)
+            } else {
+                split.saturating_sub(DETAILS_SPLIT_STEP).max(MIN_DETAILS_SPLIT)
+            });
+        }
+        Msg::ToggleDensity => model.density = model.density.toggle(),
+        Msg::ToggleMetrics => model.show_metrics = !model.show_metrics,
+        Msg::Confirm { question, then } => model.confirm = Some(Confirm { question, yes: *then, no: None }),
+        Msg::Answer(yes) => {
+            let confirm = model.confirm.take()?;
+            let msg = if yes { Some(confirm.yes) } else { confirm.no };
+            return msg.and_then(|msg| update(model, msg));
+        }
+        Msg::MarkAllRead => {
+            let items = &mut model.storylist.items;
+            let previous = items.iter().map(|item| (item.id(), item.status)).collect();
+            for item in items.iter_mut() {
+                item.status = Status::Read;
+            }
+            push_undo(model, Undo::Status(previous));
+            model.storylist.visual_anchor = None;
+            model.notice = Some("Marked every story read, u to undo".to_string());
+        }
+        Msg::ClearHistory => {
+            model.history.clear();
+            model.refresh_history();
+            model.notice = Some("History cleared".to_string());
+        }
+        Msg::ClearCache(caches) => return Some(Cmd::ClearCache(caches)),
+        Msg::CacheMeasured(usage) => model.cache_usage = usage,
+        Msg::Refresh => return refresh_feed(model),
+        Msg::Pipe => start_pipe(model),
+        Msg::ViewText { editor } => return view_text(model, editor),
+        Msg::ToggleReader => return toggle_reader(model),
+        Msg::OpenArticleSearch => {
+            if model.reader.is_some() {
+                model.prompt = Some(Prompt::new("/", PromptKind::ArticleSearch));
+            }
+        }
+        Msg::FindInArticle { forward } => {
+            let reader = model.reader.as_mut()?;
+            match reader.search.clone() {
+                Some(search) if !reader.find(forward) => model.notice = Some(format!("No match for \"{}\" in the article", search)),
+                Some(_) => {}
+                None => model.notice = Some("Search the article with /".to_string()),
+            }
+        }
+        Msg::OpenLink(n) => return open_link(model, n),
+        Msg::OpenRankPrompt(digit) => {
+            let mut prompt = Prompt::new("Go to #", PromptKind::Rank);
+            prompt.input.apply(Edit::Insert(digit));
+            model.prompt = Some(prompt);
+        }
+        Msg::JumpToRank(rank) => {
+            jump_to_rank(model, rank);
+            return prefetch_selected(model);
+        }
+        Msg::StartQuickOpen => model.quick_open = !model.storylist.items.is_empty(),
+        Msg::QuickOpen(hint) => {
+            let offset = model.storylist.state.offset();
+            let row = hint.map(|hint| offset + hint - 1).filter(|row| model.quick_open_hint(*row).is_some());
+            model.quick_open = false;
+            model.storylist.visual_anchor = None;
+            model.storylist.state.select(Some(row?));
+            return open_stories(model);
+        }
+        Msg::FollowLink => {
+            let links = numbered_links(model);
+            match links.len() {
+                0 => model.notice = Some(no_links_notice(model)),
+                n => model.prompt = Some(Prompt::new(&format!("Open link 1-{}: ", n), PromptKind::FollowLink { links })),
+            }
+        }
+        Msg::ArticleLoaded { article, editor } => {
+            model.reading_times.insert(article.url.clone(), article.reading_minutes());
+            model.article_links = Some((article.url.clone(), article.links.clone()));
+            model.notice = article
+                .archived_from
+                .clone()
+                .filter(|_| !model.archived_articles)
+                .map(|snapshot| format!("The live page was unavailable, this was the archived copy at {}", snapshot));
+            if model.reader_pending.as_ref() == Some(&article.url) {
+                model.reader_pending = None;
+                return open_reader(model, &article);
+            }
+            return Some(Cmd::ViewText { text: article.text, editor });
+        }
+        Msg::OnlyThisDomain if model.only_domain.is_some() => {
+            model.only_domain = None;
+            return reload_unfiltered(model);
+        }
+        Msg::OnlyThisDomain => {
+            if let Some(site) = selected_domain(model) {
+                model.notice = Some(format!("Only stories from {}, d again for all", site));
+                model.only_domain = Some(site);
+                apply_filters(model);
+            }
+        }
+        Msg::OnlyThisKind if model.only_kind.is_some() => {
+            model.only_kind = None;
+            return reload_unfiltered(model);
+        }
+        Msg::OnlyThisKind => {
+            if let Some(item) = model.storylist.selected_item() {
+                let kind = item.story.kind();
+                model.notice = Some(match kind {
+                    HnStoryType::Story => "Only plain stories, t again for all".to_string(),
+                    kind => format!("Only [{}] stories, t again for all", kind),
+                });
+                model.only_kind = Some(kind);
+                apply_filters(model);
+            }
+        }
+        Msg::FoldGroup => fold_group(model),
+        Msg::OnlyThisAuthor if model.only_author.is_some() => {
+            model.only_author = None;
+            return reload_unfiltered(model);
+        }
+        Msg::OnlyThisAuthor => {
+            if let Some(author) = model.storylist.selected_item().map(|item| item.story.author().to_string()) {
+                model.notice = Some(format!("Only stories by {}, w again for all, W for their latest", author));
+                model.only_author = Some(author);
+                apply_filters(model);
+            }
+        }
+        Msg::ShowSubmissions => {
+            if let Some(author) = model.storylist.selected_item().map(|item| item.story.author().to_string()) {
+                model.only_author = None;
+                model.submitter = Some(author);
+                return switch_feed(model, Feed::Submissions);
+            }
+        }
+        Msg::ShowDiscussions => {
+            let story = model.storylist.selected_item()?.story.clone();
+            let Some(url) = story.url().clone() else {
+                model.notice = Some("A text post has no link to look up".to_string());
+                return None;
+            };
+            model.discussed = Some((story.id(), url));
+            return switch_feed(model, Feed::Discussions);
+        }
+        Msg::NeverThisDomain => {
+            if let Some(site) = selected_domain(model) {
+                model.notice = Some(format!("No more stories from {}, :domain to undo", site));
+                model.never_domains.push(site);
+                apply_filters(model);
+            }
+        }
+        Msg::ToggleArchived => {
+            model.archived_articles = !model.archived_articles;
+            model.notice = Some(if model.archived_articles {
+                "Articles now open from the Wayback Machine, A for the live site".to_string()
+            } else {
+                "Articles now open from the live site".to_string()
+            });
+        }
+    }
+    None
+}
+
+/// Translate a key press into the message it stands for, as understood by the focused pane
+///
+/// A held key repeats like presses of it, as it does on terminals that do not tell them apart.
+fn handle_key(model: &Model, prefix: Option<char>, key: KeyEvent) -> Option<Msg> {
+    // Raw mode turns Ctrl-Z into a key, so job control has to be done by hand
+    if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        return Some(Msg::Suspend);
+    }
+    match model.focused_pane() {
+        Pane::Confirm => confirm_key(key),
+        Pane::Prompt => prompt_key(key),
+        Pane::Compose => compose_key(key),
+        Pane::Comments => comments_key(model, prefix, key),
+        // Keys the details pane has no use for still act on the list
+        Pane::Details => details_key(model, key).or_else(|| list_key(model, key)),
+        Pane::List => list_key(model, key),
+    }
+}
+
+/// Put pasted text in at the cursor of the prompt or the reply box. The prompt takes a single
+/// line, so line breaks become spaces there. Anywhere else a paste is dropped, its characters are
+/// not keys.
+fn paste(model: &mut Model, text: String) -> Option<Cmd> {
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    match model.focused_pane() {
+        Pane::Prompt => {
+            let line = text.trim_end_matches('\n').replace('\n', " ");
+            update(model, Msg::PromptEdit(Edit::InsertText(line)))
+        }
+        Pane::Compose => update(model, Msg::ComposeEdit(Edit::InsertText(text))),
+        _ => None,
+    }
+}
+
+fn prompt_key(key: KeyEvent) -> Option<Msg> {
+    match key.code {
+        KeyCode::Enter => Some(Msg::PromptSubmit),
+        KeyCode::Esc => Some(Msg::PromptCancel),
+        KeyCode::Backspace => Some(Msg::PromptBackspace),
+        KeyCode::Tab => Some(Msg::PromptComplete),
+        KeyCode::Up => Some(Msg::PromptHistoryPrevious),
+        KeyCode::Down => Some(Msg::PromptHistoryNext),
+        _ => Edit::from_key(&key).map(Msg::PromptEdit),
+    }
+}
+
+/// Only the answers get through while a question is open
+fn confirm_key(key: KeyEvent) -> Option<Msg> {
+    match key.code {
+        KeyCode::Char('y' | 'Y') | KeyCode::Enter => Some(Msg::Answer(true)),
+        KeyCode::Char('n' | 'N' | 'q') | KeyCode::Esc => Some(Msg::Answer(false)),
+        _ => None,
+    }
+}
+
+fn compose_key(key: KeyEvent) -> Option<Msg> {
+    match key.code {
+        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(Msg::ComposePreview)
+        }
+        KeyCode::Esc => Some(Msg::ComposeCancel),
+        KeyCode::Enter => Some(Msg::ComposeInput('\n')),
+        _ => Edit::from_key(&key).map(Msg::ComposeEdit),
+    }
+}
+
+fn comments_key(model: &Model, prefix: Option<char>, key: KeyEvent) -> Option<Msg> {
+    match (prefix, key.code) {
+        (Some('z'), KeyCode::Char('M')) => return Some(Msg::CollapseAll(true)),
+        (Some('z'), KeyCode::Char('R')) => return Some(Msg::CollapseAll(false)),
+        (Some('z'), KeyCode::Char('a')) => return Some(Msg::ToggleCollapsed),
+        (Some(']'), KeyCode::Char(']')) => return Some(Msg::JumpTopLevel { forward: true }),
+        (Some('['), KeyCode::Char('[')) => return Some(Msg::JumpTopLevel { forward: false }),
+        // An unknown sequence is dropped whole, like in vim
+        (Some(_), _) => return None,
+        (None, _) => {}
+    }
+    match key.code {
+        KeyCode::Char('z') => return Some(Msg::KeyPrefix('z')),
+        KeyCode::Char(c @ (']' | '[')) => return Some(Msg::KeyPrefix(c)),
+        KeyCode::Char(c @ '1'..='9') => return Some(Msg::OpenLink(c as usize - '0' as usize)),
+        _ => {}
+    }
+    Some(match model.keymap.action(Mode::Comments, &key)? {
+        Action::Fold => Msg::ToggleCollapsed,
+        Action::NextTopLevel => Msg::JumpTopLevel { forward: true },
+        Action::PreviousTopLevel => Msg::JumpTopLevel { forward: false },
+        Action::ToggleDead => Msg::ToggleDead,
+        Action::FollowLink => Msg::FollowLink,
+        Action::ToggleIgnored => Msg::ToggleIgnored,
+        Action::Find => Msg::OpenCommentSearch,
+        Action::Yank => Msg::YankComment,
+        Action::FindNext => Msg::FindNext { forward: true },
+        Action::FindPrevious => Msg::FindNext { forward: false },
+        Action::Back => Msg::CloseComments,
+        Action::Down => Msg::SelectNext,
+        Action::Up => Msg::SelectPrevious,
+        Action::Top => Msg::SelectFirst,
+        Action::Bottom => Msg::SelectLast,
+        Action::Reply => Msg::Reply,
+        Action::Vote => Msg::ToggleVote,
+        Action::CommandLine => Msg::OpenCommandLine,
+        Action::Pipe => Msg::Pipe,
+        Action::Pager => Msg::ViewText { editor: false },
+        Action::Editor => Msg::ViewText { editor: true },
+        _ => return None,
+    })
+}
+
+fn details_key(model: &Model, key: KeyEvent) -> Option<Msg> {
+    // The number keys open the related discussions listed, rather than the links of an article
+    if let KeyCode::Char(c @ '1'..='9') = key.code {
+        let n = c as usize - '0' as usize;
+        if model.reader.is_none() && n <= model.selected_related().len() {
+            return Some(Msg::OpenRelated(n));
+        }
+    }
+    let lines = match model.keymap.action(Mode::Details, &key)? {
+        Action::OnlyAuthor => return Some(Msg::OnlyThisAuthor),
+        Action::Submissions => return Some(Msg::ShowSubmissions),
+        Action::Discussions => return Some(Msg::ShowDiscussions),
+        // Searching is for the reader, other details are short enough to read whole
+        Action::Find if model.reader.is_some() => return Some(Msg::OpenArticleSearch),
+        Action::FindNext if model.reader.is_some() => return Some(Msg::FindInArticle { forward: true }),
+        Action::FindPrevious if model.reader.is_some() => return Some(Msg::FindInArticle { forward: false }),
+        Action::Down => 1,
+        Action::Up => -1,
+        Action::PageDown => DETAILS_PAGE as i32,
+        Action::PageUp => -(DETAILS_PAGE as i32),
+        Action::Top => -(u16::MAX as i32),
+        Action::Bottom => u16::MAX as i32,
+        _ => return None,
+    };
+    Some(Msg::ScrollDetails(lines))
+}
+
+fn list_key(model: &Model, key: KeyEvent) -> Option<Msg> {
+    if model.storylist.visual_anchor.is_some() && key.code == KeyCode::Esc {
+        return Some(Msg::ToggleVisual);
+    }
+    if model.reader.is_some() && key.code == KeyCode::Esc {
+        return Some(Msg::ToggleReader);
+    }
+    if model.show_details && model.details_full_screen && key.code == KeyCode::Esc {
+        return Some(Msg::ToggleFullScreen);
+    }
+    if model.quick_open {
+        // Any other key leaves without opening anything
+        return Some(Msg::QuickOpen(match key.code {
+            KeyCode::Char(c @ '1'..='9') => Some(c as usize - '0' as usize),
+            _ => None,
+        }));
+    }
+    // Keys that only do something in some state stay as they are
+    match key.code {
+        KeyCode::Char('/') if model.feed == Feed::History => return Some(Msg::OpenHistorySearch),
+        KeyCode::Char('J') if model.feed == Feed::Queue => return Some(Msg::MoveInQueue { up: false }),
+        KeyCode::Char('K') if model.feed == Feed::Queue => return Some(Msg::MoveInQueue { up: true }),
+        KeyCode::Char(c @ '1'..='9') if numbered_links(model).is_empty() => return Some(Msg::OpenRankPrompt(c)),
+        KeyCode::Char(c @ '1'..='9') => return Some(Msg::OpenLink(c as usize - '0' as usize)),
+        _ => {}
+    }
+    Some(match model.keymap.action(Mode::List, &key)? {
+        Action::Quit => Msg::Quit,
+        Action::Unselect => Msg::SelectNone,
+        Action::Down => Msg::SelectNext,
+        Action::Up => Msg::SelectPrevious,
+        Action::Top => Msg::SelectFirst,
+        Action::Bottom => Msg::SelectLast,
+        Action::ToggleStatus => Msg::ToggleStatus,
+        Action::NextFeed => Msg::NextFeed,
+        Action::PreviousFeed => Msg::PreviousFeed,
+        Action::CommandLine => Msg::OpenCommandLine,
+        Action::Vote => Msg::ToggleVote,
+        Action::Favorite => Msg::ToggleFavorite,
+        Action::Comments => Msg::OpenComments,
+        // Hiding a marked range asks first, a single story is easily brought back
+        Action::Hide => match model.storylist.targets().len() {
+            0 | 1 => Msg::HideStory,
+            n => Msg::Confirm { question: format!("Hide {} stories?", n), then: Box::new(Msg::HideStory) },
+        },
+        Action::FindNext => Msg::SearchNext,
+        Action::NextSavedSearch => Msg::NextSavedSearch,
+        Action::History => Msg::ShowHistory,
+        Action::Visual => Msg::ToggleVisual,
+        Action::MarkRead => Msg::MarkRead,
+        Action::Bookmark => Msg::ToggleBookmark,
+        // With nothing to open, `o` and a number open one of the stories on screen
+        Action::Open if model.storylist.targets().is_empty() => Msg::StartQuickOpen,
+        Action::Open => Msg::OpenInBrowser,
+        Action::QuickOpen => Msg::StartQuickOpen,
+        Action::FollowLink => Msg::FollowLink,
+        Action::Undo => Msg::Undo,
+        Action::Snooze => Msg::Snooze,
+        Action::Pipe => Msg::Pipe,
+        Action::Pager => Msg::ViewText { editor: false },
+        Action::Editor => Msg::ViewText { editor: true },
+        Action::Reader => Msg::ToggleReader,
+        Action::Archived => Msg::ToggleArchived,
+        Action::OnlyDomain => Msg::OnlyThisDomain,
+        Action::NeverDomain => Msg::NeverThisDomain,
+        Action::OnlyKind => Msg::OnlyThisKind,
+        Action::Fold => Msg::FoldGroup,
+        Action::FullScreen => Msg::ToggleFullScreen,
+        Action::Density => Msg::ToggleDensity,
+        Action::Metrics => Msg::ToggleMetrics,
+        Action::Refresh => Msg::Refresh,
+        Action::SwitchFocus => Msg::SwitchFocus,
+        Action::ShrinkList => Msg::ShiftDetailsSplit { grow_list: false },
+        Action::GrowList => Msg::ShiftDetailsSplit { grow_list: true },
+        _ => return None,
+    })
+}
+
+fn submit_prompt(model: &mut Model) -> Option<Cmd> {
+    let prompt = model.prompt.take()?;
+    match prompt.kind {
+        PromptKind::Command => {
+            let line = prompt.input.text().trim();
+            push_history(&mut model.command_history, line);
+            run_command(model, line)
+        }
+        PromptKind::LoginUsername => {
+            let username = prompt.input.text().trim().to_string();
+            if !username.is_empty() {
+                model.prompt = Some(Prompt::new("Password: ", PromptKind::LoginPassword { username }));
+            }
+            None
+        }
+        PromptKind::LoginPassword { username } => {
+            model.notice = Some(format!("Logging in as {}...", username));
+            Some(Cmd::Login { username, password: prompt.input.to_string() })
+        }
+        PromptKind::SubmitTitle => {
+            let title = prompt.input.text().trim().to_string();
+            if title.chars().count() > MAX_TITLE_LEN {
+                model.notice = Some(format!("Titles are limited to {} characters", MAX_TITLE_LEN));
+                let mut retry = Prompt::new("Title: ", PromptKind::SubmitTitle);
+                retry.input.set(&title);
+                model.prompt = Some(retry);
+            } else if !title.is_empty() {
+                model.prompt = Some(url_prompt(title));
+            }
+            None
+        }
+        PromptKind::SubmitUrl { title } => {
+            submit_url_entered(model, title, prompt.input.text().trim());
+            None
+        }
+        PromptKind::Snooze { ids } => {
+            let input = prompt.input.text().trim();
+            match parse_duration(if input.is_empty() { DEFAULT_SNOOZE } else { input }) {
+                Some(seconds) => snooze_stories(model, &ids, seconds),
+                None => {
+                    model.notice = Some("Snooze for a time like 30m, 4h or 1d".to_string());
+                    model.prompt = Some(Prompt::new(&prompt.label, PromptKind::Snooze { ids }));
+                    None
+                }
+            }
+        }
+        PromptKind::Pipe { input } => {
+            let command = prompt.input.text().trim();
+            (!command.is_empty()).then(|| Cmd::Pipe { command: command.to_string(), input })
+        }
+        PromptKind::CommentSearch => {
+            let view = model.comments.as_mut()?;
+            let search = prompt.input.text().trim();
+            view.search = (!search.is_empty()).then(|| search.to_string());
+            if view.search.is_some() {
+                return update(model, Msg::FindNext { forward: true });
+            }
+            None
+        }
+        PromptKind::HistorySearch => show_history(model, prompt.input.text().trim()),
+        PromptKind::ArticleSearch => {
+            let reader = model.reader.as_mut()?;
+            let search = prompt.input.text().trim();
+            reader.search = (!search.is_empty()).then(|| search.to_string());
+            if reader.search.is_some() {
+                return update(model, Msg::FindInArticle { forward: true });
+            }
+            None
+        }
+        PromptKind::Rank => match prompt.input.text().trim().parse::<usize>() {
+            Ok(rank) => update(model, Msg::JumpToRank(rank)),
+            _ if prompt.input.text().trim().is_empty() => None,
+            _ => {
+                model.notice = Some("Type the number of a story, as in #17".to_string());
+                None
+            }
+        },
+        PromptKind::FollowLink { links } => match prompt.input.text().trim().parse::<usize>() {
+            Ok(n) if (1..=links.len()).contains(&n) => Some(Cmd::OpenUrls(vec![links[n - 1].clone()])),
+            _ if prompt.input.text().trim().is_empty() => None,
+            _ => {
+                model.notice = Some(format!("Pick a link from 1 to {}", links.len()));
+                model.prompt = Some(Prompt::new(&prompt.label, PromptKind::FollowLink { links }));
+                None
+            }
+        },
+    }
+}
+
+/// Execute a line typed at the `:` prompt
+fn run_command(model: &mut Model, line: &str) -> Option<Cmd> {
+    let (command, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let rest = rest.trim();
+    // `:42` like in vim
+    if let Ok(rank) = command.parse::<usize>() {
+        return update(model, Msg::JumpToRank(rank));
+    }
+    match command {
+        "" => None,
+        "login" => {
+            model.prompt = Some(match rest.split_whitespace().next() {
+                Some(username) => Prompt::new(
+                    "Password: ",
+                    PromptKind::LoginPassword { username: username.to_string() },
+                ),
+                None => Prompt::new("Username: ", PromptKind::LoginUsername),
+            });
+            None
+        }
+        "logout" => Some(Cmd::Logout),
+        "favorites" => show_favorites(model),
+        "queue" => switch_feed(model, Feed::Queue),
+        "history" => show_history(model, rest),
+        "between" => show_between(model, rest),
+        "past" => match hint_search::parse_day(rest, chrono::Utc::now().date_naive()) {
+            Some(day) => show_past_day(model, day),
+            None => usage_notice(model, "past"),
+        },
+        "feed" => match Feed::from_name(rest) {
+            Some(Feed::Favorites) => show_favorites(model),
+            Some(feed) => switch_feed(model, feed),
+            None => usage_notice(model, "feed"),
+        },
+        "limit" => {
+            set_limit(model, rest);
+            None
+        }
+        "theme" => {
+            match Theme::by_name(rest) {
+                Some(theme) => model.theme = theme,
+                None => {
+                    let names = Theme::names().join(", ");
+                    model.notice = Some(format!("Themes: {}", names));
+                }
+            }
+            None
+        }
+        "search" => {
+            model.search = (!rest.is_empty()).then(|| rest.to_string());
+            search_next(model);
+            None
+        }
+        "unhide" => update(model, Msg::UnhideLast),
+        "readall" => {
+            let unread = model.storylist.items.iter().filter(|item| item.status == Status::Unread).count();
+            if unread == 0 {
+                model.notice = Some("Every story is read already".to_string());
+                return None;
+            }
+            let question = format!("Mark {} as read?", plural(unread, "story", "stories"));
+            update(model, Msg::Confirm { question, then: Box::new(Msg::MarkAllRead) })
+        }
+        "cache" => cache_command(model, rest),
+        "clearhistory" => {
+            if model.history.is_empty() {
+                model.notice = Some("Nothing opened yet, the history is empty".to_string());
+                return None;
+            }
+            let question = format!("Forget all {} visits in the history?", model.history.len());
+            update(model, Msg::Confirm { question, then: Box::new(Msg::ClearHistory) })
+        }
+        "tabnew" => update(model, Msg::NewTab),
+        "tabnext" => update(model, Msg::SwitchTab { forward: true }),
+        "tabprevious" => update(model, Msg::SwitchTab { forward: false }),
+        "tabclose" => update(model, Msg::CloseTab),
+        "ignore" if rest.is_empty() => {
+            model.notice = Some(if model.ignored.is_empty() {
+                "Nobody is ignored, I on a comment ignores its author".to_string()
+            } else {
+                format!("Ignoring {}", model.ignored.iter().cloned().collect::<Vec<_>>().join(", "))
+            });
+            None
+        }
+        "ignore" => update(model, Msg::SetIgnored { user: rest.to_string(), ignored: true }),
+        "unignore" if !rest.is_empty() => update(model, Msg::SetIgnored { user: rest.to_string(), ignored: false }),
+        "export" => export(model, rest),
+        "domain" => {
+            let (how, site) = rest.split_once(' ').unwrap_or((rest, ""));
+            let site = site.trim().to_lowercase();
+            match (how, site.as_str()) {
+                ("", _) => {
+                    model.only_domain = None;
+                    model.never_domains.clear();
+                    model.notice = Some("Showing stories from every site".to_string());
+                    reload_unfiltered(model)
+                }
+                ("only", site) if !site.is_empty() => {
+                    model.only_domain = Some(site.to_string());
+                    apply_filters(model);
+                    None
+                }
+                ("never", site) if !site.is_empty() => {
+                    model.never_domains.push(site.to_string());
+                    apply_filters(model);
+                    None
+                }
+                _ => usage_notice(model, "domain"),
+            }
+        }
+        "sort" => match StorySort::from_name(if rest.is_empty() { "rank" } else { rest }) {
+            Some(sort) => {
+                model.sort = sort;
+                model.picked_sort = Some(sort);
+                sort_list(model);
+                None
+            }
+            None => usage_notice(model, "sort"),
+        },
+        "group" if rest.is_empty() => {
+            unfold_groups(model);
+            model.group = None;
+            sort_list(model);
+            None
+        }
+        "group" => match Grouping::from_name(rest) {
+            Some(group) => {
+                unfold_groups(model);
+                model.group = Some(group);
+                sort_list(model);
+                None
+            }
+            None => usage_notice(model, "group"),
+        },
+        "minscore" if rest.is_empty() => {
+            model.min_score = None;
+            model.notice = Some("Showing stories of any score".to_string());
+            reload_unfiltered(model)
+        }
+        "minscore" => match rest.parse::<u32>() {
+            Ok(min) => {
+                let cmd = model.min_score.is_some_and(|before| min < before).then(|| reload_unfiltered(model)).flatten();
+                model.min_score = Some(min);
+                apply_filters(model);
+                cmd
+            }
+            Err(_) => usage_notice(model, "minscore"),
+        },
+        "type" if rest.is_empty() => {
+            model.only_kind = None;
+            model.notice = Some("Showing every kind of story".to_string());
+            reload_unfiltered(model)
+        }
+        "type" => match HnStoryType::from_name(rest) {
+            Some(kind) if kind != HnStoryType::Comment => {
+                let cmd = model.only_kind.is_some().then(|| reload_unfiltered(model)).flatten();
+                model.only_kind = Some(kind);
+                apply_filters(model);
+                cmd
+            }
+            _ => usage_notice(model, "type"),
+        },
+        "submit" => {
+            if model.hn_session.is_none() {
+                model.notice = Some("Log in with :login to submit".to_string());
+            } else {
+                model.prompt = Some(Prompt::new("Title: ", PromptKind::SubmitTitle));
+            }
+            None
+        }
+        other => {
+            model.notice = Some(format!("Unknown command: {}", other));
+            None
+        }
+    }
+}
+
+/// Write the marked stories, or the whole list, as `:export <format> [file]`
+fn export(model: &mut Model, args: &str) -> Option<Cmd> {
+    if model.comments.is_some() {
+        return export_thread(model, args);
+    }
+    let (format, path) = args.split_once(' ').unwrap_or((args, ""));
+    let Some(format) = ExportFormat::from_name(format) else {
+        return usage_notice(model, "export");
+    };
+    let rows = match model.storylist.marked_range() {
+        Some(range) => &model.storylist.items[range],
+        None => &model.storylist.items[..],
+    };
+    if rows.is_empty() {
+        model.notice = Some("Nothing to export".to_string());
+        return None;
+    }
+    let stories: Vec<ExportedStory> = rows.iter().map(exported).collect();
+    let path = match path.trim() {
+        "" => PathBuf::from(format!("hint-{}.{}", model.feed.to_string().to_lowercase(), format.extension())),
+        path => expand_home(path),
+    };
+    let contents = format.render(&format!("HackerNews | {}", model.feed_title()), &stories);
+    model.storylist.visual_anchor = None;
+    Some(Cmd::Export { path, contents })
+}
+
+fn exported(item: &DisplayListItem) -> ExportedStory {
+    ExportedStory {
+        id: item.id(),
+        title: item.title().to_string(),
+        url: story_url(&item.story),
+        score: item.story.score(),
+        author: item.story.author().to_string(),
+        read: item.status == Status::Read,
+    }
+}
+
+/// Prompt for the command of `|`, with the article being read, the open thread as Markdown or the
+/// stories' details as input
+fn start_pipe(model: &mut Model) {
+    let input = match (&model.reader, model.comments.as_ref()) {
+        (Some(reader), _) => reader.text(),
+        (None, Some(view)) => match &view.thread {
+            Some(thread) => hint_export::thread_markdown(thread),
+            None => return,
+        },
+        (None, None) => {
+            let targets = model.storylist.targets();
+            if targets.is_empty() {
+                return;
+            }
+            let stories: Vec<ExportedStory> =
+                targets.iter().map(|i| exported(&model.storylist.items[*i])).collect();
+            hint_export::plain_text(&stories)
+        }
+    };
+    model.storylist.visual_anchor = None;
+    model.prompt = Some(Prompt::new("| ", PromptKind::Pipe { input }));
+}
+
+/// The open thread goes straight to the pager; an article has to be fetched first
+fn view_text(model: &mut Model, editor: bool) -> Option<Cmd> {
+    if let Some(view) = &model.comments {
+        let thread = view.thread.as_ref()?;
+        return Some(Cmd::ViewText { text: hint_export::thread_markdown(thread), editor });
+    }
+    let item = model.storylist.selected_item()?;
+    let Some(url) = item.story.url().clone() else {
+        model.notice = Some("Text posts have no article, open the comments with c".to_string());
+        return None;
+    };
+    let story = item.story.clone();
+    record_visits(model, &[story]);
+    if let Some(article) = model.prefetched.article(&url).filter(|_| !model.archived_articles) {
+        return update(model, Msg::ArticleLoaded { article: article.clone(), editor });
+    }
+    model.notice = Some("Fetching article...".to_string());
+    Some(Cmd::FetchArticle { url, editor, archived: model.archived_articles })
+}
+
+/// Read the selected story's article in the details pane, fetching it first unless it is in
+/// already; or close the reader
+fn toggle_reader(model: &mut Model) -> Option<Cmd> {
+    if model.reader.is_some() {
+        leave_reader(model);
+        model.focus = Pane::List;
+        return None;
+    }
+    let item = model.storylist.selected_item()?;
+    let Some(url) = item.story.url().clone() else {
+        model.notice = Some("Text posts have no article, their text is in the details".to_string());
+        return None;
+    };
+    let story = item.story.clone();
+    record_visits(model, &[story]);
+    if let Some(article) = model.prefetched.article(&url).filter(|_| !model.archived_articles) {
+        let article = article.clone();
+        return open_reader(model, &article);
+    }
+    model.notice = Some("Fetching article...".to_string());
+    model.reader_pending = Some(url.clone());
+    Some(Cmd::FetchArticle { url, editor: false, archived: model.archived_articles })
+}
+
+/// Show the article in the reader, and fetch its images if the terminal can show them
+fn open_reader(model: &mut Model, article: &Article) -> Option<Cmd> {
+    // The selection moved on while it was being fetched
+    if model.storylist.selected_item().and_then(|item| item.story.url().as_ref()) != Some(&article.url) {
+        return None;
+    }
+    model.article_links = Some((article.url.clone(), article.links.clone()));
+    let mut reader = Reader::new(article);
+    if let Some(paragraph) = model.positions.article(&article.url) {
+        reader.go_to(paragraph);
+    }
+    let urls = reader.image_urls();
+    model.images.retain(|url, _| urls.contains(url));
+    let missing: Vec<String> = urls.into_iter().filter(|url| !model.images.contains_key(url)).collect();
+    let fetch = model.inline_images.is_some() && !missing.is_empty();
+    if model.inline_images.is_some() {
+        reader.show_images(IMAGE_ROWS);
+    }
+    model.reader = Some(reader);
+    model.show_details = true;
+    model.focus = Pane::Details;
+    fetch.then_some(Cmd::FetchImages(missing))
+}
+
+/// Close the reader, remembering where reading stopped
+fn leave_reader(model: &mut Model) {
+    if let Some(reader) = model.reader.take() {
+        model.positions.set_article(&reader.url, reader.position().0);
+    }
+}
+
+/// Close the comment thread, remembering the comment reading stopped at
+fn leave_thread(model: &mut Model) {
+    if let Some(view) = model.comments.take() {
+        remember_thread(&mut model.positions, &view);
+    }
+}
+
+fn remember_thread(positions: &mut ReadingPositions, view: &CommentView) {
+    let Some(thread) = &view.thread else {
+        return;
+    };
+    let comment = view.selected_comment().map(|index| thread.comments[index].id);
+    positions.set_thread(view.story_id, comment);
+}
+
+/// The links the number keys open: those of the selected comment, or of the story's own text on
+/// the header row, or of its article once it has been read
+fn numbered_links(model: &Model) -> Vec<String> {
+    if let Some(view) = &model.comments {
+        let Some(thread) = &view.thread else {
+            return Vec::new();
+        };
+        let html = match view.selected_comment() {
+            Some(index) => Some(&thread.comments[index].text),
+            None => thread.text.as_ref(),
+        };
+        return html.map(|html| hint_html::links(html)).unwrap_or_default();
+    }
+    let url = model.storylist.selected_item().and_then(|item| item.story.url().clone());
+    match (&model.article_links, url) {
+        (Some((read, links)), Some(url)) if *read == url => links.clone(),
+        _ => Vec::new(),
+    }
+}
+
+/// Select the story HN ranks `rank`, or the row at `rank` in lists hint keeps itself. A ranked
+/// story not loaded yet is fetched first and selected when it arrives.
+fn jump_to_rank(model: &mut Model, rank: usize) {
+    let Some(index) = rank.checked_sub(1) else {
+        model.notice = Some("Stories are numbered from 1".to_string());
+        return;
+    };
+    let ranked = model.feed_order.iter().find(|(_, r)| **r == index).map(|(id, _)| *id);
+    let row = match ranked {
+        Some(id) => model.storylist.items.iter().position(|item| item.id() == id),
+        None if model.feed_order.is_empty() => Some(index).filter(|i| *i < model.storylist.items.len()),
+        None => None,
+    };
+    match (row, ranked) {
+        (Some(row), _) => {
+            model.pending_selection = None;
+            model.storylist.state.select(Some(row));
+            model.details_scroll = 0;
+        }
+        (None, Some(id)) => {
+            model.pending_selection = Some(id);
+            model.notice = Some(format!("Going to #{} once it loads", rank));
+        }
+        (None, None) => {
+            let count = model.feed_order.len().max(model.storylist.items.len());
+            model.notice = Some(format!("There are only {} stories", count));
+        }
+    }
+}
+
+fn no_links_notice(model: &Model) -> String {
+    if model.comments.is_some() {
+        "No links in this comment".to_string()
+    } else {
+        "Read the article with p to number its links".to_string()
+    }
+}
+
+fn open_link(model: &mut Model, n: usize) -> Option<Cmd> {
+    let links = numbered_links(model);
+    match links.get(n.wrapping_sub(1)) {
+        Some(url) => Some(Cmd::OpenUrls(vec![url.clone()])),
+        None => {
+            model.notice = Some(match links.len() {
+                0 => no_links_notice(model),
+                1 => "There is only 1 link".to_string(),
+                count => format!("There are only {} links", count),
+            });
+            None
+        }
+    }
+}
+
+/// From the comment viewer, `:export [markdown] [file]` archives the open thread
+fn export_thread(model: &mut Model, args: &str) -> Option<Cmd> {
+    let thread = model.comments.as_ref().and_then(|view| view.thread.as_ref());
+    let Some(thread) = thread else {
+        model.notice = Some("Comments are still loading".to_string());
+        return None;
+    };
+    let (first, rest) = args.split_once(' ').unwrap_or((args, ""));
+    let path = match ExportFormat::from_name(first) {
+        Some(ExportFormat::Markdown) => rest.trim(),
+        Some(_) => {
+            model.notice = Some("Threads are exported as Markdown only".to_string());
+            return None;
+        }
+        None => args,
+    };
+    let path = match path {
+        "" => PathBuf::from(format!("hint-{}.md", thread.story_id)),
+        path => expand_home(path),
+    };
+    Some(Cmd::Export { path, contents: hint_export::thread_markdown(thread) })
+}
+
+fn selected_domain(model: &mut Model) -> Option<String> {
+    let item = model.storylist.selected_item()?;
+    let site = item.story.url().as_deref().and_then(domain);
+    if site.is_none() {
+        model.notice = Some("Text posts are not from any site".to_string());
+    }
+    site
+}
+
+fn domain_allowed(model: &Model, story: &HnStory) -> bool {
+    let site = story.url().as_deref().and_then(domain);
+    let site = site.as_deref().unwrap_or_default();
+    if let Some(only) = &model.only_domain {
+        if !on_site(site, only) {
+            return false;
+        }
+    }
+    !model.never_domains.iter().any(|never| on_site(site, never))
+}
+
+/// Whether the domain, kind and score filters keep `story` in the list
+fn story_allowed(model: &Model, story: &HnStory) -> bool {
+    domain_allowed(model, story)
+        && model.only_kind.is_none_or(|kind| story.kind() == kind)
+        && model.only_author.as_ref().is_none_or(|author| story.author() == author)
+        && model.min_score.is_none_or(|min| story.score().unwrap_or(0) >= min)
+}
+
+/// Put the rows in the order `model.sort` asks for, in their groups if the list is grouped,
+/// keeping the selected and marked stories the same
+fn sort_list(model: &mut Model) {
+    match model.sort {
+        // A refresh reorders the front page; rows it no longer ranks stay where they are
+        StorySort::Rank => {
+            let order = &model.feed_order;
+            model.storylist.sort_ranked(|id| order.get(&id).copied());
+        }
+        // Stable, so stories that tie keep the feed's order
+        sort => model.storylist.rearrange(|items| items.sort_by_key(|item| std::cmp::Reverse(sort.key(&item.story)))),
+    }
+    if let Some(group) = model.group {
+        model.storylist.group(|story| group.key(story));
+    }
+}
+
+/// Fold the selected story's group away under its first story, or bring a folded one back
+fn fold_group(model: &mut Model) {
+    let Some(group) = model.group else {
+        model.notice = Some("Group the list with :group domain or :group author to fold it".to_string());
+        return;
+    };
+    let Some(key) = model.storylist.selected_item().map(|item| group.key(&item.story)) else {
+        return;
+    };
+    let list = &mut model.storylist;
+    let Some(first) = list.items.iter().position(|item| group.key(&item.story) == key) else {
+        return;
+    };
+    match list.folded.remove(&key) {
+        Some(stories) => list.rearrange(|items| drop(items.splice(first + 1..first + 1, stories))),
+        None => {
+            let mut stories = Vec::new();
+            let first_id = list.items[first].id();
+            list.rearrange(|items| {
+                let in_group = |item: &DisplayListItem| item.id() != first_id && group.key(&item.story) == key;
+                (stories, *items) = std::mem::take(items).into_iter().partition(in_group);
+            });
+            if stories.is_empty() {
+                return;
+            }
+            list.folded.insert(key, stories);
+            list.state.select(Some(first));
+        }
+    }
+}
+
+/// Put the stories of every folded group back in the list, in their groups' order
+fn unfold_groups(model: &mut Model) {
+    let Some(group) = model.group else {
+        return;
+    };
+    let list = &mut model.storylist;
+    for (key, stories) in std::mem::take(&mut list.folded) {
+        let first = list.items.iter().position(|item| group.key(&item.story) == key);
+        let at = first.map_or(list.items.len(), |first| first + 1);
+        list.rearrange(|items| drop(items.splice(at..at, stories)));
+    }
+}
+
+/// Drop the stories the filters leave out, keeping the selection on the same story if it stays
+fn apply_filters(model: &mut Model) {
+    let selected = model.storylist.selected_item().map(DisplayListItem::id);
+    let items = std::mem::take(&mut model.storylist.items);
+    model.storylist.items = items.into_iter().filter(|item| story_allowed(model, &item.story)).collect();
+    let mut folded = std::mem::take(&mut model.storylist.folded);
+    folded.values_mut().for_each(|stories| stories.retain(|item| story_allowed(model, &item.story)));
+    folded.retain(|_, stories| !stories.is_empty());
+    model.storylist.folded = folded;
+    model.storylist.visual_anchor = None;
+    let position = selected.and_then(|id| model.storylist.items.iter().position(|item| item.id() == id));
+    let fallback = (!model.storylist.items.is_empty()).then_some(0);
+    model.storylist.state.select(position.or(fallback));
+}
+
+/// Fetch the feed's order again and put the rows in it, fetching only the stories new to the list.
+/// Hidden stories count as known, they would only be dropped again.
+fn refresh_feed(model: &mut Model) -> Option<Cmd> {
+    let filled_by_app =
+        [Feed::Queue, Feed::History, Feed::Search, Feed::Favorites, Feed::Past, Feed::Between, Feed::Submissions, Feed::Discussions];
+    if filled_by_app.contains(&model.feed) {
+        return reload_unfiltered(model);
+    }
+    let mut known: HashSet<u64> = model.storylist.items.iter().map(DisplayListItem::id).collect();
+    known.extend(model.hidden.iter());
+    model.loading = true;
+    Some(Cmd::RefreshFeed { feed: model.feed, known })
+}
+
+/// Filtered-out stories are gone from the list, so lifting a filter loads the feed again
+fn reload_unfiltered(model: &mut Model) -> Option<Cmd> {
+    let selected = model.storylist.selected_item().map(DisplayListItem::id);
+    let cmd = switch_feed(model, model.feed);
+    model.pending_selection = selected;
+    if model.feed == Feed::Queue {
+        model.refresh_queue();
+    }
+    cmd
+}
+
+/// "1 story", "3 stories"
+fn plural(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+/// `:cache stats` opens the debug overlay with what the disk caches hold, `:cache clear` empties
+/// them after asking
+fn cache_command(model: &mut Model, arg: &str) -> Option<Cmd> {
+    let (action, which) = arg.split_once(' ').map_or((arg, ""), |(action, which)| (action, which.trim()));
+    match action {
+        "stats" if which.is_empty() => {
+            model.show_metrics = true;
+            Some(Cmd::MeasureCache)
+        }
+        "clear" => {
+            let caches = match which {
+                "" => DiskCache::ALL.to_vec(),
+                name => match DiskCache::from_name(name) {
+                    Some(cache) => vec![cache],
+                    None => return usage_notice(model, "cache"),
+                },
+            };
+            let names: Vec<&str> = caches.iter().map(|cache| cache.name()).collect();
+            let question = format!("Delete the cached {}?", names.join(" and "));
+            update(model, Msg::Confirm { question, then: Box::new(Msg::ClearCache(caches)) })
+        }
+        _ => usage_notice(model, "cache"),
+    }
+}
+
+fn usage_notice(model: &mut Model, command: &str) -> Option<Cmd> {
+    if let Some(usage) = hint_command::usage(command) {
+        model.notice = Some(format!("Usage: {}", usage));
+    }
+    None
+}
+
+fn set_limit(model: &mut Model, arg: &str) {
+    if arg.is_empty() {
+        model.limit = None;
+        model.notice = Some("Showing all stories".to_string());
+        return;
+    }
+    let Ok(limit) = arg.parse::<usize>() else {
+        usage_notice(model, "limit");
+        return;
+    };
+    model.limit = Some(limit);
+    model.storylist.items.truncate(limit);
+    if model.storylist.state.selected().is_some_and(|i| i >= limit) {
+        model.storylist.state.select(limit.checked_sub(1));
+    }
+}
+
+/// Whether the comment's author or text contains `needle`, which is lowercase
+pub fn comment_matches(comment: &Comment, needle: &str) -> bool {
+    comment.author.to_lowercase().contains(needle) || hint_html::to_plain_text(&comment.text).to_lowercase().contains(needle)
+}
+
+/// Select the next story after the current one whose title contains the search text
+fn search_next(model: &mut Model) {
+    let Some(search) = model.search.as_ref() else {
+        return;
+    };
+    let needle = search.to_lowercase();
+    let items = &model.storylist.items;
+    let start = model.storylist.state.selected().map_or(0, |i| i + 1);
+    let found = (0..items.len())
+        .map(|offset| (start + offset) % items.len())
+        .find(|&i| items[i].title().to_lowercase().contains(&needle));
+    match found {
+        Some(i) => model.storylist.state.select(Some(i)),
+        None => model.notice = Some(format!("No story matches \"{}\"", search)),
+    }
+}
+
+fn complete_prompt(model: &mut Model) {
+    let Some(prompt) = model.prompt.as_mut().filter(|p| matches!(p.kind, PromptKind::Command)) else {
+        return;
+    };
+    let completion = hint_command::complete(prompt.input.text());
+    prompt.input.set(&completion.line);
+    if completion.candidates.len() > 1 {
+        model.notice = Some(completion.candidates.join("  "));
+    }
+}
+
+/// Step through earlier command lines, `older` towards the first one
+fn browse_history(model: &mut Model, older: bool) {
+    let Some(prompt) = model.prompt.as_mut().filter(|p| matches!(p.kind, PromptKind::Command)) else {
+        return;
+    };
+    prompt.input.browse(&model.command_history, older);
+}
+
+/// Where a story goes in the list: before the first story that comes after it in the feed
+fn feed_position(model: &Model, id: u64) -> usize {
+    let items = &model.storylist.items;
+    let Some(rank) = model.feed_order.get(&id) else {
+        return items.len();
+    };
+    let later = items.iter().position(|item| model.feed_order.get(&item.id()).is_some_and(|other| other > rank));
+    later.unwrap_or(items.len())
+}
+
+fn story_loaded(model: &mut Model, feed: Feed, story: HnStory) -> Option<Cmd> {
+    if feed != model.feed || model.hidden.contains(story.id()) {
+        return None;
+    }
+    // A refresh brings stories again, which update their rows rather than add another
+    if let Some(item) = model.storylist.item_mut(story.id()) {
+        let score = item.story.score();
+        item.story = story;
+        if let (None, Some(score)) = (item.story.score(), score) {
+            item.story.set_score(score);
+        }
+        return None;
+    }
+    if model.snoozed.is_snoozed(story.id(), hint_snooze::now()) {
+        return None;
+    }
+    if model.limit.is_some_and(|limit| model.storylist.items.len() >= limit) {
+        return None;
+    }
+    if !story_allowed(model, &story) {
+        return None;
+    }
+    let id = story.id();
+    let mut item = DisplayListItem::from_hnstory(story);
+    item.favorite = feed == Feed::Favorites;
+    if model.story_cache.contains(id) {
+        item.status = Status::Read;
+    }
+    // A folded group takes its new stories in with the others
+    let group = model.group.map(|group| group.key(&item.story));
+    if let Some(folded) = group.and_then(|group| model.storylist.folded.get_mut(&group)) {
+        folded.push(item);
+        return None;
+    }
+    let i = match model.sort.key(&item.story) {
+        Some(key) => {
+            let items = &model.storylist.items;
+            let later = items.iter().position(|other| model.sort.key(&other.story) < Some(key));
+            later.unwrap_or(items.len())
+        }
+        None => feed_position(model, id),
+    };
+    model.storylist.insert_item(i, item);
+    if model.pending_selection == Some(id) {
+        model.pending_selection = None;
+        model.storylist.state.select(Some(i));
+    }
+    if model.group.is_some() {
+        sort_list(model);
+    }
+    evict_stories(model)
+}
+
+/// Keep a list that runs for days within `max_stories`, moving its oldest read stories to the
+/// disk cache
+fn evict_stories(model: &mut Model) -> Option<Cmd> {
+    let excess = model.storylist.items.len().checked_sub(model.max_stories()).filter(|n| *n > 0)?;
+    let bookmarks = &model.bookmarks;
+    let keep = |item: &DisplayListItem| item.status == Status::Unread || bookmarks.contains(item.id());
+    let evicted = model.storylist.evict(excess + EVICTION_BATCH, keep);
+    if evicted.is_empty() {
+        return None;
+    }
+    for item in &evicted {
+        model.story_cache.add(SavedStory::from_story(&item.story));
+    }
+    Some(Cmd::SaveStoryCache(model.story_cache.clone()))
+}
+
+/// Drop the selected or marked stories from every feed, on HN too when logged in
+fn hide_stories(model: &mut Model) -> Option<Cmd> {
+    let targets = model.storylist.targets();
+    let rows = remove_rows(model, &targets);
+    if rows.is_empty() {
+        return None;
+    }
+    let ids: Vec<u64> = rows.iter().map(|(_, item)| item.id()).collect();
+    let hooks = model.hooks.commands(HookEvent::Hide, rows.iter().map(|(_, item)| &item.story));
+    push_undo(model, Undo::Hide { feed: model.feed, rows });
+    for id in &ids {
+        model.hidden.hide(*id);
+    }
+    model.show_details = false;
+    model.details_full_screen = false;
+    model.focus = Pane::List;
+    model.notice = Some(match ids.len() {
+        1 => "Story hidden, :unhide brings it back".to_string(),
+        n => format!("{} stories hidden, :unhide brings them back one by one", n),
+    });
+    with_hooks(Some(hidden_changed(model, &ids, true)), hooks)
+}
+
+/// Take rows out of the list, keeping the selection on the row that moves up into their place
+fn remove_rows(model: &mut Model, rows: &[usize]) -> Vec<(usize, DisplayListItem)> {
+    let mut removed = Vec::new();
+    for i in rows.iter().rev() {
+        if *i < model.storylist.items.len() {
+            removed.push((*i, model.storylist.items.remove(*i)));
+        }
+    }
+    removed.reverse();
+    model.storylist.visual_anchor = None;
+    if let Some((first, _)) = removed.first() {
+        model.storylist.state.select(Some(*first));
+    }
+    removed
+}
+
+/// Put rows taken out by `remove_rows` back, if the list still shows the feed they came from
+fn restore_rows(model: &mut Model, feed: Feed, rows: Vec<(usize, DisplayListItem)>) {
+    if feed != model.feed {
+        return;
+    }
+    let first = rows.first().map(|(i, _)| *i);
+    for (i, item) in rows {
+        let i = i.min(model.storylist.items.len());
+        model.storylist.items.insert(i, item);
+    }
+    model.storylist.state.select(first);
+}
+
+fn snooze_stories(model: &mut Model, ids: &[u64], seconds: i64) -> Option<Cmd> {
+    let targets: Vec<usize> = model
+        .storylist
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| ids.contains(&item.id()))
+        .map(|(i, _)| i)
+        .collect();
+    let rows = remove_rows(model, &targets);
+    if rows.is_empty() {
+        return None;
+    }
+    let until = hint_snooze::now() + seconds;
+    let hooks = model.hooks.commands(HookEvent::Snooze, rows.iter().map(|(_, item)| &item.story));
+    for (_, item) in &rows {
+        model.snoozed.snooze(SavedStory::from_story(&item.story), until);
+    }
+    let wake = chrono::DateTime::from_timestamp(until, 0)
+        .map(|time| time.with_timezone(&chrono::Local).format("%a %H:%M").to_string())
+        .unwrap_or_default();
+    model.notice = Some(match rows.len() {
+        1 => format!("Snoozed until {}", wake),
+        n => format!("Snoozed {} stories until {}", n, wake),
+    });
+    push_undo(model, Undo::Snooze { feed: model.feed, rows });
+    model.show_details = false;
+    model.details_full_screen = false;
+    model.focus = Pane::List;
+    with_hooks(Some(Cmd::SaveSnoozed(model.snoozed.clone())), hooks)
+}
+
+/// Bring back snoozed stories whose time is up, at the top of whatever list is showing
+fn wake_snoozed(model: &mut Model) -> Option<Cmd> {
+    if model.snoozed.is_empty() {
+        return None;
+    }
+    let due = model.snoozed.take_due(hint_snooze::now());
+    if due.is_empty() {
+        return None;
+    }
+    for story in due.iter().rev() {
+        match model.storylist.item_mut(story.id) {
+            Some(item) => item.resurfaced = true,
+            None => {
+                let mut item = DisplayListItem::from_hnstory(story.to_story());
+                item.resurfaced = true;
+                model.storylist.insert_item(0, item);
+            }
+        }
+    }
+    model.notice = Some(match due.len() {
+        1 => format!("Back from snooze: {}", due[0].title),
+        n => format!("{} stories are back from snooze", n),
+    });
+    Some(Cmd::SaveSnoozed(model.snoozed.clone()))
+}
+
+/// Take the stories that were just read off the Queue, returning the bookmarks to save
+fn pop_queue(model: &mut Model) -> Option<Bookmarks> {
+    let targets = model.storylist.targets();
+    if targets.is_empty() {
+        return None;
+    }
+    push_undo(model, Undo::Bookmarks(model.bookmarks.clone()));
+    for i in &targets {
+        model.bookmarks.remove(model.storylist.items[*i].id());
+    }
+    model.refresh_queue();
+    model.notice = Some(format!("{} left in the queue", model.bookmarks.len()));
+    Some(model.bookmarks.clone())
+}
+
+fn move_in_queue(model: &mut Model, up: bool) -> Option<Cmd> {
+    let i = model.storylist.state.selected()?;
+    let id = model.storylist.items.get(i)?.id();
+    if !model.bookmarks.shift(id, up) {
+        return None;
+    }
+    // Filtered out bookmarks have no row, so the list is built again rather than rows swapped
+    model.refresh_queue();
+    Some(Cmd::SaveBookmarks(model.bookmarks.clone()))
+}
+
+/// Bookmark the selected or marked stories, or remove the bookmarks if they all have one
+fn toggle_bookmarks(model: &mut Model) -> Option<Cmd> {
+    let targets = model.storylist.targets();
+    if targets.is_empty() {
+        return None;
+    }
+    let stories: Vec<&HnStory> = targets.iter().map(|i| &model.storylist.items[*i].story).collect();
+    let remove = stories.iter().all(|story| model.bookmarks.contains(story.id()));
+    let hooks = if remove {
+        Vec::new()
+    } else {
+        model.hooks.commands(HookEvent::Bookmark, stories.iter().copied())
+    };
+    let previous = model.bookmarks.clone();
+    for story in stories {
+        if remove {
+            model.bookmarks.remove(story.id());
+        } else {
+            model.bookmarks.add(SavedStory::from_story(story));
+        }
+    }
+    push_undo(model, Undo::Bookmarks(previous));
+    model.storylist.visual_anchor = None;
+    model.refresh_queue();
+    model.notice = Some(match (remove, targets.len()) {
+        (true, 1) => "Bookmark removed".to_string(),
+        (false, 1) => "Bookmarked".to_string(),
+        (true, n) => format!("Removed {} bookmarks", n),
+        (false, n) => format!("Bookmarked {} stories", n),
+    });
+    with_hooks(Some(Cmd::SaveBookmarks(model.bookmarks.clone())), hooks)
+}
+
+fn set_ignored(model: &mut Model, user: String, ignored: bool) -> Option<Cmd> {
+    if ignored == model.ignored.contains(&user) {
+        model.notice = Some(format!("{} is {}ignored", user, if ignored { "already " } else { "not " }));
+        return None;
+    }
+    if ignored {
+        model.ignored.ignore(&user);
+    } else {
+        model.ignored.unignore(&user);
+    }
+    if let Some(view) = model.comments.as_mut() {
+        view.set_ignored(&user, ignored);
+    }
+    model.notice = Some(match (ignored, model.ignore_mode) {
+        (true, IgnoreMode::Collapse) => format!("Ignoring {}, Space opens a comment, I stops ignoring", user),
+        (true, IgnoreMode::Hide) => format!("Ignoring {}, :unignore {} brings their comments back", user, user),
+        (false, _) => format!("No longer ignoring {}", user),
+    });
+    Some(Cmd::SaveIgnored(model.ignored.clone()))
+}
+
+/// Open the selected or marked stories, each with its configured opener or else the browser
+fn open_stories(model: &mut Model) -> Option<Cmd> {
+    let targets = model.storylist.targets();
+    if targets.is_empty() {
+        return None;
+    }
+    let (mut urls, mut commands) = (Vec::new(), Vec::new());
+    for i in &targets {
+        let story = &model.storylist.items[*i].story;
+        let url = story_url(story);
+        match opener(&model.open_with, &url) {
+            Some(command) => commands.push(expand_opener(command, story)),
+            None => urls.push(url),
+        }
+    }
+    let mut cmds = Vec::new();
+    if !urls.is_empty() {
+        cmds.push(Cmd::OpenUrls(urls));
+    }
+    if !commands.is_empty() {
+        cmds.push(Cmd::RunCommands(commands));
+    }
+    let hooks = target_hooks(model, HookEvent::Open);
+    let opened: Vec<HnStory> = targets.iter().map(|i| model.storylist.items[*i].story.clone()).collect();
+    record_visits(model, &opened);
+    if model.feed == Feed::Queue {
+        cmds.extend(pop_queue(model).map(Cmd::SaveBookmarks));
+    }
+    model.storylist.visual_anchor = None;
+    let cmd = match cmds.len() {
+        1 => cmds.pop(),
+        _ => Some(Cmd::Batch(cmds)),
+    };
+    with_hooks(cmd, hooks)
+}
+
+/// Hook commands for `event` on the selected or marked stories
+fn target_hooks(model: &Model, event: HookEvent) -> Vec<String> {
+    let targets = model.storylist.targets();
+    model.hooks.commands(event, targets.iter().map(|i| &model.storylist.items[*i].story))
+}
+
+/// Run the hooks alongside whatever the action itself needs
+fn with_hooks(cmd: Option<Cmd>, hooks: Vec<String>) -> Option<Cmd> {
+    if hooks.is_empty() {
+        return cmd;
+    }
+    let hooks = Cmd::RunCommands(hooks);
+    Some(match cmd {
+        Some(Cmd::Batch(mut cmds)) => {
+            cmds.push(hooks);
+            Cmd::Batch(cmds)
+        }
+        Some(cmd) => Cmd::Batch(vec![cmd, hooks]),
+        None => hooks,
+    })
+}
+
+fn unhide_last(model: &mut Model) -> Option<Cmd> {
+    let Some(id) = model.hidden.unhide_last() else {
+        model.notice = Some("No hidden stories".to_string());
+        return None;
+    };
+    model.notice = Some(format!("Story {} is back in its feeds", id));
+    Some(hidden_changed(model, &[id], false))
+}
+
+fn hidden_changed(model: &Model, ids: &[u64], hide: bool) -> Cmd {
+    let save = Cmd::SaveHidden(model.hidden.clone());
+    let Some(session) = model.hn_session.clone() else {
+        return save;
+    };
+    let mut cmds = vec![save];
+    cmds.extend(ids.iter().map(|id| Cmd::SyncHide { session: session.clone(), id: *id, hide }));
+    Cmd::Batch(cmds)
+}
+
+/// Upvote the selected story, or take the vote back if we already gave it
+fn toggle_vote(model: &mut Model) -> Option<Cmd> {
+    let Some(session) = model.hn_session.clone() else {
+        model.notice = Some("Log in with :login to vote".to_string());
+        return None;
+    };
+    // In a thread, the selected comment; its first row is the story itself
+    if let Some(view) = model.comments.as_mut() {
+        let (id, _) = view.selected_target()?;
+        if let Some(comment) = view.comment_mut(id) {
+            comment.voted = !comment.voted;
+            return Some(Cmd::Vote { session, id, up: comment.voted });
+        }
+        let item = model.storylist.item_mut(id)?;
+        let up = !item.voted;
+        item.apply_vote(up);
+        return Some(Cmd::Vote { session, id, up });
+    }
+    let i = model.storylist.state.selected()?;
+    let item = model.storylist.items.get_mut(i)?;
+    let up = !item.voted;
+    item.apply_vote(up);
+    Some(Cmd::Vote { session, id: item.id(), up })
+}
+
+fn toggle_favorite(model: &mut Model) -> Option<Cmd> {
+    let Some(session) = model.hn_session.clone() else {
+        model.notice = Some("Log in with :login to use favorites".to_string());
+        return None;
+    };
+    let i = model.storylist.state.selected()?;
+    let item = model.storylist.items.get_mut(i)?;
+    item.favorite = !item.favorite;
+    Some(Cmd::Favorite { session, id: item.id(), add: item.favorite })
+}
+
+fn show_favorites(model: &mut Model) -> Option<Cmd> {
+    let Some(session) = model.hn_session.clone() else {
+        model.notice = Some("Log in with :login to see your favorites".to_string());
+        return None;
+    };
+    switch_feed(model, Feed::Favorites);
+    Some(Cmd::LoadFavorites(session))
+}
+
+/// Move the selection of the list on screen; a comment thread may need more of itself fetched
+fn select_in(model: &mut Model, select: impl FnOnce(&mut ListState)) -> Option<Cmd> {
+    select(active_list_state(model));
+    match model.comments.as_mut() {
+        Some(view) => view.load_near_selection(),
+        None => {
+            let mut cmds: Vec<Cmd> = [load_more(model), prefetch_selected(model)].into_iter().flatten().collect();
+            cmds.extend(fetch_for_details(model));
+            batch(cmds)
+        }
+    }
+}
+
+/// Fetch what the details pane shows of the selected story beyond the story itself: the account
+/// of its author, for its karma and age, and stories of titles like its own. Each once a session,
+/// and only while the pane is up.
+fn fetch_for_details(model: &mut Model) -> Vec<Cmd> {
+    let story = match model.storylist.selected_item() {
+        Some(item) if model.show_details && !model.offline => item.story.clone(),
+        _ => return Vec::new(),
+    };
+    let mut cmds = Vec::new();
+    let author = story.author().to_string();
+    if let (false, Entry::Vacant(entry)) = (author.is_empty(), model.authors.entry(author.clone())) {
+        entry.insert(None);
+        cmds.push(Cmd::FetchAuthor(author));
+    }
+    if let Entry::Vacant(entry) = model.related.entry(story.id()) {
+        entry.insert(None);
+        cmds.push(Cmd::FindRelated { id: story.id(), title: story.title().to_string() });
+    }
+    cmds
+}
+
+/// The commands run one after the other, none if there are none
+fn batch(mut cmds: Vec<Cmd>) -> Option<Cmd> {
+    match cmds.len() {
+        0 | 1 => cmds.pop(),
+        _ => Some(Cmd::Batch(cmds)),
+    }
+}
+
+/// The articles of the unread stories at the top of the feed, for reading them offline later
+fn articles_to_save(model: &Model) -> Option<Cmd> {
+    let unread = model.storylist.items.iter().filter(|item| item.status == Status::Unread);
+    let urls: Vec<String> = unread.filter_map(|item| item.story.url().clone()).take(model.prefetch_articles).collect();
+    (!urls.is_empty()).then_some(Cmd::SaveArticles(urls))
+}
+
+/// Fetch ahead what `c` and `p` would show for the selected story, when the config asks for it
+fn prefetch_selected(model: &Model) -> Option<Cmd> {
+    if !model.prefetch {
+        return None;
+    }
+    // `select_last` leaves the clamping to the next render, which this cannot wait for
+    let selected = model.storylist.state.selected()?.min(model.storylist.items.len().checked_sub(1)?);
+    let story = &model.storylist.items[selected].story;
+    let has_comments = story.has_comments() && story.descendants() != Some(0);
+    let thread = Some(story.id()).filter(|id| has_comments && !model.prefetched.has_thread(*id));
+    // Archived copies are only fetched on demand
+    let url = story.url().clone().filter(|url| !model.archived_articles && !model.prefetched.has_article(url));
+    (thread.is_some() || url.is_some()).then_some(Cmd::Prefetch(Prefetch { thread, url }))
+}
+
+/// Fetch the next stories of the feed once the selection nears the end of the list, and nothing
+/// else is on its way
+fn load_more(model: &mut Model) -> Option<Cmd> {
+    let selected = model.storylist.state.selected()?;
+    if model.loading || model.more_stories.is_empty() || selected.saturating_add(LOAD_MORE_AHEAD) < model.storylist.items.len() {
+        return None;
+    }
+    let count = model.more_stories.len().min(LOAD_MORE_STORIES);
+    let ids: Vec<u64> = model.more_stories.drain(..count).collect();
+    let start = model.feed_order.len();
+    model.feed_order.extend(ids.iter().enumerate().map(|(i, id)| (*id, start + i)));
+    model.loading = true;
+    model.notice = Some(format!("Loading {} more stories, {} left", count, model.more_stories.len()));
+    Some(Cmd::LoadMore { feed: model.feed, ids })
+}
+
+/// The list that the movement keys currently act on
+fn active_list_state(model: &mut Model) -> &mut ListState {
+    match model.comments.as_mut() {
+        Some(view) => &mut view.state,
+        None => &mut model.storylist.state,
+    }
+}
+
+/// The thread of `story_id` in the tab showing, or in one parked, which still takes what was
+/// fetched for it
+fn open_thread<'a>(comments: &'a mut Option<CommentView>, tabs: &'a mut [Tab], story_id: u64) -> Option<&'a mut CommentView> {
+    let parked = tabs.iter_mut().filter_map(|tab| tab.comments.as_mut());
+    comments.as_mut().into_iter().chain(parked).find(|view| view.story_id == story_id)
+}
+
+fn switch_tab(model: &mut Model, forward: bool) {
+    if model.tabs.is_empty() {
+        model.notice = Some("Only one tab, :tabnew opens another".to_string());
+        return;
+    }
+    let count = model.tab_count();
+    let current = model.park();
+    model.tabs.insert(model.tab, current);
+    model.tab = if forward { (model.tab + 1) % count } else { (model.tab + count - 1) % count };
+    let tab = model.tabs.remove(model.tab);
+    model.unpark(tab);
+}
+
+/// Drop the tab showing for the one after it, or the one before at the end
+fn close_tab(model: &mut Model) {
+    if model.tabs.is_empty() {
+        model.notice = Some("The last tab stays open".to_string());
+        return;
+    }
+    if model.tab == model.tabs.len() {
+        model.tab -= 1;
+    }
+    let tab = model.tabs.remove(model.tab);
+    model.compose = None;
+    model.unpark(tab);
+}
+
+fn open_comments(model: &mut Model) -> Option<Cmd> {
+    let story = &model.storylist.selected_item()?.story;
+    if !story.has_comments() {
+        model.notice = Some("Job ads take no comments".to_string());
+        return None;
+    }
+    let story_id = story.id();
+    open_thread_of(model, story_id)
+}
+
+/// Show the comments of story `story_id`, whether or not it is in the list
+fn open_thread_of(model: &mut Model, story_id: u64) -> Option<Cmd> {
+    let mut view = CommentView::new(story_id);
+    view.show_dead = model.show_dead;
+    view.ignored = model.ignored.iter().cloned().collect();
+    view.ignore_mode = model.ignore_mode;
+    model.comments = Some(view);
+    match model.prefetched.take_thread(story_id) {
+        Some(thread) => update(model, Msg::CommentsLoaded(thread)),
+        None => Some(Cmd::LoadComments(story_id)),
+    }
+}
+
+fn start_reply(model: &mut Model) {
+    if model.hn_session.is_none() {
+        model.notice = Some("Log in with :login to reply".to_string());
+        return;
+    }
+    let Some((parent, replying_to)) = model.comments.as_ref().and_then(CommentView::selected_target) else {
+        return;
+    };
+    let target = ComposeTarget::Reply { parent, replying_to };
+    model.compose = Some(Compose { target, text: TextInput::default(), preview: false });
+}
+
+fn submit_compose(model: &mut Model) -> Option<Cmd> {
+    let Some(session) = model.hn_session.clone() else {
+        model.notice = Some("Log in with :login to post".to_string());
+        return None;
+    };
+    let compose = model.compose.take()?;
+    model.notice = Some(match compose.target {
+        ComposeTarget::Reply { .. } => "Posting reply...".to_string(),
+        ComposeTarget::Submission { .. } => "Submitting...".to_string(),
+    });
+    Some(Cmd::Post {
+        session,
+        target: compose.target,
+        text: compose.text.text().trim_end().to_string(),
+    })
+}
+
+fn url_prompt(title: String) -> Prompt {
+    Prompt::new("URL (empty for a text post): ", PromptKind::SubmitUrl { title })
+}
+
+/// Title and URL are asked for at the prompt, the optional text in a compose box
+fn submit_url_entered(model: &mut Model, title: String, url: &str) {
+    let url = match url {
+        "" => None,
+        url if url.starts_with("http://") || url.starts_with("https://") => Some(url.to_string()),
+        _ => {
+            model.notice = Some("The URL must start with http:// or https://".to_string());
+            model.prompt = Some(url_prompt(title));
+            return;
+        }
+    };
+    let target = ComposeTarget::Submission { title, url };
+    model.compose = Some(Compose { target, text: TextInput::default(), preview: false });
+}
+
+fn switch_feed(model: &mut Model, feed: Feed) -> Option<Cmd> {
+    // Reloading the same feed keeps a sort or filter changed since
+    if feed != model.feed {
+        model.feed = feed;
+        model.picked_sort = None;
+        model.use_feed_defaults();
+    }
+    model.storylist.clear();
+    model.pending_selection = None;
+    model.feed_order.clear();
+    model.more_stories.clear();
+    model.loading = false;
+    model.refreshed_at = None;
+    model.cached_at = None;
+    model.show_details = false;
+    model.details_full_screen = false;
+    model.focus = Pane::List;
+    leave_reader(model);
+    leave_thread(model);
+    if feed != Feed::History {
+        model.history_query = None;
+    }
+    if feed == Feed::Queue {
+        model.refresh_queue();
+        return None;
+    }
+    if feed == Feed::Search {
+        return show_search_results(model);
+    }
+    if feed == Feed::History {
+        model.refresh_history();
+        return None;
+    }
+    model.loading = true;
+    if feed == Feed::Past {
+        return model.past_day.map(Cmd::LoadPastFrontPage);
+    }
+    if feed == Feed::Between {
+        return model.between.map(|(start, end)| Cmd::LoadBetween { start, end });
+    }
+    if feed == Feed::Submissions {
+        return model.submitter.clone().map(Cmd::LoadSubmissions);
+    }
+    if feed == Feed::Discussions {
+        return model.discussed.clone().map(|(_, url)| Cmd::LoadDiscussions(url));
+    }
+    Some(Cmd::LoadFeed(feed))
+}
+
+/// Show what was on the front page on `day`, UTC like HN's own past pages
+fn show_past_day(model: &mut Model, day: NaiveDate) -> Option<Cmd> {
+    if day > chrono::Utc::now().date_naive() {
+        model.notice = Some(format!("{} has not happened yet", day));
+        return None;
+    }
+    model.past_day = Some(day);
+    let cmd = switch_feed(model, Feed::Past);
+    model.notice = Some(format!("Front page of {}, [ and ] go a day back or forth", day.format("%A %Y-%m-%d")));
+    cmd
+}
+
+/// List the stories posted between the two moments in `args`, the second one now if left out
+fn show_between(model: &mut Model, args: &str) -> Option<Cmd> {
+    let now = chrono::Local::now();
+    let (from, to) = match args.split_whitespace().collect::<Vec<_>>()[..] {
+        [from] => (from, "now"),
+        [from, to] => (from, to),
+        _ => return usage_notice(model, "between"),
+    };
+    let (Some(start), Some(end)) = (hint_search::parse_moment(from, &now), hint_search::parse_moment(to, &now)) else {
+        return usage_notice(model, "between");
+    };
+    let end = end.min(now.timestamp());
+    if start >= end {
+        model.notice = Some("The stretch has to start before it ends, and before now".to_string());
+        return None;
+    }
+    model.between = Some((start, end));
+    switch_feed(model, Feed::Between)
+}
+
+fn step_past_day(model: &mut Model, forward: bool) -> Option<Cmd> {
+    let day = model.past_day?;
+    let next = if forward { day.checked_add_days(Days::new(1)) } else { day.checked_sub_days(Days::new(1)) };
+    show_past_day(model, next?)
+}
+
+/// List the stories opened before that match `query`, all of them if it is empty
+fn show_history(model: &mut Model, query: &str) -> Option<Cmd> {
+    model.history_query = (!query.is_empty()).then(|| query.to_string());
+    let cmd = switch_feed(model, Feed::History);
+    if model.storylist.items.is_empty() {
+        model.notice = Some(match &model.history_query {
+            Some(query) => format!("Nothing opened before matches \"{}\"", query),
+            None => "Nothing opened yet, stories opened with o or read with p show up here".to_string(),
+        });
+    } else {
+        model.storylist.state.select(Some(0));
+    }
+    cmd
+}
+
+/// Remember the stories as opened just now, for the History feed
+fn record_visits(model: &mut Model, stories: &[HnStory]) {
+    let now = hint_snooze::now();
+    for story in stories {
+        model.history.record(story, now);
+    }
+}
+
+/// Show the results of the saved search after the current one, or of the first
+fn next_saved_search(model: &mut Model) -> Option<Cmd> {
+    if model.saved_searches.is_empty() {
+        model.notice = Some("No saved searches, add them to \"searches\" in config.json".to_string());
+        return None;
+    }
+    let next = match (model.feed, model.active_search) {
+        (Feed::Search, Some(i)) => (i + 1) % model.saved_searches.len(),
+        _ => 0,
+    };
+    model.active_search = Some(next);
+    switch_feed(model, Feed::Search)
+}
+
+/// Fill the list with the active search's results, marking them seen, or run it for the first time
+fn show_search_results(model: &mut Model) -> Option<Cmd> {
+    let search = model.active_search()?.clone();
+    let Some(stories) = model.search_results.get(&search.name).cloned() else {
+        return (!search.local).then_some(Cmd::RunSearch { name: search.name, query: search.query });
+    };
+    let selected = model.storylist.selected_item().map(DisplayListItem::id);
+    model.storylist.clear();
+    model.pending_selection = selected;
+    let ids: Vec<u64> = stories.iter().map(HnStory::id).collect();
+    for story in stories {
+        story_loaded(model, Feed::Search, story);
+    }
+    model.pending_selection = None;
+    model.seen_results.mark(&search.name, ids).then(|| Cmd::SaveSeenResults(model.seen_results.clone()))
+}
+
+/// Ask HN Search again for the results of every saved search that is not local
+fn run_saved_searches(model: &Model) -> Vec<Cmd> {
+    model
+        .saved_searches
+        .iter()
+        .filter(|search| !search.local)
+        .map(|search| Cmd::RunSearch { name: search.name.clone(), query: search.query.clone() })
+        .collect()
+}
+
+/// Add a story loaded for any feed to the results of the local saved searches it matches, and
+/// to the list when one of them is showing
+fn local_search_match(model: &mut Model, story: &HnStory) -> Option<Cmd> {
+    let mut cmd = None;
+    for (i, search) in model.saved_searches.iter().enumerate() {
+        if !search.local || !search.matches(story) {
+            continue;
+        }
+        let results = model.search_results.entry(search.name.clone()).or_default();
+        if results.iter().any(|result| result.id() == story.id()) {
+            continue;
+        }
+        results.push(story.clone());
+        if model.feed == Feed::Search && model.active_search == Some(i) {
+            model.seen_results.mark(&search.name, [story.id()]);
+            cmd = Some(Cmd::SaveSeenResults(model.seen_results.clone()));
+        }
+    }
+    if cmd.is_some() {
+        story_loaded(model, Feed::Search, story.clone());
+    }
+    cmd
+}
+
+/// Changes the status of the selected list item
+fn toggle_status(model: &mut Model) -> Option<Cmd> {
+    let i = model.storylist.state.selected()?;
+    // The spinner row below the stories is selectable but has no item behind it
+    let item = model.storylist.items.get_mut(i)?;
+    let previous = vec![(item.id(), item.status)];
+    item.status = match item.status {
+        Status::Read => Status::Unread,
+        Status::Unread => Status::Read,
+    };
+    let hooks = match item.status {
+        Status::Read => model.hooks.commands(HookEvent::Read, [&item.story]),
+        Status::Unread => Vec::new(),
+    };
+    push_undo(model, Undo::Status(previous));
+    model.show_details = !model.show_details;
+    model.details_full_screen &= model.show_details;
+    if !model.show_details {
+        model.focus = Pane::List;
+    }
+    with_hooks(batch(fetch_for_details(model)), hooks)
+}
+
+fn push_undo(model: &mut Model, undo: Undo) {
+    model.undo.push(undo);
+    if model.undo.len() > MAX_UNDO {
+        model.undo.remove(0);
+    }
+}
+
+fn undo(model: &mut Model) -> Option<Cmd> {
+    let Some(undo) = model.undo.pop() else {
+        model.notice = Some("Nothing to undo".to_string());
+        return None;
+    };
+    match undo {
+        Undo::Status(previous) => {
+            for (id, status) in previous {
+                if let Some(item) = model.storylist.item_mut(id) {
+                    item.status = status;
+                }
+            }
+            model.notice = Some("Undid status change".to_string());
+            None
+        }
+        Undo::Hide { feed, rows } => {
+            let ids: Vec<u64> = rows.iter().map(|(_, item)| item.id()).collect();
+            for id in &ids {
+                model.hidden.unhide(*id);
+            }
+            // Rows from another feed come back when that feed is loaded again
+            restore_rows(model, feed, rows);
+            model.notice = Some(match ids.len() {
+                1 => "Undid hide".to_string(),
+                n => format!("Undid hiding {} stories", n),
+            });
+            Some(hidden_changed(model, &ids, false))
+        }
+        Undo::Snooze { feed, rows } => {
+            for (_, item) in &rows {
+                model.snoozed.unsnooze(item.id());
+            }
+            restore_rows(model, feed, rows);
+            model.notice = Some("Undid snooze".to_string());
+            Some(Cmd::SaveSnoozed(model.snoozed.clone()))
+        }
+        Undo::Bookmarks(previous) => {
+            model.bookmarks = previous;
+            model.refresh_queue();
+            model.notice = Some("Undid bookmark change".to_string());
+            Some(Cmd::SaveBookmarks(model.bookmarks.clone()))
+        }
+    }
+}
@@ -0,0 +1,375 @@
+use crate::hint_highlight::fence;
+use crate::hint_html::{attribute, decode_entities, href};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// The Wayback Machine's "closest snapshot" lookup
+pub const WAYBACK_API: &str = "https://archive.org/wayback/available";
+
+/// Give up on slow sites rather than leave the reader waiting
+const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+/// Elements whose content is never article text
+const SKIPPED: [&str; 9] = ["script", "style", "noscript", "nav", "header", "footer", "aside", "svg", "form"];
+/// Elements that start a new line
+const BLOCKS: [&str; 16] = [
+    "p", "div", "br", "li", "h1", "h2", "h3", "h4", "h5", "h6", "tr", "blockquote", "pre", "section",
+    "figure", "table",
+];
+
+// Unlike `HnWeb`'s client this one follows redirects, as most article links redirect somewhere
+static HTTP: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(concat!("hint/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// Words read a minute, a usual pace for reading on a screen
+const WORDS_PER_MINUTE: usize = 230;
+/// Statuses after which the archived copy is worth trying: gone, blocked or unavailable for legal reasons
+const DEAD_STATUSES: [u16; 4] = [403, 404, 410, 451];
+/// Signs of a page that hides the article from non-subscribers, matched against the lowercased page
+const PAYWALL_MARKERS: [&str; 6] = [
+    "\"isaccessibleforfree\":false",
+    "\"isaccessibleforfree\": false",
+    "\"isaccessibleforfree\":\"false\"",
+    "class=\"paywall",
+    "subscribe to continue reading",
+    "to continue reading, subscribe",
+];
+
+/// Readable text of an article
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Article {
+    /// The page that was asked for, which relative links are resolved against
+    pub url: String,
+    /// Ends with the article's links as numbered footnotes, matching the `[n]` after each link
+    pub text: String,
+    pub links: Vec<String>,
+    /// The Wayback Machine copy the text came from, `None` for the live page
+    pub archived_from: Option<String>,
+}
+
+impl Article {
+    /// Minutes the article takes to read, at least one
+    pub fn reading_minutes(&self) -> u32 {
+        // Each link adds a `[n]` to the text, and a footnote of two words at its end
+        let words = self.text.split_whitespace().count().saturating_sub(3 * self.links.len());
+        words.div_ceil(WORDS_PER_MINUTE).max(1) as u32
+    }
+}
+
+#[derive(Debug)]
+pub enum ArticleError {
+    Http(reqwest::Error),
+    /// The site answered with an error status and there was no archived copy to fall back on
+    Status(u16),
+    /// Asked for the archived copy, but the Wayback Machine has none
+    NotArchived,
+}
+
+impl fmt::Display for ArticleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ArticleError::Http(err) => write!(f, "request failed: {}", err),
+            ArticleError::Status(status) => write!(f, "the site answered {} and there is no archived copy", status),
+            ArticleError::NotArchived => write!(f, "the Wayback Machine has no copy of it"),
+        }
+    }
+}
+
+impl std::error::Error for ArticleError {}
+
+impl From<reqwest::Error> for ArticleError {
+    fn from(err: reqwest::Error) -> Self {
+        ArticleError::Http(err)
+    }
+}
+
+/// Download a page and reduce it to its readable text.
+///
+/// Dead and paywalled pages fall back to the latest Wayback Machine snapshot, looked up with
+/// `wayback_api`; with `archived` the snapshot is used straight away.
+pub async fn fetch(url: &str, archived: bool, wayback_api: &str) -> Result<Article, ArticleError> {
+    if archived {
+        return fetch_archived(url, wayback_api).await?.ok_or(ArticleError::NotArchived);
+    }
+    let response = HTTP.get(url).send().await?;
+    let status = response.status();
+    if DEAD_STATUSES.contains(&status.as_u16()) {
+        return fetch_archived(url, wayback_api).await?.ok_or(ArticleError::Status(status.as_u16()));
+    }
+    let page = response.error_for_status()?.text().await?;
+    if looks_paywalled(&page) {
+        // A teaser is still better than nothing when the archive has no copy either
+        if let Ok(Some(article)) = fetch_archived(url, wayback_api).await {
+            return Ok(article);
+        }
+    }
+    Ok(extract_article(&page, url))
+}
+
+async fn fetch_archived(url: &str, wayback_api: &str) -> Result<Option<Article>, ArticleError> {
+    let Some(snapshot) = wayback_snapshot(url, wayback_api).await? else {
+        return Ok(None);
+    };
+    let page = HTTP.get(raw_snapshot_url(&snapshot)).send().await?.error_for_status()?.text().await?;
+    // Raw snapshots keep the page's own links, so they resolve against the original address
+    Ok(Some(Article { archived_from: Some(snapshot), ..extract_article(&page, url) }))
+}
+
+#[derive(Deserialize)]
+struct Availability {
+    archived_snapshots: Snapshots,
+}
+
+#[derive(Deserialize)]
+struct Snapshots {
+    closest: Option<Snapshot>,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    available: bool,
+    url: String,
+}
+
+/// URL of the Wayback Machine's most recent copy of `url`, if it has one
+pub async fn wayback_snapshot(url: &str, wayback_api: &str) -> Result<Option<String>, ArticleError> {
+    let availability: Availability =
+        HTTP.get(wayback_api).query(&[("url", url)]).send().await?.error_for_status()?.json().await?;
+    let closest = availability.archived_snapshots.closest;
+    Ok(closest.filter(|snapshot| snapshot.available).map(|snapshot| snapshot.url))
+}
+
+/// The snapshot without the Wayback toolbar: `/web/<timestamp>id_/` serves the page as archived
+pub fn raw_snapshot_url(snapshot: &str) -> String {
+    let Some(start) = snapshot.find("/web/").map(|i| i + "/web/".len()) else {
+        return snapshot.to_string();
+    };
+    let digits = snapshot[start..].find(|c: char| !c.is_ascii_digit()).map_or(snapshot.len(), |i| start + i);
+    if digits == start || snapshot[digits..].starts_with("id_") {
+        return snapshot.to_string();
+    }
+    format!("{}id_{}", &snapshot[..digits], &snapshot[digits..])
+}
+
+pub fn looks_paywalled(page: &str) -> bool {
+    let page = page.to_ascii_lowercase();
+    PAYWALL_MARKERS.iter().any(|marker| page.contains(marker))
+}
+
+/// The readable text of an HTML page: its title, then the `<article>` (or `<main>`, or `<body>`)
+/// with scripts, navigation and other page furniture left out
+pub fn extract_text(html: &str) -> String {
+    extract_article(html, "").text
+}
+
+/// The page at `url` as an article, its text followed by its numbered links
+pub fn extract_article(html: &str, url: &str) -> Article {
+    let lower = html.to_ascii_lowercase();
+    let title = element(html, &lower, "title").map(|title| collapse(&decode_entities(title)));
+    let content = ["article", "main", "body"]
+        .iter()
+        .find_map(|tag| element(html, &lower, tag))
+        .unwrap_or(html);
+
+    let mut text = String::new();
+    if let Some(title) = title.filter(|title| !title.is_empty()) {
+        text.push_str(&format!("{}\n{}\n\n", title, "=".repeat(title.chars().count())));
+    }
+    let (body, links) = blocks_to_text(content, url);
+    text.push_str(&body);
+    if !links.is_empty() {
+        text.push('\n');
+        for (n, link) in links.iter().enumerate() {
+            text.push_str(&format!("[{}] {}\n", n + 1, link));
+        }
+    }
+    Article { url: url.to_string(), text, links, archived_from: None }
+}
+
+/// `href` as an absolute address, for a link on the page at `base`. Anchors within the page and
+/// other schemes such as `mailto:` are not worth numbering, so they give `None`.
+pub fn resolve_link(base: &str, href: &str) -> Option<String> {
+    let href = href.trim();
+    if href.starts_with("http://") || href.starts_with("https://") {
+        return Some(href.to_string());
+    }
+    let (scheme, rest) = base.split_once("://")?;
+    if let Some(network_path) = href.strip_prefix("//") {
+        return Some(format!("{}://{}", scheme, network_path));
+    }
+    if href.is_empty() || href.starts_with('#') || href.contains(':') {
+        return None;
+    }
+    let origin_len = scheme.len() + 3 + rest.find('/').unwrap_or(rest.len());
+    let (origin, path) = base.split_at(origin_len);
+    if href.starts_with('/') {
+        return Some(format!("{}{}", origin, href));
+    }
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let directory = path.rfind('/').map_or("/", |slash| &path[..=slash]);
+    Some(format!("{}{}{}", origin, directory, href))
+}
+
+/// The inner HTML of the first `<tag>` element; `lower` is `html` lowercased, for matching
+fn element<'a>(html: &'a str, lower: &str, tag: &str) -> Option<&'a str> {
+    let open = find_tag(lower, tag, 0)?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = lower[start..].find(&format!("</{}", tag)).map_or(html.len(), |end| start + end);
+    Some(&html[start..end])
+}
+
+/// Position of the next `<tag` that is the whole tag name, so `<p` does not match `<pre>`
+fn find_tag(lower: &str, tag: &str, from: usize) -> Option<usize> {
+    let pattern = format!("<{}", tag);
+    let mut at = from;
+    while let Some(i) = lower[at..].find(&pattern) {
+        let start = at + i;
+        let next = lower[start + pattern.len()..].chars().next();
+        if next.is_none_or(|c| c == '>' || c == '/' || c.is_ascii_whitespace()) {
+            return Some(start);
+        }
+        at = start + pattern.len();
+    }
+    None
+}
+
+/// The text of `html` a paragraph per line, and the links in it resolved against `base`.
+/// Each link gets its number in brackets after its text, and each image a line of its own such as
+/// `[image: alt text][3]`, numbered as a link to it. `<pre>` blocks keep their lines, fenced
+/// with ```` ``` ```` and the language their class names, for the reader to colour.
+fn blocks_to_text(html: &str, base: &str) -> (String, Vec<String>) {
+    let lower = html.to_ascii_lowercase();
+    let mut out = String::new();
+    let mut links = Vec::new();
+    // Address of the link whose text is being read
+    let mut link: Option<String> = None;
+    let mut at = 0;
+    while let Some(i) = html[at..].find('<') {
+        out.push_str(&html[at..at + i]);
+        let start = at + i;
+        let Some(len) = html[start..].find('>') else {
+            at = html.len();
+            break;
+        };
+        let tag = &lower[start + 1..start + len];
+        let closing = tag.starts_with('/');
+        let name = tag.trim_start_matches('/').split(|c: char| c.is_ascii_whitespace() || c == '/').next();
+        let name = name.unwrap_or_default();
+        at = start + len + 1;
+        if !closing && SKIPPED.contains(&name) {
+            // Jump past the matching close tag, or drop the rest if there is none
+            at = lower[at..].find(&format!("</{}", name)).map_or(html.len(), |end| at + end);
+            continue;
+        }
+        if !closing && name == "pre" {
+            let end = lower[at..].find("</pre").map_or(html.len(), |end| at + end);
+            let code_tag = lower[at..end].trim_start().strip_prefix("<code").and_then(|rest| rest.split_once('>'));
+            let language = [Some(tag), code_tag.map(|(code_tag, _)| code_tag)].into_iter().flatten().find_map(class_language);
+            out.push_str(&format!("\n```{}\n{}\n```", language.unwrap_or_default(), pre_text(&html[at..end])));
+            at = end;
+            continue;
+        }
+        if name == "img" && !closing {
+            let tag = &html[start + 1..start + len];
+            let alt = attribute(tag, "alt").map(|alt| collapse(&alt)).filter(|alt| !alt.is_empty());
+            let mut placeholder = alt.map_or("[image]".to_string(), |alt| format!("[image: {}]", alt));
+            // Images inlined as `data:` have no address to number
+            if let Some(url) = attribute(tag, "src").and_then(|src| resolve_link(base, &src)) {
+                links.push(url);
+                placeholder.push_str(&format!("[{}]", links.len()));
+            }
+            out.push_str(&format!("\n{}\n", placeholder));
+            continue;
+        }
+        if name == "a" {
+            if closing {
+                if let Some(url) = link.take() {
+                    links.push(url);
+                    out.push_str(&format!("[{}]", links.len()));
+                }
+            } else {
+                link = href(&html[start + 1..start + len]).and_then(|href| resolve_link(base, &href));
+            }
+        }
+        if BLOCKS.contains(&name) {
+            out.push('\n');
+            if !closing && name == "li" {
+                out.push_str("- ");
+            }
+            if !closing && name.starts_with('h') && name != "h" {
+                out.push('\n');
+            }
+        }
+    }
+    out.push_str(&html[at..]);
+
+    // One paragraph per line, at most one blank line between them; code lines stay as they are
+    let mut text = String::new();
+    let mut blank = true;
+    let mut in_code = false;
+    for line in decode_entities(&out).lines() {
+        if fence(line).is_some() {
+            if !in_code && !blank {
+                text.push('\n');
+            }
+            text.push_str(line.trim());
+            text.push('\n');
+            (in_code, blank) = (!in_code, false);
+            continue;
+        }
+        if in_code {
+            text.push_str(line.trim_end());
+            text.push('\n');
+            continue;
+        }
+        let line = collapse(line);
+        if line.is_empty() {
+            if !blank {
+                text.push('\n');
+            }
+            blank = true;
+        } else {
+            text.push_str(&line);
+            text.push('\n');
+            blank = false;
+        }
+    }
+    (text, links)
+}
+
+/// The text of a `<pre>` block, its tags dropped but for `<br>`, which breaks the line
+fn pre_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('>') else {
+            rest = "";
+            break;
+        };
+        if rest[start + 1..start + len].trim().to_ascii_lowercase().starts_with("br") {
+            text.push('\n');
+        }
+        rest = &rest[start + len + 1..];
+    }
+    text.push_str(rest);
+    text.trim_matches('\n').to_string()
+}
+
+/// The language a tag's class names the way highlighters on the web do, `language-rust` or
+/// `lang-rust`; `tag` is lowercased
+fn class_language(tag: &str) -> Option<String> {
+    let class = tag.split("class=").nth(1)?;
+    let class = class.trim_start_matches(['"', '\'']).split(['"', '\'']).next()?;
+    class.split_whitespace().find_map(|name| name.strip_prefix("language-").or_else(|| name.strip_prefix("lang-")).map(str::to_string))
+}
+
+fn collapse(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
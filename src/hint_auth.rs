@@ -0,0 +1,353 @@
+use reqwest::header::{COOKIE, LOCATION, SET_COOKIE};
+use reqwest::redirect::Policy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const HN_WEB_URL: &str = "https://news.ycombinator.com/";
+const KEYRING_SERVICE: &str = "hint";
+const KEYRING_ENTRY: &str = "hn-session";
+/// Stop following "More" links on long favorites lists after this many pages
+const MAX_FAVORITES_PAGES: usize = 5;
+
+/// A logged-in news.ycombinator.com session, identified by HN's `user` cookie
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HnSession {
+    pub username: String,
+    cookie: String,
+}
+
+// The cookie is a credential, keep it out of logs
+impl fmt::Debug for HnSession {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("HnSession")
+            .field("username", &self.username)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HnSession {
+    pub fn new(username: String, cookie: String) -> Self {
+        Self { username, cookie }
+    }
+
+    /// Value for the `Cookie` header of authenticated requests
+    pub fn cookie_header(&self) -> String {
+        format!("user={}", self.cookie)
+    }
+}
+
+#[derive(Debug)]
+pub enum AuthError {
+    /// HN rejected the username/password
+    BadLogin,
+    /// The item page offered no link for the action, e.g. voting on your own story
+    Unavailable(&'static str),
+    /// HN answered a form post with a page instead of the usual redirect, e.g. "You're posting too fast"
+    Rejected(String),
+    Http(reqwest::Error),
+    Keyring(keyring::Error),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthError::BadLogin => write!(f, "bad login"),
+            AuthError::Unavailable(action) => write!(f, "HN does not allow {} here", action),
+            AuthError::Rejected(reason) => write!(f, "HN said: {}", reason),
+            AuthError::Http(err) => write!(f, "request failed: {}", err),
+            AuthError::Keyring(err) => write!(f, "keyring unavailable: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for AuthError {}
+
+impl From<reqwest::Error> for AuthError {
+    fn from(err: reqwest::Error) -> Self {
+        AuthError::Http(err)
+    }
+}
+
+impl From<keyring::Error> for AuthError {
+    fn from(err: keyring::Error) -> Self {
+        AuthError::Keyring(err)
+    }
+}
+
+/// Client for the parts of the HN website that need an account.
+///
+/// Unlike the Firebase API these are plain HTML form endpoints, so the base URL is injectable
+/// for tests in the same way as `HnClient`'s.
+#[derive(Debug, Clone)]
+pub struct HnWeb {
+    web_url: String,
+    http: reqwest::Client,
+}
+
+impl Default for HnWeb {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HnWeb {
+    pub fn new() -> Self {
+        Self::with_web_url(HN_WEB_URL)
+    }
+
+    pub fn with_web_url(web_url: &str) -> Self {
+        let mut web_url = web_url.to_string();
+        if !web_url.ends_with('/') {
+            web_url.push('/');
+        }
+        // HN answers form posts with redirects that carry the cookies we are after
+        let http = reqwest::Client::builder()
+            .redirect(Policy::none())
+            .build()
+            .expect("Failed to build HTTP client");
+        Self { web_url, http }
+    }
+
+    pub fn web_url(&self) -> &str {
+        &self.web_url
+    }
+
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// Log in with the HN login form, returning the session on success
+    pub async fn login(&self, username: &str, password: &str) -> Result<HnSession, AuthError> {
+        let response = self
+            .http
+            .post(format!("{}login", self.web_url))
+            .form(&[("acct", username), ("pw", password), ("goto", "news")])
+            .send()
+            .await?;
+
+        let cookie = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(user_cookie);
+        match cookie {
+            Some(cookie) => Ok(HnSession::new(username.to_string(), cookie)),
+            None => Err(AuthError::BadLogin),
+        }
+    }
+
+    /// GET a page as the logged-in user
+    pub async fn get_page(&self, session: &HnSession, path: &str) -> Result<String, AuthError> {
+        let response = self
+            .http
+            .get(format!("{}{}", self.web_url, path))
+            .header(COOKIE, session.cookie_header())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(response.text().await?)
+    }
+
+    /// Follow one of the per-item action links (`vote`, `fave`, `hide`, ...) on the item page.
+    ///
+    /// HN protects these with an `auth` token that is only found in the links themselves.
+    async fn item_action(
+        &self,
+        session: &HnSession,
+        id: u64,
+        action: &'static str,
+        params: &str,
+    ) -> Result<(), AuthError> {
+        let page = self.get_page(session, &format!("item?id={}", id)).await?;
+        let auth = auth_token(&page, action, id).ok_or(AuthError::Unavailable(action))?;
+        let mut url = format!("{}{}?id={}&auth={}", self.web_url, action, id, auth);
+        if !params.is_empty() {
+            url.push('&');
+            url.push_str(params);
+        }
+        self.http
+            .get(url)
+            .header(COOKIE, session.cookie_header())
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Upvote an item, or remove our vote with `up == false`
+    pub async fn vote(&self, session: &HnSession, id: u64, up: bool) -> Result<(), AuthError> {
+        let how = if up { "how=up" } else { "how=un" };
+        self.item_action(session, id, "vote", how).await
+    }
+
+    /// Add an item to the account's favorites, or remove it with `add == false`
+    pub async fn favorite(&self, session: &HnSession, id: u64, add: bool) -> Result<(), AuthError> {
+        let params = if add { "" } else { "un=t" };
+        self.item_action(session, id, "fave", params).await
+    }
+
+    /// Hide an item for the account, or bring it back with `hide == false`
+    pub async fn hide(&self, session: &HnSession, id: u64, hide: bool) -> Result<(), AuthError> {
+        let params = if hide { "" } else { "un=t" };
+        self.item_action(session, id, "hide", params).await
+    }
+
+    /// Post a comment as a reply to a story or comment
+    pub async fn reply(&self, session: &HnSession, parent: u64, text: &str) -> Result<(), AuthError> {
+        let page = self.get_page(session, &format!("reply?id={}", parent)).await?;
+        let hmac = hidden_input(&page, "hmac").ok_or(AuthError::Unavailable("reply"))?;
+        let parent = parent.to_string();
+        let goto = format!("item?id={}", parent);
+        let response = self
+            .http
+            .post(format!("{}comment", self.web_url))
+            .header(COOKIE, session.cookie_header())
+            .form(&[
+                ("parent", parent.as_str()),
+                ("goto", goto.as_str()),
+                ("hmac", hmac.as_str()),
+                ("text", text),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+        if response.status().is_redirection() {
+            return Ok(());
+        }
+        let page = response.text().await?;
+        Err(AuthError::Rejected(rejection_reason(&page)))
+    }
+
+    /// Submit a new story, returning the id HN gave it.
+    ///
+    /// Either `url` or `text` may be empty, as on the website's submit form.
+    pub async fn submit(
+        &self,
+        session: &HnSession,
+        title: &str,
+        url: &str,
+        text: &str,
+    ) -> Result<u64, AuthError> {
+        let page = self.get_page(session, "submit").await?;
+        let fnid = hidden_input(&page, "fnid").ok_or(AuthError::Unavailable("submit"))?;
+        let response = self
+            .http
+            .post(format!("{}r", self.web_url))
+            .header(COOKIE, session.cookie_header())
+            .form(&[
+                ("fnid", fnid.as_str()),
+                ("fnop", "submit-page"),
+                ("title", title),
+                ("url", url),
+                ("text", text),
+            ])
+            .send()
+            .await?
+            .error_for_status()?;
+        if !response.status().is_redirection() {
+            let page = response.text().await?;
+            return Err(AuthError::Rejected(rejection_reason(&page)));
+        }
+        // A repost of a recent URL goes to the existing item, anything new ends up on "newest"
+        let location = response.headers().get(LOCATION).and_then(|value| value.to_str().ok());
+        if let Some(id) = location.and_then(|location| location.strip_prefix("item?id=")) {
+            if let Ok(id) = id.parse() {
+                return Ok(id);
+            }
+        }
+        let submitted = self.get_page(session, &format!("submitted?id={}", session.username)).await?;
+        item_ids(&submitted)
+            .first()
+            .copied()
+            .ok_or(AuthError::Rejected("the new story did not show up under submitted".to_string()))
+    }
+
+    /// Ids of the account's favorite stories, most recently favorited first
+    pub async fn favorite_ids(&self, session: &HnSession) -> Result<Vec<u64>, AuthError> {
+        let mut ids = Vec::new();
+        for page_no in 1..=MAX_FAVORITES_PAGES {
+            let path = format!("favorites?id={}&p={}", session.username, page_no);
+            let page = self.get_page(session, &path).await?;
+            ids.extend(item_ids(&page));
+            if !page.contains(&format!("&amp;p={}", page_no + 1)) {
+                break;
+            }
+        }
+        Ok(ids)
+    }
+}
+
+/// Ids of the item rows (`<tr class="athing" id="...">`) of an HN listing page
+pub fn item_ids(page: &str) -> Vec<u64> {
+    page.match_indices("athing")
+        .filter_map(|(start, _)| {
+            let tag = &page[start..];
+            let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+            let id = tag.split("id=").nth(1)?.trim_start_matches(['\'', '"']);
+            let digits = id.find(|c: char| !c.is_ascii_digit()).unwrap_or(id.len());
+            id[..digits].parse().ok()
+        })
+        .collect()
+}
+
+/// Find the `auth` token of an action link for item `id` in an HN page
+pub fn auth_token(page: &str, action: &str, id: u64) -> Option<String> {
+    let link = format!("{}?id={}&amp;", action, id);
+    page.match_indices(&link).find_map(|(start, _)| {
+        let rest = &page[start..];
+        let end = rest.find(['\'', '"']).unwrap_or(rest.len());
+        let href = &rest[..end];
+        let token = href.split("&amp;").find_map(|param| param.strip_prefix("auth="))?;
+        (!token.is_empty()).then(|| token.to_string())
+    })
+}
+
+/// Value of a hidden form field such as `<input type="hidden" name="hmac" value="...">`
+pub fn hidden_input(page: &str, name: &str) -> Option<String> {
+    let attr = format!("name=\"{}\"", name);
+    page.match_indices(&attr).find_map(|(start, _)| {
+        let tag_start = page[..start].rfind('<')?;
+        let tag_end = start + page[start..].find('>')?;
+        let tag = &page[tag_start..tag_end];
+        let value = tag.split("value=\"").nth(1)?;
+        let value = &value[..value.find('"')?];
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
+/// The message of an HN error page, which is just a line of text in the body
+fn rejection_reason(page: &str) -> String {
+    let text = crate::hint_html::to_plain_text(page);
+    let reason = text.lines().map(str::trim).find(|line| !line.is_empty());
+    reason.unwrap_or("comment was not accepted").to_string()
+}
+
+/// Pull the value of HN's `user` cookie out of a `Set-Cookie` header
+fn user_cookie(header: &str) -> Option<String> {
+    let value = header.split(';').next()?.trim().strip_prefix("user=")?;
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn keyring_entry() -> Result<keyring::Entry, AuthError> {
+    Ok(keyring::Entry::new(KEYRING_SERVICE, KEYRING_ENTRY)?)
+}
+
+/// Keep the session in the OS keyring so it survives restarts without a plaintext file
+pub fn save_session(session: &HnSession) -> Result<(), AuthError> {
+    let secret = serde_json::to_string(session).expect("session serializes");
+    keyring_entry()?.set_password(&secret)?;
+    Ok(())
+}
+
+pub fn load_session() -> Option<HnSession> {
+    let secret = keyring_entry().ok()?.get_password().ok()?;
+    serde_json::from_str(&secret).ok()
+}
+
+pub fn forget_session() -> Result<(), AuthError> {
+    match keyring_entry()?.delete_credential() {
+        Err(keyring::Error::NoEntry) | Ok(()) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -0,0 +1,95 @@
+use crate::hint_hackernews::HnStory;
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const BOOKMARKS_FILE: &str = "bookmarks.json";
+
+/// Enough of a story to list it again without fetching it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedStory {
+    pub id: u64,
+    pub title: String,
+    pub author: String,
+    pub url: Option<String>,
+}
+
+impl SavedStory {
+    pub fn from_story(story: &HnStory) -> Self {
+        Self {
+            id: story.id(),
+            title: story.title().to_string(),
+            author: story.author().to_string(),
+            url: story.url().clone(),
+        }
+    }
+
+    pub fn to_story(&self) -> HnStory {
+        HnStory::new(
+            self.id.to_string(),
+            self.author.clone(),
+            self.title.clone(),
+            self.url.clone(),
+            "story".to_string(),
+        )
+    }
+}
+
+/// Stories saved for later with `b`, in reading order; they make up the Queue feed
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bookmarks {
+    stories: Vec<SavedStory>,
+}
+
+impl Bookmarks {
+    pub fn load(store: &Store) -> Self {
+        store.load(BOOKMARKS_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(BOOKMARKS_FILE, self)
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.stories.iter().any(|bookmark| bookmark.id == id)
+    }
+
+    pub fn add(&mut self, bookmark: SavedStory) {
+        if !self.contains(bookmark.id) {
+            self.stories.push(bookmark);
+        }
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<SavedStory> {
+        let i = self.stories.iter().position(|bookmark| bookmark.id == id)?;
+        Some(self.stories.remove(i))
+    }
+
+    /// Move a story one place towards the front of the list, or the back with `up == false`
+    pub fn shift(&mut self, id: u64, up: bool) -> bool {
+        let Some(i) = self.stories.iter().position(|bookmark| bookmark.id == id) else {
+            return false;
+        };
+        let j = if up { i.checked_sub(1) } else { Some(i + 1).filter(|j| *j < self.stories.len()) };
+        match j {
+            Some(j) => {
+                self.stories.swap(i, j);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SavedStory> {
+        self.stories.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stories.is_empty()
+    }
+}
@@ -0,0 +1,101 @@
+use crate::hint_auth::HN_WEB_URL;
+use crate::hint_hackernews::HnStory;
+use crate::hint_hooks;
+use std::collections::BTreeMap;
+use std::io;
+use std::process::{Command, Stdio};
+
+/// The link a story points to, or its HN discussion for Ask HN and other text posts
+pub fn story_url(story: &HnStory) -> String {
+    match story.url() {
+        Some(url) => url.clone(),
+        None => format!("{}item?id={}", HN_WEB_URL, story.id()),
+    }
+}
+
+/// The configured command for a URL, if any.
+///
+/// Keys are either a file extension such as `.pdf`, matched against the end of the path, or a
+/// domain such as `youtube.com`, which also covers its subdomains. Extensions win over domains,
+/// and the longest matching domain wins over shorter ones.
+pub fn opener<'a>(open_with: &'a BTreeMap<String, String>, url: &str) -> Option<&'a str> {
+    let (host, path) = split_url(url)?;
+    let path = path.to_ascii_lowercase();
+    let by_extension = open_with
+        .iter()
+        .find(|(key, _)| key.starts_with('.') && path.ends_with(&key.to_ascii_lowercase()));
+    let by_domain = || {
+        open_with
+            .iter()
+            .filter(|(key, _)| !key.starts_with('.'))
+            .filter(|(key, _)| {
+                let key = key.to_ascii_lowercase();
+                host == key || host.ends_with(&format!(".{}", key))
+            })
+            .max_by_key(|(key, _)| key.len())
+    };
+    by_extension.or_else(by_domain).map(|(_, command)| command.as_str())
+}
+
+/// An opener command for a story, with the URL added at the end when it has no `{url}`
+pub fn expand_opener(command: &str, story: &HnStory) -> String {
+    if command.contains("{url}") {
+        hint_hooks::expand(command, story)
+    } else {
+        hint_hooks::expand(&format!("{} {{url}}", command), story)
+    }
+}
+
+/// The site a URL is on, as HN shows it next to titles: `github.com` for `https://www.github.com/x`
+pub fn domain(url: &str) -> Option<String> {
+    let (host, _) = split_url(url)?;
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    (!host.is_empty()).then(|| host.to_string())
+}
+
+/// Whether `domain` is `site` or one of its subdomains
+pub fn on_site(domain: &str, site: &str) -> bool {
+    domain == site || domain.strip_suffix(site).is_some_and(|rest| rest.ends_with('.'))
+}
+
+/// Lowercased host and the path without query or fragment, for `http(s)` URLs
+fn split_url(url: &str) -> Option<(String, &str)> {
+    let rest = url.split_once("://")?.1;
+    let rest = &rest[..rest.find(['?', '#']).unwrap_or(rest.len())];
+    let (authority, path) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?;
+    Some((host.to_ascii_lowercase(), path))
+}
+
+/// Open a URL in the user's browser, `$BROWSER` if set, else the platform's opener.
+///
+/// The browser is started in the background with its output discarded, so it cannot draw over
+/// the TUI.
+pub fn open(url: &str) -> io::Result<()> {
+    let mut command = match std::env::var("BROWSER") {
+        Ok(browser) if !browser.trim().is_empty() => Command::new(browser.trim()),
+        _ => platform_opener(),
+    };
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    // Reap the opener once it exits so it does not linger as a zombie
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+fn platform_opener() -> Command {
+    if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else {
+        Command::new("xdg-open")
+    }
+}
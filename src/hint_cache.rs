@@ -0,0 +1,313 @@
+use crate::hint_article::Article;
+use crate::hint_bookmarks::SavedStory;
+use crate::hint_hackernews::{Feed, HnStory};
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const CACHE_FILE: &str = "story_cache.json";
+/// Stories kept, the ones evicted longest ago dropped first
+const MAX_CACHED: usize = 5000;
+const FRONT_PAGE_FILE: &str = "front_page.json";
+/// Rows of the feed kept for the next launch, a screenful or two
+const FRONT_PAGE_ROWS: usize = 60;
+/// Articles saved for reading offline, one file each
+const ARTICLES_DIR: &str = "articles";
+/// HN API responses as last fetched, laid out like `--record` writes them
+const API_DIR: &str = "api";
+
+/// Read stories evicted from a list that grew too long, kept on disk so they come back read if
+/// they show up again
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StoryCache {
+    /// Oldest eviction first
+    stories: Vec<SavedStory>,
+}
+
+impl StoryCache {
+    pub fn load(store: &Store) -> Self {
+        store.load(CACHE_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(CACHE_FILE, self)
+    }
+
+    pub fn add(&mut self, story: SavedStory) {
+        self.stories.retain(|cached| cached.id != story.id);
+        self.stories.push(story);
+        if self.stories.len() > MAX_CACHED {
+            self.stories.drain(..self.stories.len() - MAX_CACHED);
+        }
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.stories.iter().any(|story| story.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SavedStory> {
+        self.stories.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.stories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stories.is_empty()
+    }
+}
+
+/// The top of the feed showing at the last quit, shown at the next launch until it loads afresh
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrontPage {
+    pub feed: Feed,
+    /// Unix time the stories were fresh
+    pub saved_at: i64,
+    /// In the feed's order
+    pub stories: Vec<CachedStory>,
+}
+
+impl FrontPage {
+    pub fn new<'a>(feed: Feed, saved_at: i64, stories: impl IntoIterator<Item = &'a HnStory>) -> Self {
+        let stories = stories.into_iter().take(FRONT_PAGE_ROWS).map(CachedStory::from_story).collect();
+        Self { feed, saved_at, stories }
+    }
+
+    pub fn load(store: &Store) -> Self {
+        store.load(FRONT_PAGE_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(FRONT_PAGE_FILE, self)
+    }
+}
+
+/// A row of the front page, with the figures it showed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedStory {
+    #[serde(flatten)]
+    pub story: SavedStory,
+    pub score: Option<u32>,
+    pub time: Option<u64>,
+    pub comments: Option<u32>,
+    /// As `HnStory::kind` names it
+    pub kind: String,
+}
+
+impl CachedStory {
+    pub fn from_story(story: &HnStory) -> Self {
+        Self {
+            story: SavedStory::from_story(story),
+            score: story.score(),
+            time: story.time(),
+            comments: story.descendants(),
+            kind: story.kind().to_string(),
+        }
+    }
+
+    pub fn to_story(&self) -> HnStory {
+        let saved = &self.story;
+        let mut story = HnStory::new(saved.id.to_string(), saved.author.clone(), saved.title.clone(), saved.url.clone(), self.kind.clone());
+        if let Some(score) = self.score {
+            story.set_score(score);
+        }
+        if let Some(time) = self.time {
+            story.set_time(time);
+        }
+        if let Some(comments) = self.comments {
+            story.set_descendants(comments);
+        }
+        story
+    }
+}
+
+/// Where every HN API response is saved, for `--offline` to read back with `HnClient::replay`
+pub fn api_cache_dir(store: &Store) -> PathBuf {
+    store.dir().join(API_DIR)
+}
+
+/// The caches kept on disk that `:cache` reports on and clears
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiskCache {
+    /// HN API responses, for `--offline`
+    Items,
+    /// Articles saved for reading offline
+    Articles,
+}
+
+impl DiskCache {
+    pub const ALL: [DiskCache; 2] = [DiskCache::Items, DiskCache::Articles];
+    pub const NAMES: &'static [&'static str] = &["items", "articles"];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DiskCache::Items => "items",
+            DiskCache::Articles => "articles",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|cache| cache.name() == name)
+    }
+
+    pub fn dir(self, store: &Store) -> PathBuf {
+        match self {
+            DiskCache::Items => api_cache_dir(store),
+            DiskCache::Articles => store.dir().join(ARTICLES_DIR),
+        }
+    }
+
+    /// Files in the cache and the room they take
+    pub fn usage(self, store: &Store) -> CacheUsage {
+        cached_files(&self.dir(store)).iter().map(|file| CacheUsage { files: 1, bytes: file.bytes }).sum()
+    }
+
+    /// Delete everything in the cache, giving what was deleted
+    pub fn clear(self, store: &Store) -> io::Result<CacheUsage> {
+        let usage = self.usage(store);
+        match fs::remove_dir_all(self.dir(store)) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(usage),
+        }
+    }
+
+    /// Delete the files written longest ago until the cache takes at most `max_bytes`, giving
+    /// what was deleted
+    pub fn trim(self, store: &Store, max_bytes: u64) -> io::Result<CacheUsage> {
+        let mut files = cached_files(&self.dir(store));
+        let mut total: u64 = files.iter().map(|file| file.bytes).sum();
+        files.sort_by_key(|file| file.modified);
+        let mut deleted = CacheUsage::default();
+        for file in files {
+            if total <= max_bytes {
+                break;
+            }
+            fs::remove_file(&file.path)?;
+            total -= file.bytes;
+            deleted = deleted + CacheUsage { files: 1, bytes: file.bytes };
+        }
+        Ok(deleted)
+    }
+}
+
+impl fmt::Display for DiskCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How much a disk cache holds
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheUsage {
+    pub files: usize,
+    pub bytes: u64,
+}
+
+impl std::ops::Add for CacheUsage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self { files: self.files + other.files, bytes: self.bytes + other.bytes }
+    }
+}
+
+impl std::iter::Sum for CacheUsage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), |total, usage| total + usage)
+    }
+}
+
+impl fmt::Display for CacheUsage {
+    /// Such as `120 files, 1.5 MB`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let files = if self.files == 1 { "file" } else { "files" };
+        write!(f, "{} {}, {}", self.files, files, format_size(self.bytes))
+    }
+}
+
+/// Bytes in the largest unit that keeps a whole number in front, such as `980 KB` or `1.5 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 || size >= 10.0 {
+        format!("{:.0} {}", size, UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+struct CachedFile {
+    path: PathBuf,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// Every file under `dir`, however deep, none if it is missing
+fn cached_files(dir: &Path) -> Vec<CachedFile> {
+    let mut files = Vec::new();
+    let mut dirs = vec![dir.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                dirs.push(entry.path());
+            } else {
+                let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.push(CachedFile { path: entry.path(), bytes: meta.len(), modified });
+            }
+        }
+    }
+    files
+}
+
+/// Articles saved on disk for the reader to show without the network, see
+/// `Config::prefetch_articles`
+#[derive(Debug, Clone)]
+pub struct ArticleCache {
+    store: Store,
+}
+
+impl ArticleCache {
+    pub fn new(store: &Store) -> Self {
+        Self { store: store.clone() }
+    }
+
+    pub fn get(&self, url: &str) -> Option<Article> {
+        let path = self.store.dir().join(file_name(url));
+        let article: Article = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+        // Two addresses hashing alike is unlikely, but would show the wrong article
+        (article.url == url).then_some(article)
+    }
+
+    pub fn contains(&self, url: &str) -> bool {
+        self.get(url).is_some()
+    }
+
+    pub fn put(&self, article: &Article) -> io::Result<()> {
+        self.store.save(&file_name(&article.url), article)
+    }
+}
+
+/// Where the article of `url` goes: named by a hash that stays the same from one build to the
+/// next, which `std`'s hasher does not promise (64-bit FNV-1a)
+fn file_name(url: &str) -> String {
+    let hash = url.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    format!("{}/{:016x}.json", ARTICLES_DIR, hash)
+}
@@ -0,0 +1,71 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// The program that sets the system clipboard from its stdin, judged from the environment:
+/// `pbcopy` on macOS, `clip` on Windows, then Wayland's `wl-copy` and X11's `xclip`
+pub fn clipboard_command(var: impl Fn(&str) -> Option<String>) -> Option<&'static [&'static str]> {
+    if cfg!(target_os = "macos") {
+        return Some(&["pbcopy"]);
+    }
+    if cfg!(windows) {
+        return Some(&["clip"]);
+    }
+    if var("WAYLAND_DISPLAY").is_some() {
+        return Some(&["wl-copy"]);
+    }
+    if var("DISPLAY").is_some() {
+        return Some(&["xclip", "-selection", "clipboard"]);
+    }
+    None
+}
+
+/// Put `text` on the clipboard with the system's clipboard program, or else with the OSC 52
+/// escape, which most terminals honour even over SSH. Says which of the two it used.
+pub fn copy(text: &str) -> io::Result<&'static str> {
+    if let Some([program, args @ ..]) = clipboard_command(|name| std::env::var(name).ok()) {
+        if copy_with(program, args, text).is_ok() {
+            return Ok(program);
+        }
+    }
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52(text).as_bytes())?;
+    stdout.flush()?;
+    Ok("the terminal")
+}
+
+fn copy_with(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    match child.wait()? {
+        status if status.success() => Ok(()),
+        status => Err(io::Error::other(format!("{} exited with {}", program, status))),
+    }
+}
+
+/// The escape asking the terminal to put `text` on the clipboard
+pub fn osc52(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64(text.as_bytes()))
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, byte)| n | (*byte as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,123 @@
+use crate::hint_app::{Grouping, StorySort};
+use crate::hint_export::ExportFormat;
+use crate::hint_hackernews::{Feed, HnStoryType};
+use crate::hint_theme::Theme;
+
+/// Oldest entries are dropped from the command history beyond this
+pub const MAX_HISTORY: usize = 100;
+
+/// A command of the `:` prompt, as listed by completion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub usage: &'static str,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec { name: "between", usage: ":between <from> [to], each like 8h, 23:30 or 2024-01-31T23:30" },
+    CommandSpec { name: "cache", usage: ":cache <stats|clear [items|articles]>" },
+    CommandSpec { name: "clearhistory", usage: ":clearhistory" },
+    CommandSpec { name: "domain", usage: ":domain [only|never <site>]" },
+    CommandSpec { name: "export", usage: ":export <markdown|json|csv> [file]" },
+    CommandSpec { name: "favorites", usage: ":favorites" },
+    CommandSpec { name: "ignore", usage: ":ignore [username]" },
+    CommandSpec { name: "feed", usage: ":feed <top|new|best|ask|show|jobs|favorites|queue|history>" },
+    CommandSpec { name: "group", usage: ":group [domain|author]" },
+    CommandSpec { name: "history", usage: ":history [words or a day like tuesday]" },
+    CommandSpec { name: "limit", usage: ":limit [number]" },
+    CommandSpec { name: "login", usage: ":login [username]" },
+    CommandSpec { name: "logout", usage: ":logout" },
+    CommandSpec { name: "minscore", usage: ":minscore [points]" },
+    CommandSpec { name: "past", usage: ":past [yesterday|today|-days|YYYY-MM-DD]" },
+    CommandSpec { name: "queue", usage: ":queue" },
+    CommandSpec { name: "readall", usage: ":readall" },
+    CommandSpec { name: "search", usage: ":search [text]" },
+    CommandSpec { name: "sort", usage: ":sort [rank|new|score|comments]" },
+    CommandSpec { name: "submit", usage: ":submit" },
+    CommandSpec { name: "tabclose", usage: ":tabclose" },
+    CommandSpec { name: "tabnew", usage: ":tabnew" },
+    CommandSpec { name: "tabnext", usage: ":tabnext" },
+    CommandSpec { name: "tabprevious", usage: ":tabprevious" },
+    CommandSpec { name: "theme", usage: ":theme <name>" },
+    CommandSpec { name: "type", usage: ":type [story|ask|show|job|poll]" },
+    CommandSpec { name: "unhide", usage: ":unhide" },
+    CommandSpec { name: "unignore", usage: ":unignore <username>" },
+];
+
+pub fn usage(name: &str) -> Option<&'static str> {
+    COMMANDS.iter().find(|spec| spec.name == name).map(|spec| spec.usage)
+}
+
+/// Result of pressing Tab at the prompt
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// The line with the word under completion extended as far as it is unambiguous
+    pub line: String,
+    /// Everything the word could still become
+    pub candidates: Vec<&'static str>,
+}
+
+/// Values the argument of a command can take
+fn argument_candidates(command: &str) -> Vec<&'static str> {
+    match command {
+        "cache" => vec!["stats", "clear"],
+        "domain" => vec!["only", "never"],
+        "export" => ExportFormat::NAMES.to_vec(),
+        "feed" => Feed::NAMES.to_vec(),
+        "group" => Grouping::NAMES.to_vec(),
+        "past" => vec!["yesterday", "today"],
+        "sort" => StorySort::NAMES.to_vec(),
+        "theme" => Theme::names(),
+        "type" => HnStoryType::NAMES.to_vec(),
+        _ => Vec::new(),
+    }
+}
+
+/// Complete the command name, or the first argument once the name is typed
+pub fn complete(line: &str) -> Completion {
+    let (prefix, word, candidates) = match line.split_once(' ') {
+        None => {
+            let names = COMMANDS.iter().map(|spec| spec.name);
+            (String::new(), line, names.filter(|name| name.starts_with(line)).collect())
+        }
+        Some((command, arg)) if !arg.contains(' ') => {
+            let values = argument_candidates(command).into_iter();
+            (format!("{} ", command), arg, values.filter(|value| value.starts_with(arg)).collect())
+        }
+        Some(_) => (String::new(), line, Vec::new()),
+    };
+    let line = match candidates.as_slice() {
+        [] => line.to_string(),
+        // A finished command name is followed by its argument, so move on to it
+        [only] if prefix.is_empty() => format!("{} ", only),
+        [only] => format!("{}{}", prefix, only),
+        many => {
+            let common = common_prefix(many);
+            format!("{}{}", prefix, if common.len() > word.len() { common } else { word })
+        }
+    };
+    Completion { line, candidates }
+}
+
+fn common_prefix<'a>(words: &[&'a str]) -> &'a str {
+    let first = words[0];
+    let len = words[1..].iter().fold(first.len(), |len, word| {
+        first[..len]
+            .char_indices()
+            .zip(word.chars())
+            .find(|((_, a), b)| a != b)
+            .map_or(len.min(word.len()), |((i, _), _)| i)
+    });
+    &first[..len]
+}
+
+/// Remember a command line, skipping immediate repeats
+pub fn push_history(history: &mut Vec<String>, line: &str) {
+    if line.is_empty() || history.last().is_some_and(|last| last == line) {
+        return;
+    }
+    history.push(line.to_string());
+    if history.len() > MAX_HISTORY {
+        history.remove(0);
+    }
+}
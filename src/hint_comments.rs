@@ -0,0 +1,223 @@
+use crate::hnreader::{HnClient, HnError, Story};
+use std::collections::HashMap;
+use tokio::task::JoinSet;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// How many items of one tree level are fetched at the same time
+const FETCH_BATCH: usize = 32;
+/// Top-level comments fetched when a thread opens, and again each time the reader nears the end
+pub const TOP_LEVEL_BATCH: usize = 30;
+/// Tree levels fetched at once: the comments asked for and their direct replies
+const LEVELS_PER_FETCH: usize = 2;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    pub id: u64,
+    pub author: String,
+    /// HTML body as served by the API
+    pub text: String,
+    pub time: Option<u64>,
+    /// 0 for direct replies to the story
+    pub depth: usize,
+    pub dead: bool,
+    pub deleted: bool,
+    /// Upvoted by the logged-in account
+    pub voted: bool,
+    /// Replies not fetched yet, loaded once the comment is selected
+    pub unloaded: Vec<u64>,
+}
+
+/// A story with as much of its discussion as has been fetched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentThread {
+    pub story_id: u64,
+    pub title: String,
+    pub author: String,
+    pub url: Option<String>,
+    /// HTML body of Ask HN style posts
+    pub text: Option<String>,
+    /// Pre-order walk of the comment tree, so it renders as an indented flat list
+    pub comments: Vec<Comment>,
+    /// Top-level comments not fetched yet, in HN's order
+    pub unloaded: Vec<u64>,
+}
+
+impl Comment {
+    fn from_item(item: &Story, depth: usize) -> Self {
+        Self {
+            id: item.id,
+            author: item.by.clone().unwrap_or_else(|| String::from("[deleted]")),
+            text: item.text.clone().unwrap_or_default(),
+            time: item.time,
+            depth,
+            dead: item.dead,
+            deleted: item.deleted,
+            voted: false,
+            unloaded: Vec::new(),
+        }
+    }
+}
+
+impl CommentThread {
+    /// Assemble a thread from the story and any of its comments that could be fetched
+    pub fn from_items(story: &Story, items: &HashMap<u64, Story>) -> Self {
+        let mut comments = Vec::new();
+        flatten(&story.kids, 0, items, &mut comments);
+        Self {
+            story_id: story.id,
+            title: story.title.clone().unwrap_or_else(|| String::from("Untitled")),
+            author: story.by.clone().unwrap_or_else(|| String::from("Anonymous Author")),
+            url: story.url.clone(),
+            text: story.text.clone(),
+            comments,
+            unloaded: Vec::new(),
+        }
+    }
+
+    /// Put fetched replies under their `parent` comment, or after everything for more top-level
+    /// comments, and stop counting them as unloaded
+    pub fn insert_replies(&mut self, parent: Option<u64>, replies: Vec<Comment>) {
+        let at = match parent.and_then(|id| self.comments.iter().position(|comment| comment.id == id)) {
+            Some(i) => {
+                self.comments[i].unloaded.clear();
+                let depth = self.comments[i].depth;
+                let subtree = self.comments[i + 1..].iter().take_while(|comment| comment.depth > depth).count();
+                i + 1 + subtree
+            }
+            // The parent is gone, e.g. the thread was reloaded meanwhile
+            None if parent.is_some() => return,
+            None => self.comments.len(),
+        };
+        self.comments.splice(at..at, replies);
+    }
+}
+
+/// Walk the fetched part of the tree; replies that were not fetched are kept as `unloaded`
+fn flatten(ids: &[u64], depth: usize, items: &HashMap<u64, Story>, out: &mut Vec<Comment>) {
+    for id in ids {
+        if let Some(item) = items.get(id) {
+            let mut comment = Comment::from_item(item, depth);
+            comment.unloaded = item.kids.iter().copied().filter(|kid| !items.contains_key(kid)).collect();
+            out.push(comment);
+            flatten(&item.kids, depth + 1, items, out);
+        }
+    }
+}
+
+/// Fetch a story with its first `TOP_LEVEL_BATCH` comments and their direct replies.
+///
+/// Deeper replies and the remaining top-level comments are left for `fetch_replies`, so that
+/// huge threads open quickly. Comments that fail to load are left out rather than failing the
+/// whole thread.
+pub async fn fetch_thread(client: &HnClient, story_id: u64) -> Result<CommentThread, HnError> {
+    let mut story = client.fetch_story_details(story_id).await?;
+    let unloaded = story.kids.split_off(story.kids.len().min(TOP_LEVEL_BATCH));
+    let items = fetch_levels(client, &story.kids).await;
+    let mut thread = CommentThread::from_items(&story, &items);
+    thread.unloaded = unloaded;
+    Ok(thread)
+}
+
+/// Fetch the comments `ids`, which sit at `depth` in the tree, with their direct replies
+pub async fn fetch_replies(client: &HnClient, ids: &[u64], depth: usize) -> Vec<Comment> {
+    let items = fetch_levels(client, ids).await;
+    let mut comments = Vec::new();
+    flatten(ids, depth, &items, &mut comments);
+    comments
+}
+
+/// Fetch `LEVELS_PER_FETCH` levels of the tree below and including `ids`, one level at a time
+async fn fetch_levels(client: &HnClient, ids: &[u64]) -> HashMap<u64, Story> {
+    let mut items = HashMap::new();
+    let mut level = ids.to_vec();
+    for _ in 0..LEVELS_PER_FETCH {
+        if level.is_empty() {
+            break;
+        }
+        let mut next_level = Vec::new();
+        for batch in level.chunks(FETCH_BATCH) {
+            let mut fetches = JoinSet::new();
+            for id in batch.iter().copied() {
+                let client = client.clone();
+                fetches.spawn(async move { client.fetch_story_details(id).await });
+            }
+            while let Some(result) = fetches.join_next().await {
+                match result {
+                    Ok(Ok(item)) => {
+                        next_level.extend(item.kids.iter().copied());
+                        items.insert(item.id, item);
+                    }
+                    Ok(Err(err)) => log::warn!("Failed to fetch comment: {}", err),
+                    Err(err) => log::warn!("Comment fetch task failed: {}", err),
+                }
+            }
+        }
+        level = next_level;
+    }
+    items
+}
+
+/// Short "how long ago" for a Unix timestamp, like HN's "3 hours ago"
+pub fn format_age(time: u64, now: u64) -> String {
+    let secs = now.saturating_sub(time);
+    match secs {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86_399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86_400),
+    }
+}
+
+/// Greedy word wrap, keeping the text's own line breaks and splitting words longer than `width`.
+/// Widths are terminal columns, so wide characters such as CJK and emoji count double.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        let mut line_width = 0;
+        for word in paragraph.split(' ') {
+            let word_width = word.width();
+            if line_width > 0 && line_width + 1 + word_width > width {
+                lines.push(std::mem::take(&mut line));
+                line_width = 0;
+            }
+            if line_width > 0 {
+                line.push(' ');
+                line_width += 1;
+            }
+            for c in word.chars() {
+                let c_width = c.width().unwrap_or(0);
+                if line_width > 0 && line_width + c_width > width {
+                    lines.push(std::mem::take(&mut line));
+                    line_width = 0;
+                }
+                line.push(c);
+                line_width += c_width;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// `text` cut to at most `width` columns, ending in "…" where something was cut
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.width() <= width {
+        return text.to_string();
+    }
+    let mut cut = String::new();
+    let mut cut_width = 0;
+    for c in text.chars() {
+        let c_width = c.width().unwrap_or(0);
+        if cut_width + c_width + 1 > width {
+            break;
+        }
+        cut.push(c);
+        cut_width += c_width;
+    }
+    if width > 0 {
+        cut.push('…');
+    }
+    cut
+}
@@ -0,0 +1,131 @@
+use crate::hint_app::{DetailsLayout, FeedDefaults};
+use crate::hint_cache::DiskCache;
+use crate::hint_hackernews::{Feed, StoriesPerFeed};
+use crate::hint_hooks::Hooks;
+use crate::hint_ignored::IgnoreMode;
+use crate::hint_search::SavedSearch;
+use crate::hint_store::Store;
+use crate::hint_terminal::{image_protocol, supports_hyperlinks, ImageProtocol, TitleMode};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+const CONFIG_FILE: &str = "config.json";
+const DEFAULT_ANIMATION_MS: u64 = 250;
+/// Faster than a screen refreshes would only burn CPU
+const MIN_ANIMATION_MS: u64 = 16;
+const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 10;
+const DEFAULT_STARTUP_DEADLINE_SECS: u64 = 30;
+const DEFAULT_PREFETCH_CONCURRENCY: usize = 4;
+
+/// Settings written by hand in `config.json` next to the other data files; hint never saves it
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub hooks: Hooks,
+    /// Commands for opening links by domain or file extension, see `hint_browser::opener`
+    pub open_with: BTreeMap<String, String>,
+    /// Write titles and URLs as terminal hyperlinks; left out, hint guesses from the terminal
+    pub hyperlinks: Option<bool>,
+    /// Keep the terminal title on the selected story or the feed
+    pub title: TitleMode,
+    /// Put the details pane below the list or to its right
+    pub details: DetailsLayout,
+    /// Percent of the screen the list starts with next to the details pane, until `<` or `>`
+    pub details_split: Option<u16>,
+    /// Fold comments by users ignored with `I`, or leave them out entirely
+    pub ignored_comments: IgnoreMode,
+    /// Searches shown as tabs above the list, each with its count of unseen results
+    pub searches: Vec<SavedSearch>,
+    /// Milliseconds between frames of animations such as the spinner, 250 if left out
+    pub animation_ms: Option<u64>,
+    /// Most stories a feed keeps in memory, the oldest read ones going to the disk cache past it
+    pub max_stories: Option<usize>,
+    /// Stories listed per feed, such as `100` or `{"top": 100, "new": 30}`; all of them if left out
+    pub stories_per_feed: StoriesPerFeed,
+    /// Sort and filters by feed, such as `{"ask": {"min_score": 20, "sort": "new"}}`, put in
+    /// place each time the feed is switched to
+    pub feeds: HashMap<Feed, FeedDefaults>,
+    /// Give titles too long for a narrow terminal a second line instead of cutting them short
+    pub wrap_titles: bool,
+    /// Show how long the article takes to read next to the title, once it has been fetched
+    pub reading_time_column: bool,
+    /// Leave code blocks in comments and articles in one colour instead of colouring their syntax
+    pub plain_code: bool,
+    /// Show the images of articles in the reader, on terminals that can; otherwise, and without
+    /// this, the reader shows a placeholder with the image's alt text
+    pub inline_images: bool,
+    /// How the terminal shows images, `kitty`, `iterm` or `sixel`; left out, hint guesses from
+    /// the terminal
+    pub image_protocol: Option<ImageProtocol>,
+    /// Fetch the thread and article of the selected story in the background, so `c` and `p` show
+    /// them at once
+    pub prefetch: bool,
+    /// Save the articles of this many unread stories from the top of each feed loaded, for the
+    /// reader to show later without the network; none if left out
+    pub prefetch_articles: usize,
+    /// Articles downloaded at once for `prefetch_articles`, 4 if left out
+    pub prefetch_concurrency: Option<usize>,
+    /// Megabytes the HN API responses saved for `--offline` may take, the ones written longest
+    /// ago deleted past it; no limit if left out
+    pub item_cache_mb: Option<u64>,
+    /// Megabytes the articles saved for reading offline may take, likewise
+    pub article_cache_mb: Option<u64>,
+    /// Keys for actions by mode, such as `{"list": {"bookmark": ["B"]}}`, see `hint_keymap`
+    pub keys: BTreeMap<String, BTreeMap<String, Vec<String>>>,
+    /// Write the API request figures of the debug overlay to the log on exit
+    pub log_metrics: bool,
+    /// Seconds a single API request may take before it is retried, 10 if left out
+    pub request_timeout_secs: Option<u64>,
+    /// Seconds the first feed gets to load at startup before hint gives up on it, 30 if left out
+    pub startup_deadline_secs: Option<u64>,
+    /// Use the kitty keyboard protocol on terminals that have it; left out, it is used wherever
+    /// the terminal answers for it
+    pub kitty_keyboard: Option<bool>,
+}
+
+impl Config {
+    pub fn load(store: &Store) -> Self {
+        store.load(CONFIG_FILE)
+    }
+
+    /// Whether to write hyperlinks, asking the environment unless the config decides
+    pub fn hyperlinks(&self) -> bool {
+        self.hyperlinks.unwrap_or_else(|| supports_hyperlinks(|name| std::env::var(name).ok()))
+    }
+
+    /// How to show images in the reader, if `inline_images` asks for them and the terminal can
+    pub fn image_protocol(&self) -> Option<ImageProtocol> {
+        let protocol = || self.image_protocol.or_else(|| image_protocol(|name| std::env::var(name).ok()));
+        self.inline_images.then(protocol).flatten()
+    }
+
+    pub fn animation_interval(&self) -> Duration {
+        Duration::from_millis(self.animation_ms.unwrap_or(DEFAULT_ANIMATION_MS).max(MIN_ANIMATION_MS))
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_secs(self.request_timeout_secs.unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS).max(1))
+    }
+
+    pub fn kitty_keyboard(&self) -> bool {
+        self.kitty_keyboard.unwrap_or(true)
+    }
+
+    pub fn prefetch_concurrency(&self) -> usize {
+        self.prefetch_concurrency.unwrap_or(DEFAULT_PREFETCH_CONCURRENCY).max(1)
+    }
+
+    /// Bytes the disk cache may take, if the config limits it
+    pub fn cache_limit(&self, cache: DiskCache) -> Option<u64> {
+        let mb = match cache {
+            DiskCache::Items => self.item_cache_mb,
+            DiskCache::Articles => self.article_cache_mb,
+        };
+        mb.map(|mb| mb * 1024 * 1024)
+    }
+
+    pub fn startup_deadline(&self) -> Duration {
+        Duration::from_secs(self.startup_deadline_secs.unwrap_or(DEFAULT_STARTUP_DEADLINE_SECS).max(1))
+    }
+}
@@ -0,0 +1,74 @@
+use crate::hint_auth::HN_WEB_URL;
+use crate::hint_export::{ExportFormat, ExportedStory};
+use crate::hnreader::{HnClient, HnError, Story};
+use tokio::task::JoinSet;
+
+/// How many stories are fetched at the same time
+const FETCH_BATCH: usize = 32;
+
+/// What goes into a digest
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DigestOptions {
+    /// Only stories posted within this many seconds
+    pub since: i64,
+    pub min_score: u32,
+    /// Stories whose titles contain one of these words are kept whatever their score
+    pub watches: Vec<String>,
+    /// Longest digest, best stories first
+    pub limit: usize,
+}
+
+/// Fetch every story on the front page lists, skipping the ones that fail to load
+pub async fn fetch_candidates(client: &HnClient) -> Result<Vec<Story>, HnError> {
+    let ids = client.fetch_top_stories().await?;
+    let mut stories = Vec::with_capacity(ids.len());
+    for batch in ids.chunks(FETCH_BATCH) {
+        let mut fetches = JoinSet::new();
+        for id in batch.iter().copied() {
+            let client = client.clone();
+            fetches.spawn(async move { client.fetch_story_details(id).await });
+        }
+        while let Some(result) = fetches.join_next().await {
+            match result {
+                Ok(Ok(story)) => stories.push(story),
+                Ok(Err(err)) => log::warn!("Failed to fetch story: {}", err),
+                Err(err) => log::warn!("Story fetch task failed: {}", err),
+            }
+        }
+    }
+    Ok(stories)
+}
+
+/// Pick and rank the stories for a digest written at `now`
+pub fn select(stories: Vec<Story>, options: &DigestOptions, now: i64) -> Vec<Story> {
+    let watches: Vec<String> = options.watches.iter().map(|word| word.to_lowercase()).collect();
+    let watched = |story: &Story| {
+        let title = story.title.as_deref().unwrap_or_default().to_lowercase();
+        watches.iter().any(|word| title.contains(word.as_str()))
+    };
+    let mut picked: Vec<Story> = stories
+        .into_iter()
+        .filter(|story| !story.dead && !story.deleted && story.title.is_some())
+        .filter(|story| story.time.is_some_and(|time| time as i64 >= now - options.since))
+        .filter(|story| story.score.unwrap_or(0) >= options.min_score || watched(story))
+        .collect();
+    picked.sort_by_key(|story| std::cmp::Reverse(story.score.unwrap_or(0)));
+    picked.truncate(options.limit);
+    picked
+}
+
+/// Write the digest out; `period` is how it is titled, e.g. "24h"
+pub fn render(format: ExportFormat, period: &str, stories: &[Story]) -> String {
+    let stories: Vec<ExportedStory> = stories
+        .iter()
+        .map(|story| ExportedStory {
+            id: story.id,
+            title: story.title.clone().unwrap_or_default(),
+            url: story.url.clone().unwrap_or_else(|| format!("{}item?id={}", HN_WEB_URL, story.id)),
+            score: story.score,
+            author: story.by.clone().unwrap_or_else(|| String::from("Anonymous Author")),
+            read: false,
+        })
+        .collect();
+    format.render(&format!("HackerNews digest | last {}", period), &stories)
+}
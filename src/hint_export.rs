@@ -0,0 +1,184 @@
+use crate::hint_auth::HN_WEB_URL;
+use crate::hint_comments::CommentThread;
+use crate::hint_html::to_plain_text;
+use chrono::DateTime;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Spaces per nesting level of the comment list, what Markdown needs for a sub-list
+const THREAD_INDENT: usize = 2;
+
+/// File formats `:export` can write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+    Csv,
+}
+
+/// One story as it is written out
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedStory {
+    pub id: u64,
+    pub title: String,
+    /// The story's link, or its HN page for text posts
+    pub url: String,
+    pub score: Option<u32>,
+    pub author: String,
+    pub read: bool,
+}
+
+impl ExportFormat {
+    /// Names accepted by `from_name`, as typed at the command line
+    pub const NAMES: [&'static str; 3] = ["markdown", "json", "csv"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "markdown" | "md" => Some(ExportFormat::Markdown),
+            "json" => Some(ExportFormat::Json),
+            "csv" => Some(ExportFormat::Csv),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+
+    /// The file contents for `stories`; `heading` titles the Markdown document
+    pub fn render(self, heading: &str, stories: &[ExportedStory]) -> String {
+        match self {
+            ExportFormat::Markdown => markdown(heading, stories),
+            ExportFormat::Json => {
+                let mut json = serde_json::to_string_pretty(stories).expect("stories serialize");
+                json.push('\n');
+                json
+            }
+            ExportFormat::Csv => csv(stories),
+        }
+    }
+}
+
+/// A task list, so the read status survives as checked boxes
+fn markdown(heading: &str, stories: &[ExportedStory]) -> String {
+    let mut out = format!("# {}\n\n", heading);
+    for story in stories {
+        let check = if story.read { "x" } else { " " };
+        out.push_str(&format!("- [{}] [{}]({})", check, markdown_escape(&story.title), story.url));
+        match story.score {
+            Some(score) => out.push_str(&format!(" ({} points by {})\n", score, story.author)),
+            None => out.push_str(&format!(" (by {})\n", story.author)),
+        }
+    }
+    out
+}
+
+fn markdown_escape(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+fn csv(stories: &[ExportedStory]) -> String {
+    let mut out = String::from("id,title,url,score,author,read\n");
+    for story in stories {
+        let score = story.score.map(|score| score.to_string()).unwrap_or_default();
+        let fields = [
+            story.id.to_string(),
+            csv_field(&story.title),
+            csv_field(&story.url),
+            score,
+            csv_field(&story.author),
+            story.read.to_string(),
+        ];
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+/// Quote a field when it holds a separator, a quote or a line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Stories as `Field: value` blocks separated by blank lines, for piping to other programs
+pub fn plain_text(stories: &[ExportedStory]) -> String {
+    let blocks: Vec<String> = stories
+        .iter()
+        .map(|story| {
+            let mut block = format!("Title: {}\nURL: {}\nAuthor: {}\n", story.title, story.url, story.author);
+            if let Some(score) = story.score {
+                block.push_str(&format!("Score: {}\n", score));
+            }
+            block.push_str(&format!("Comments: {}item?id={}\n", HN_WEB_URL, story.id));
+            block
+        })
+        .collect();
+    blocks.join("\n")
+}
+
+/// A story and its comments as one Markdown document, replies nested as sub-lists
+pub fn thread_markdown(thread: &CommentThread) -> String {
+    let mut out = format!("# {}\n\n", markdown_escape(&thread.title));
+    let link = format!("{}item?id={}", HN_WEB_URL, thread.story_id);
+    if let Some(url) = &thread.url {
+        out.push_str(&format!("<{}>\n\n", url));
+    }
+    out.push_str(&format!("by {} | [discussion]({})\n\n", thread.author, link));
+    if let Some(text) = &thread.text {
+        out.push_str(to_plain_text(text).trim());
+        out.push_str("\n\n");
+    }
+    out.push_str("---\n\n");
+    for comment in &thread.comments {
+        let indent = " ".repeat(comment.depth * THREAD_INDENT);
+        let mut meta = format!("**{}**", comment.author);
+        if let Some(time) = comment.time.and_then(|time| DateTime::from_timestamp(time as i64, 0)) {
+            meta.push_str(&time.format(" · %Y-%m-%d %H:%M UTC").to_string());
+        }
+        out.push_str(&format!("{}- {}\n", indent, meta));
+        let body = if comment.deleted {
+            "[deleted]".to_string()
+        } else if comment.dead {
+            "[dead]".to_string()
+        } else {
+            to_plain_text(&comment.text).trim().to_string()
+        };
+        // Continuation lines line up with the text after "- ", which keeps them in the list item
+        for line in body.lines() {
+            match line.trim_end() {
+                "" => out.push('\n'),
+                line => out.push_str(&format!("{}  {}\n", indent, line)),
+            }
+        }
+    }
+    out
+}
+
+/// A comment or post as a Markdown blockquote, signed with its author and a permalink to item `id`
+pub fn quote_markdown(author: &str, html: &str, id: u64) -> String {
+    let mut out = String::new();
+    for line in to_plain_text(html).trim().lines() {
+        match line.trim_end() {
+            "" => out.push_str(">\n"),
+            line => out.push_str(&format!("> {}\n", line)),
+        }
+    }
+    out.push_str(&format!(">\n> — {}, <{}item?id={}>\n", author, HN_WEB_URL, id));
+    out
+}
+
+/// Resolve a leading `~/` to the home directory, as a shell would
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
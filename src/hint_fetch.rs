@@ -0,0 +1,83 @@
+use std::collections::{BTreeSet, HashSet};
+use std::future::Future;
+use std::ops::Range;
+use tokio::task::AbortHandle;
+
+/// Rows past the bottom of the screen fetched as eagerly as the ones on it
+pub const FETCH_AHEAD: usize = 10;
+
+/// Stories of a feed still to be fetched, by their rank in the feed.
+///
+/// `pop` hands out whatever is in the window on screen first, then what is below it, and what
+/// is above it last; moving the window re-prioritizes whatever is still pending.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FetchQueue {
+    ids: Vec<u64>,
+    pending: BTreeSet<usize>,
+    window: Range<usize>,
+}
+
+impl FetchQueue {
+    /// A queue for `ids` in feed order, of which the first `fetched` are already in
+    pub fn new(ids: Vec<u64>, fetched: usize) -> Self {
+        let pending = (fetched.min(ids.len())..ids.len()).collect();
+        Self { ids, pending, window: 0..0 }
+    }
+
+    /// Leave out the stories already in hand, which a refresh only puts in their new places
+    pub fn skip(&mut self, known: &HashSet<u64>) {
+        let ids = &self.ids;
+        self.pending.retain(|rank| !known.contains(&ids[*rank]));
+    }
+
+    /// The ranks on or near the screen
+    pub fn set_window(&mut self, window: Range<usize>) {
+        self.window = window;
+    }
+
+    /// Whether something on or near the screen is still missing
+    pub fn is_urgent(&self) -> bool {
+        self.pending.range(self.window.clone()).next().is_some()
+    }
+
+    /// Take the rank and id of the story to fetch next
+    pub fn pop(&mut self) -> Option<(usize, u64)> {
+        let start = self.window.start;
+        let below = self.pending.range(start..).next();
+        let rank = *below.or_else(|| self.pending.range(..start).next_back())?;
+        self.pending.remove(&rank);
+        Some((rank, self.ids[rank]))
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// The background task fetching the stories of the feed showing. Starting another one, for a new
+/// feed or a reload, aborts the one before along with whatever requests it has in flight.
+#[derive(Debug, Default)]
+pub struct FetchTask {
+    handle: Option<AbortHandle>,
+}
+
+impl FetchTask {
+    pub fn start(&mut self, task: impl Future<Output = ()> + Send + 'static) {
+        self.stop();
+        self.handle = Some(tokio::spawn(task).abort_handle());
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            handle.abort();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.handle.as_ref().is_some_and(|handle| !handle.is_finished())
+    }
+}
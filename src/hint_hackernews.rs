@@ -1,13 +1,15 @@
+use std::collections::HashMap;
 use std::fmt;
-use crate::hnreader;
-use tokio;
-use tokio::sync::mpsc;
+use crate::hnreader::{HnClient, HnError, Story};
+use serde::{Deserialize, Serialize};
 
-#[allow(dead_code)]
+/// What an item is, as HN types it; Ask HN and Show HN posts are stories to the API, see
+/// `HnStory::kind`
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum HnStoryType {
+pub enum HnStoryType {
     Story,
     Ask,
+    Show,
     Comment,
     Job,
     Poll,
@@ -15,34 +17,209 @@ enum HnStoryType {
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HnStory {
-    id: usize,
+    id: u64,
     author: String,
     title: String,
     url: Option<String>,
+    score: Option<u32>,
+    /// Unix time it was posted
+    time: Option<u64>,
+    /// Number of comments; HN leaves it out for most jobs, which take none
+    descendants: Option<u32>,
+    /// The body of Ask HN posts and job ads, as HTML
+    text: Option<String>,
     hntype: HnStoryType,
 }
 
+impl fmt::Display for HnStoryType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            HnStoryType::Story => "story",
+            HnStoryType::Ask => "ask",
+            HnStoryType::Show => "show",
+            HnStoryType::Comment => "comment",
+            HnStoryType::Job => "job",
+            HnStoryType::Poll => "poll",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 impl HnStoryType {
-    #[allow(dead_code)]
-    pub fn to_string(&self) -> String {
-        match self {
-            HnStoryType::Story => "story".to_string(),
-            HnStoryType::Ask => "ask".to_string(),
-            HnStoryType::Comment => "comment".to_string(),
-            HnStoryType::Job => "job".to_string(),
-            HnStoryType::Poll => "poll".to_string(),
+    /// Names accepted by `from_name` for the kinds of stories a list can be narrowed to
+    pub const NAMES: [&'static str; 5] = ["story", "ask", "show", "job", "poll"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "story" => Some(HnStoryType::Story),
+            "ask" => Some(HnStoryType::Ask),
+            "show" => Some(HnStoryType::Show),
+            "comment" => Some(HnStoryType::Comment),
+            "job" => Some(HnStoryType::Job),
+            "poll" => Some(HnStoryType::Poll),
+            _ => None,
         }
     }
 
-    #[allow(dead_code)]
     pub fn from_string(typev: String) -> Self {
-        match typev.as_str() {
-            "story" => HnStoryType::Story,
-            "ask" => HnStoryType::Ask,
-            "comment" => HnStoryType::Comment,
-            "job" => HnStoryType::Job,
-            "poll" => HnStoryType::Poll,
-            &_ => todo!(),
+        // Such as "pollopt", which no feed lists; shown like any story
+        Self::from_name(&typev).unwrap_or(HnStoryType::Story)
+    }
+}
+
+/// Stories `HnStoryList::from_ids` fetches before returning
+pub const INITIAL_STORIES: usize = 10;
+
+/// The story lists HN publishes
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Feed {
+    #[default]
+    Top,
+    New,
+    /// Highest-voted recent stories
+    Best,
+    Ask,
+    Show,
+    Jobs,
+    /// The logged-in account's favorites, scraped from the HN website rather than the API
+    Favorites,
+    /// Bookmarked stories in reading order, kept locally rather than fetched
+    Queue,
+    /// Stories opened before, most recent first, kept locally rather than fetched
+    History,
+    /// Results of the saved search picked with `s`, run by the app rather than fetched here
+    Search,
+    /// A past day's front page, picked with `:past` and fetched by the app from HN Search
+    Past,
+    /// Stories posted within a stretch of time, picked with `:between` and fetched by the app from
+    /// HN Search
+    Between,
+    /// The latest stories of an author picked from the details pane, fetched by the app from the
+    /// user API
+    Submissions,
+    /// Every other submission of the selected story's link, picked from the details pane and
+    /// fetched by the app from HN Search
+    Discussions,
+}
+
+impl Feed {
+    pub const ALL: [Feed; 6] = [Feed::Top, Feed::New, Feed::Best, Feed::Ask, Feed::Show, Feed::Jobs];
+    /// Names accepted by `from_name`, as typed at the command line
+    pub const NAMES: [&'static str; 9] = ["top", "new", "best", "ask", "show", "jobs", "favorites", "queue", "history"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "top" => Some(Feed::Top),
+            "new" => Some(Feed::New),
+            "best" => Some(Feed::Best),
+            "ask" => Some(Feed::Ask),
+            "show" => Some(Feed::Show),
+            "jobs" => Some(Feed::Jobs),
+            "favorites" => Some(Feed::Favorites),
+            "queue" => Some(Feed::Queue),
+            "history" => Some(Feed::History),
+            _ => None,
+        }
+    }
+
+    /// Cycle through `ALL`; lists outside of it (favorites) go back to the first feed
+    pub fn next(self) -> Self {
+        match Self::ALL.iter().position(|f| *f == self) {
+            Some(i) => Self::ALL[(i + 1) % Self::ALL.len()],
+            None => Self::ALL[0],
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match Self::ALL.iter().position(|f| *f == self) {
+            Some(i) => Self::ALL[(i + Self::ALL.len() - 1) % Self::ALL.len()],
+            None => Self::ALL[0],
+        }
+    }
+
+    /// The API path of the feed's list, for the feeds HN's API has
+    pub fn api_path(self) -> Option<&'static str> {
+        match self {
+            Feed::Top => Some("topstories.json"),
+            Feed::New => Some("newstories.json"),
+            Feed::Best => Some("beststories.json"),
+            Feed::Ask => Some("askstories.json"),
+            Feed::Show => Some("showstories.json"),
+            Feed::Jobs => Some("jobstories.json"),
+            _ => None,
+        }
+    }
+
+    pub async fn fetch_ids(self, client: &HnClient) -> Result<Vec<u64>, HnError> {
+        match self {
+            Feed::Top => client.fetch_top_stories().await,
+            Feed::New => client.fetch_new_stories().await,
+            Feed::Best => client.fetch_best_stories().await,
+            Feed::Ask => client.fetch_ask_stories().await,
+            Feed::Show => client.fetch_show_stories().await,
+            Feed::Jobs => client.fetch_job_stories().await,
+            // Needs an HN session, so the runtime scrapes these and uses `from_ids`
+            Feed::Favorites => Ok(Vec::new()),
+            // Filled by the app itself, from the bookmarks, the history or HN Search
+            Feed::Queue | Feed::History | Feed::Search | Feed::Past | Feed::Between | Feed::Submissions | Feed::Discussions => {
+                Ok(Vec::new())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Feed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Feed::Top => "Top",
+            Feed::New => "New",
+            Feed::Best => "Best",
+            Feed::Ask => "Ask",
+            Feed::Show => "Show",
+            Feed::Jobs => "Jobs",
+            Feed::Favorites => "Favorites",
+            Feed::Queue => "Queue",
+            Feed::History => "History",
+            Feed::Search => "Search",
+            Feed::Past => "Past",
+            Feed::Between => "Between",
+            Feed::Submissions => "Submissions",
+            Feed::Discussions => "Discussions",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// How many stories of each feed to list, `stories_per_feed` in the config: one number for
+/// every feed, or one per feed such as `{"top": 100, "new": 30}`. Feeds left out list all the
+/// API has, up to 500 for Top and New.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum StoriesPerFeed {
+    Every(usize),
+    ByFeed(HashMap<Feed, usize>),
+}
+
+impl Default for StoriesPerFeed {
+    fn default() -> Self {
+        StoriesPerFeed::ByFeed(HashMap::new())
+    }
+}
+
+impl StoriesPerFeed {
+    pub fn of(&self, feed: Feed) -> Option<usize> {
+        match self {
+            StoriesPerFeed::Every(count) => Some(*count),
+            StoriesPerFeed::ByFeed(counts) => counts.get(&feed).copied(),
+        }
+    }
+
+    /// Cut the ids of `feed` down to the stories it lists, giving back the rest
+    pub fn apply(&self, feed: Feed, ids: &mut Vec<u64>) -> Vec<u64> {
+        match self.of(feed) {
+            Some(count) => ids.split_off(count.max(1).min(ids.len())),
+            None => Vec::new(),
         }
     }
 }
@@ -55,10 +232,33 @@ impl HnStory {
             author,
             title,
             url,
+            score: None,
+            time: None,
+            descendants: None,
+            text: None,
             hntype: HnStoryType::from_string(typev),
         }
     }
 
+    /// The story an API item is
+    pub fn from_item(item: Story) -> Self {
+        Self {
+            id: item.id,
+            author: item.by.unwrap_or_else(|| String::from("Anonymous Author")),
+            title: item.title.unwrap_or_else(|| String::from("Untitled")),
+            url: item.url,
+            score: item.score,
+            time: item.time,
+            descendants: item.descendants,
+            text: item.text,
+            hntype: item.kind.map_or(HnStoryType::Story, HnStoryType::from_string),
+        }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
     pub fn author(&self) -> &str {
         &self.author
     }
@@ -72,13 +272,69 @@ impl HnStory {
         &self.url
     }
 
+    pub fn score(&self) -> Option<u32> {
+        self.score
+    }
+
+    pub fn set_score(&mut self, score: u32) {
+        self.score = Some(score);
+    }
+
+    pub fn time(&self) -> Option<u64> {
+        self.time
+    }
+
+    pub fn set_time(&mut self, time: u64) {
+        self.time = Some(time);
+    }
+
+    pub fn descendants(&self) -> Option<u32> {
+        self.descendants
+    }
+
+    pub fn set_descendants(&mut self, descendants: u32) {
+        self.descendants = Some(descendants);
+    }
+
+    pub fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    pub fn set_text(&mut self, text: String) {
+        self.text = Some(text);
+    }
+
+    pub fn is_job(&self) -> bool {
+        self.hntype == HnStoryType::Job
+    }
+
+    /// What the story is, from its type or, for Ask HN and Show HN posts, its title
+    pub fn kind(&self) -> HnStoryType {
+        match self.hntype {
+            HnStoryType::Story if self.title.starts_with("Ask HN") => HnStoryType::Ask,
+            HnStoryType::Story if self.title.starts_with("Show HN") => HnStoryType::Show,
+            kind => kind,
+        }
+    }
+
+    /// Whether there is a thread to open; a job without a comment count takes no comments
+    pub fn has_comments(&self) -> bool {
+        !self.is_job() || self.descendants.is_some()
+    }
+
     pub fn details(&self) -> String {
-        format!("URL : {:?} Author: {:?}", self.url(), self.author())
+        let mut details = format!("URL : {:?} Author: {:?}", self.url(), self.author());
+        if let Some(score) = self.score {
+            details.push_str(&format!(" Score: {}", score));
+        }
+        details
     }
 }
 
-#[derive(Clone, PartialEq, Eq)]
+#[derive(Clone)]
 pub struct HnStoryList {
+    client: HnClient,
+    feed: Feed,
     storyidlist: Vec<u64>,
     storylist: Vec<HnStory>,
     story_writer: usize,
@@ -106,58 +362,93 @@ impl<'a> Iterator for HnStoryListIter<'a> {
 }
 
 impl HnStoryList {
-    pub async fn new() -> Self {
-        match hnreader::fetch_top_stories().await {
-            Ok(story_ids) => {
-                let mut idx = 0;
-                let mut storydets = vec!();
-                for (i, sid) in story_ids.iter().enumerate() {
-                    if i > 10 {
-                        break;
-                    }
-                    let mut title = String::from("abc");
-                    let mut url = String::from("hcker");
-                    let mut author = String::from("anony");
-                    match hnreader::fetch_story_details(*sid).await {
-                        Ok(story) => {
-                            //println!("Story Details: {:?}", story);
-                            title = story.title.clone().unwrap_or_else(|| String::from("Untitled"));
-                            url = story.url.clone().unwrap_or_else(|| String::from("http://example.com"));
-                            author = story.by.clone().unwrap_or_else(|| String::from("Anonymous Author"));
-                        }
-                        Err(err) => eprintln!("Failed to fetch story details: {}", err),
-                    }
-                    //println!("\n");
-                    storydets.push(HnStory {
-                        id: i,
-                        author: String::from("Unknown"),
-                        title,
-                        url: Some(url),
-                        hntype: HnStoryType::Story,
-                    });
-                    idx += 1;
-                }
-                Self {
-                    storyidlist: story_ids.clone(),
-                    storylist: storydets,
-                    story_writer: idx,
-                    story_maxlen: story_ids.len(),
-                }
-            },
+    pub async fn new(client: HnClient, feed: Feed) -> Self {
+        match feed.fetch_ids(&client).await {
+            Ok(story_ids) => Self::from_ids(client, feed, story_ids).await,
             Err(err) => {
-                eprintln!("Failed to fetch top stories: {}", err);
+                eprintln!("Failed to fetch {} stories: {}", feed, err);
                 // Return a default value for `HnStoryList` in case of an error
                 Self {
+                    client,
+                    feed,
                     storyidlist: vec!(),  // Default empty list
                     storylist: vec!(),
                     story_writer: 0,
                     story_maxlen: 0,
                 }
-            },
+            }
+        }
+    }
+
+    /// The list for `story_ids` with none of their stories fetched yet, for `fetch_story` to get
+    pub fn unfetched(client: HnClient, feed: Feed, story_ids: Vec<u64>) -> Self {
+        Self {
+            client,
+            feed,
+            story_maxlen: story_ids.len(),
+            storyidlist: story_ids,
+            storylist: vec!(),
+            story_writer: 0,
+        }
+    }
+
+    /// Build the list for ids obtained elsewhere, e.g. scraped from the HN website. The first
+    /// `INITIAL_STORIES` are fetched here, the rest by `update_story_details` as they are needed.
+    pub async fn from_ids(client: HnClient, feed: Feed, story_ids: Vec<u64>) -> Self {
+        let mut idx = 0;
+        let mut storydets = vec!();
+        for sid in story_ids.iter().take(INITIAL_STORIES) {
+            let mut title = String::from("abc");
+            let mut url = None;
+            let mut author = String::from("anony");
+            let mut score = None;
+            let mut time = None;
+            let mut descendants = None;
+            let mut text = None;
+            let mut hntype = HnStoryType::Story;
+            match client.fetch_story_details(*sid).await {
+                Ok(story) => {
+                    //println!("Story Details: {:?}", story);
+                    title = story.title.clone().unwrap_or_else(|| String::from("Untitled"));
+                    url = story.url.clone();
+                    author = story.by.clone().unwrap_or_else(|| String::from("Anonymous Author"));
+                    score = story.score;
+                    time = story.time;
+                    descendants = story.descendants;
+                    text = story.text;
+                    hntype = story.kind.map_or(HnStoryType::Story, HnStoryType::from_string);
+                }
+                Err(err) => eprintln!("Failed to fetch story details: {}", err),
+            }
+            //println!("\n");
+            storydets.push(HnStory {
+                id: *sid,
+                author,
+                title,
+                url,
+                score,
+                time,
+                descendants,
+                text,
+                hntype,
+            });
+            idx += 1;
+        }
+        Self {
+            client,
+            feed,
+            storyidlist: story_ids.clone(),
+            storylist: storydets,
+            story_writer: idx,
+            story_maxlen: story_ids.len(),
         }
     }
 
-    pub fn iter(&self) -> HnStoryListIter {
+    pub fn feed(&self) -> Feed {
+        self.feed
+    }
+
+    pub fn iter(&self) -> HnStoryListIter<'_> {
         HnStoryListIter {
             index: 0,
             storylist: &self.storylist,
@@ -186,28 +477,7 @@ impl HnStoryList {
             return Err(String::from("No more stories to process"));
         }
 
-        let hnstoryid = self.storyidlist[self.story_writer];
-        //let mut title = String::from("Untitled");
-        //let mut url = String::from("http://example.com");
-        let (title, url);
-
-        match hnreader::fetch_story_details(hnstoryid).await {
-            Ok(story) => {
-                title = story.title.clone().unwrap_or_else(|| String::from("Untitled"));
-                url = story.url.clone().unwrap_or_else(|| String::from("http://example.com"));
-            }
-            Err(err) => {
-                return Err(format!("Failed to fetch story details: {}", err));
-            }
-        }
-
-        let hnstory = HnStory {
-            id: self.story_writer,
-            author: String::from("Unknown"),
-            title,
-            url: Some(url),
-            hntype: HnStoryType::Story,
-        };
+        let hnstory = self.fetch_story(self.storyidlist[self.story_writer]).await?;
 
         self.add_story_at_index(self.story_writer, hnstory.clone()).map_err(|e| {
             format!("Failed to add story at index {}: {}", self.story_writer, e)
@@ -217,52 +487,30 @@ impl HnStoryList {
         Ok(hnstory)
     }
 
-    // This method starts a separate thread and runs the `update_story_details` method within a tokio runtime
-    pub fn start_update_thread_with_callback(&mut self, tx: mpsc::Sender<HnStory>) {
-        // Clone the current story list for use in the thread
-        let mut story_list = self.clone();
-
-        // Start a new thread to handle the updates
-        std::thread::spawn(move || {
-            // Create a single Tokio runtime for asynchronous operations
-            let rt = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
-
-            let mut keep_running = true;
-
-            while keep_running {
-                // Perform the asynchronous update using the runtime
-                rt.block_on(async {
-                    let newstory = story_list.update_story_details().await;
-
-                    // Create a story from the updated details
-                    let story = HnStory {
-                        id: story_list.story_writer,
-                        author: String::from("Unknown"),
-                        title: newstory.unwrap().title,
-                        url: Some(String::from("http://updated-url.com")),
-                        hntype: HnStoryType::Story,
-                    };
-
-                    // Try to send the updated story to the main thread
-                    if let Err(err) = tx.send(story).await {
-                        eprintln!("Failed to send story: {}", err);
-                        keep_running = false; // Mark the loop to stop
-                    }
-                });
-
-                // Sleep for 5 seconds before the next update
-                if keep_running {
-                    std::thread::sleep(std::time::Duration::from_secs(1));
-                }
-            }
-        });
+    /// Fetch any story of the feed, whatever its place, see `hint_fetch::FetchQueue`
+    pub async fn fetch_story(&self, hnstoryid: u64) -> Result<HnStory, String> {
+        match self.client.fetch_story_details(hnstoryid).await {
+            Ok(story) => Ok(HnStory::from_item(story)),
+            Err(err) => Err(format!("Failed to fetch story details: {}", err)),
+        }
     }
 
+    /// Every id in the feed, in its order
+    pub fn ids(&self) -> &[u64] {
+        &self.storyidlist
+    }
+
+    /// How many stories from the front of the feed have been fetched
+    pub fn fetched(&self) -> usize {
+        self.story_writer
+    }
 }
 
 impl fmt::Debug for HnStoryList {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("HnStoryList")
+            .field("client", &self.client)
+            .field("feed", &self.feed)
             .field("storyidlist", &self.storyidlist)
             .field("storylist", &self.storylist)
             .field("story_writer", &self.story_writer)
@@ -0,0 +1,53 @@
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const HIDDEN_FILE: &str = "hidden.json";
+
+/// Stories the user never wants to see again, in the order they were hidden
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HiddenStories {
+    ids: Vec<u64>,
+}
+
+impl HiddenStories {
+    pub fn load(store: &Store) -> Self {
+        store.load(HIDDEN_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(HIDDEN_FILE, self)
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.ids.contains(&id)
+    }
+
+    pub fn hide(&mut self, id: u64) {
+        if !self.contains(id) {
+            self.ids.push(id);
+        }
+    }
+
+    pub fn unhide(&mut self, id: u64) {
+        self.ids.retain(|hidden| *hidden != id);
+    }
+
+    /// Take back the most recently hidden story
+    pub fn unhide_last(&mut self) -> Option<u64> {
+        self.ids.pop()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.ids.iter().copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
@@ -0,0 +1,94 @@
+use once_cell::sync::Lazy;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Theme, ThemeSet};
+use syntect::parsing::{SyntaxReference, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+/// The bundled syntect theme code is coloured with; both of hint's themes are dark
+const SYNTAX_THEME: &str = "base16-ocean.dark";
+/// Tokens of code that give its language away, by the name syntect finds the language under.
+/// Code is only guessed to be in a language when at least `MIN_CLUES` of its tokens turn up, so
+/// prose set in a code block stays as it is.
+const CLUES: [(&str, &[&str]); 10] = [
+    ("rs", &["fn ", "let ", "mut ", "impl ", "pub ", "&self", "println!", "::<", "-> "]),
+    ("py", &["def ", "import ", "self.", "elif ", "print(", "None", "__init__", "):\n"]),
+    ("go", &["func ", "package ", ":= ", "fmt.", "err != nil", "go "]),
+    ("js", &["function", "const ", "=> ", "console.", "var ", "===", "require(", "let "]),
+    ("c", &["#include", "int main", "printf(", "NULL", "void ", "->", "sizeof"]),
+    ("java", &["public class", "System.out", "private ", "public static", "new ", "@Override"]),
+    ("sh", &["$ ", "sudo ", "echo ", "export ", "apt ", "cd ", "fi\n", " | "]),
+    ("sql", &["SELECT ", "FROM ", "WHERE ", "INSERT ", "CREATE TABLE", "JOIN "]),
+    ("html", &["<div", "</", "<html", "class=\"", "<span", "<p>"]),
+    ("json", &["{\"", "\": ", "\":", "[{", "null", "true,"]),
+];
+const MIN_CLUES: usize = 2;
+
+static SYNTAXES: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+static THEME: Lazy<Theme> = Lazy::new(|| {
+    let mut themes = ThemeSet::load_defaults().themes;
+    themes.remove(SYNTAX_THEME).unwrap_or_default()
+});
+
+/// The language of a Markdown fence line such as ```` ```rust ````, empty if it names none;
+/// `None` if the line is no fence
+pub fn fence(line: &str) -> Option<&str> {
+    let language = line.trim().strip_prefix("```")?;
+    language.chars().all(|c| c.is_alphanumeric() || "+#-_.".contains(c)).then_some(language)
+}
+
+/// Lines of `code` coloured token by token over `base`. `language` is a name or file extension,
+/// such as from a fence or a `language-` class; without one, or if syntect does not know it, the
+/// language is guessed from the code. Code of no language found stays in `base`.
+pub fn highlight(code: &str, language: Option<&str>, base: Style) -> Vec<Line<'static>> {
+    let syntax = language.filter(|language| !language.is_empty()).and_then(|language| {
+        SYNTAXES.find_syntax_by_token(language).or_else(|| SYNTAXES.find_syntax_by_token(&language.to_lowercase()))
+    });
+    let Some(syntax) = syntax.or_else(|| detect(code)) else {
+        return code.lines().map(|line| Line::from(Span::styled(line.to_string(), base))).collect();
+    };
+    let mut highlighter = HighlightLines::new(syntax, &THEME);
+    LinesWithEndings::from(code)
+        .map(|line| {
+            let text = line.trim_end_matches(['\n', '\r']);
+            match highlighter.highlight_line(line, &SYNTAXES) {
+                Ok(ranges) => Line::from(
+                    ranges
+                        .into_iter()
+                        .map(|(style, run)| (style, run.trim_end_matches(['\n', '\r'])))
+                        .filter(|(_, run)| !run.is_empty())
+                        .map(|(style, run)| Span::styled(run.to_string(), token_style(base, style)))
+                        .collect::<Vec<_>>(),
+                ),
+                Err(_) => Line::from(Span::styled(text.to_string(), base)),
+            }
+        })
+        .collect()
+}
+
+/// The language `code` seems to be in: from its first line, such as a `#!` line, or else from
+/// the tokens of each language it has most of
+fn detect(code: &str) -> Option<&'static SyntaxReference> {
+    if let Some(syntax) = code.lines().next().and_then(|line| SYNTAXES.find_syntax_by_first_line(line)) {
+        return Some(syntax);
+    }
+    let (token, clues) = CLUES
+        .iter()
+        .map(|(token, clues)| (*token, clues.iter().filter(|clue| code.contains(*clue)).count()))
+        .max_by_key(|(_, clues)| *clues)?;
+    (clues >= MIN_CLUES).then(|| SYNTAXES.find_syntax_by_token(token)).flatten()
+}
+
+/// `base` in the colour and weight syntect gives a token, keeping the background of `base`
+fn token_style(base: Style, style: syntect::highlighting::Style) -> Style {
+    let colour = style.foreground;
+    let mut styled = base.fg(Color::Rgb(colour.r, colour.g, colour.b));
+    if style.font_style.contains(FontStyle::BOLD) {
+        styled = styled.add_modifier(Modifier::BOLD);
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        styled = styled.add_modifier(Modifier::ITALIC);
+    }
+    styled
+}
@@ -0,0 +1,98 @@
+use crate::hint_bookmarks::SavedStory;
+use crate::hint_hackernews::HnStory;
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io;
+
+const HISTORY_FILE: &str = "history.json";
+/// The oldest visits are forgotten beyond this many
+const MAX_VISITS: usize = 5000;
+
+/// A story opened in the browser or the reader, and when
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Visit {
+    #[serde(flatten)]
+    pub story: SavedStory,
+    /// Unix time of the visit
+    pub at: i64,
+}
+
+impl Visit {
+    /// The local day of the visit, spelled out so it can be searched for, e.g. "2024-03-05 Tuesday"
+    pub fn day(&self) -> String {
+        local_time(self.at, "%Y-%m-%d %A")
+    }
+
+    /// Whether each of the lowercase `words` is in the title, the URL, the author or the day
+    fn matches(&self, words: &[String]) -> bool {
+        let story = &self.story;
+        let haystack = format!("{} {} {} {}", story.title, story.url.as_deref().unwrap_or(""), story.author, self.day());
+        let haystack = haystack.to_lowercase();
+        words.iter().all(|word| haystack.contains(word.as_str()))
+    }
+}
+
+/// When a story was opened, short enough for the end of a list row, e.g. "Tue 5 Mar 14:05"
+pub fn visit_time(at: i64) -> String {
+    local_time(at, "%a %-d %b %H:%M")
+}
+
+/// `at` in the local time zone, formatted like chrono's `format`
+pub fn local_time(at: i64, format: &str) -> String {
+    chrono::DateTime::from_timestamp(at, 0)
+        .map(|time| time.with_timezone(&chrono::Local).format(format).to_string())
+        .unwrap_or_default()
+}
+
+/// Every story opened, oldest first, making up the History feed
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct History {
+    visits: Vec<Visit>,
+}
+
+impl History {
+    pub fn load(store: &Store) -> Self {
+        store.load(HISTORY_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(HISTORY_FILE, self)
+    }
+
+    pub fn record(&mut self, story: &HnStory, at: i64) {
+        self.visits.push(Visit { story: SavedStory::from_story(story), at });
+        if self.visits.len() > MAX_VISITS {
+            self.visits.drain(..self.visits.len() - MAX_VISITS);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.visits.clear();
+    }
+
+    /// The latest visit of each story matching every word of `query`, most recent first
+    pub fn search(&self, query: &str) -> Vec<&Visit> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut listed = HashSet::new();
+        self.visits
+            .iter()
+            .rev()
+            .filter(|visit| visit.matches(&words))
+            .filter(|visit| listed.insert(visit.story.id))
+            .collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Visit> {
+        self.visits.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.visits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visits.is_empty()
+    }
+}
@@ -0,0 +1,111 @@
+use crate::hint_browser::story_url;
+use crate::hint_hackernews::HnStory;
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+use std::process::{Command, ExitStatus, Stdio};
+
+/// Story actions a hook can be attached to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    Open,
+    Bookmark,
+    Read,
+    Hide,
+    Snooze,
+}
+
+/// Shell commands to run when a story action fires, set in the config file.
+///
+/// `{url}`, `{title}`, `{id}` and `{author}` are replaced by the story's values, quoted for the
+/// shell, e.g. `"on_open": "wget -P ~/articles {url}"`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub on_open: Option<String>,
+    pub on_bookmark: Option<String>,
+    pub on_read: Option<String>,
+    pub on_hide: Option<String>,
+    pub on_snooze: Option<String>,
+}
+
+impl Hooks {
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        let command = match event {
+            HookEvent::Open => &self.on_open,
+            HookEvent::Bookmark => &self.on_bookmark,
+            HookEvent::Read => &self.on_read,
+            HookEvent::Hide => &self.on_hide,
+            HookEvent::Snooze => &self.on_snooze,
+        };
+        command.as_deref().filter(|command| !command.trim().is_empty())
+    }
+
+    /// The commands to run for `event`, one per story
+    pub fn commands<'a>(&self, event: HookEvent, stories: impl IntoIterator<Item = &'a HnStory>) -> Vec<String> {
+        match self.command(event) {
+            Some(template) => stories.into_iter().map(|story| expand(template, story)).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Fill in a hook's placeholders for one story
+pub fn expand(template: &str, story: &HnStory) -> String {
+    template
+        .replace("{url}", &shell_quote(&story_url(story)))
+        .replace("{title}", &shell_quote(story.title()))
+        .replace("{id}", &story.id().to_string())
+        .replace("{author}", &shell_quote(story.author()))
+}
+
+/// Single-quote a value so titles with `;` or `$(...)` in them stay plain text
+pub fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', ""))
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+fn shell(command: &str) -> Command {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    shell
+}
+
+/// Start a shell command in the background with its output discarded, like the browser
+pub fn run(command: &str) -> io::Result<()> {
+    let mut child = shell(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// Run a shell command on the terminal and wait for it
+pub fn run_foreground(command: &str) -> io::Result<ExitStatus> {
+    shell(command).status()
+}
+
+/// Run a shell command on the terminal with `input` on its stdin and wait for it
+pub fn pipe(command: &str, input: &str) -> io::Result<ExitStatus> {
+    let mut child = shell(command).stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that stops reading early (`head`) closes the pipe, which is not an error here
+        match stdin.write_all(input.as_bytes()) {
+            Err(err) if err.kind() != io::ErrorKind::BrokenPipe => return Err(err),
+            _ => {}
+        }
+    }
+    child.wait()
+}
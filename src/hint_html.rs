@@ -0,0 +1,320 @@
+use crate::hint_highlight::{fence, highlight};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+
+/// Turn the small HTML subset HN uses in comments and posts into plain text.
+///
+/// Paragraphs become blank-line separated, tags are dropped and entities decoded.
+pub fn to_plain_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        text.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('>') else {
+            // Unterminated tag, keep the text as it is
+            text.push_str(&rest[start..]);
+            rest = "";
+            break;
+        };
+        let tag = rest[start + 1..start + end].trim().to_ascii_lowercase();
+        if tag == "p" || tag.starts_with("p ") {
+            text.push_str("\n\n");
+        }
+        rest = &rest[start + end + 1..];
+    }
+    text.push_str(rest);
+    decode_entities(&text)
+}
+
+/// A run of HN's HTML: a paragraph of styled text, or a `<pre>` or fenced block kept as it is,
+/// with the language its fence names
+enum Block {
+    Paragraph(Vec<(String, Style)>),
+    Code(String, Option<String>),
+}
+
+/// Turn the HTML of a comment or post into lines of `width` for the terminal.
+///
+/// Paragraphs are wrapped and separated by a blank line, `<i>` is set in italics and links are
+/// underlined and numbered, with their addresses listed as footnotes at the end. `<pre>` blocks
+/// keep their own line breaks in the `code` style and are never wrapped, so indentation survives.
+/// So do paragraphs fenced with ```` ``` ````, as people write Markdown on HN all the same. With
+/// `highlight` the code is coloured by its syntax.
+pub fn to_styled_text(html: &str, width: usize, text: Style, code: Style, highlight_code: bool) -> Text<'static> {
+    let (blocks, links) = parse_blocks(html, text, code);
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    for block in blocks {
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        match block {
+            Block::Paragraph(runs) => lines.extend(wrap_runs(&runs, width)),
+            Block::Code(code_text, language) if highlight_code => lines.extend(highlight(&code_text, language.as_deref(), code)),
+            Block::Code(code_text, _) => {
+                lines.extend(code_text.lines().map(|line| Line::from(Span::styled(line.to_string(), code))))
+            }
+        }
+    }
+    if !links.is_empty() {
+        lines.push(Line::default());
+        for (n, url) in links.iter().enumerate() {
+            let footnote = vec![(format!("[{}] {}", n + 1, url), text)];
+            lines.extend(wrap_runs(&footnote, width));
+        }
+    }
+    Text::from(lines)
+}
+
+/// The addresses of the links in a comment or post, in the order `to_styled_text` numbers them
+pub fn links(html: &str) -> Vec<String> {
+    parse_blocks(html, Style::new(), Style::new()).1
+}
+
+/// Split HTML into blocks, and collect the addresses of its links in order
+fn parse_blocks(html: &str, text: Style, code: Style) -> (Vec<Block>, Vec<String>) {
+    let mut blocks = Vec::new();
+    let mut links = Vec::new();
+    let mut paragraph: Vec<(String, Style)> = Vec::new();
+    // Text of the `<pre>` block being read
+    let mut pre: Option<String> = None;
+    let (mut italic, mut inline_code) = (0usize, 0usize);
+    let mut link: Option<String> = None;
+
+    let mut rest = html;
+    loop {
+        let (run, tag) = match rest.find('<') {
+            Some(start) => match rest[start..].find('>') {
+                Some(end) => (&rest[..start], Some(&rest[start + 1..start + end])),
+                // Unterminated tag, keep the text as it is
+                None => (rest, None),
+            },
+            None => (rest, None),
+        };
+        if !run.is_empty() {
+            let run = decode_entities(run);
+            match pre.as_mut() {
+                Some(pre) => pre.push_str(&run),
+                None => {
+                    let mut style = if inline_code > 0 { code } else { text };
+                    if italic > 0 {
+                        style = style.add_modifier(Modifier::ITALIC);
+                    }
+                    if link.is_some() {
+                        style = style.add_modifier(Modifier::UNDERLINED);
+                    }
+                    paragraph.push((run, style));
+                }
+            }
+        }
+        let Some(tag) = tag else {
+            break;
+        };
+        rest = &rest[run.len() + tag.len() + 2..];
+
+        let (closing, tag) = match tag.trim().strip_prefix('/') {
+            Some(name) => (true, name.trim()),
+            None => (false, tag.trim()),
+        };
+        let name = tag.split_whitespace().next().unwrap_or("").to_ascii_lowercase();
+        match (name.as_str(), closing) {
+            ("p", false) => end_paragraph(&mut paragraph, &mut blocks),
+            ("br", _) => paragraph.push(("\n".to_string(), text)),
+            ("pre", false) => {
+                end_paragraph(&mut paragraph, &mut blocks);
+                pre = Some(String::new());
+            }
+            ("pre", true) => {
+                if let Some(code_text) = pre.take() {
+                    blocks.push(Block::Code(code_text.trim_end_matches('\n').to_string(), None));
+                }
+            }
+            // Inside `<pre>` the whole block is code already
+            ("code", false) if pre.is_none() => inline_code += 1,
+            ("code", true) if pre.is_none() => inline_code = inline_code.saturating_sub(1),
+            ("i" | "em", false) => italic += 1,
+            ("i" | "em", true) => italic = italic.saturating_sub(1),
+            ("a", false) => link = Some(href(tag).unwrap_or_default()),
+            ("a", true) => {
+                if let Some(url) = link.take().filter(|url| !url.is_empty()) {
+                    links.push(url);
+                    paragraph.push((format!("[{}]", links.len()), text));
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(code_text) = pre {
+        blocks.push(Block::Code(code_text.trim_end_matches('\n').to_string(), None));
+    }
+    end_paragraph(&mut paragraph, &mut blocks);
+    (fenced_code(blocks), links)
+}
+
+/// Turn the paragraphs from a ```` ``` ```` line to the next into a code block. HN knows nothing
+/// of fences, so blank lines in the code split it into paragraphs, joined again here.
+fn fenced_code(blocks: Vec<Block>) -> Vec<Block> {
+    let mut out = Vec::new();
+    // The language and lines of the fenced block being read
+    let mut open: Option<(String, Vec<String>)> = None;
+    for block in blocks {
+        let Block::Paragraph(runs) = block else {
+            out.push(block);
+            continue;
+        };
+        let text: String = runs.iter().map(|(run, _)| run.as_str()).collect();
+        // Only the line breaks around a paragraph go, spaces at its start may be indentation
+        let mut lines = text.trim_end().trim_start_matches('\n').lines();
+        let (language, mut code) = match open.take() {
+            // A blank line between paragraphs of the block
+            Some((language, mut code)) => {
+                code.push(String::new());
+                (language, code)
+            }
+            None => match lines.next().and_then(fence) {
+                Some(language) => (language.to_string(), Vec::new()),
+                None => {
+                    out.push(Block::Paragraph(runs));
+                    continue;
+                }
+            },
+        };
+        let mut closed = false;
+        for line in lines.by_ref() {
+            if fence(line) == Some("") {
+                closed = true;
+                break;
+            }
+            code.push(line.to_string());
+        }
+        if closed {
+            out.push(Block::Code(code.join("\n"), Some(language).filter(|language| !language.is_empty())));
+            // Whatever follows the fence in the same paragraph is text again, though unstyled
+            let rest = lines.collect::<Vec<_>>().join("\n");
+            if let Some((_, style)) = runs.first().filter(|_| !rest.trim().is_empty()) {
+                out.push(Block::Paragraph(vec![(rest, *style)]));
+            }
+        } else {
+            open = Some((language, code));
+        }
+    }
+    // An unclosed fence runs to the end
+    if let Some((language, code)) = open {
+        out.push(Block::Code(code.join("\n"), Some(language).filter(|language| !language.is_empty())));
+    }
+    out
+}
+
+/// Close the paragraph being read, unless there was nothing in it
+fn end_paragraph(paragraph: &mut Vec<(String, Style)>, blocks: &mut Vec<Block>) {
+    if paragraph.iter().any(|(run, _)| !run.trim().is_empty()) {
+        blocks.push(Block::Paragraph(std::mem::take(paragraph)));
+    }
+    paragraph.clear();
+}
+
+/// The decoded `href` of an `<a ...>` tag, given what is between the brackets
+pub fn href(tag: &str) -> Option<String> {
+    attribute(tag, "href")
+}
+
+/// The decoded value of the attribute `name` of a tag, given what is between the brackets
+pub fn attribute(tag: &str, name: &str) -> Option<String> {
+    let start = tag.find(&format!("{}=", name))? + name.len() + 1;
+    let value = &tag[start..];
+    let value = match value.chars().next()? {
+        quote @ ('"' | '\'') => value[1..].split(quote).next()?,
+        _ => value.split_whitespace().next()?,
+    };
+    Some(decode_entities(value))
+}
+
+/// Greedy word wrap of styled runs, like `wrap_text`: line breaks in the text are kept and words
+/// longer than `width` are split. A word may change style part way, e.g. a link and its number.
+fn wrap_runs(runs: &[(String, Style)], width: usize) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    let chars: Vec<(char, Style)> =
+        runs.iter().flat_map(|(run, style)| run.chars().map(move |c| (c, *style))).collect();
+    let start = chars.iter().position(|(c, _)| !c.is_whitespace()).unwrap_or(chars.len());
+    let end = chars.iter().rposition(|(c, _)| !c.is_whitespace()).map_or(start, |end| end + 1);
+
+    let mut lines = Vec::new();
+    for paragraph in chars[start..end].split(|(c, _)| *c == '\n') {
+        let mut line: Vec<(char, Style)> = Vec::new();
+        for word in paragraph.split(|(c, _)| c.is_whitespace()).filter(|word| !word.is_empty()) {
+            let mut word = word.to_vec();
+            if !line.is_empty() && line.len() + 1 + word.len() > width {
+                lines.push(styled_line(std::mem::take(&mut line)));
+            }
+            // The space takes the style of what it follows, so underlines end with the link
+            if let Some((_, style)) = line.last().copied() {
+                line.push((' ', style));
+            }
+            while word.len() > width - line.len() {
+                let rest = word.split_off(width - line.len());
+                line.extend(word);
+                lines.push(styled_line(std::mem::take(&mut line)));
+                word = rest;
+            }
+            line.extend(word);
+        }
+        lines.push(styled_line(line));
+    }
+    lines
+}
+
+/// Join characters into as few spans as their styles allow
+fn styled_line(chars: Vec<(char, Style)>) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for (c, style) in chars {
+        match spans.last_mut().filter(|span| span.style == style) {
+            Some(span) => span.content.to_mut().push(c),
+            None => spans.push(Span::styled(c.to_string(), style)),
+        }
+    }
+    Line::from(spans)
+}
+
+/// Decode the named and numeric character references HN emits
+pub fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let number = name.strip_prefix('#')?;
+            let code = match number.strip_prefix(['x', 'X']) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => number.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
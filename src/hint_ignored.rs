@@ -0,0 +1,59 @@
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const IGNORED_FILE: &str = "ignored.json";
+
+/// Commenters whose comments the user does not want to read, in the order they were ignored
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IgnoredUsers {
+    users: Vec<String>,
+}
+
+impl IgnoredUsers {
+    pub fn load(store: &Store) -> Self {
+        store.load(IGNORED_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(IGNORED_FILE, self)
+    }
+
+    pub fn contains(&self, user: &str) -> bool {
+        self.users.iter().any(|ignored| ignored == user)
+    }
+
+    pub fn ignore(&mut self, user: &str) {
+        if !self.contains(user) {
+            self.users.push(user.to_string());
+        }
+    }
+
+    pub fn unignore(&mut self, user: &str) {
+        self.users.retain(|ignored| ignored != user);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &String> {
+        self.users.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+}
+
+/// What happens to comments by ignored users, set in the config file
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoreMode {
+    /// Fold them to their author line, replies included, until Space opens them
+    #[default]
+    Collapse,
+    /// Leave them out of the thread along with their replies
+    Hide,
+}
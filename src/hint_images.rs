@@ -0,0 +1,279 @@
+use crate::hint_terminal::ImageProtocol;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use image::imageops::FilterType;
+use image::{ImageFormat, RgbaImage};
+use once_cell::sync::Lazy;
+use ratatui::layout::Rect;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::time::Duration;
+
+/// Rows of the reader an image takes, under its placeholder
+pub const IMAGE_ROWS: usize = 12;
+/// Pixels of a terminal cell, across and down, when the terminal does not say
+pub const DEFAULT_CELL: (u16, u16) = (10, 20);
+/// Images bigger than this are left as their placeholder
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+/// Decoded images are scaled down to at most this many pixels a side, still more than a pane shows
+const MAX_SIDE: u32 = 800;
+/// Most bytes of image data in one of kitty's escape sequences
+const KITTY_CHUNK: usize = 4096;
+/// Levels of each of red, green and blue in the sixel palette, a colour cube
+const SIXEL_LEVELS: usize = 6;
+/// Clear every image kitty has placed, keeping their data to place again
+const KITTY_CLEAR: &str = "\x1b_Ga=d,d=a,q=2\x1b\\";
+/// Clear kitty's images and drop their data, on the way out
+pub const KITTY_FORGET: &str = "\x1b_Ga=d,d=A,q=2\x1b\\";
+
+// Images come from all sorts of hosts, which answer slowly as often as articles do
+static HTTP: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(20))
+        .user_agent(concat!("hint/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// An image of an article, decoded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    /// Red, green, blue and alpha of each pixel, row by row
+    pub rgba: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Http(reqwest::Error),
+    TooLarge,
+    Decode(image::ImageError),
+}
+
+impl fmt::Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ImageError::Http(err) => write!(f, "request failed: {}", err),
+            ImageError::TooLarge => write!(f, "larger than {} MB", MAX_IMAGE_BYTES / 1024 / 1024),
+            ImageError::Decode(err) => write!(f, "not an image hint can read: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ImageError {}
+
+impl From<reqwest::Error> for ImageError {
+    fn from(err: reqwest::Error) -> Self {
+        ImageError::Http(err)
+    }
+}
+
+impl From<image::ImageError> for ImageError {
+    fn from(err: image::ImageError) -> Self {
+        ImageError::Decode(err)
+    }
+}
+
+/// Download the image at `url`, for `decode` to read off the async threads
+pub async fn download(url: &str) -> Result<Vec<u8>, ImageError> {
+    let response = HTTP.get(url).send().await?.error_for_status()?;
+    if response.content_length().is_some_and(|length| length as usize > MAX_IMAGE_BYTES) {
+        return Err(ImageError::TooLarge);
+    }
+    let bytes = response.bytes().await?;
+    if bytes.len() > MAX_IMAGE_BYTES {
+        return Err(ImageError::TooLarge);
+    }
+    Ok(bytes.to_vec())
+}
+
+/// Read a PNG, JPEG, GIF or WebP image, scaled down to `MAX_SIDE` if bigger
+pub fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    let mut image = image::load_from_memory(bytes)?;
+    if image.width() > MAX_SIDE || image.height() > MAX_SIDE {
+        image = image.resize(MAX_SIDE, MAX_SIDE, FilterType::Triangle);
+    }
+    let rgba = image.to_rgba8();
+    Ok(Image { width: rgba.width(), height: rgba.height(), rgba: rgba.into_raw() })
+}
+
+impl Image {
+    /// The columns and rows the image takes, fitted into `columns` by `rows` cells of `cell`
+    /// pixels keeping its shape, and never scaled up
+    pub fn fit(&self, columns: u16, rows: u16, cell: (u16, u16)) -> (u16, u16) {
+        let (cell_width, cell_height) = (cell.0.max(1) as f64, cell.1.max(1) as f64);
+        let (width, height) = (self.width.max(1) as f64, self.height.max(1) as f64);
+        let scale = (columns as f64 * cell_width / width).min(rows as f64 * cell_height / height).min(1.0);
+        let fitted_columns = (width * scale / cell_width).ceil() as u16;
+        let fitted_rows = (height * scale / cell_height).ceil() as u16;
+        (fitted_columns.clamp(1, columns.max(1)), fitted_rows.clamp(1, rows.max(1)))
+    }
+
+    fn to_buffer(&self) -> Option<RgbaImage> {
+        RgbaImage::from_raw(self.width, self.height, self.rgba.clone())
+    }
+}
+
+/// Draws the images the reader leaves room for, after each frame. The terminal knows nothing of
+/// them, so they go around ratatui: written at their cells with the cursor put back after.
+#[derive(Debug)]
+pub struct ImageWriter {
+    protocol: ImageProtocol,
+    /// kitty keeps the images it was sent, by id, to place again without sending them again
+    sent: HashMap<String, u32>,
+}
+
+impl ImageWriter {
+    pub fn new(protocol: ImageProtocol) -> Self {
+        Self { protocol, sent: HashMap::new() }
+    }
+
+    /// Whether images drawn in the cells stay there until drawn over; kitty's float above them
+    pub fn in_cells(&self) -> bool {
+        self.protocol != ImageProtocol::Kitty
+    }
+
+    /// What to write for the `images` loaded to show in their `slots`, of cells `cell` pixels big
+    pub fn frame(&mut self, slots: &[(String, Rect)], images: &HashMap<String, Image>, cell: (u16, u16)) -> String {
+        let mut out = String::new();
+        if self.protocol == ImageProtocol::Kitty {
+            out.push_str(KITTY_CLEAR);
+        }
+        for (url, area) in slots {
+            let Some(image) = images.get(url) else {
+                continue;
+            };
+            let (columns, rows) = image.fit(area.width, area.height, cell);
+            let sequence = match self.protocol {
+                ImageProtocol::Kitty => {
+                    let next_id = self.sent.len() as u32 + 1;
+                    let mut sequence = String::new();
+                    let id = *self.sent.entry(url.clone()).or_insert_with(|| {
+                        sequence = kitty_transmit(next_id, image);
+                        next_id
+                    });
+                    sequence + &kitty_place(id, columns, rows)
+                }
+                ImageProtocol::Iterm => iterm(image, columns, rows),
+                ImageProtocol::Sixel => sixel(image, columns, rows, cell),
+            };
+            out.push_str(&format!("\x1b[{};{}H{}", area.y + 1, area.x + 1, sequence));
+        }
+        if out.is_empty() {
+            return out;
+        }
+        // Save and restore the cursor around it all, where ratatui left it
+        format!("\x1b7{}\x1b8", out)
+    }
+}
+
+/// Send kitty `image` to keep as `id`, without showing it yet
+pub fn kitty_transmit(id: u32, image: &Image) -> String {
+    let data = BASE64.encode(&image.rgba);
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            out.push_str(&format!("\x1b_Ga=t,f=32,s={},v={},i={},q=2,m={};{}\x1b\\", image.width, image.height, id, more, chunk));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+/// Show kitty's image `id` at the cursor, over `columns` by `rows` cells, leaving the cursor be
+pub fn kitty_place(id: u32, columns: u16, rows: u16) -> String {
+    format!("\x1b_Ga=p,i={},c={},r={},C=1,q=2\x1b\\", id, columns, rows)
+}
+
+/// `image` as an iTerm2 inline image at the cursor, over `columns` by `rows` cells
+pub fn iterm(image: &Image, columns: u16, rows: u16) -> String {
+    let mut png = Vec::new();
+    if let Some(buffer) = image.to_buffer() {
+        let _ = buffer.write_to(&mut Cursor::new(&mut png), ImageFormat::Png);
+    }
+    format!(
+        "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+        png.len(),
+        columns,
+        rows,
+        BASE64.encode(&png)
+    )
+}
+
+/// `image` as sixels at the cursor, scaled to fit `columns` by `rows` cells of `cell` pixels.
+/// Colours are rounded to a cube of `SIXEL_LEVELS` levels each, which is plenty for a glance.
+pub fn sixel(image: &Image, columns: u16, rows: u16, cell: (u16, u16)) -> String {
+    let Some(buffer) = image.to_buffer() else {
+        return String::new();
+    };
+    let scale = (columns as f64 * cell.0 as f64 / image.width.max(1) as f64)
+        .min(rows as f64 * cell.1 as f64 / image.height.max(1) as f64)
+        .min(1.0);
+    let width = ((image.width as f64 * scale) as u32).max(1);
+    let height = ((image.height as f64 * scale) as u32).max(1);
+    let buffer = image::imageops::resize(&buffer, width, height, FilterType::Triangle);
+
+    let mut out = format!("\x1bPq\"1;1;{};{}", width, height);
+    let top = SIXEL_LEVELS - 1;
+    for colour in 0..SIXEL_LEVELS.pow(3) {
+        let (r, g, b) = (colour / SIXEL_LEVELS / SIXEL_LEVELS, colour / SIXEL_LEVELS % SIXEL_LEVELS, colour % SIXEL_LEVELS);
+        out.push_str(&format!("#{};2;{};{};{}", colour, r * 100 / top, g * 100 / top, b * 100 / top));
+    }
+    let level = |value: u8| (value as usize * top + 127) / 255;
+    // The palette colour of each pixel, none where it is see-through
+    let colours: Vec<Option<usize>> = buffer
+        .pixels()
+        .map(|pixel| {
+            let [r, g, b, a] = pixel.0;
+            (a >= 128).then(|| (level(r) * SIXEL_LEVELS + level(g)) * SIXEL_LEVELS + level(b))
+        })
+        .collect();
+    let (width, height) = (width as usize, height as usize);
+    for band in (0..height).step_by(6) {
+        let rows = band..(band + 6).min(height);
+        let mut used: Vec<usize> = rows.clone().flat_map(|y| colours[y * width..(y + 1) * width].iter().flatten().copied()).collect();
+        used.sort_unstable();
+        used.dedup();
+        for colour in used {
+            out.push_str(&format!("#{}", colour));
+            let sixels = (0..width).map(|x| {
+                let bits = rows.clone().enumerate().filter(|(_, y)| colours[y * width + x] == Some(colour));
+                (63 + bits.fold(0u8, |bits, (dy, _)| bits | 1 << dy)) as char
+            });
+            push_runs(&mut out, sixels);
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+/// Sixels with runs of four or more written as `!<count><sixel>`
+fn push_runs(out: &mut String, sixels: impl Iterator<Item = char>) {
+    let mut run: Option<(char, usize)> = None;
+    let flush = |out: &mut String, (sixel, count): (char, usize)| match count {
+        1..=3 => out.extend(std::iter::repeat_n(sixel, count)),
+        _ => out.push_str(&format!("!{}{}", count, sixel)),
+    };
+    for sixel in sixels {
+        match run {
+            Some((same, count)) if same == sixel => run = Some((same, count + 1)),
+            _ => {
+                if let Some(run) = run {
+                    flush(out, run);
+                }
+                run = Some((sixel, 1));
+            }
+        }
+    }
+    if let Some(run) = run {
+        flush(out, run);
+    }
+}
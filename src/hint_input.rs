@@ -0,0 +1,261 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::fmt;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// A change to a `TextInput`, as the editing keys ask for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Edit {
+    Insert(char),
+    /// Text pasted in one go
+    InsertText(String),
+    Backspace,
+    Delete,
+    /// Ctrl-W and Alt-Backspace: the word before the cursor
+    DeleteWord,
+    /// Ctrl-U: the line up to the cursor
+    DeleteToStart,
+    /// Ctrl-K: the line from the cursor on
+    DeleteToEnd,
+    Left,
+    Right,
+    /// Ctrl-Left and Alt-B
+    WordLeft,
+    /// Ctrl-Right and Alt-F
+    WordRight,
+    /// Home and Ctrl-A: the start of the line
+    Home,
+    /// End and Ctrl-E: the end of the line
+    End,
+    /// The line above, in text of several lines
+    Up,
+    Down,
+}
+
+impl Edit {
+    /// What `key` does to the text, readline style. Enter, Esc, Tab and the like are left to the
+    /// prompt or box the input is in.
+    pub fn from_key(key: &KeyEvent) -> Option<Self> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        Some(match key.code {
+            KeyCode::Char('w') if ctrl => Edit::DeleteWord,
+            KeyCode::Char('u') if ctrl => Edit::DeleteToStart,
+            KeyCode::Char('k') if ctrl => Edit::DeleteToEnd,
+            KeyCode::Char('a') if ctrl => Edit::Home,
+            KeyCode::Char('e') if ctrl => Edit::End,
+            KeyCode::Char('b') if ctrl => Edit::Left,
+            KeyCode::Char('f') if ctrl => Edit::Right,
+            KeyCode::Char('h') if ctrl => Edit::Backspace,
+            KeyCode::Char('d') if ctrl => Edit::Delete,
+            KeyCode::Char('b') if alt => Edit::WordLeft,
+            KeyCode::Char('f') if alt => Edit::WordRight,
+            KeyCode::Char(_) if ctrl || alt => return None,
+            KeyCode::Char(c) => Edit::Insert(c),
+            KeyCode::Backspace if ctrl || alt => Edit::DeleteWord,
+            KeyCode::Backspace => Edit::Backspace,
+            KeyCode::Delete => Edit::Delete,
+            KeyCode::Left if ctrl || alt => Edit::WordLeft,
+            KeyCode::Right if ctrl || alt => Edit::WordRight,
+            KeyCode::Left => Edit::Left,
+            KeyCode::Right => Edit::Right,
+            KeyCode::Home => Edit::Home,
+            KeyCode::End => Edit::End,
+            KeyCode::Up => Edit::Up,
+            KeyCode::Down => Edit::Down,
+            _ => return None,
+        })
+    }
+}
+
+/// Text being typed, with a cursor that can move about in it. Backs the prompts in the footer
+/// and the reply box; the latter's text runs over several lines.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextInput {
+    text: String,
+    /// Byte offset into `text`, always on a character boundary
+    cursor: usize,
+    /// Place in the history while Up and Down step through it, and the text typed before
+    browsing: Option<(usize, String)>,
+}
+
+impl TextInput {
+    /// `text`, with the cursor at its end
+    pub fn new(text: &str) -> Self {
+        Self { text: text.to_string(), cursor: text.len(), browsing: None }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// The text before the cursor
+    pub fn before_cursor(&self) -> &str {
+        &self.text[..self.cursor]
+    }
+
+    /// Replace the text, with the cursor at its end
+    pub fn set(&mut self, text: &str) {
+        self.text = text.to_string();
+        self.cursor = self.text.len();
+    }
+
+    pub fn apply(&mut self, edit: Edit) {
+        match edit {
+            Edit::Insert(c) => {
+                self.text.insert(self.cursor, c);
+                self.cursor += c.len_utf8();
+            }
+            Edit::InsertText(text) => {
+                self.text.insert_str(self.cursor, &text);
+                self.cursor += text.len();
+            }
+            Edit::Backspace => {
+                let start = self.previous(self.cursor);
+                self.text.replace_range(start..self.cursor, "");
+                self.cursor = start;
+            }
+            Edit::Delete => {
+                let end = self.next(self.cursor);
+                self.text.replace_range(self.cursor..end, "");
+            }
+            Edit::DeleteWord => {
+                let start = self.word_start(self.cursor);
+                self.text.replace_range(start..self.cursor, "");
+                self.cursor = start;
+            }
+            Edit::DeleteToStart => {
+                let start = self.line_start(self.cursor);
+                self.text.replace_range(start..self.cursor, "");
+                self.cursor = start;
+            }
+            Edit::DeleteToEnd => {
+                let end = self.line_end(self.cursor);
+                self.text.replace_range(self.cursor..end, "");
+            }
+            Edit::Left => self.cursor = self.previous(self.cursor),
+            Edit::Right => self.cursor = self.next(self.cursor),
+            Edit::WordLeft => self.cursor = self.word_start(self.cursor),
+            Edit::WordRight => self.cursor = self.word_end(self.cursor),
+            Edit::Home => self.cursor = self.line_start(self.cursor),
+            Edit::End => self.cursor = self.line_end(self.cursor),
+            Edit::Up => {
+                let start = self.line_start(self.cursor);
+                if start > 0 {
+                    let column = self.text[start..self.cursor].chars().count();
+                    self.cursor = self.at_column(self.line_start(start - 1), column);
+                }
+            }
+            Edit::Down => {
+                let end = self.line_end(self.cursor);
+                if end < self.text.len() {
+                    let column = self.text[self.line_start(self.cursor)..self.cursor].chars().count();
+                    self.cursor = self.at_column(end + 1, column);
+                }
+            }
+        }
+    }
+
+    /// Step through `history`, oldest first, towards its start if `older`. Stepping past its end
+    /// brings back what was typed before.
+    pub fn browse(&mut self, history: &[String], older: bool) {
+        let index = match (self.browsing.as_ref().map(|(i, _)| *i), older) {
+            (None, true) => history.len().checked_sub(1),
+            (None, false) => return,
+            (Some(i), true) => Some(i.saturating_sub(1)),
+            (Some(i), false) if i + 1 < history.len() => Some(i + 1),
+            (Some(_), false) => None,
+        };
+        let draft = match self.browsing.take() {
+            Some((_, draft)) => draft,
+            None => self.text.clone(),
+        };
+        match index {
+            Some(i) => {
+                self.set(&history[i]);
+                self.browsing = Some((i, draft));
+            }
+            None => self.set(&draft),
+        }
+    }
+
+    /// Where the cursor goes once the text is wrapped with `hint_comments::wrap_text` at `width`:
+    /// the line and the column on it
+    pub fn wrapped_cursor(&self, lines: &[String]) -> (usize, usize) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let cursor = self.before_cursor().chars().count();
+        // Each line is a run of the text's characters; the space or line break a line was broken
+        // at is not on either line
+        let mut start = 0;
+        for (row, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            if cursor <= start + len {
+                let column: usize = chars[start..cursor].iter().map(|c| c.width().unwrap_or(0)).sum();
+                return (row, column);
+            }
+            start += len;
+            if matches!(chars.get(start), Some(' ' | '\n')) {
+                start += 1;
+            }
+        }
+        let last = lines.len().saturating_sub(1);
+        (last, lines.last().map_or(0, |line| line.width()))
+    }
+
+    fn previous(&self, at: usize) -> usize {
+        self.text[..at].char_indices().next_back().map_or(0, |(i, _)| i)
+    }
+
+    fn next(&self, at: usize) -> usize {
+        self.text[at..].chars().next().map_or(at, |c| at + c.len_utf8())
+    }
+
+    /// Start of the word before `at`, skipping the spaces in between
+    fn word_start(&self, at: usize) -> usize {
+        let before = self.text[..at].trim_end_matches(|c: char| c.is_whitespace());
+        before.rfind(|c: char| c.is_whitespace()).map_or(0, |i| i + 1)
+    }
+
+    /// End of the word after `at`, skipping the spaces in between
+    fn word_end(&self, at: usize) -> usize {
+        let after = &self.text[at..];
+        let word = after.len() - after.trim_start_matches(|c: char| c.is_whitespace()).len();
+        let rest = &after[word..];
+        at + word + rest.find(|c: char| c.is_whitespace()).unwrap_or(rest.len())
+    }
+
+    fn line_start(&self, at: usize) -> usize {
+        self.text[..at].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    fn line_end(&self, at: usize) -> usize {
+        self.text[at..].find('\n').map_or(self.text.len(), |i| at + i)
+    }
+
+    /// The place `column` characters into the line starting at `start`, or its end if shorter
+    fn at_column(&self, start: usize, column: usize) -> usize {
+        let end = self.line_end(start);
+        self.text[start..end].char_indices().nth(column).map_or(end, |(i, _)| start + i)
+    }
+}
+
+impl fmt::Display for TextInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl PartialEq<str> for TextInput {
+    fn eq(&self, other: &str) -> bool {
+        self.text == other
+    }
+}
+
+impl PartialEq<&str> for TextInput {
+    fn eq(&self, other: &&str) -> bool {
+        self.text == *other
+    }
+}
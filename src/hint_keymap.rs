@@ -0,0 +1,297 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// The part of the screen a key goes to, each with bindings of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Mode {
+    List,
+    /// The details pane, the reader, while it has the focus
+    Details,
+    Comments,
+}
+
+impl Mode {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "list" => Some(Mode::List),
+            "details" => Some(Mode::Details),
+            "comments" => Some(Mode::Comments),
+            _ => None,
+        }
+    }
+}
+
+/// Something a key can do; what exactly can depend on the mode, `down` moves the selection in the
+/// list but scrolls the details pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Action {
+    Quit,
+    Back,
+    Unselect,
+    Down,
+    Up,
+    Top,
+    Bottom,
+    PageDown,
+    PageUp,
+    ToggleStatus,
+    NextFeed,
+    PreviousFeed,
+    CommandLine,
+    Vote,
+    Favorite,
+    Comments,
+    Hide,
+    FindNext,
+    FindPrevious,
+    Find,
+    NextSavedSearch,
+    History,
+    Visual,
+    MarkRead,
+    Bookmark,
+    Open,
+    QuickOpen,
+    FollowLink,
+    Undo,
+    Snooze,
+    Pipe,
+    Pager,
+    Editor,
+    Reader,
+    Archived,
+    OnlyDomain,
+    NeverDomain,
+    OnlyKind,
+    OnlyAuthor,
+    Submissions,
+    Discussions,
+    FullScreen,
+    Density,
+    Metrics,
+    Refresh,
+    SwitchFocus,
+    ShrinkList,
+    GrowList,
+    Fold,
+    NextTopLevel,
+    PreviousTopLevel,
+    ToggleDead,
+    ToggleIgnored,
+    Yank,
+    Reply,
+}
+
+/// Every action of each mode, with its name in the config file and its keys unless the config
+/// says otherwise. Keys that only do something in some state, such as `J` and `K` in the Queue, and
+/// the number keys, are not remappable and not listed.
+const DEFAULTS: &[(Mode, Action, &str, &[&str])] = &[
+    (Mode::List, Action::Quit, "quit", &["q", "Esc"]),
+    (Mode::List, Action::Unselect, "unselect", &["Left", "h"]),
+    (Mode::List, Action::Down, "down", &["Down", "j"]),
+    (Mode::List, Action::Up, "up", &["Up", "k"]),
+    (Mode::List, Action::Top, "top", &["g", "Home"]),
+    (Mode::List, Action::Bottom, "bottom", &["G", "End"]),
+    (Mode::List, Action::ToggleStatus, "toggle_status", &["Right", "l", "Enter"]),
+    (Mode::List, Action::NextFeed, "next_feed", &["]"]),
+    (Mode::List, Action::PreviousFeed, "previous_feed", &["["]),
+    (Mode::List, Action::CommandLine, "command_line", &[":"]),
+    (Mode::List, Action::Vote, "vote", &["v"]),
+    (Mode::List, Action::Favorite, "favorite", &["F"]),
+    (Mode::List, Action::Comments, "comments", &["c"]),
+    (Mode::List, Action::Hide, "hide", &["x"]),
+    (Mode::List, Action::FindNext, "find_next", &["n"]),
+    (Mode::List, Action::NextSavedSearch, "next_saved_search", &["s"]),
+    (Mode::List, Action::History, "history", &["H"]),
+    (Mode::List, Action::Visual, "visual", &["V"]),
+    (Mode::List, Action::MarkRead, "mark_read", &["r"]),
+    (Mode::List, Action::Bookmark, "bookmark", &["b"]),
+    (Mode::List, Action::Open, "open", &["o"]),
+    (Mode::List, Action::QuickOpen, "quick_open", &["O"]),
+    (Mode::List, Action::FollowLink, "follow_link", &["L"]),
+    (Mode::List, Action::Undo, "undo", &["u"]),
+    (Mode::List, Action::Snooze, "snooze", &["z"]),
+    (Mode::List, Action::Pipe, "pipe", &["|"]),
+    (Mode::List, Action::Pager, "pager", &["p"]),
+    (Mode::List, Action::Editor, "editor", &["e"]),
+    (Mode::List, Action::Reader, "reader", &["a"]),
+    (Mode::List, Action::Archived, "archived", &["A"]),
+    (Mode::List, Action::OnlyDomain, "only_domain", &["d"]),
+    (Mode::List, Action::NeverDomain, "never_domain", &["D"]),
+    (Mode::List, Action::OnlyKind, "only_type", &["t"]),
+    (Mode::List, Action::Fold, "fold", &["Space"]),
+    (Mode::List, Action::FullScreen, "full_screen", &["f"]),
+    (Mode::List, Action::Density, "density", &["i"]),
+    (Mode::List, Action::Metrics, "metrics", &["M"]),
+    (Mode::List, Action::Refresh, "refresh", &["R"]),
+    (Mode::List, Action::SwitchFocus, "switch_focus", &["Tab"]),
+    (Mode::List, Action::ShrinkList, "shrink_list", &["<"]),
+    (Mode::List, Action::GrowList, "grow_list", &[">"]),
+    (Mode::Details, Action::Down, "down", &["Down", "j"]),
+    (Mode::Details, Action::Up, "up", &["Up", "k"]),
+    (Mode::Details, Action::PageDown, "page_down", &["PageDown", "Space"]),
+    (Mode::Details, Action::PageUp, "page_up", &["PageUp"]),
+    (Mode::Details, Action::Top, "top", &["g", "Home"]),
+    (Mode::Details, Action::Bottom, "bottom", &["G", "End"]),
+    (Mode::Details, Action::Find, "find", &["/"]),
+    (Mode::Details, Action::FindNext, "find_next", &["n"]),
+    (Mode::Details, Action::FindPrevious, "find_previous", &["N"]),
+    (Mode::Details, Action::OnlyAuthor, "only_author", &["w"]),
+    (Mode::Details, Action::Submissions, "submissions", &["W"]),
+    (Mode::Details, Action::Discussions, "discussions", &["P"]),
+    (Mode::Comments, Action::Back, "back", &["Left", "q", "Esc", "h"]),
+    (Mode::Comments, Action::Down, "down", &["Down", "j"]),
+    (Mode::Comments, Action::Up, "up", &["Up", "k"]),
+    (Mode::Comments, Action::Top, "top", &["g", "Home"]),
+    (Mode::Comments, Action::Bottom, "bottom", &["G", "End"]),
+    (Mode::Comments, Action::Fold, "fold", &["Space"]),
+    (Mode::Comments, Action::NextTopLevel, "next_top_level", &["J"]),
+    (Mode::Comments, Action::PreviousTopLevel, "previous_top_level", &["K"]),
+    (Mode::Comments, Action::ToggleDead, "toggle_dead", &["d"]),
+    (Mode::Comments, Action::ToggleIgnored, "toggle_ignored", &["I"]),
+    (Mode::Comments, Action::Find, "find", &["/"]),
+    (Mode::Comments, Action::FindNext, "find_next", &["n"]),
+    (Mode::Comments, Action::FindPrevious, "find_previous", &["N"]),
+    (Mode::Comments, Action::Yank, "yank", &["y"]),
+    (Mode::Comments, Action::FollowLink, "follow_link", &["L"]),
+    (Mode::Comments, Action::Reply, "reply", &["R"]),
+    (Mode::Comments, Action::Vote, "vote", &["v"]),
+    (Mode::Comments, Action::CommandLine, "command_line", &[":"]),
+    (Mode::Comments, Action::Pipe, "pipe", &["|"]),
+    (Mode::Comments, Action::Pager, "pager", &["p"]),
+    (Mode::Comments, Action::Editor, "editor", &["e"]),
+];
+
+/// A key as written in the config file: a character, a name such as `Down`, `PageUp`, `Enter` or
+/// `Space`, or either after `Ctrl-`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    ctrl: bool,
+}
+
+impl Key {
+    pub fn parse(name: &str) -> Option<Self> {
+        let (ctrl, name) = match name.strip_prefix("Ctrl-") {
+            Some(rest) => (true, rest),
+            None => (false, name),
+        };
+        let code = match name {
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            "Tab" => KeyCode::Tab,
+            "Backspace" => KeyCode::Backspace,
+            "Space" => KeyCode::Char(' '),
+            _ => {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return None,
+                }
+            }
+        };
+        Some(Self { code, ctrl })
+    }
+
+    /// Keys without `Ctrl-` match whatever the modifiers, as Shift comes with capitals anyway
+    fn matches(&self, key: &KeyEvent) -> bool {
+        self.code == key.code && (!self.ctrl || key.modifiers.contains(KeyModifiers::CONTROL))
+    }
+}
+
+/// As the footer shows it
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl-")?;
+        }
+        match self.code {
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            code => write!(f, "{}", code),
+        }
+    }
+}
+
+/// Which key does what in each mode, the defaults changed by the `keys` of the config file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(Mode, Action, Vec<Key>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULTS
+            .iter()
+            .map(|(mode, action, _, keys)| (*mode, *action, keys.iter().filter_map(|key| Key::parse(key)).collect()))
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// The defaults, with each action named in `keys` (by mode, then action) bound to the keys
+    /// given instead, which the other actions of its mode then lose. Unknown names are skipped.
+    pub fn with_keys(keys: &BTreeMap<String, BTreeMap<String, Vec<String>>>) -> Self {
+        let mut keymap = Self::default();
+        for (mode_name, actions) in keys {
+            let Some(mode) = Mode::from_name(mode_name) else {
+                log::warn!("Unknown mode in the config's keys: {}", mode_name);
+                continue;
+            };
+            for (action_name, names) in actions {
+                let action = DEFAULTS.iter().find(|(m, _, name, _)| *m == mode && name == action_name);
+                let Some((_, action, _, _)) = action else {
+                    log::warn!("Unknown action for {} in the config's keys: {}", mode_name, action_name);
+                    continue;
+                };
+                let keys: Vec<Key> = names.iter().filter_map(|name| Key::parse(name)).collect();
+                keymap.bind(mode, *action, keys);
+            }
+        }
+        keymap
+    }
+
+    fn bind(&mut self, mode: Mode, action: Action, keys: Vec<Key>) {
+        for (_, _, bound) in self.bindings.iter_mut().filter(|(m, a, _)| *m == mode && *a != action) {
+            bound.retain(|key| !keys.contains(key));
+        }
+        if let Some((_, _, bound)) = self.bindings.iter_mut().find(|(m, a, _)| *m == mode && *a == action) {
+            *bound = keys;
+        }
+    }
+
+    /// What `key` does in `mode`; a binding with `Ctrl-` wins over one without
+    pub fn action(&self, mode: Mode, key: &KeyEvent) -> Option<Action> {
+        let bound: Vec<(bool, Action)> = self
+            .bindings
+            .iter()
+            .filter(|(m, _, _)| *m == mode)
+            .flat_map(|(_, action, keys)| keys.iter().filter(|k| k.matches(key)).map(move |k| (k.ctrl, *action)))
+            .collect();
+        bound.iter().find(|(ctrl, _)| *ctrl).or(bound.first()).map(|(_, action)| *action)
+    }
+
+    /// The keys bound to `action` in `mode`, the one to show first
+    pub fn keys(&self, mode: Mode, action: Action) -> &[Key] {
+        self.bindings
+            .iter()
+            .find(|(m, a, _)| *m == mode && *a == action)
+            .map_or(&[], |(_, _, keys)| keys.as_slice())
+    }
+}
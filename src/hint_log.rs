@@ -1,14 +1,141 @@
 use log::{Level, Metadata, Record};
-use std::fs::{File, OpenOptions};
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use std::io::{Write, BufWriter};
-use once_cell::sync::Lazy; // For lazy initialization
-use chrono::Utc;
+use std::io::{self, Write, BufWriter};
+use once_cell::sync::OnceCell; // For one-time initialization with the chosen config
+use chrono::{DateTime, NaiveDate, Utc};
 use log::Log;
 use std::fmt::Arguments;
 
+const DEFAULT_LOG_FILE: &str = "./hint.log";
+const DEFAULT_MAX_BYTES: u64 = 5 * 1024 * 1024;
+const DEFAULT_KEEP: usize = 3;
+
+/// Where to log and how much old log to keep around
+#[derive(Debug, Clone)]
+pub struct LogConfig {
+    pub path: PathBuf,
+    /// Start a new file once the current one would grow beyond this size
+    pub max_bytes: u64,
+    /// Number of rotated files (`hint.log.1`, `hint.log.2`, ...) kept next to the current one
+    pub keep: usize,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from(DEFAULT_LOG_FILE),
+            max_bytes: DEFAULT_MAX_BYTES,
+            keep: DEFAULT_KEEP,
+        }
+    }
+}
+
+/// A log file that rotates when it gets too big or when the day changes
+pub struct RotatingFile {
+    config: LogConfig,
+    writer: Option<BufWriter<File>>,
+    written: u64,
+    day: NaiveDate,
+}
+
+impl RotatingFile {
+    pub fn open(config: LogConfig) -> io::Result<Self> {
+        let today = Utc::now().date_naive();
+        // A log left over from an earlier day is rotated away before we append to it
+        if let Ok(modified) = fs::metadata(&config.path).and_then(|meta| meta.modified()) {
+            if DateTime::<Utc>::from(modified).date_naive() != today {
+                shift_rotated(&config)?;
+            }
+        }
+        let mut file = Self {
+            config,
+            writer: None,
+            written: 0,
+            day: today,
+        };
+        file.open_current()?;
+        Ok(file)
+    }
+
+    fn open_current(&mut self) -> io::Result<()> {
+        if let Some(dir) = self.config.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.path)?;
+        self.written = file.metadata()?.len();
+        self.writer = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    pub fn write_entry(&mut self, entry: &str) -> io::Result<()> {
+        let today = Utc::now().date_naive();
+        let too_big = self.written > 0 && self.written + entry.len() as u64 > self.config.max_bytes;
+        if today != self.day || too_big {
+            self.rotate()?;
+            self.day = today;
+        }
+        if let Some(writer) = self.writer.as_mut() {
+            writer.write_all(entry.as_bytes())?;
+            writer.flush()?;
+            self.written += entry.len() as u64;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self.writer.as_mut() {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+        shift_rotated(&self.config)?;
+        self.open_current()
+    }
+}
+
+/// `hint.log` -> `hint.log.1` -> `hint.log.2` ..., dropping whatever falls off the end
+fn shift_rotated(config: &LogConfig) -> io::Result<()> {
+    if config.keep == 0 {
+        return remove_if_exists(&config.path);
+    }
+    remove_if_exists(&rotated_path(&config.path, config.keep))?;
+    for n in (1..config.keep).rev() {
+        let from = rotated_path(&config.path, n);
+        if from.exists() {
+            fs::rename(from, rotated_path(&config.path, n + 1))?;
+        }
+    }
+    if config.path.exists() {
+        fs::rename(&config.path, rotated_path(&config.path, 1))?;
+    }
+    Ok(())
+}
+
+pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", n));
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
 struct FileLogger {
-    file: Option<Mutex<BufWriter<File>>>, // Use Option to safely allow closing the file
+    file: Mutex<Option<RotatingFile>>, // Use Option to safely allow closing the file
 }
 
 impl log::Log for FileLogger {
@@ -18,24 +145,23 @@ impl log::Log for FileLogger {
 
     fn log(&self, record: &Record) {
         if self.enabled(record.metadata()) {
-            if let Some(ref file_mutex) = self.file.as_ref() { // Borrow the file safely
-                if let Ok(mut file) = file_mutex.lock() {
+            if let Ok(mut file) = self.file.lock() {
+                if let Some(file) = file.as_mut() { // Borrow the file safely
                     let log_entry = format!(
                         "{} - [{}] {}\n",
                         Utc::now().to_rfc3339(),
                         record.level(),
                         record.args()
                     );
-                    let _ = file.write_all(log_entry.as_bytes());
-                    let _ = file.flush();
+                    let _ = file.write_entry(&log_entry);
                 }
             }
         }
     }
 
     fn flush(&self) {
-        if let Some(ref file_mutex) = self.file.as_ref() { // Safely borrow
-            if let Ok(mut file) = file_mutex.lock() {
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(file) = file.as_mut() { // Safely borrow
                 let _ = file.flush();
             }
         }
@@ -44,30 +170,20 @@ impl log::Log for FileLogger {
 
 impl Drop for FileLogger {
     fn drop(&mut self) {
-        if let Some(ref file_mutex) = self.file.as_ref() {
-            if let Ok(mut file) = file_mutex.lock() {
-                let _ = file.flush();
-            }
-        }
+        self.flush();
         println!("Logger dropped, file closed.");
     }
 }
 
-// Lazy initialization of the logger
-static LOGGER: Lazy<FileLogger> = Lazy::new(|| {
-    let file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open("./hint.log")
-        .expect("Failed to open log file");
-    FileLogger {
-        file: Some(Mutex::new(BufWriter::new(file))),
-    }
-});
+// Set once by `init_debug_log`, with the config chosen on the command line
+static LOGGER: OnceCell<FileLogger> = OnceCell::new();
 
 /// Initialize the file logger
-pub fn init_debug_log() {
-    log::set_logger(&*LOGGER)
+pub fn init_debug_log(config: LogConfig) {
+    let logger = LOGGER.get_or_init(|| FileLogger {
+        file: Mutex::new(Some(RotatingFile::open(config).expect("Failed to open log file"))),
+    });
+    log::set_logger(logger)
         .map(|()| log::set_max_level(log::LevelFilter::Info))
         .expect("Failed to set logger");
 }
@@ -75,11 +191,9 @@ pub fn init_debug_log() {
 #[allow(dead_code)]
 /// Shutdown and flush the logs before exiting
 pub fn shutdown_debug_log() {
-    if let Some(ref file_mutex) = LOGGER.file.as_ref() {
-        if let Ok(mut file) = file_mutex.lock() {
-            let _ = file.flush();
-            println!("Shutdown and flushed log data.");
-        }
+    if let Some(logger) = LOGGER.get() {
+        logger.flush();
+        println!("Shutdown and flushed log data.");
     }
 }
 
@@ -87,7 +201,7 @@ pub fn log_debug_info(message: &str, args: Arguments) {
     let formatted_message = format!("{}{}", message, args); // Convert Arguments to string
     // Assuming logging to a file or stdout
     log::info!("{}", formatted_message); // Print or log the formatted message
-    LOGGER.flush();
+    log::logger().flush();
 }
 
 #[allow(dead_code)]
@@ -98,4 +212,4 @@ pub fn log_debug_warn(message: String) {
 #[allow(dead_code)]
 pub fn log_debug_error(message: String) {
     log::error!("{}", message);
-}
\ No newline at end of file
+}
@@ -0,0 +1,87 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Latencies kept per endpoint for the percentiles, the most recent ones
+const MAX_SAMPLES: usize = 500;
+
+/// How the requests to one endpoint went
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct EndpointStats {
+    pub requests: u64,
+    /// Requests that failed to connect or got an error status
+    pub errors: u64,
+    latencies: VecDeque<Duration>,
+}
+
+impl EndpointStats {
+    /// Share of the requests that failed, from 0 to 1
+    pub fn error_rate(&self) -> f64 {
+        match self.requests {
+            0 => 0.0,
+            requests => self.errors as f64 / requests as f64,
+        }
+    }
+
+    /// The latency `percent` of the recent requests stayed within, by the nearest-rank method
+    pub fn percentile(&self, percent: u32) -> Option<Duration> {
+        let mut sorted: Vec<Duration> = self.latencies.iter().copied().collect();
+        sorted.sort();
+        let rank = (sorted.len() * percent.min(100) as usize).div_ceil(100).max(1);
+        sorted.get(rank - 1).copied()
+    }
+}
+
+/// Counts and latencies of the API requests by endpoint, kept in memory for the debug overlay and
+/// the log. Clones share the same figures, so each client clone records into one registry.
+#[derive(Debug, Default, Clone)]
+pub struct Metrics {
+    endpoints: Arc<Mutex<BTreeMap<String, EndpointStats>>>,
+}
+
+impl Metrics {
+    pub fn record(&self, endpoint: &str, latency: Duration, ok: bool) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let stats = endpoints.entry(endpoint.to_string()).or_default();
+        stats.requests += 1;
+        if !ok {
+            stats.errors += 1;
+        }
+        if stats.latencies.len() == MAX_SAMPLES {
+            stats.latencies.pop_front();
+        }
+        stats.latencies.push_back(latency);
+    }
+
+    /// The figures so far, by endpoint
+    pub fn snapshot(&self) -> BTreeMap<String, EndpointStats> {
+        self.endpoints.lock().unwrap().clone()
+    }
+
+    /// A line per endpoint, such as `item  120 req  2.5% err  p50 80ms  p90 210ms  p99 600ms`
+    pub fn report(&self) -> Vec<String> {
+        let endpoints = self.snapshot();
+        let width = endpoints.keys().map(String::len).max().unwrap_or(0);
+        let ms = |latency: Option<Duration>| latency.map_or("-".to_string(), |latency| format!("{}ms", latency.as_millis()));
+        endpoints
+            .iter()
+            .map(|(endpoint, stats)| {
+                format!(
+                    "{:width$}  {} req  {:.1}% err  p50 {}  p90 {}  p99 {}",
+                    endpoint,
+                    stats.requests,
+                    stats.error_rate() * 100.0,
+                    ms(stats.percentile(50)),
+                    ms(stats.percentile(90)),
+                    ms(stats.percentile(99)),
+                )
+            })
+            .collect()
+    }
+}
+
+/// The endpoint an API path belongs to: `item` for every item, the list's name for feed lists
+pub fn endpoint(path: &str) -> &str {
+    let name = path.split('/').next().unwrap_or(path);
+    name.strip_suffix(".json").unwrap_or(name)
+}
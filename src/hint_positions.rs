@@ -0,0 +1,66 @@
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const POSITIONS_FILE: &str = "reading_positions.json";
+/// Articles and threads not left for longer than the last this many are forgotten
+const MAX_PLACES: usize = 500;
+
+/// Where reading stopped in each article and comment thread, so reopening one goes back there
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadingPositions {
+    /// Article addresses and the paragraph at the top of the reader, least recently left first
+    articles: Vec<(String, usize)>,
+    /// Story ids and the comment selected in their thread, least recently left first
+    threads: Vec<(u64, u64)>,
+}
+
+impl ReadingPositions {
+    pub fn load(store: &Store) -> Self {
+        store.load(POSITIONS_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(POSITIONS_FILE, self)
+    }
+
+    pub fn article(&self, url: &str) -> Option<usize> {
+        self.articles.iter().find(|(read, _)| read == url).map(|(_, paragraph)| *paragraph)
+    }
+
+    pub fn thread(&self, story: u64) -> Option<u64> {
+        self.threads.iter().find(|(read, _)| *read == story).map(|(_, comment)| *comment)
+    }
+
+    /// Remember the paragraph reading stopped at, or forget the article if it was its first
+    pub fn set_article(&mut self, url: &str, paragraph: usize) {
+        self.articles.retain(|(read, _)| read != url);
+        if paragraph > 0 {
+            remember(&mut self.articles, (url.to_string(), paragraph));
+        }
+    }
+
+    /// Remember the comment reading stopped at, or forget the thread if it was left at the story
+    pub fn set_thread(&mut self, story: u64, comment: Option<u64>) {
+        self.threads.retain(|(read, _)| *read != story);
+        if let Some(comment) = comment {
+            remember(&mut self.threads, (story, comment));
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.articles.len() + self.threads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn remember<T>(places: &mut Vec<T>, place: T) {
+    places.push(place);
+    if places.len() > MAX_PLACES {
+        places.drain(..places.len() - MAX_PLACES);
+    }
+}
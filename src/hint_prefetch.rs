@@ -0,0 +1,63 @@
+use crate::hint_article::Article;
+use crate::hint_comments::CommentThread;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Threads and articles kept for the stories selected lately, the oldest dropped first
+const KEEP: usize = 20;
+/// How long the selection has to rest on a story before its thread and article are fetched, so
+/// scrolling past a screenful of stories does not fire a request for each one
+pub const PREFETCH_DELAY: Duration = Duration::from_millis(400);
+
+/// What to fetch ahead for the selected story
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Prefetch {
+    /// The story whose comments to fetch, unless it has none or they are in already
+    pub thread: Option<u64>,
+    /// The article to fetch, unless it is a text post or the article is in already
+    pub url: Option<String>,
+}
+
+/// Comment threads and articles fetched before they were asked for, so `c` and `p` show them at
+/// once
+#[derive(Debug, Default, Clone)]
+pub struct Prefetched {
+    threads: VecDeque<CommentThread>,
+    articles: VecDeque<Article>,
+}
+
+impl Prefetched {
+    pub fn has_thread(&self, story_id: u64) -> bool {
+        self.threads.iter().any(|thread| thread.story_id == story_id)
+    }
+
+    pub fn has_article(&self, url: &str) -> bool {
+        self.articles.iter().any(|article| article.url == url)
+    }
+
+    /// The thread fetched for `story_id`, given up as it only grows staler from here
+    pub fn take_thread(&mut self, story_id: u64) -> Option<CommentThread> {
+        let index = self.threads.iter().position(|thread| thread.story_id == story_id)?;
+        self.threads.remove(index)
+    }
+
+    pub fn article(&self, url: &str) -> Option<&Article> {
+        self.articles.iter().find(|article| article.url == url)
+    }
+
+    pub fn add_thread(&mut self, thread: CommentThread) {
+        self.threads.retain(|kept| kept.story_id != thread.story_id);
+        self.threads.push_back(thread);
+        if self.threads.len() > KEEP {
+            self.threads.pop_front();
+        }
+    }
+
+    pub fn add_article(&mut self, article: Article) {
+        self.articles.retain(|kept| kept.url != article.url);
+        self.articles.push_back(article);
+        if self.articles.len() > KEEP {
+            self.articles.pop_front();
+        }
+    }
+}
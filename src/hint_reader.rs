@@ -0,0 +1,249 @@
+use crate::hint_article::Article;
+use crate::hint_comments::wrap_text;
+use crate::hint_highlight::{fence, highlight};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use std::collections::HashMap;
+
+/// An article read in the details pane, wrapped to the pane's width as it is drawn.
+///
+/// The place read to is a paragraph and a row within it rather than a row of the whole text, so
+/// resizing the terminal or the split with `<` and `>` keeps the same text at the top. Code
+/// between ```` ``` ```` fences keeps its lines unwrapped.
+#[derive(Debug, Clone)]
+pub struct Reader {
+    pub url: String,
+    /// The article's lines, each wrapped on its own
+    paragraphs: Vec<String>,
+    /// The code blocks: the paragraphs from each opening fence to its closing one, and the
+    /// language the fence names
+    code: Vec<(std::ops::Range<usize>, Option<String>)>,
+    /// Code lines in the style and, if the flag is on, coloured by their syntax, by paragraph
+    highlighted: Option<(Style, bool, HashMap<usize, Line<'static>>)>,
+    /// The address of each image placeholder's image, by paragraph
+    images: HashMap<usize, String>,
+    /// Blank rows left under each image placeholder for the image itself; none unless the
+    /// terminal can show images
+    image_rows: usize,
+    /// Paragraph at the top of the pane
+    top: usize,
+    /// Rows of that paragraph scrolled past
+    top_row: usize,
+    /// Width last wrapped to, and the rows of each paragraph at it
+    wrapped: Option<(usize, Vec<Vec<String>>)>,
+    /// Text searched for with `/`, which `n` and `N` then move between
+    pub search: Option<String>,
+}
+
+impl Reader {
+    pub fn new(article: &Article) -> Self {
+        let paragraphs: Vec<String> = article.text.lines().map(str::to_string).collect();
+        let mut code = Vec::new();
+        let mut open: Option<(usize, String)> = None;
+        for (i, paragraph) in paragraphs.iter().enumerate() {
+            match (fence(paragraph), open.take()) {
+                (Some(language), None) => open = Some((i, language.to_string())),
+                (Some(_), Some((start, language))) => {
+                    code.push((start..i + 1, Some(language).filter(|language| !language.is_empty())))
+                }
+                (None, still_open) => open = still_open,
+            }
+        }
+        // A placeholder such as `[image: alt text][3]` is numbered as a link to its image
+        let images = (paragraphs.iter().enumerate())
+            .filter(|(i, paragraph)| paragraph.starts_with("[image") && !code.iter().any(|(lines, _)| lines.contains(i)))
+            .filter_map(|(i, paragraph)| {
+                let (_, number) = paragraph.strip_suffix(']')?.rsplit_once('[')?;
+                let link = article.links.get(number.parse::<usize>().ok()?.checked_sub(1)?)?;
+                Some((i, link.clone()))
+            })
+            .collect();
+        Self {
+            url: article.url.clone(),
+            paragraphs,
+            code,
+            highlighted: None,
+            images,
+            image_rows: 0,
+            top: 0,
+            top_row: 0,
+            wrapped: None,
+            search: None,
+        }
+    }
+
+    /// The article's text as extracted, a line to each paragraph
+    pub fn text(&self) -> String {
+        self.paragraphs.join("\n")
+    }
+
+    /// Leave `rows` blank rows under each image placeholder, for the image
+    pub fn show_images(&mut self, rows: usize) {
+        self.image_rows = rows;
+        self.wrapped = None;
+    }
+
+    /// The addresses of the article's images, in order
+    pub fn image_urls(&self) -> Vec<String> {
+        let mut images: Vec<(&usize, &String)> = self.images.iter().collect();
+        images.sort();
+        images.into_iter().map(|(_, url)| url.clone()).collect()
+    }
+
+    /// The images with all their rows in a pane `width` columns wide and `height` rows high, and
+    /// the row of the pane where each starts
+    pub fn image_slots(&mut self, width: usize, height: usize) -> Vec<(String, usize)> {
+        if self.image_rows == 0 {
+            return Vec::new();
+        }
+        self.wrap(width);
+        let wrapped = self.wrapped.as_ref().map_or(&[][..], |(_, rows)| rows);
+        let rows = wrapped.iter().enumerate().skip(self.top);
+        let rows = rows.flat_map(|(i, rows)| (0..rows.len()).map(move |row| (i, row, rows.len())));
+        rows.skip(self.top_row)
+            .take(height)
+            .enumerate()
+            .filter(|(line, (_, row, len))| *row + self.image_rows == *len && line + self.image_rows <= height)
+            .filter_map(|(line, (i, _, _))| Some((self.images.get(&i)?.clone(), line)))
+            .collect()
+    }
+
+    fn is_code(&self, paragraph: usize) -> bool {
+        self.code.iter().any(|(lines, _)| lines.contains(&paragraph))
+    }
+
+    /// The paragraph at the top of the pane, and the rows of it scrolled past
+    pub fn position(&self) -> (usize, usize) {
+        (self.top, self.top_row)
+    }
+
+    /// Start reading at `paragraph`, where an earlier visit stopped
+    pub fn go_to(&mut self, paragraph: usize) {
+        self.top = paragraph.min(self.paragraphs.len().saturating_sub(1));
+        self.top_row = 0;
+    }
+
+    /// How far into the article the top of the pane is, from 0 to 100, at the width last drawn
+    pub fn percent(&self) -> usize {
+        let Some((_, wrapped)) = &self.wrapped else {
+            return self.top * 100 / self.paragraphs.len().saturating_sub(1).max(1);
+        };
+        let total: usize = wrapped.iter().map(Vec::len).sum();
+        let before: usize = wrapped.iter().take(self.top).map(Vec::len).sum::<usize>() + self.top_row;
+        before * 100 / total.saturating_sub(1).max(1)
+    }
+
+    /// Wrap to `width` columns unless already wrapped so. The row within the top paragraph moves
+    /// in proportion, as the paragraph takes more or fewer rows than before.
+    fn wrap(&mut self, width: usize) -> &[Vec<String>] {
+        if self.wrapped.as_ref().is_none_or(|(wrapped_to, _)| *wrapped_to != width) {
+            let rows: Vec<Vec<String>> = (self.paragraphs.iter().enumerate())
+                .map(|(i, paragraph)| match self.is_code(i) {
+                    true => vec![paragraph.clone()],
+                    false if self.images.contains_key(&i) => {
+                        let mut rows = wrap_text(paragraph, width);
+                        rows.extend(std::iter::repeat_n(String::new(), self.image_rows));
+                        rows
+                    }
+                    false => wrap_text(paragraph, width),
+                })
+                .collect();
+            if let (Some((_, before)), Some(after)) = (&self.wrapped, rows.get(self.top)) {
+                let before = before[self.top].len().max(1);
+                self.top_row = (self.top_row * after.len() / before).min(after.len().saturating_sub(1));
+            }
+            self.wrapped = Some((width, rows));
+        }
+        self.wrapped.as_ref().map_or(&[], |(_, rows)| rows)
+    }
+
+    /// The rows to show in a pane `width` columns wide and `height` rows high
+    pub fn rows(&mut self, width: usize, height: usize) -> Vec<String> {
+        self.wrap(width);
+        let wrapped = self.wrapped.as_ref().map_or(&[][..], |(_, rows)| rows);
+        wrapped.iter().skip(self.top).flatten().skip(self.top_row).take(height).cloned().collect()
+    }
+
+    /// How many times the search text occurs in the article
+    pub fn match_count(&self) -> usize {
+        let Some(search) = &self.search else {
+            return 0;
+        };
+        let needle = search.to_lowercase();
+        self.paragraphs.iter().map(|paragraph| paragraph.to_lowercase().matches(&needle).count()).sum()
+    }
+
+    /// Bring the next or previous row matching the search to the top, wrapping around at the
+    /// ends; `false` if none matches. Like highlighting, matches broken across rows are not found.
+    pub fn find(&mut self, forward: bool) -> bool {
+        let Some(needle) = self.search.as_ref().map(|search| search.to_lowercase()) else {
+            return false;
+        };
+        let width = self.wrapped.as_ref().map_or(usize::MAX, |(width, _)| *width);
+        let here = (self.top, self.top_row);
+        let wrapped = self.wrap(width);
+        let rows: Vec<(usize, usize)> = wrapped
+            .iter()
+            .enumerate()
+            .flat_map(|(top, rows)| rows.iter().enumerate().map(move |(row, text)| (top, row, text)))
+            .filter(|(_, _, text)| text.to_lowercase().contains(&needle))
+            .map(|(top, row, _)| (top, row))
+            .collect();
+        let target = if forward {
+            rows.iter().find(|row| **row > here).or(rows.first())
+        } else {
+            rows.iter().rev().find(|row| **row < here).or(rows.last())
+        };
+        if let Some(target) = target {
+            (self.top, self.top_row) = *target;
+        }
+        target.is_some()
+    }
+
+    /// The rows to show as `rows` does, code in the `code` style, coloured by its syntax with
+    /// `highlight_code`
+    pub fn lines(&mut self, width: usize, height: usize, code: Style, highlight_code: bool) -> Vec<Line<'static>> {
+        if self.highlighted.as_ref().is_none_or(|(style, colour, _)| (*style, *colour) != (code, highlight_code)) {
+            let mut lines: HashMap<usize, Line<'static>> = HashMap::new();
+            for (range, language) in &self.code {
+                // The fences themselves are left uncoloured
+                let inside = range.start + 1..range.end - 1;
+                let text = self.paragraphs[inside.clone()].join("\n");
+                let styled = match highlight_code {
+                    true => highlight(&text, language.as_deref(), code),
+                    false => text.lines().map(|line| Line::from(Span::styled(line.to_string(), code))).collect(),
+                };
+                lines.extend(inside.zip(styled));
+                for fence in [range.start, range.end - 1] {
+                    lines.insert(fence, Line::from(Span::styled(self.paragraphs[fence].clone(), code)));
+                }
+            }
+            self.highlighted = Some((code, highlight_code, lines));
+        }
+        self.wrap(width);
+        let wrapped = self.wrapped.as_ref().map_or(&[][..], |(_, rows)| rows);
+        let highlighted = self.highlighted.as_ref().map(|(_, _, lines)| lines);
+        let rows = wrapped.iter().enumerate().skip(self.top);
+        rows.flat_map(|(i, rows)| rows.iter().map(move |row| (i, row)))
+            .skip(self.top_row)
+            .take(height)
+            .map(|(i, row)| highlighted.and_then(|lines| lines.get(&i)).cloned().unwrap_or_else(|| Line::raw(row.clone())))
+            .collect()
+    }
+
+    /// Scroll by `rows`, up if negative, at the width last drawn. The last row stops at the top.
+    pub fn scroll(&mut self, rows: i32) {
+        let width = self.wrapped.as_ref().map_or(usize::MAX, |(width, _)| *width);
+        let (top, top_row) = (self.top, self.top_row);
+        let wrapped = self.wrap(width);
+        let total: usize = wrapped.iter().map(Vec::len).sum();
+        let before: usize = wrapped.iter().take(top).map(Vec::len).sum::<usize>() + top_row;
+        let mut row = (before as i64 + rows as i64).clamp(0, total.saturating_sub(1) as i64) as usize;
+        let mut top = 0;
+        while let Some(paragraph) = wrapped.get(top).filter(|paragraph| row >= paragraph.len()) {
+            row -= paragraph.len();
+            top += 1;
+        }
+        (self.top, self.top_row) = (top, row);
+    }
+}
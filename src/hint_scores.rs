@@ -0,0 +1,124 @@
+use crate::hint_store::Store;
+use crate::hnreader::HnClient;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io;
+
+const SCORES_FILE: &str = "scores.json";
+/// Samples kept per story, the oldest dropped first
+const MAX_SAMPLES: usize = 500;
+/// Lowest to highest
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// A story's points and comments as just fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StoryCounts {
+    pub id: u64,
+    pub score: Option<u32>,
+    pub comments: Option<u32>,
+}
+
+/// A story's score at one moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreSample {
+    /// Unix time it was fetched
+    pub at: i64,
+    pub score: u32,
+}
+
+/// Scores of bookmarked stories, fetched every so often to show how each story is doing
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScoreHistory {
+    /// By story id, oldest sample first
+    stories: BTreeMap<u64, Vec<ScoreSample>>,
+}
+
+impl ScoreHistory {
+    pub fn load(store: &Store) -> Self {
+        store.load(SCORES_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(SCORES_FILE, self)
+    }
+
+    pub fn record(&mut self, id: u64, score: u32, at: i64) {
+        let samples = self.stories.entry(id).or_default();
+        samples.push(ScoreSample { at, score });
+        if samples.len() > MAX_SAMPLES {
+            samples.drain(..samples.len() - MAX_SAMPLES);
+        }
+    }
+
+    pub fn samples(&self, id: u64) -> &[ScoreSample] {
+        self.stories.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn len(&self) -> usize {
+        self.stories.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stories.is_empty()
+    }
+}
+
+/// The last `width` scores as bars from the lowest to the highest of them
+pub fn sparkline(scores: &[u32], width: usize) -> String {
+    let scores = &scores[scores.len().saturating_sub(width)..];
+    let (Some(low), Some(high)) = (scores.iter().min(), scores.iter().max()) else {
+        return String::new();
+    };
+    let range = (high - low).max(1) as usize;
+    let top = BARS.len() - 1;
+    scores.iter().map(|score| BARS[(score - low) as usize * top / range]).collect()
+}
+
+/// A line for the details pane such as "Score ▁▃▅█ 12 → 340 in 5h", once there are two samples
+pub fn trend(samples: &[ScoreSample], width: usize) -> Option<String> {
+    let (first, last) = (samples.first()?, samples.last()?);
+    if samples.len() < 2 {
+        return None;
+    }
+    let scores: Vec<u32> = samples.iter().map(|sample| sample.score).collect();
+    Some(format!(
+        "Score {} {} → {} in {}",
+        sparkline(&scores, width),
+        first.score,
+        last.score,
+        span(last.at - first.at)
+    ))
+}
+
+fn span(seconds: i64) -> String {
+    match seconds.max(0) {
+        0..=3599 => format!("{}m", seconds.max(0) / 60),
+        3600..=172_799 => format!("{}h", seconds / 3600),
+        _ => format!("{}d", seconds / 86_400),
+    }
+}
+
+/// The current points and comments of each story, leaving out the ones that fail to load
+pub async fn fetch_counts(client: &HnClient, ids: &[u64]) -> Vec<StoryCounts> {
+    let mut counts = Vec::with_capacity(ids.len());
+    for id in ids {
+        match client.fetch_story_details(*id).await {
+            Ok(story) => counts.push(StoryCounts { id: *id, score: story.score, comments: story.descendants }),
+            Err(err) => log::warn!("Failed to refresh {}: {}", id, err),
+        }
+    }
+    counts
+}
+
+/// The current score of each story, leaving out the ones that fail to load
+pub async fn fetch_scores(client: &HnClient, ids: &[u64]) -> Vec<(u64, u32)> {
+    let mut scores = Vec::with_capacity(ids.len());
+    for id in ids {
+        match client.fetch_story_details(*id).await {
+            Ok(story) => scores.extend(story.score.map(|score| (*id, score))),
+            Err(err) => log::warn!("Failed to fetch the score of {}: {}", id, err),
+        }
+    }
+    scores
+}
@@ -0,0 +1,235 @@
+use crate::hint_hackernews::HnStory;
+use crate::hint_snooze::parse_duration;
+use crate::hint_store::Store;
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::io;
+use std::time::Duration;
+
+pub const ALGOLIA_API: &str = "https://hn.algolia.com/api/v1";
+const SEEN_RESULTS_FILE: &str = "seen_results.json";
+/// Most recent results asked of Algolia per search
+const HITS_PER_PAGE: usize = 30;
+/// Stories asked of Algolia for a past day's front page, more than one screen of it held at once
+const FRONT_PAGE_HITS: usize = 50;
+/// Most stories listed for a stretch of time, a quiet night's worth of New; Algolia's cap is 1000
+const BETWEEN_HITS: usize = 500;
+/// Stories asked of Algolia as related to one, the story itself likely among them
+const RELATED_HITS: usize = 6;
+/// Results remembered as seen per search, the oldest forgotten first
+const MAX_SEEN: usize = 1000;
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(20);
+
+static HTTP: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(SEARCH_TIMEOUT)
+        .user_agent(concat!("hint/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .expect("Failed to build HTTP client")
+});
+
+/// A search kept in the config file, shown as a tab above the story list
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedSearch {
+    pub name: String,
+    pub query: String,
+    /// Match the query against the titles of stories loaded in any feed instead of asking Algolia
+    #[serde(default)]
+    pub local: bool,
+}
+
+impl SavedSearch {
+    /// Whether every word of the query is in the story's title, ignoring case
+    pub fn matches(&self, story: &HnStory) -> bool {
+        let title = story.title().to_lowercase();
+        let mut words = self.query.split_whitespace().peekable();
+        words.peek().is_some() && words.all(|word| title.contains(&word.to_lowercase()))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    hits: Vec<Hit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Hit {
+    #[serde(rename = "objectID")]
+    object_id: String,
+    title: Option<String>,
+    url: Option<String>,
+    author: Option<String>,
+    points: Option<u32>,
+    num_comments: Option<u32>,
+    created_at_i: Option<u64>,
+}
+
+/// The newest stories matching `query` on HN Search, as `api` (normally `ALGOLIA_API`) has them
+pub async fn search(query: &str, api: &str) -> Result<Vec<HnStory>, reqwest::Error> {
+    let hits_per_page = HITS_PER_PAGE.to_string();
+    let params = [("query", query), ("tags", "story"), ("hitsPerPage", hits_per_page.as_str())];
+    let url = format!("{}/search_by_date", api.trim_end_matches('/'));
+    let response: SearchResponse = HTTP.get(url).query(&params).send().await?.error_for_status()?.json().await?;
+    Ok(response.hits.into_iter().filter_map(Hit::into_story).collect())
+}
+
+/// The stories posted on `day` (UTC) that made the front page, most points first, as `api`
+/// (normally `ALGOLIA_API`) has them
+pub async fn front_page(day: NaiveDate, api: &str) -> Result<Vec<HnStory>, reqwest::Error> {
+    let start = day.and_hms_opt(0, 0, 0).map_or(0, |time| time.and_utc().timestamp());
+    let filters = format!("created_at_i>={},created_at_i<{}", start, start + 24 * 60 * 60);
+    let hits_per_page = FRONT_PAGE_HITS.to_string();
+    let params = [("tags", "front_page"), ("numericFilters", filters.as_str()), ("hitsPerPage", hits_per_page.as_str())];
+    let url = format!("{}/search", api.trim_end_matches('/'));
+    let response: SearchResponse = HTTP.get(url).query(&params).send().await?.error_for_status()?.json().await?;
+    let mut stories: Vec<HnStory> = response.hits.into_iter().filter_map(Hit::into_story).collect();
+    stories.sort_by_key(|story| std::cmp::Reverse(story.score()));
+    Ok(stories)
+}
+
+/// The stories posted from `start` to `end`, unix times, newest first like the New feed
+pub async fn posted_between(start: i64, end: i64, api: &str) -> Result<Vec<HnStory>, reqwest::Error> {
+    let filters = format!("created_at_i>={},created_at_i<{}", start, end);
+    let hits_per_page = BETWEEN_HITS.to_string();
+    let params = [("tags", "story"), ("numericFilters", filters.as_str()), ("hitsPerPage", hits_per_page.as_str())];
+    let url = format!("{}/search_by_date", api.trim_end_matches('/'));
+    let response: SearchResponse = HTTP.get(url).query(&params).send().await?.error_for_status()?.json().await?;
+    Ok(response.hits.into_iter().filter_map(Hit::into_story).collect())
+}
+
+/// Every submission of the page at `url`, newest first, like HN's own "past" link. HN Search
+/// matches words of the address, so hits of other pages on the same site are left out.
+pub async fn discussions(url: &str, api: &str) -> Result<Vec<HnStory>, reqwest::Error> {
+    let hits_per_page = HITS_PER_PAGE.to_string();
+    let params = [("query", url), ("restrictSearchableAttributes", "url"), ("tags", "story"), ("hitsPerPage", hits_per_page.as_str())];
+    let address = format!("{}/search_by_date", api.trim_end_matches('/'));
+    let response: SearchResponse = HTTP.get(address).query(&params).send().await?.error_for_status()?.json().await?;
+    let page = same_page(url);
+    let hits = response.hits.into_iter().filter(|hit| hit.url.as_deref().is_some_and(|other| same_page(other) == page));
+    Ok(hits.filter_map(Hit::into_story).collect())
+}
+
+/// Stories whose titles are most like `title`, best match first. The `Show HN:` and like
+/// prefixes are left out, or every post of the kind would match.
+pub async fn related(title: &str, api: &str) -> Result<Vec<HnStory>, reqwest::Error> {
+    let title = ["Show HN:", "Ask HN:", "Tell HN:", "Launch HN:"].iter().find_map(|kind| title.strip_prefix(kind)).unwrap_or(title);
+    let hits_per_page = RELATED_HITS.to_string();
+    let params = [("similarQuery", title.trim()), ("tags", "story"), ("hitsPerPage", hits_per_page.as_str())];
+    let url = format!("{}/search", api.trim_end_matches('/'));
+    let response: SearchResponse = HTTP.get(url).query(&params).send().await?.error_for_status()?.json().await?;
+    Ok(response.hits.into_iter().filter_map(Hit::into_story).collect())
+}
+
+/// `url` without what differs between links to the same page: the scheme, `www.`, the fragment
+/// and a trailing slash
+fn same_page(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let url = url.strip_prefix("www.").unwrap_or(url);
+    let url = url.split('#').next().unwrap_or(url);
+    url.trim_end_matches('/').to_lowercase()
+}
+
+/// The unix time `text` names, as typed after `:between`: `now`, a duration back like `8h`, the
+/// last time the clock read `23:30`, a day like 2024-01-31 from its midnight, or both like
+/// 2024-01-31T23:30, all in the time zone of `now`
+pub fn parse_moment<Tz: TimeZone>(text: &str, now: &DateTime<Tz>) -> Option<i64> {
+    let text = text.trim();
+    if text == "now" {
+        return Some(now.timestamp());
+    }
+    if let Some(seconds) = parse_duration(text) {
+        return Some(now.timestamp() - seconds);
+    }
+    let zone = now.timezone();
+    let local = |time: NaiveDateTime| zone.from_local_datetime(&time).earliest().map(|time| time.timestamp());
+    if let Ok(clock) = NaiveTime::parse_from_str(text, "%H:%M") {
+        let today = now.naive_local().date().and_time(clock);
+        let yesterday = today.checked_sub_days(Days::new(1))?;
+        let latest = if local(today)? <= now.timestamp() { today } else { yesterday };
+        return local(latest);
+    }
+    if let Ok(time) = NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M") {
+        return local(time);
+    }
+    let day = NaiveDate::parse_from_str(text, "%Y-%m-%d").ok()?;
+    local(day.and_time(NaiveTime::MIN))
+}
+
+/// The day `text` names, as typed after `:past`: a date like 2024-01-31, `yesterday` (also when
+/// left out), `today`, or a number of days back like `-3`
+pub fn parse_day(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match text.trim() {
+        "" | "yesterday" => today.checked_sub_days(Days::new(1)),
+        "today" => Some(today),
+        text => match text.strip_prefix('-') {
+            Some(days) => today.checked_sub_days(Days::new(days.parse().ok()?)),
+            None => NaiveDate::parse_from_str(text, "%Y-%m-%d").ok(),
+        },
+    }
+}
+
+impl Hit {
+    fn into_story(self) -> Option<HnStory> {
+        self.object_id.parse::<u64>().ok()?;
+        let title = self.title.unwrap_or_else(|| String::from("Untitled"));
+        let author = self.author.unwrap_or_else(|| String::from("Anonymous Author"));
+        let url = self.url.filter(|url| !url.is_empty());
+        let mut story = HnStory::new(self.object_id, author, title, url, "story".to_string());
+        if let Some(points) = self.points {
+            story.set_score(points);
+        }
+        if let Some(comments) = self.num_comments {
+            story.set_descendants(comments);
+        }
+        if let Some(time) = self.created_at_i {
+            story.set_time(time);
+        }
+        Some(story)
+    }
+}
+
+/// The results of each saved search already shown, so the tabs can count the new ones
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeenResults {
+    /// By search name, oldest first
+    searches: BTreeMap<String, Vec<u64>>,
+}
+
+impl SeenResults {
+    pub fn load(store: &Store) -> Self {
+        store.load(SEEN_RESULTS_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(SEEN_RESULTS_FILE, self)
+    }
+
+    /// How many of `stories` were never shown under the search `name`
+    pub fn unseen<'a>(&self, name: &str, stories: impl IntoIterator<Item = &'a HnStory>) -> usize {
+        let seen: HashSet<u64> = self.searches.get(name).into_iter().flatten().copied().collect();
+        stories.into_iter().filter(|story| !seen.contains(&story.id())).count()
+    }
+
+    /// Remember `ids` as shown under the search `name`; says whether any of them is new
+    pub fn mark(&mut self, name: &str, ids: impl IntoIterator<Item = u64>) -> bool {
+        let seen = self.searches.entry(name.to_string()).or_default();
+        let mut known: HashSet<u64> = seen.iter().copied().collect();
+        let before = known.len();
+        seen.extend(ids.into_iter().filter(|id| known.insert(*id)));
+        if seen.len() > MAX_SEEN {
+            seen.drain(..seen.len() - MAX_SEEN);
+        }
+        known.len() > before
+    }
+
+    pub fn len(&self) -> usize {
+        self.searches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.searches.is_empty()
+    }
+}
@@ -0,0 +1,60 @@
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io;
+
+const SEEN_FILE: &str = "seen_comments.json";
+/// Threads not opened for longer than the last this many are forgotten, keeping the file small
+const MAX_THREADS: usize = 500;
+
+/// The comments loaded in each thread the user opened, so the next visit can tell what is new
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeenComments {
+    /// Least recently opened first
+    threads: Vec<SeenThread>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SeenThread {
+    story: u64,
+    comments: Vec<u64>,
+}
+
+impl SeenComments {
+    pub fn load(store: &Store) -> Self {
+        store.load(SEEN_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(SEEN_FILE, self)
+    }
+
+    /// The comments seen in a thread, `None` if it was never opened
+    pub fn get(&self, story: u64) -> Option<HashSet<u64>> {
+        let thread = self.threads.iter().find(|thread| thread.story == story)?;
+        Some(thread.comments.iter().copied().collect())
+    }
+
+    /// Remember `comments` as seen, and the thread as the one opened most recently
+    pub fn mark(&mut self, story: u64, comments: impl IntoIterator<Item = u64>) {
+        let mut thread = match self.threads.iter().position(|thread| thread.story == story) {
+            Some(i) => self.threads.remove(i),
+            None => SeenThread { story, comments: Vec::new() },
+        };
+        let mut known: HashSet<u64> = thread.comments.iter().copied().collect();
+        thread.comments.extend(comments.into_iter().filter(|id| known.insert(*id)));
+        self.threads.push(thread);
+        if self.threads.len() > MAX_THREADS {
+            self.threads.drain(..self.threads.len() - MAX_THREADS);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.threads.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.threads.is_empty()
+    }
+}
@@ -0,0 +1,36 @@
+use crate::hint_app::{Density, StorySort};
+use crate::hint_hackernews::Feed;
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const SESSION_FILE: &str = "session.json";
+
+/// Where the user was when they last quit, so the next launch can put them back there
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub feed: Feed,
+    pub selected_story: Option<u64>,
+    pub scroll_offset: usize,
+    /// Name of the `:theme` in use
+    pub theme: Option<String>,
+    /// Lines typed at the `:` prompt, oldest first
+    pub command_history: Vec<String>,
+    /// Where `<` and `>` left the details split, in percent of the screen for the list
+    pub details_split: Option<u16>,
+    /// One line per story or two, as `i` left it
+    pub density: Density,
+    /// Order of the list, if `:sort` picked one for the feed
+    pub sort: Option<StorySort>,
+}
+
+impl Session {
+    pub fn load(store: &Store) -> Self {
+        store.load(SESSION_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(SESSION_FILE, self)
+    }
+}
@@ -0,0 +1,82 @@
+use crate::hint_bookmarks::SavedStory;
+use crate::hint_store::Store;
+use serde::{Deserialize, Serialize};
+use std::io;
+
+const SNOOZED_FILE: &str = "snoozed.json";
+/// Used when the snooze prompt is left empty
+pub const DEFAULT_SNOOZE: &str = "4h";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnoozedStory {
+    pub story: SavedStory,
+    /// Unix time at which the story comes back
+    pub until: i64,
+}
+
+/// Stories put away with `z`, kept out of the feeds until their time is up
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snoozed {
+    entries: Vec<SnoozedStory>,
+}
+
+impl Snoozed {
+    pub fn load(store: &Store) -> Self {
+        store.load(SNOOZED_FILE)
+    }
+
+    pub fn save(&self, store: &Store) -> io::Result<()> {
+        store.save(SNOOZED_FILE, self)
+    }
+
+    /// Whether the story is still asleep at `now`
+    pub fn is_snoozed(&self, id: u64, now: i64) -> bool {
+        self.entries.iter().any(|entry| entry.story.id == id && entry.until > now)
+    }
+
+    /// Snooze a story, replacing any earlier snooze of it
+    pub fn snooze(&mut self, story: SavedStory, until: i64) {
+        self.unsnooze(story.id);
+        self.entries.push(SnoozedStory { story, until });
+    }
+
+    pub fn unsnooze(&mut self, id: u64) {
+        self.entries.retain(|entry| entry.story.id != id);
+    }
+
+    /// Remove and return the stories whose time is up, soonest first
+    pub fn take_due(&mut self, now: i64) -> Vec<SavedStory> {
+        let (mut due, waiting): (Vec<_>, Vec<_>) =
+            self.entries.drain(..).partition(|entry| entry.until <= now);
+        self.entries = waiting;
+        due.sort_by_key(|entry| entry.until);
+        due.into_iter().map(|entry| entry.story).collect()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SnoozedStory> {
+        self.entries.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Seconds in a duration like `30m`, `4h` or `1d`
+pub fn parse_duration(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let unit = text.chars().last()?;
+    let amount: i64 = text[..text.len() - unit.len_utf8()].parse().ok()?;
+    let seconds = match unit {
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        _ => return None,
+    };
+    (amount > 0).then(|| amount * seconds)
+}
+
+pub fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
@@ -0,0 +1,52 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Small JSON documents persisted between runs (session, history, ...), one file per document.
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The per-user data directory, overridable with `HINT_DATA_DIR`
+    pub fn open_default() -> Self {
+        let dir = std::env::var_os("HINT_DATA_DIR")
+            .map(PathBuf::from)
+            .or_else(|| dirs::data_local_dir().map(|dir| dir.join("hint")))
+            .unwrap_or_else(|| PathBuf::from(".hint"));
+        Self::new(dir)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Read a document, falling back to its default when missing or unreadable
+    pub fn load<T: DeserializeOwned + Default>(&self, name: &str) -> T {
+        let path = self.dir.join(name);
+        match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+                log::warn!("Ignoring corrupt {}: {}", path.display(), err);
+                T::default()
+            }),
+            Err(_) => T::default(),
+        }
+    }
+
+    /// Write a document atomically, so a crash never leaves a half-written file behind
+    pub fn save<T: Serialize>(&self, name: &str, value: &T) -> io::Result<()> {
+        let path = self.dir.join(name);
+        // Names such as `articles/...` keep a kind of document in a directory of its own
+        fs::create_dir_all(path.parent().unwrap_or(&self.dir))?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(value)?)?;
+        fs::rename(tmp, path)
+    }
+}
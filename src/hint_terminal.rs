@@ -0,0 +1,97 @@
+use ratatui::crossterm::event::KeyboardEnhancementFlags;
+use serde::{Deserialize, Serialize};
+
+/// `TERM_PROGRAM` values of terminals known to follow OSC 8 links
+const HYPERLINK_PROGRAMS: &[&str] = &["Hyper", "iTerm.app", "terminology", "WezTerm", "vscode", "ghostty", "rio"];
+/// `TERM` values of terminals known to follow OSC 8 links
+const HYPERLINK_TERMS: &[&str] = &["xterm-kitty", "alacritty", "alacritty-direct", "xterm-ghostty", "foot", "foot-extra"];
+/// GNOME Terminal and other VTE terminals got OSC 8 in VTE 0.50
+const MIN_VTE_VERSION: u32 = 5000;
+/// `TERM` values of terminals known to show sixel images
+const SIXEL_TERMS: &[&str] = &["foot", "foot-extra", "mlterm", "yaft-256color", "contour"];
+
+/// Whether the terminal hint runs in makes OSC 8 hyperlinks clickable, judged from its environment.
+///
+/// Terminals that don't know the escape are supposed to ignore it, but some print it instead, so
+/// links are only written where support is known. `FORCE_HYPERLINK` overrides the guess either way.
+pub fn supports_hyperlinks(var: impl Fn(&str) -> Option<String>) -> bool {
+    if let Some(force) = var("FORCE_HYPERLINK") {
+        return force != "0";
+    }
+    let term = var("TERM").unwrap_or_default();
+    if term == "dumb" || term == "linux" {
+        return false;
+    }
+    if var("DOMTERM").is_some() || var("WT_SESSION").is_some() || var("KITTY_WINDOW_ID").is_some() {
+        return true;
+    }
+    if var("KONSOLE_VERSION").is_some() {
+        return true;
+    }
+    if var("TERM_PROGRAM").is_some_and(|program| HYPERLINK_PROGRAMS.contains(&program.as_str())) {
+        return true;
+    }
+    if var("VTE_VERSION").and_then(|version| version.parse::<u32>().ok()).is_some_and(|v| v >= MIN_VTE_VERSION) {
+        return true;
+    }
+    HYPERLINK_TERMS.contains(&term.as_str())
+}
+
+/// How a terminal can be made to show an image in its cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageProtocol {
+    /// kitty's graphics protocol, which ghostty and WezTerm speak too
+    Kitty,
+    /// iTerm2's inline images, OSC 1337
+    Iterm,
+    /// DEC sixel graphics
+    Sixel,
+}
+
+/// The image protocol the terminal hint runs in speaks, judged from its environment. Terminals
+/// answer a query for it, but only on stdin, which the key reader owns; so like hyperlinks,
+/// images are only drawn where support is known, and the config can name the protocol instead.
+pub fn image_protocol(var: impl Fn(&str) -> Option<String>) -> Option<ImageProtocol> {
+    let term = var("TERM").unwrap_or_default();
+    // tmux and screen pass none of them through without being told to
+    if var("TMUX").is_some() || term.starts_with("screen") || term.starts_with("tmux") {
+        return None;
+    }
+    let program = var("TERM_PROGRAM").unwrap_or_default();
+    if var("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" || term == "xterm-ghostty" || program == "ghostty" {
+        return Some(ImageProtocol::Kitty);
+    }
+    if program == "iTerm.app" || program == "WezTerm" {
+        return Some(ImageProtocol::Iterm);
+    }
+    SIXEL_TERMS.contains(&term.as_str()).then_some(ImageProtocol::Sixel)
+}
+
+/// `text` as a link to `url`, for terminals that support OSC 8
+pub fn hyperlink(url: &str, text: &str) -> String {
+    format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+}
+
+/// What hint shows in the terminal window title (and the tmux pane title), set in the config file
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TitleMode {
+    /// Leave the title alone
+    #[default]
+    Off,
+    /// The feed being read, e.g. "hint | Top"
+    Feed,
+    /// The selected story, or the feed while nothing is selected
+    Story,
+}
+
+/// Save the current title on the terminal's title stack, for `POP_TITLE` to put back on exit
+pub const PUSH_TITLE: &str = "\x1b[22;0t";
+pub const POP_TITLE: &str = "\x1b[23;0t";
+
+/// What hint asks of terminals with the kitty keyboard protocol: Esc, Alt and Shift combos such as
+/// Shift-Enter told apart from the sequences they share with other keys, and repeats and releases
+/// told apart from presses
+pub const KEYBOARD_FLAGS: KeyboardEnhancementFlags =
+    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES.union(KeyboardEnhancementFlags::REPORT_EVENT_TYPES);
@@ -0,0 +1,99 @@
+use ratatui::style::{
+    palette::tailwind::{AMBER, BLUE, TEAL},
+    Color, Modifier, Style,
+};
+
+/// Colours for every part of the UI, switchable at runtime with `:theme`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Pane titles and borders
+    pub header: Style,
+    pub row_bg: Color,
+    /// Every other row, so long lists are easier to follow
+    pub alt_row_bg: Color,
+    pub selected: Style,
+    /// Rows marked in visual mode
+    pub marked: Color,
+    pub text: Color,
+    /// Stories marked as read
+    pub read_text: Color,
+    /// Author and age lines of comments
+    pub meta: Style,
+    /// Code blocks in comments and posts
+    pub code: Style,
+    /// The badge on comments posted since the thread was last opened
+    pub new_badge: Style,
+}
+
+impl Theme {
+    pub const DEFAULT: Theme = Theme {
+        name: "default",
+        header: Style::new().fg(BLUE.c300).bg(BLUE.c700),
+        row_bg: BLUE.c950,
+        alt_row_bg: BLUE.c900,
+        selected: Style::new().bg(BLUE.c700).add_modifier(Modifier::BOLD),
+        marked: BLUE.c800,
+        text: BLUE.c200,
+        read_text: TEAL.c400, // Slightly shifted for better contrast with blue
+        meta: Style::new().fg(BLUE.c400).add_modifier(Modifier::BOLD),
+        code: Style::new().fg(AMBER.c200),
+        new_badge: Style::new().fg(BLUE.c950).bg(AMBER.c300).add_modifier(Modifier::BOLD),
+    };
+
+    pub const GRUVBOX: Theme = Theme {
+        name: "gruvbox",
+        header: Style::new().fg(Color::Rgb(0xfa, 0xbd, 0x2f)).bg(Color::Rgb(0x50, 0x49, 0x45)),
+        row_bg: Color::Rgb(0x28, 0x28, 0x28),
+        alt_row_bg: Color::Rgb(0x32, 0x30, 0x2f),
+        selected: Style::new().bg(Color::Rgb(0x66, 0x5c, 0x54)).add_modifier(Modifier::BOLD),
+        marked: Color::Rgb(0x45, 0x40, 0x3d),
+        text: Color::Rgb(0xeb, 0xdb, 0xb2),
+        read_text: Color::Rgb(0x8e, 0xc0, 0x7c),
+        meta: Style::new().fg(Color::Rgb(0xfe, 0x80, 0x19)).add_modifier(Modifier::BOLD),
+        code: Style::new().fg(Color::Rgb(0xb8, 0xbb, 0x26)),
+        new_badge: Style::new().fg(Color::Rgb(0x28, 0x28, 0x28)).bg(Color::Rgb(0xfa, 0xbd, 0x2f)).add_modifier(Modifier::BOLD),
+    };
+
+    pub const ALL: [Theme; 2] = [Theme::DEFAULT, Theme::GRUVBOX];
+
+    pub fn by_name(name: &str) -> Option<Theme> {
+        Self::ALL.into_iter().find(|theme| theme.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn names() -> Vec<&'static str> {
+        Self::ALL.iter().map(|theme| theme.name).collect()
+    }
+}
+
+/// Saturation and lightness of the accent each site gets, light enough to read on the dark rows of
+/// either theme; only the hue differs from site to site
+const SITE_SATURATION: f64 = 0.6;
+const SITE_LIGHTNESS: f64 = 0.68;
+
+/// The accent colour of a site's name in the list, so stories from the same site are easy to spot
+/// together. The hue comes from a hash of the name that is the same on every run and machine.
+pub fn site_colour(site: &str) -> Color {
+    // FNV-1a, as the standard library's hasher may change between Rust versions
+    let hash = site.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    let hue = (hash % 360) as f64;
+    let chroma = (1.0 - (2.0 * SITE_LIGHTNESS - 1.0).abs()) * SITE_SATURATION;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let light = SITE_LIGHTNESS - chroma / 2.0;
+    let channel = |value: f64| ((value + light) * 255.0).round() as u8;
+    Color::Rgb(channel(r), channel(g), channel(b))
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
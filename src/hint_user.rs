@@ -0,0 +1,50 @@
+use crate::hint_hackernews::HnStory;
+use crate::hnreader::{HnClient, HnError, Story};
+use std::collections::HashMap;
+use tokio::task::JoinSet;
+
+/// Most stories `fetch_submissions` lists
+pub const MAX_SUBMISSIONS: usize = 30;
+/// Latest items of a user looked through for their stories; most of what people post is comments
+const MAX_SCANNED: usize = 120;
+/// Items fetched at once
+const FETCH_BATCH: usize = 20;
+
+/// The latest stories `user` submitted, newest first, leaving out their comments and dead or
+/// deleted stories. `None` if HN has no such user; items that fail to load are left out.
+pub async fn fetch_submissions(client: &HnClient, user: &str) -> Result<Option<Vec<HnStory>>, HnError> {
+    let Some(user) = client.fetch_user(user).await? else {
+        return Ok(None);
+    };
+    let mut stories = Vec::new();
+    for batch in user.submitted[..user.submitted.len().min(MAX_SCANNED)].chunks(FETCH_BATCH) {
+        let mut fetches = JoinSet::new();
+        for id in batch.iter().copied() {
+            let client = client.clone();
+            fetches.spawn(async move { client.fetch_story_details(id).await });
+        }
+        let mut items: HashMap<u64, Story> = HashMap::new();
+        while let Some(result) = fetches.join_next().await {
+            match result {
+                Ok(Ok(item)) => {
+                    items.insert(item.id, item);
+                }
+                Ok(Err(err)) => log::warn!("Failed to fetch a submission of {}: {}", user.id, err),
+                Err(err) => log::warn!("Submission fetch task failed: {}", err),
+            }
+        }
+        // Back in the order submitted, as they land in any
+        stories.extend(batch.iter().filter_map(|id| items.remove(id)).filter(is_story).map(HnStory::from_item));
+        if stories.len() >= MAX_SUBMISSIONS {
+            break;
+        }
+    }
+    stories.truncate(MAX_SUBMISSIONS);
+    Ok(Some(stories))
+}
+
+/// Whether the item is a story, job or poll still up, not a comment or poll option
+fn is_story(item: &Story) -> bool {
+    let kind = item.kind.as_deref().unwrap_or_default();
+    matches!(kind, "story" | "job" | "poll") && item.title.is_some() && !item.dead && !item.deleted
+}
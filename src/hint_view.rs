@@ -0,0 +1,1026 @@
+use crate::hint_app::{
+    comment_matches, CommentView, Compose, ComposeTarget, Confirm, Density, DetailsLayout, DisplayListItem, Grouping, Model, Pane, Status,
+    StorySort,
+};
+use crate::hint_auth::HN_WEB_URL;
+use crate::hint_browser::{domain, story_url};
+use crate::hint_comments::{format_age, truncate, wrap_text, Comment};
+use crate::hint_hackernews::{Feed, HnStory, HnStoryType};
+use crate::hint_history::{local_time, visit_time};
+use crate::hint_html::{to_plain_text, to_styled_text};
+use crate::hint_images::IMAGE_ROWS;
+use crate::hint_keymap::{Action, Key, Mode};
+use crate::hint_scores::trend;
+use crate::hint_terminal::hyperlink;
+use crate::hint_theme::{site_colour, Theme};
+use crate::hnreader::User;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols,
+    text::{Line, Span, Text},
+    widgets::{
+        Block, Borders, Clear, HighlightSpacing, List, ListItem, ListState, Padding, Paragraph,
+        StatefulWidget, Widget, Wrap,
+    },
+    Frame,
+};
+use unicode_width::UnicodeWidthStr;
+
+/// Columns of indentation per comment nesting level
+const COMMENT_INDENT: usize = 2;
+/// Deeper replies stop moving right, so they stay readable on narrow terminals
+const MAX_COMMENT_DEPTH: usize = 8;
+/// Below this many rows the details pane is dropped so the list keeps a title and a story
+const MIN_SPLIT_HEIGHT: u16 = 4;
+/// Likewise for the details pane to the right of the list
+const MIN_SPLIT_WIDTH: u16 = 40;
+/// Most score samples drawn in the details pane, the latest ones
+const SPARKLINE_WIDTH: usize = 40;
+/// Narrowest a title gets before the site after it is left out
+const MIN_TITLE_WIDTH: usize = 20;
+/// Story rows built beyond each edge of the list pane, besides those on screen
+const LIST_OVERSCAN: usize = 5;
+
+/// Draw the whole app for the current model.
+///
+/// The model is borrowed mutably only so the list widget can keep its scroll offset between
+/// frames; everything else is read-only.
+pub fn view(model: &mut Model, frame: &mut Frame) {
+    let area = frame.area();
+    render(model, area, frame.buffer_mut());
+
+    // Show the terminal cursor at the end of whatever is being typed
+    if let Some(prompt) = &model.prompt {
+        let x = area.x + (prompt.cursor_column() as u16).min(area.width.saturating_sub(1));
+        frame.set_cursor_position((x, area.bottom().saturating_sub(1)));
+    } else if let Some(compose) = model.compose.as_ref().filter(|compose| !compose.preview) {
+        let inner = compose_area(area).inner(ratatui::layout::Margin::new(1, 1));
+        let lines = wrap_text(compose.text.text(), inner.width as usize);
+        let (row, column) = compose.text.wrapped_cursor(&lines);
+        let x = inner.x + (column as u16).min(inner.width.saturating_sub(1));
+        let y = inner.y + (row as u16).min(inner.height.saturating_sub(1));
+        frame.set_cursor_position((x, y));
+    }
+}
+
+fn render(model: &mut Model, area: Rect, buf: &mut Buffer) {
+    model.image_slots.clear();
+    let [main_area, footer_area] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(area);
+
+    let (list_area, item_area);
+
+    // Too small a terminal would leave one of the halves without a single row or column
+    let (split, fits) = match model.details_layout {
+        DetailsLayout::Below => (Direction::Vertical, main_area.height >= MIN_SPLIT_HEIGHT),
+        DetailsLayout::Right => (Direction::Horizontal, main_area.width >= MIN_SPLIT_WIDTH),
+    };
+    let show_details = model.show_details && fits;
+    if model.show_details && model.details_full_screen {
+        list_area = Rect::default();
+        item_area = main_area;
+    } else if show_details {
+        let list_share = Constraint::Percentage(model.details_split());
+        let areas: [Rect; 2] = Layout::new(split, [list_share, Constraint::Fill(1)]).areas(main_area);
+        list_area = areas[0];
+        item_area = areas[1];
+    } else {
+        let areas: [Rect; 1] = Layout::vertical([Constraint::Fill(1)]).areas(main_area);
+        list_area = areas[0];
+        item_area = Rect::default(); // Use a default value when not needed
+    }
+
+    let theme = model.theme;
+    render_footer(model, footer_area, buf);
+    if let Some(comments) = model.comments.as_mut() {
+        render_comments(comments, &theme, model.hyperlinks, !model.plain_code, main_area, buf);
+    } else {
+        // Only worth pointing out which pane has the keys while both are on screen
+        let both = !list_area.is_empty() && !item_area.is_empty();
+        let focused = model.focused_pane();
+        if !list_area.is_empty() {
+            render_list(model, both && focused == Pane::List, list_area, buf);
+        }
+        if !item_area.is_empty() {
+            render_selected_item(model, both && focused == Pane::Details, item_area, buf);
+        }
+    }
+    if let Some(compose) = &model.compose {
+        render_compose(compose, &theme, compose_area(area), buf);
+    }
+    if model.show_metrics {
+        render_metrics(model, compose_area(area), buf);
+    }
+    if let Some(confirm) = &model.confirm {
+        render_confirm(confirm, &theme, area, buf);
+    }
+    // Images are drawn over the frame, so they would cover anything on top of the reader
+    if model.compose.is_some() || model.show_metrics || model.confirm.is_some() {
+        model.image_slots.clear();
+    }
+}
+
+/// The question box, just above the footer so a reply being previewed stays readable
+fn render_confirm(confirm: &Confirm, theme: &Theme, area: Rect, buf: &mut Buffer) {
+    let answers = "y yes · n no";
+    // Borders and a space either side
+    let width = (confirm.question.width().max(answers.width()) + 4).min(area.width as usize) as u16;
+    let height = 4.min(area.height.saturating_sub(1));
+    let x = area.x + (area.width - width) / 2;
+    let y = area.bottom().saturating_sub(1 + height).max(area.y);
+    let box_area = Rect::new(x, y, width, height);
+    let block = Block::bordered().border_style(theme.header).bg(theme.row_bg);
+    let lines = vec![Line::raw(confirm.question.as_str()).bold(), Line::styled(answers, theme.meta)];
+    Clear.render(box_area, buf);
+    Paragraph::new(lines).centered().block(block).fg(theme.text).render(box_area, buf);
+}
+
+/// The debug overlay: requests, errors and latencies of each API endpoint so far, and what the
+/// disk caches hold once `:cache stats` measured them
+fn render_metrics(model: &Model, area: Rect, buf: &mut Buffer) {
+    let theme = &model.theme;
+    let block = Block::bordered()
+        .title(Line::raw("API requests").centered())
+        .border_style(theme.header)
+        .bg(theme.row_bg);
+    let mut lines = model.metrics.report();
+    if lines.is_empty() {
+        lines.push("No requests yet".to_string());
+    }
+    if !model.cache_usage.is_empty() {
+        lines.push(String::new());
+        let width = model.cache_usage.iter().map(|(cache, _)| cache.name().len()).max().unwrap_or(0);
+        lines.extend(model.cache_usage.iter().map(|(cache, usage)| format!("{:width$}  {} on disk", cache.name(), usage)));
+    }
+    Clear.render(area, buf);
+    Paragraph::new(lines.into_iter().map(Line::raw).collect::<Vec<_>>()).block(block).fg(theme.text).render(area, buf);
+}
+
+fn render_footer(model: &Model, area: Rect, buf: &mut Buffer) {
+    if let Some(prompt) = &model.prompt {
+        Paragraph::new(format!("{}{}", prompt.label, prompt.display_input())).render(area, buf);
+    } else if let Some(notice) = &model.notice {
+        Paragraph::new(notice.as_str()).centered().render(area, buf);
+    } else {
+        let hint = match (&model.compose, &model.comments) {
+            (Some(compose), _) if compose.preview => "Post this? y to post, n to keep editing.".to_string(),
+            (Some(_), _) => "Enter for a new line, Ctrl-S to preview, Esc to cancel.".to_string(),
+            (None, _) => {
+                let mut hints = key_hints(model);
+                if model.tab_count() > 1 {
+                    hints.insert(0, format!("tab {}/{} gt/gT", model.current_tab() + 1, model.tab_count()));
+                }
+                fit_hints(hints, area.width as usize)
+            }
+        };
+        Paragraph::new(hint).centered().render(area, buf);
+    }
+}
+
+/// What the keys of the current mode do, as the keymap has them, most useful first
+fn key_hints(model: &Model) -> Vec<String> {
+    let keys = |mode, actions: &[Action], label: &str| {
+        let keys: Vec<String> =
+            actions.iter().filter_map(|action| model.keymap.keys(mode, *action).first()).map(Key::to_string).collect();
+        (!keys.is_empty()).then(|| format!("{} {}", keys.join("/"), label))
+    };
+    let fixed = |hint: &str| Some(hint.to_string());
+    let hints = if model.comments.is_some() {
+        vec![
+            keys(Mode::Comments, &[Action::Down, Action::Up], "move"),
+            keys(Mode::Comments, &[Action::Fold], "fold"),
+            keys(Mode::Comments, &[Action::Reply], "reply"),
+            keys(Mode::Comments, &[Action::Back], "back"),
+        ]
+    } else if model.reader.is_some() && model.focus == Pane::Details {
+        vec![
+            keys(Mode::Details, &[Action::Down, Action::Up], "scroll"),
+            keys(Mode::Details, &[Action::PageDown, Action::PageUp], "page"),
+            keys(Mode::Details, &[Action::Find], "search"),
+            keys(Mode::List, &[Action::Reader], "close"),
+        ]
+    } else if model.show_details && model.focus == Pane::Details {
+        vec![
+            keys(Mode::Details, &[Action::Down, Action::Up], "scroll"),
+            keys(Mode::Details, &[Action::PageDown, Action::PageUp], "page"),
+            keys(Mode::Details, &[Action::OnlyAuthor, Action::Submissions], "author"),
+            keys(Mode::Details, &[Action::Discussions], "past"),
+            (!model.selected_related().is_empty()).then(|| format!("1-{} related", model.selected_related().len())),
+            keys(Mode::List, &[Action::SwitchFocus], "list"),
+        ]
+    } else if model.show_details && model.details_full_screen {
+        vec![
+            keys(Mode::List, &[Action::Down, Action::Up], "stories"),
+            keys(Mode::List, &[Action::FullScreen], "back"),
+        ]
+    } else if model.quick_open {
+        let offset = model.storylist.state.offset();
+        let last = (offset..model.storylist.items.len()).map_while(|row| model.quick_open_hint(row)).last();
+        vec![last.map(|last| format!("1-{} open", last)), fixed("any other key cancels")]
+    } else if model.storylist.visual_anchor.is_some() {
+        vec![
+            fixed("-- VISUAL --"),
+            keys(Mode::List, &[Action::MarkRead], "read"),
+            keys(Mode::List, &[Action::Bookmark], "bookmark"),
+            keys(Mode::List, &[Action::Hide], "hide"),
+            keys(Mode::List, &[Action::Snooze], "snooze"),
+            keys(Mode::List, &[Action::Open], "open"),
+            fixed("Esc leave"),
+        ]
+    } else if model.feed == Feed::Queue {
+        vec![
+            keys(Mode::List, &[Action::Down, Action::Up], "move"),
+            fixed("J/K reorder"),
+            keys(Mode::List, &[Action::MarkRead, Action::Open], "take off"),
+        ]
+    } else if model.feed == Feed::History {
+        vec![
+            keys(Mode::List, &[Action::Down, Action::Up], "move"),
+            fixed("/ search"),
+            keys(Mode::List, &[Action::Open], "open again"),
+        ]
+    } else {
+        vec![
+            keys(Mode::List, &[Action::Down, Action::Up], "move"),
+            keys(Mode::List, &[Action::Unselect], "unselect"),
+            keys(Mode::List, &[Action::ToggleStatus], "status"),
+            keys(Mode::List, &[Action::Top, Action::Bottom], "top/bottom"),
+            keys(Mode::List, &[Action::Fold], "fold group").filter(|_| model.group.is_some()),
+            keys(Mode::List, &[Action::Open], "open"),
+            keys(Mode::List, &[Action::Comments], "comments").filter(|_| {
+                model.storylist.selected_item().is_none_or(|item| item.story.has_comments())
+            }),
+            keys(Mode::List, &[Action::Quit], "quit"),
+        ]
+    };
+    hints.into_iter().flatten().collect()
+}
+
+/// Join `hints`, leaving off the last ones until they fit in `width`
+fn fit_hints(mut hints: Vec<String>, width: usize) -> String {
+    loop {
+        let line = hints.join(" · ");
+        if hints.len() <= 1 || line.width() <= width {
+            return line;
+        }
+        hints.pop();
+    }
+}
+
+fn render_comments(
+    comments: &mut CommentView,
+    theme: &Theme,
+    hyperlinks: bool,
+    highlight: bool,
+    area: Rect,
+    buf: &mut Buffer,
+) {
+    let block = Block::new()
+        .title(Line::raw(comments_title(comments)).centered())
+        .borders(Borders::TOP)
+        .border_set(symbols::border::EMPTY)
+        .border_style(theme.header)
+        .bg(theme.row_bg);
+    // One column for the highlight symbol
+    let width = area.width.saturating_sub(1) as usize;
+    let now = chrono::Utc::now().timestamp() as u64;
+
+    let items: Vec<ListItem> = match &comments.thread {
+        None => vec![ListItem::from("  Loading comments...")],
+        Some(thread) => {
+            let mut header = vec![Line::styled(thread.title.clone(), theme.text).bold()];
+            let mut byline = format!("by {}", thread.author);
+            if let Some(url) = &thread.url {
+                byline.push_str(&format!(" | {}", url));
+            }
+            header.push(Line::styled(byline, theme.meta));
+            if let Some(text) = &thread.text {
+                header.extend(to_styled_text(text, width, Style::new().fg(theme.text), theme.code, highlight).lines);
+            }
+            let mut items = vec![ListItem::new(Text::from(header)).bg(theme.alt_row_bg)];
+            items.extend(comments.visible().into_iter().enumerate().map(|(row, i)| {
+                comment_item(comments, i, theme, highlight, width, now).bg(alternate_colors(theme, row))
+            }));
+            if comments.loading_more > 0 {
+                items.push(ListItem::from(format!("  Loading {}...", count_of(comments.loading_more, "more comment", "more comments"))));
+            } else if !thread.unloaded.is_empty() {
+                let more = count_of(thread.unloaded.len(), "more comment", "more comments");
+                items.push(ListItem::from(format!("  {}, scroll down to load them", more)));
+            }
+            items
+        }
+    };
+
+    let inner = block.inner(area);
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.selected)
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always);
+    StatefulWidget::render(list, area, buf, &mut comments.state);
+
+    // Link the title and URL of the story while its header is on screen
+    if let Some(thread) = comments.thread.as_ref().filter(|_| hyperlinks && comments.state.offset() == 0) {
+        let discussion = format!("{}item?id={}", HN_WEB_URL, thread.story_id);
+        let title = Rect::new(inner.x + 1, inner.y, Span::raw(thread.title.as_str()).width() as u16, 1);
+        link_cells(buf, title.intersection(inner), thread.url.as_deref().unwrap_or(&discussion));
+        if let Some(url) = &thread.url {
+            let x = inner.x + 1 + Span::raw(format!("by {} | ", thread.author)).width() as u16;
+            let link = Rect::new(x, inner.y + 1, Span::raw(url.as_str()).width() as u16, 1);
+            link_cells(buf, link.intersection(inner), url);
+        }
+    }
+}
+
+fn comments_title(comments: &CommentView) -> String {
+    if let Some(search) = &comments.search {
+        let needle = search.to_lowercase();
+        let thread = comments.thread.as_ref();
+        let matches = comments
+            .visible()
+            .into_iter()
+            .filter(|i| thread.is_some_and(|thread| comment_matches(&thread.comments[*i], &needle)))
+            .count();
+        return format!("Comments (/{}: {}, n to jump)", search, count_of(matches, "match", "matches"));
+    }
+    match comments.new_count() {
+        0 => "Comments".to_string(),
+        n => format!("Comments ({} new, n to jump)", n),
+    }
+}
+
+/// Set every case-insensitive occurrence of `needle` in the line in reverse. Occurrences broken
+/// across lines by wrapping are not found.
+fn highlight_matches(line: Line<'static>, needle: &str) -> Line<'static> {
+    // Each character folds to one, so positions in the line and in its lowercase agree
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let needle: Vec<char> = needle.chars().map(fold).collect();
+    let chars: Vec<(char, Style)> = line
+        .spans
+        .iter()
+        .flat_map(|span| span.content.chars().map(move |c| (c, span.style)))
+        .collect();
+    if needle.is_empty() || chars.len() < needle.len() {
+        return line;
+    }
+    let lower: Vec<char> = chars.iter().map(|(c, _)| fold(*c)).collect();
+    let mut marked = vec![false; chars.len()];
+    let mut i = 0;
+    while i + needle.len() <= lower.len() {
+        if lower[i..i + needle.len()] == needle[..] {
+            marked[i..i + needle.len()].fill(true);
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    if !marked.contains(&true) {
+        return line;
+    }
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for ((c, style), marked) in chars.into_iter().zip(marked) {
+        let style = if marked { style.add_modifier(Modifier::REVERSED) } else { style };
+        match spans.last_mut().filter(|span| span.style == style) {
+            Some(span) => span.content.to_mut().push(c),
+            None => spans.push(Span::styled(c.to_string(), style)),
+        }
+    }
+    Line { spans, ..line }
+}
+
+/// "1 reply", "3 replies"
+fn count_of(count: usize, one: &str, many: &str) -> String {
+    format!("{} {}", count, if count == 1 { one } else { many })
+}
+
+/// Whether the comment is folded down to its author line, as comments of ignored users are
+/// until opened
+fn is_folded_ignored(comments: &CommentView, comment: &Comment) -> bool {
+    comments.is_ignored(comment) && comments.collapsed.contains(&comment.id)
+}
+
+/// What stands in for the parts of the comment at `index` that are not shown: folded replies,
+/// replies loading or not fetched yet
+fn comment_summary(comments: &CommentView, comment: &Comment, index: usize) -> Option<String> {
+    if is_folded_ignored(comments, comment) {
+        match comments.descendants(index) {
+            0 => Some("[ignored, Space to show]".to_string()),
+            n => Some(format!("[ignored, Space to show it and {}]", count_of(n, "reply", "replies"))),
+        }
+    } else if comments.collapsed.contains(&comment.id) {
+        Some(format!("[+ {}]", count_of(comments.descendants(index), "child", "children")))
+    } else if let Some(count) = comments.loading_replies.get(&comment.id) {
+        Some(format!("[loading {}...]", count_of(*count, "reply", "replies")))
+    } else if !comment.unloaded.is_empty() {
+        Some(format!("[+ {}]", count_of(comment.unloaded.len(), "more reply", "more replies")))
+    } else {
+        None
+    }
+}
+
+/// The comment at `index` in the thread. Comments by the author of the story get an `[OP]`
+/// badge and those posted since the last visit a `new` one. Dead and deleted comments are greyed
+/// out; the text of dead ones is still there, deleted ones only leave their place in the tree.
+fn comment_item(
+    comments: &CommentView,
+    index: usize,
+    theme: &Theme,
+    highlight: bool,
+    width: usize,
+    now: u64,
+) -> ListItem<'static> {
+    let Some(thread) = &comments.thread else {
+        return ListItem::from("");
+    };
+    let comment = &thread.comments[index];
+    let op = comment.author == thread.author && !comment.deleted;
+    let indent = " ".repeat(comment.depth.min(MAX_COMMENT_DEPTH) * COMMENT_INDENT + 1);
+    let greyed = comment.dead || comment.deleted;
+    let meta_style = if greyed { theme.meta.fg(theme.read_text) } else { theme.meta };
+    let vote = if comment.voted { "▲ " } else { "" };
+    let mut meta = vec![Span::styled(format!("{}{}{}", indent, vote, comment.author), meta_style)];
+    if op {
+        meta.push(Span::raw(" "));
+        meta.push(Span::styled("[OP]", theme.header.add_modifier(Modifier::BOLD)));
+    }
+    if let Some(time) = comment.time {
+        meta.push(Span::styled(format!(" {}", format_age(time, now)), meta_style));
+    }
+    if comment.dead {
+        meta.push(Span::styled(" [dead]", meta_style));
+    }
+    if comments.is_new(comment) {
+        meta.push(Span::raw(" "));
+        meta.push(Span::styled(" new ", theme.new_badge));
+    }
+    let mut lines = vec![Line::from(meta)];
+    let text = Style::new().fg(if greyed { theme.read_text } else { theme.text });
+    let body = if is_folded_ignored(comments, comment) {
+        Text::default()
+    } else if comment.deleted {
+        Text::styled("[deleted]", text)
+    } else {
+        // Read comments are greyed out whole, code and all
+        let code = if greyed { text } else { theme.code };
+        to_styled_text(&comment.text, width.saturating_sub(indent.len()).max(1), text, code, highlight && !greyed)
+    };
+    for mut line in body.lines {
+        line.spans.insert(0, Span::raw(indent.clone()));
+        lines.push(line);
+    }
+    if let Some(summary) = comment_summary(comments, comment, index) {
+        lines.push(Line::styled(format!("{}{}", indent, summary), theme.meta));
+    }
+    if let Some(search) = &comments.search {
+        lines = lines.into_iter().map(|line| highlight_matches(line, search)).collect();
+    }
+    ListItem::new(Text::from(lines))
+}
+
+/// Where the reply box goes: most of the screen, leaving the footer visible
+fn compose_area(area: Rect) -> Rect {
+    let [_, middle, _] = Layout::vertical([
+        Constraint::Percentage(15),
+        Constraint::Percentage(70),
+        Constraint::Percentage(15),
+    ])
+    .areas(area);
+    let [_, center, _] = Layout::horizontal([
+        Constraint::Percentage(10),
+        Constraint::Percentage(80),
+        Constraint::Percentage(10),
+    ])
+    .areas(middle);
+    center
+}
+
+fn render_compose(compose: &Compose, theme: &Theme, area: Rect, buf: &mut Buffer) {
+    let (what, mut lines) = match &compose.target {
+        ComposeTarget::Reply { replying_to, .. } => (format!("Reply to {}", replying_to), Vec::new()),
+        ComposeTarget::Submission { title, url } => {
+            let mut header = vec![Line::from(vec!["Title: ".bold(), title.clone().into()])];
+            if let Some(url) = url {
+                header.push(Line::from(vec!["URL: ".bold(), url.clone().into()]));
+            }
+            header.push(Line::raw(""));
+            ("Submit".to_string(), header)
+        }
+    };
+    let title = if compose.preview { format!("Preview: {}", what) } else { what };
+    let block = Block::bordered()
+        .title(Line::raw(title).centered())
+        .border_style(theme.header)
+        .bg(theme.row_bg);
+    let width = area.width.saturating_sub(2) as usize;
+    // The header lines are only shown in the preview, so the cursor math for editing stays simple
+    if !compose.preview {
+        lines.clear();
+    }
+    lines.extend(wrap_text(compose.text.text(), width).into_iter().map(Line::raw));
+    Clear.render(area, buf);
+    Paragraph::new(lines).block(block).fg(theme.text).render(area, buf);
+}
+
+fn render_list(model: &mut Model, focused: bool, area: Rect, buf: &mut Buffer) {
+    let area = if model.saved_searches.is_empty() {
+        area
+    } else {
+        let [tabs_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(area);
+        render_search_tabs(model, tabs_area, buf);
+        list_area
+    };
+    let theme = &model.theme;
+    let mut title = format!("HackerNews | {}", model.feed_title());
+    if let Some(site) = &model.only_domain {
+        title.push_str(&format!(" | only {}", site));
+    }
+    if !model.never_domains.is_empty() {
+        title.push_str(&format!(" | not {}", model.never_domains.join(", ")));
+    }
+    if let Some(kind) = model.only_kind {
+        title.push_str(&format!(" | only {}", kind));
+    }
+    if let Some(author) = &model.only_author {
+        title.push_str(&format!(" | by {}", author));
+    }
+    if let Some(min) = model.min_score {
+        title.push_str(&format!(" | {}+ points", min));
+    }
+    match model.sort {
+        StorySort::Rank => {}
+        StorySort::New => title.push_str(" | newest first"),
+        StorySort::Score => title.push_str(" | by points"),
+        StorySort::Comments => title.push_str(" | by comments"),
+    }
+    match model.group {
+        Some(Grouping::Domain) => title.push_str(" | grouped by site"),
+        Some(Grouping::Author) => title.push_str(" | grouped by author"),
+        None => {}
+    }
+    if model.offline {
+        title.push_str(" | offline");
+    }
+    if let Some(at) = model.cached_at {
+        title.push_str(&format!(" | cached {}", format_age(at as u64, chrono::Utc::now().timestamp() as u64)));
+    }
+    let mut block = Block::new()
+        .title(pane_title(title, focused))
+        .borders(Borders::TOP)
+        .border_set(symbols::border::EMPTY)
+        .border_style(theme.header)
+        .bg(theme.row_bg);
+    if let Some(session) = &model.hn_session {
+        block = block.title(Line::raw(format!("{} ", session.username)).right_aligned());
+    }
+
+    // Only the rows on screen, and a few either side, are built, however long the list
+    let inner = block.inner(area);
+    // Less the highlight symbol
+    let row_width = (inner.width as usize).saturating_sub(1);
+    let visible = model.storylist.visible_rows(inner.height as usize, LIST_OVERSCAN);
+    let marked = model.storylist.marked_range();
+    let spinner_frames = ["|", "/", "-", "\\"];
+    let now = chrono::Utc::now().timestamp() as u64;
+    let items: Vec<ListItem> = visible
+        .clone()
+        .map(|i| match model.storylist.items.get(i) {
+            Some(storyitem) => {
+                let color = match &marked {
+                    Some(range) if range.contains(&i) => theme.marked,
+                    _ => alternate_colors(theme, i),
+                };
+                let bookmarked = model.bookmarks.contains(storyitem.id());
+                let header = group_header(model, i);
+                story_item(storyitem, bookmarked, model.quick_open_hint(i), header, row_width, model, now).bg(color)
+            }
+            // Below the stories, a spinner while more are coming and when they last did otherwise
+            None if model.loading && model.storylist.items.is_empty() => {
+                let frame = spinner_frames[model.frame_count as usize % spinner_frames.len()];
+                ListItem::from(format!("  Loading {} stories... {}", model.feed, frame))
+            }
+            None if model.loading => {
+                let frame = spinner_frames[model.frame_count as usize % spinner_frames.len()];
+                ListItem::from(format!("  Updating... {}", frame))
+            }
+            None => ListItem::from(match model.refreshed_at {
+                Some(at) => format!("  ✓ up to date, refreshed {}", format_age(at as u64, now)),
+                None => "  ✓ up to date".to_string(),
+            }),
+        })
+        .collect();
+
+    // Create a List from the rows and highlight the currently selected one
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(theme.selected)
+        .highlight_symbol(">")
+        .highlight_spacing(HighlightSpacing::Always);
+
+    // The widget sees only the slice, so it gets positions within it and hands them back
+    let state = &mut model.storylist.state;
+    let mut slice_state = ListState::default()
+        .with_offset(state.offset() - visible.start)
+        .with_selected(state.selected().map(|selected| selected.min(visible.end - 1) - visible.start));
+    StatefulWidget::render(list, area, buf, &mut slice_state);
+    *state.offset_mut() = visible.start + slice_state.offset();
+    if state.selected().is_some() {
+        state.select(slice_state.selected().map(|selected| visible.start + selected));
+    }
+
+    if model.hyperlinks {
+        let offset = model.storylist.state.offset();
+        let dated = model.feed == Feed::Discussions;
+        let mut y = inner.y;
+        for (i, storyitem) in model.storylist.items.iter().enumerate().skip(offset) {
+            if y >= inner.bottom() {
+                break;
+            }
+            if group_header(model, i).is_some() {
+                y += 1;
+            }
+            let prefix = story_prefix(storyitem, model.bookmarks.contains(storyitem.id()), model.quick_open_hint(i));
+            let url = story_url(&storyitem.story);
+            let reading = list_reading_time(model, storyitem);
+            for title in title_lines(storyitem, &prefix, row_width, model.wrap_titles, model.density, reading, dated).0 {
+                // One column for the highlight symbol
+                let x = inner.x + 1 + prefix.width() as u16;
+                let title = Rect::new(x, y, title.width() as u16, 1).intersection(inner);
+                link_cells(buf, title, &url);
+                y += 1;
+            }
+            if model.density == Density::Comfortable {
+                y += 1;
+            }
+        }
+    }
+}
+
+/// The header over the story at row `i` if it is the first of its group:
+/// "▾ github.com · 5 stories", or "▸ github.com · 5 stories, 4 folded" when folded
+fn group_header(model: &Model, i: usize) -> Option<Line<'static>> {
+    let group = model.group?;
+    let items = &model.storylist.items;
+    let key = group.key(&items.get(i)?.story);
+    if i > 0 && group.key(&items[i - 1].story) == key {
+        return None;
+    }
+    let shown = items[i..].iter().take_while(|item| group.key(&item.story) == key).count();
+    let folded = model.storylist.folded.get(&key).map_or(0, Vec::len);
+    let theme = &model.theme;
+    let name_style = match group {
+        Grouping::Domain => theme.meta.fg(site_colour(&key)),
+        Grouping::Author => theme.meta,
+    };
+    let count = count_of(shown + folded, "story", "stories");
+    let (marker, count) = match folded {
+        0 => ("▾", format!(" · {}", count)),
+        folded => ("▸", format!(" · {}, {} folded", count, folded)),
+    };
+    Some(Line::from(vec![
+        Span::styled(format!(" {} ", marker), theme.meta),
+        Span::styled(key, name_style),
+        Span::styled(count, theme.meta.remove_modifier(Modifier::BOLD)),
+    ]))
+}
+
+/// A tab per saved search, the one showing highlighted, each with how many of its results are new
+fn render_search_tabs(model: &Model, area: Rect, buf: &mut Buffer) {
+    let theme = &model.theme;
+    let mut spans = vec![Span::styled(" Saved (s) ", theme.meta)];
+    for (i, search) in model.saved_searches.iter().enumerate() {
+        let showing = model.feed == Feed::Search && model.active_search == Some(i);
+        let style = if showing { theme.selected } else { theme.header };
+        spans.push(Span::styled(format!(" {} ", search.name), style));
+        match model.unseen_results(i) {
+            0 => {}
+            unseen => spans.push(Span::styled(format!(" {} ", unseen), theme.new_badge)),
+        }
+        spans.push(Span::raw(" "));
+    }
+    Paragraph::new(Line::from(spans)).bg(theme.row_bg).render(area, buf);
+}
+
+/// Turn one row of already drawn cells into an OSC 8 hyperlink.
+///
+/// ratatui counts the escape as visible width, so the whole link goes into the first cell and the
+/// cells it covers are skipped when drawing, rather than each cell carrying its own escape.
+fn link_cells(buf: &mut Buffer, area: Rect, url: &str) {
+    // A one-cell link would make ratatui skip the cell after it as well
+    if area.width < 2 || area.height == 0 {
+        return;
+    }
+    let mut text = String::new();
+    let mut x = area.x;
+    while x < area.right() {
+        let symbol = buf[(x, area.y)].symbol().to_string();
+        let width = (Span::raw(symbol.as_str()).width() as u16).max(1);
+        text.push_str(&symbol);
+        for covered in x.max(area.x + 1)..(x + width).min(area.right()) {
+            buf[(covered, area.y)].set_skip(true);
+        }
+        x += width;
+    }
+    buf[(area.x, area.y)].set_symbol(&hyperlink(url, &text));
+}
+
+fn render_selected_item(model: &mut Model, focused: bool, area: Rect, buf: &mut Buffer) {
+    if model.reader.is_some() {
+        return render_reader(model, focused, area, buf);
+    }
+    let theme = &model.theme;
+    // We get the info depending on the item's state.
+    let info = match model.storylist.selected_item() {
+        Some(item) => {
+            let mut details = item.story.details();
+            if let Some(minutes) = model.reading_minutes(&item.story) {
+                details.push_str(&format!(" Reading time: {} min", minutes));
+            }
+            if item.voted {
+                details.push_str(" ▲ Upvoted");
+            }
+            if item.favorite {
+                details.push_str(" ★ Favorite");
+            }
+            if model.bookmarks.contains(item.id()) {
+                details.push_str(" ⚑ Bookmarked");
+            }
+            if item.resurfaced {
+                details.push_str(" ⏰ Back from snooze");
+            }
+            let mut info = match item.status {
+                Status::Read => format!("✓ DONE: {}", details),
+                Status::Unread => format!("☐ TOREAD: {}", details),
+            };
+            if let Some(Some(user)) = model.authors.get(item.story.author()) {
+                info.push_str(&format!("\n{}", author_line(user, chrono::Utc::now().timestamp() as u64)));
+            }
+            if let Some(trend) = trend(model.scores.samples(item.id()), SPARKLINE_WIDTH) {
+                info.push_str(&format!("\n{}", trend));
+            }
+            // Job ads and Ask HN posts say what they are about in their body
+            if let Some(text) = item.story.text() {
+                info.push_str(&format!("\n\n{}", to_plain_text(text)));
+            }
+            let related = model.selected_related();
+            if !related.is_empty() {
+                info.push_str(&format!("\n\nRelated discussions, 1-{} to read:", related.len()));
+                for (i, story) in related.iter().enumerate() {
+                    info.push_str(&format!("\n{}. {} ({})", i + 1, story.title(), discussion_facts(story).join(", ")));
+                }
+            }
+            info
+        }
+        None => "Nothing selected...".to_string(),
+    };
+
+    // We show the list item's info under the list in this paragraph
+    let block = Block::new()
+        .title(pane_title("Story Details".to_string(), focused))
+        .borders(Borders::TOP)
+        .border_set(symbols::border::EMPTY)
+        .border_style(theme.header)
+        .bg(theme.row_bg)
+        .padding(Padding::horizontal(1));
+
+    // Stop scrolling once the last line reaches the top
+    let inner = block.inner(area);
+    let lines = wrap_text(&info, inner.width as usize).len();
+    model.details_scroll = model.details_scroll.min(lines.saturating_sub(1) as u16);
+
+    // We can now render the item info
+    Paragraph::new(info)
+        .block(block)
+        .fg(theme.text)
+        .wrap(Wrap { trim: false })
+        .scroll((model.details_scroll, 0))
+        .render(area, buf);
+}
+
+/// "pg: 157316 karma · account 19 years old", to size up who posted a story
+fn author_line(user: &User, now: u64) -> String {
+    format!("{}: {} karma · account {} old", user.id, user.karma, account_age(user.created, now))
+}
+
+/// How long ago an account was made, in the biggest whole unit: "3 days", "5 months", "19 years"
+fn account_age(created: u64, now: u64) -> String {
+    let days = now.saturating_sub(created) / 86_400;
+    match days {
+        0..=29 => count_of(days as usize, "day", "days"),
+        30..=364 => count_of(days as usize / 30, "month", "months"),
+        _ => count_of(days as usize / 365, "year", "years"),
+    }
+}
+
+/// The article being read, in place of the story's details, wrapped to the pane as it is now
+fn render_reader(model: &mut Model, focused: bool, area: Rect, buf: &mut Buffer) {
+    let theme = &model.theme;
+    let Some(reader) = model.reader.as_mut() else {
+        return;
+    };
+    let site = domain(&reader.url).unwrap_or_default();
+    let mut title = format!("Reader: {} | {}%", site, reader.percent());
+    if let Some(search) = &reader.search {
+        title += &format!(" | /{}: {}, n to jump", search, count_of(reader.match_count(), "match", "matches"));
+    }
+    let block = Block::new()
+        .title(pane_title(title, focused))
+        .borders(Borders::TOP)
+        .border_set(symbols::border::EMPTY)
+        .border_style(theme.header)
+        .bg(theme.row_bg)
+        .padding(Padding::horizontal(1));
+    let inner = block.inner(area);
+    let mut lines = reader.lines(inner.width as usize, inner.height as usize, theme.code, !model.plain_code);
+    if let Some(search) = &reader.search {
+        lines = lines.into_iter().map(|line| highlight_matches(line, search)).collect();
+    }
+    let slots = reader.image_slots(inner.width as usize, inner.height as usize);
+    Paragraph::new(lines).block(block).fg(theme.text).render(area, buf);
+    // The main loop draws each image over the rows left blank for it
+    model.image_slots = slots
+        .into_iter()
+        .map(|(url, row)| (url, Rect::new(inner.x, inner.y + row as u16, inner.width, IMAGE_ROWS as u16)))
+        .collect();
+}
+
+/// A pane's title, shown in reverse while the pane has the keys
+fn pane_title(title: String, focused: bool) -> Line<'static> {
+    let line = Line::raw(title).centered();
+    if focused {
+        line.reversed()
+    } else {
+        line
+    }
+}
+
+fn alternate_colors(theme: &Theme, i: usize) -> Color {
+    if i.is_multiple_of(2) {
+        theme.row_bg
+    } else {
+        theme.alt_row_bg
+    }
+}
+
+/// The status box and markers in front of a story's title; quick open puts its number where the
+/// status box goes
+fn story_prefix(value: &DisplayListItem, bookmarked: bool, hint: Option<usize>) -> String {
+    let status = match (hint, value.status) {
+        (Some(hint), _) => hint.to_string(),
+        (None, Status::Unread) => "☐".to_string(),
+        (None, Status::Read) => "✓".to_string(),
+    };
+    let vote = if value.voted { "▲ " } else { "" };
+    let favorite = if value.favorite { "★ " } else { "" };
+    let bookmark = if bookmarked { "⚑ " } else { "" };
+    let snooze = if value.resurfaced { "⏰ " } else { "" };
+    let badge = match value.story.kind() {
+        HnStoryType::Story | HnStoryType::Comment => String::new(),
+        kind => format!("[{}] ", kind),
+    };
+    format!(" {} {}{}{}{}{}", status, snooze, vote, favorite, bookmark, badge)
+}
+
+/// Minutes the story's article takes to read, if the list has a column for it
+fn list_reading_time(model: &Model, value: &DisplayListItem) -> Option<u32> {
+    model.reading_time_column.then(|| model.reading_minutes(&value.story)).flatten()
+}
+
+/// The site in parentheses after the title, like on the HN website, unless the details line has
+/// it, how long the article takes to read, and when it was opened; in `style`, the site in its own
+/// colour
+fn story_suffix(value: &DisplayListItem, density: Density, reading: Option<u32>, dated: bool, style: Style) -> Vec<Span<'static>> {
+    let (site, reading) = match density {
+        // Every discussion is of the same link, so when each was is what tells them apart
+        Density::Compact if dated => (Some(Span::styled(format!(" ({})", discussion_facts(&value.story).join(", ")), style)), None),
+        Density::Compact => (
+            value.story.url().as_deref().and_then(domain).map(|site| Span::styled(format!(" ({})", site), style.fg(site_colour(&site)))),
+            reading.map(|minutes| Span::styled(format!(" {} min", minutes), style)),
+        ),
+        Density::Comfortable => (None, None),
+    };
+    let visited = value.visited.map(|at| Span::styled(format!(" {}", visit_time(at)), style));
+    site.into_iter().chain(reading).chain(visited).collect()
+}
+
+/// `text` in `style`, with `site` in its colour if it shows in full
+fn with_site(text: String, site: Option<&str>, style: Style) -> Line<'static> {
+    let Some((site, at)) = site.and_then(|site| Some((site, text.find(&format!(" · {}", site))? + " · ".len()))) else {
+        return Line::styled(text, style);
+    };
+    let (before, rest) = text.split_at(at);
+    let (site, after) = rest.split_at(site.len());
+    Line::from(vec![
+        Span::styled(before.to_string(), style),
+        Span::styled(site.to_string(), style.fg(site_colour(site))),
+        Span::styled(after.to_string(), style),
+    ])
+}
+
+/// The second line of a story in the comfortable density:
+/// "▲ 120 · by pg · 3h ago · github.com · 7 min", or with `dated` the day it was posted and its
+/// comment count in place of its age
+fn story_meta(value: &DisplayListItem, now: u64, reading: Option<u32>, dated: bool) -> String {
+    let story = &value.story;
+    let when = match dated {
+        true => Some(discussion_facts(story).join(" · ")).filter(|facts| !facts.is_empty()),
+        false => story.time().map(|time| format_age(time, now)),
+    };
+    let parts = [
+        story.score().map(|score| format!("▲ {}", score)),
+        Some(format!("by {}", story.author())),
+        when,
+        story.url().as_deref().and_then(domain),
+        reading.map(|minutes| format!("{} min", minutes)),
+    ];
+    parts.into_iter().flatten().collect::<Vec<_>>().join(" · ")
+}
+
+/// The day a story was posted and how many comments it drew: "2019-03-04", "120 comments"
+fn discussion_facts(story: &HnStory) -> Vec<String> {
+    let day = story.time().map(|time| local_time(time as i64, "%Y-%m-%d"));
+    let comments = story.descendants().map(|count| count_of(count as usize, "comment", "comments"));
+    day.into_iter().chain(comments).collect()
+}
+
+/// The lines a story's title takes after `prefix` in a row `width` columns wide: one, cut short
+/// if need be, or two if `wrap`. Also whether the suffix still fits, as the title comes first.
+fn title_lines(
+    value: &DisplayListItem,
+    prefix: &str,
+    width: usize,
+    wrap: bool,
+    density: Density,
+    reading: Option<u32>,
+    dated: bool,
+) -> (Vec<String>, bool) {
+    let title = value.title();
+    let suffix_width: usize = story_suffix(value, density, reading, dated, Style::new()).iter().map(|part| part.width()).sum();
+    let mut title_width = width.saturating_sub(prefix.width() + suffix_width);
+    let with_suffix = title_width >= MIN_TITLE_WIDTH.min(title.width());
+    if !with_suffix {
+        title_width = width.saturating_sub(prefix.width());
+    }
+    let mut lines = if wrap { wrap_text(title, title_width) } else { Vec::new() };
+    if lines.len() <= 1 {
+        return (vec![truncate(title, title_width)], with_suffix);
+    }
+    let rest = lines.split_off(1).join(" ");
+    lines.push(truncate(&rest, title_width));
+    (lines, with_suffix)
+}
+
+/// A story's row, its title cut to fit `width` columns, or wrapped onto a second line if `wrap`,
+/// with a line of details under it in the comfortable density, and its group's `header` over it
+/// if it is the first of its group
+fn story_item(
+    value: &DisplayListItem,
+    bookmarked: bool,
+    hint: Option<usize>,
+    header: Option<Line<'static>>,
+    width: usize,
+    model: &Model,
+    now: u64,
+) -> ListItem<'static> {
+    let theme = &model.theme;
+    let prefix = story_prefix(value, bookmarked, hint);
+    let mut style = Style::new().fg(match value.status {
+        Status::Unread => theme.text,
+        Status::Read => theme.read_text,
+    });
+    // Job ads are not stories to discuss, so they look apart
+    if value.story.is_job() {
+        style = style.add_modifier(Modifier::ITALIC);
+    }
+    let reading = list_reading_time(model, value);
+    let dated = model.feed == Feed::Discussions;
+    let (titles, with_suffix) = title_lines(value, &prefix, width, model.wrap_titles, model.density, reading, dated);
+    let indent = " ".repeat(prefix.width());
+    let mut lines: Vec<Line> = titles
+        .into_iter()
+        .enumerate()
+        .map(|(i, title)| match (i, hint) {
+            // The number stands out like a link hint in a browser
+            (0, Some(_)) => Line::from(vec![Span::styled(prefix.clone(), theme.new_badge), Span::raw(title)]).style(style),
+            (0, None) => Line::styled(format!("{}{}", prefix, title), style),
+            _ => Line::styled(format!("{}{}", indent, title), style),
+        })
+        .collect();
+    let meta_style = theme.meta.remove_modifier(Modifier::BOLD);
+    if let (Some(last), true) = (lines.last_mut(), with_suffix) {
+        last.spans.extend(story_suffix(value, model.density, reading, dated, meta_style));
+    }
+    if model.density == Density::Comfortable {
+        let meta = truncate(&story_meta(value, now, reading, dated), width.saturating_sub(indent.width()));
+        let site = value.story.url().as_deref().and_then(domain);
+        lines.push(with_site(format!("{}{}", indent, meta), site.as_deref(), meta_style.add_modifier(Modifier::DIM)));
+    }
+    if let Some(header) = header {
+        lines.insert(0, header);
+    }
+    ListItem::new(lines)
+}
@@ -1,8 +1,23 @@
-use reqwest::Error;
+use reqwest::header::{ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
+use crate::hint_metrics::{self, Metrics};
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::watch;
 
-const BASE_URL: &str = "https://hacker-news.firebaseio.com/v0/";
+pub const BASE_URL: &str = "https://hacker-news.firebaseio.com/v0/";
+const DEMO_FIXTURES: &str = include_str!("../fixtures/demo.json");
+const DEFAULT_RETRIES: u32 = 2;
+const RETRY_BACKOFF: Duration = Duration::from_millis(200);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Any HN item: stories, comments, jobs and polls share this shape
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 pub struct Story {
@@ -13,50 +28,408 @@ pub struct Story {
     pub score: Option<u32>,
     pub time: Option<u64>,
     pub descendants: Option<u32>,
+    /// HTML body of comments, Ask HN posts and jobs
+    pub text: Option<String>,
+    pub parent: Option<u64>,
+    #[serde(default)]
+    pub kids: Vec<u64>,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub dead: bool,
+    #[serde(default)]
+    pub deleted: bool,
 }
 
-pub async fn fetch_top_stories() -> Result<Vec<u64>, Error> {
-    let url = format!("{BASE_URL}topstories.json");
-    let response = reqwest::get(&url).await?;
-    let story_ids: Vec<u64> = response.json().await?;
-    Ok(story_ids)
+/// An HN account, as `user/<name>.json` has it
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+pub struct User {
+    pub id: String,
+    /// When the account was made, in seconds since the epoch
+    #[serde(default)]
+    pub created: u64,
+    #[serde(default)]
+    pub karma: i64,
+    /// Its stories, comments and polls, newest first
+    #[serde(default)]
+    pub submitted: Vec<u64>,
 }
 
-pub async fn fetch_story_details(story_id: u64) -> Result<Story, Error> {
-    let url = format!("{BASE_URL}item/{story_id}.json");
-    let response = reqwest::get(&url).await?;
-    let story: Story = response.json().await?;
-    Ok(story)
+/// Items and profiles changed in the last few minutes, as `updates.json` lists them
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Updates {
+    pub items: Vec<u64>,
+    pub profiles: Vec<String>,
 }
 
-#[allow(dead_code)]
-pub async fn fetch_new_stories() -> Result<Vec<u64>, Error> {
-    let url = format!("{BASE_URL}newstories.json");
-    let response = reqwest::get(&url).await?;
-    let story_ids: Vec<u64> = response.json().await?;
-    Ok(story_ids)
+#[derive(Debug)]
+pub enum HnError {
+    Http(reqwest::Error),
+    Json(serde_json::Error),
+    Io(io::Error),
+    /// The response isn't available from an offline source such as the demo fixtures
+    NotFound(String),
+    /// The same request, made elsewhere at the same time and waited on, failed with this error
+    Coalesced(String),
 }
 
-#[allow(dead_code)]
-pub async fn fetch_ask_stories() -> Result<Vec<u64>, Error> {
-    let url = format!("{BASE_URL}askstories.json");
-    let response = reqwest::get(&url).await?;
-    let story_ids: Vec<u64> = response.json().await?;
-    Ok(story_ids)
+impl HnError {
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            HnError::Http(err) => err.status(),
+            _ => None,
+        }
+    }
 }
 
-#[allow(dead_code)]
-pub async fn fetch_show_stories() -> Result<Vec<u64>, Error> {
-    let url = format!("{BASE_URL}showstories.json");
-    let response = reqwest::get(&url).await?;
-    let story_ids: Vec<u64> = response.json().await?;
-    Ok(story_ids)
+impl fmt::Display for HnError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HnError::Http(err) => write!(f, "request failed: {}", err),
+            HnError::Json(err) => write!(f, "invalid response: {}", err),
+            HnError::Io(err) => write!(f, "{}", err),
+            HnError::NotFound(path) => write!(f, "{} is not available offline", path),
+            HnError::Coalesced(err) => write!(f, "{}", err),
+        }
+    }
 }
 
-#[allow(dead_code)]
-pub async fn fetch_job_stories() -> Result<Vec<u64>, Error> {
-    let url = format!("{BASE_URL}jobstories.json");
-    let response = reqwest::get(&url).await?;
-    let story_ids: Vec<u64> = response.json().await?;
-    Ok(story_ids)
+impl std::error::Error for HnError {}
+
+impl From<reqwest::Error> for HnError {
+    fn from(err: reqwest::Error) -> Self {
+        HnError::Http(err)
+    }
+}
+
+impl From<io::Error> for HnError {
+    fn from(err: io::Error) -> Self {
+        HnError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for HnError {
+    fn from(err: serde_json::Error) -> Self {
+        HnError::Json(err)
+    }
+}
+
+/// Where the client gets its responses from
+#[derive(Debug, Clone)]
+enum Source {
+    Network,
+    /// Responses bundled into the binary, keyed by API path
+    Fixtures(Arc<HashMap<String, serde_json::Value>>),
+    /// Responses captured by an earlier run with recording enabled
+    Replay(PathBuf),
+}
+
+/// A feed list as last fetched, sent back by the server as a 304 while its ETag still matches
+#[derive(Debug, Clone)]
+struct Cached {
+    etag: String,
+    body: Arc<[u8]>,
+}
+
+/// The response to a request in flight, once it lands, for whoever asked for the same path meanwhile
+type Shared = Option<Result<Arc<[u8]>, String>>;
+type InFlight = Mutex<HashMap<String, watch::Receiver<Shared>>>;
+
+/// Takes a request off the in-flight list when it lands, or when its task is aborted
+struct Landed<'a> {
+    inflight: &'a InFlight,
+    path: &'a str,
+}
+
+impl Drop for Landed<'_> {
+    fn drop(&mut self) {
+        self.inflight.lock().unwrap().remove(self.path);
+    }
+}
+
+/// Client for the official HN Firebase API.
+///
+/// The base URL is injectable so tests can point it at a local server. A client built with
+/// `demo()` or `replay()` never touches the network; one built `with_recording()` saves every
+/// response it gets, in the layout `replay()` reads back.
+#[derive(Debug, Clone)]
+pub struct HnClient {
+    base_url: String,
+    http: reqwest::Client,
+    retries: u32,
+    timeout: Duration,
+    source: Source,
+    record_dir: Option<PathBuf>,
+    /// Feed lists by path, shared by the clones so each feed load can ask whether it changed
+    cache: Arc<Mutex<HashMap<String, Cached>>>,
+    /// Requests on their way, shared by the clones so no two ask for the same path at once
+    inflight: Arc<InFlight>,
+    metrics: Metrics,
+}
+
+impl Default for HnClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HnClient {
+    pub fn new() -> Self {
+        Self::with_base_url(BASE_URL)
+    }
+
+    pub fn with_base_url(base_url: &str) -> Self {
+        let mut base_url = base_url.to_string();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+        Self {
+            base_url,
+            http: reqwest::Client::new(),
+            retries: DEFAULT_RETRIES,
+            timeout: DEFAULT_TIMEOUT,
+            source: Source::Network,
+            record_dir: None,
+            cache: Arc::default(),
+            inflight: Arc::default(),
+            metrics: Metrics::default(),
+        }
+    }
+
+    /// A client serving the deterministic demo data set, keyed by API path
+    pub fn demo() -> Self {
+        let fixtures = serde_json::from_str(DEMO_FIXTURES).expect("demo fixtures are valid JSON");
+        Self {
+            source: Source::Fixtures(Arc::new(fixtures)),
+            ..Self::new()
+        }
+    }
+
+    /// A client serving responses captured in `dir` by a recording client
+    pub fn replay(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            source: Source::Replay(dir.into()),
+            ..Self::new()
+        }
+    }
+
+    /// Save every response fetched from the network under `dir`, one file per API path
+    pub fn with_recording(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.record_dir = Some(dir.into());
+        self
+    }
+
+    pub fn is_demo(&self) -> bool {
+        matches!(self.source, Source::Fixtures(_))
+    }
+
+    /// Whether responses come from a directory saved earlier, as with `--offline`, so nothing
+    /// else should go to the network either
+    pub fn is_offline(&self) -> bool {
+        matches!(self.source, Source::Replay(_))
+    }
+
+    /// Unix time the response for `path` was saved, for a client replaying a directory
+    pub fn saved_at(&self, path: &str) -> Option<i64> {
+        let Source::Replay(dir) = &self.source else {
+            return None;
+        };
+        let modified = std::fs::metadata(dir.join(path)).and_then(|meta| meta.modified()).ok()?;
+        Some(modified.duration_since(std::time::UNIX_EPOCH).ok()?.as_secs() as i64)
+    }
+
+    /// Number of extra attempts for requests that fail with a connection error or a 5xx
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Longest a single attempt may take before it fails, and is retried like a connection error
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Counts and latencies of the requests made over the network, by endpoint
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> Result<T, HnError> {
+        match &self.source {
+            Source::Network => {}
+            Source::Fixtures(fixtures) => {
+                let value = fixtures
+                    .get(path)
+                    .ok_or_else(|| HnError::NotFound(path.to_string()))?;
+                return Ok(T::deserialize(value)?);
+            }
+            Source::Replay(dir) => {
+                let body = match tokio::fs::read(dir.join(path)).await {
+                    Ok(body) => body,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        return Err(HnError::NotFound(path.to_string()))
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+                return Ok(serde_json::from_slice(&body)?);
+            }
+        }
+
+        let body = self.fetch_shared(path).await?;
+        Ok(serde_json::from_slice(&body)?)
+    }
+
+    /// Fetch `path`, or wait for the request already fetching it and share its response, so the
+    /// list, the comments and the score sampling asking for one item cost a single request
+    async fn fetch_shared(&self, path: &str) -> Result<Arc<[u8]>, HnError> {
+        loop {
+            let (tx, mut rx) = watch::channel(None);
+            let leading = {
+                let mut inflight = self.inflight.lock().unwrap();
+                match inflight.get(path) {
+                    Some(running) => {
+                        rx = running.clone();
+                        false
+                    }
+                    None => {
+                        inflight.insert(path.to_string(), rx.clone());
+                        true
+                    }
+                }
+            };
+            if leading {
+                let _landed = Landed { inflight: &self.inflight, path };
+                let result = self.fetch_body(path).await;
+                tx.send_replace(Some(result.as_ref().map(Arc::clone).map_err(|err| err.to_string())));
+                return result;
+            }
+            // A request aborted along with its feed leaves nothing to share, so go again
+            let shared = rx.wait_for(Option::is_some).await.ok().and_then(|shared| shared.clone());
+            match shared {
+                Some(Ok(body)) => return Ok(body),
+                Some(Err(err)) => return Err(HnError::Coalesced(err)),
+                None => {}
+            }
+        }
+    }
+
+    async fn fetch_body(&self, path: &str) -> Result<Arc<[u8]>, HnError> {
+        let url = format!("{}{}", self.base_url, path);
+        let cached = self.cached(path);
+        let mut attempt = 0;
+        loop {
+            let mut request = self.http.get(&url).timeout(self.timeout);
+            if is_polled(path) {
+                // Firebase only sends ETags when asked to
+                request = request.header("X-Firebase-ETag", "true");
+            }
+            if let Some(cached) = &cached {
+                request = request.header(IF_NONE_MATCH, &cached.etag);
+            }
+            let started = Instant::now();
+            let result = match request.send().await {
+                Ok(response) => response.error_for_status(),
+                Err(err) => Err(err),
+            };
+            self.metrics.record(hint_metrics::endpoint(path), started.elapsed(), result.is_ok());
+            // Unchanged since the cached copy, so the body is empty and the copy is the answer
+            let not_modified = matches!(&result, Ok(response) if response.status() == StatusCode::NOT_MODIFIED);
+            if let (true, Some(cached)) = (not_modified, &cached) {
+                return Ok(cached.body.clone());
+            }
+            match result {
+                Ok(response) => {
+                    let etag = response.headers().get(ETAG).and_then(|etag| etag.to_str().ok()).map(str::to_string);
+                    let body = response.bytes().await?;
+                    if let Some(dir) = &self.record_dir {
+                        record_response(dir, path, &body).await;
+                    }
+                    let body: Arc<[u8]> = body.to_vec().into();
+                    if let Some(etag) = etag.filter(|_| is_polled(path)) {
+                        self.cache.lock().unwrap().insert(path.to_string(), Cached { etag, body: body.clone() });
+                    }
+                    return Ok(body);
+                }
+                Err(err) if attempt < self.retries && is_transient(&err) => {
+                    attempt += 1;
+                    tokio::time::sleep(RETRY_BACKOFF * attempt).await;
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    fn cached(&self, path: &str) -> Option<Cached> {
+        self.cache.lock().unwrap().get(path).cloned()
+    }
+
+    pub async fn fetch_top_stories(&self) -> Result<Vec<u64>, HnError> {
+        self.get_json("topstories.json").await
+    }
+
+    pub async fn fetch_story_details(&self, story_id: u64) -> Result<Story, HnError> {
+        self.get_json(&format!("item/{story_id}.json")).await
+    }
+
+    pub async fn fetch_new_stories(&self) -> Result<Vec<u64>, HnError> {
+        self.get_json("newstories.json").await
+    }
+
+    pub async fn fetch_best_stories(&self) -> Result<Vec<u64>, HnError> {
+        self.get_json("beststories.json").await
+    }
+
+    pub async fn fetch_ask_stories(&self) -> Result<Vec<u64>, HnError> {
+        self.get_json("askstories.json").await
+    }
+
+    pub async fn fetch_show_stories(&self) -> Result<Vec<u64>, HnError> {
+        self.get_json("showstories.json").await
+    }
+
+    pub async fn fetch_job_stories(&self) -> Result<Vec<u64>, HnError> {
+        self.get_json("jobstories.json").await
+    }
+
+    /// The account called `name`, `None` if HN has none
+    pub async fn fetch_user(&self, name: &str) -> Result<Option<User>, HnError> {
+        self.get_json(&format!("user/{name}.json")).await
+    }
+
+    pub async fn fetch_updates(&self) -> Result<Updates, HnError> {
+        self.get_json("updates.json").await
+    }
+}
+
+/// Feed lists are fetched again on every visit and refresh, items mostly once, so only the lists
+/// are worth keeping for conditional requests
+fn is_polled(path: &str) -> bool {
+    !path.starts_with("item/")
+}
+
+/// Failing to record must not break the session, so errors are only logged
+async fn record_response(dir: &Path, path: &str, body: &[u8]) {
+    let file = dir.join(path);
+    if let Some(parent) = file.parent() {
+        if let Err(err) = tokio::fs::create_dir_all(parent).await {
+            log::warn!("Failed to create {}: {}", parent.display(), err);
+            return;
+        }
+    }
+    if let Err(err) = tokio::fs::write(&file, body).await {
+        log::warn!("Failed to record {}: {}", file.display(), err);
+    }
+}
+
+/// Connection problems and server errors are worth retrying, client errors and bad JSON are not
+fn is_transient(err: &reqwest::Error) -> bool {
+    err.is_connect()
+        || err.is_timeout()
+        || err.status().is_some_and(|status| status.is_server_error())
 }
@@ -0,0 +1,39 @@
+pub mod hnreader;
+pub mod hint_app;
+pub mod hint_article;
+pub mod hint_auth;
+pub mod hint_bookmarks;
+pub mod hint_browser;
+pub mod hint_cache;
+pub mod hint_clipboard;
+pub mod hint_command;
+pub mod hint_comments;
+pub mod hint_config;
+pub mod hint_digest;
+pub mod hint_export;
+pub mod hint_fetch;
+pub mod hint_hackernews;
+pub mod hint_hidden;
+pub mod hint_highlight;
+pub mod hint_history;
+pub mod hint_hooks;
+pub mod hint_html;
+pub mod hint_ignored;
+pub mod hint_images;
+pub mod hint_input;
+pub mod hint_keymap;
+pub mod hint_log;
+pub mod hint_metrics;
+pub mod hint_positions;
+pub mod hint_prefetch;
+pub mod hint_reader;
+pub mod hint_scores;
+pub mod hint_search;
+pub mod hint_seen;
+pub mod hint_session;
+pub mod hint_snooze;
+pub mod hint_store;
+pub mod hint_terminal;
+pub mod hint_theme;
+pub mod hint_user;
+pub mod hint_view;
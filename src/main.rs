@@ -1,353 +1,1242 @@
+use clap::{Args, Parser, Subcommand};
 use color_eyre::Result;
-use hint_hackernews::HnStory;
-use ratatui::{
-    buffer::Buffer,
-    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind},
-    layout::{Constraint, Layout, Rect},
-    style::{
-        palette::tailwind::{BLUE, GREEN, SLATE, TEAL},
-        Color, Modifier, Style, Stylize,
-    },
-    symbols,
-    text::Line,
-    widgets::{
-        Block, Borders, HighlightSpacing, List, ListItem, ListState, Padding, Paragraph,
-        StatefulWidget, Widget, Wrap,
-    },
-    DefaultTerminal,
+use ratatui::crossterm::event::{
+    self, DisableBracketedPaste, EnableBracketedPaste, Event, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
 };
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen, SetTitle};
+use ratatui::layout::Rect;
+use ratatui::DefaultTerminal;
+use std::collections::HashSet;
+use std::io::{self, BufRead, Write};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-mod hnreader;
-mod hint_hackernews;
-mod hint_log;
-use crate::hint_log::init_debug_log;
-use crate::hint_log::log_debug_info;
-
-const HEADER_STYLE: Style = Style::new().fg(BLUE.c300).bg(BLUE.c700);
-const NORMAL_ROW_BG: Color = BLUE.c950;
-const ALT_ROW_BG_COLOR: Color = BLUE.c900;
-const SELECTED_STYLE: Style = Style::new().bg(BLUE.c700).add_modifier(Modifier::BOLD);
-const TEXT_FG_COLOR: Color = BLUE.c200;
-const COMPLETED_TEXT_FG_COLOR: Color = TEAL.c400; // Slightly shifted for better contrast with blue
-
-use tokio::sync::{Mutex};
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{mpsc, watch, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::MissedTickBehavior;
+use hint::hint_app::{update, Cmd, ComposeTarget, Model, Msg};
+use hint::hint_article;
+use hint::hint_auth::{self, HnSession, HnWeb};
+use hint::hint_bookmarks::Bookmarks;
+use hint::hint_browser;
+use hint::hint_cache::{api_cache_dir, ArticleCache, CacheUsage, DiskCache, FrontPage, StoryCache};
+use hint::hint_clipboard;
+use hint::hint_comments;
+use hint::hint_config::Config;
+use hint::hint_digest::{self, DigestOptions};
+use hint::hint_export::ExportFormat;
+use hint::hint_fetch::{FetchQueue, FetchTask};
+use hint::hint_hackernews::{Feed, HnStory, HnStoryList, StoriesPerFeed};
+use hint::hint_hidden::HiddenStories;
+use hint::hint_history::History;
+use hint::hint_hooks;
+use hint::hint_ignored::IgnoredUsers;
+use hint::hint_images::{self, ImageWriter, DEFAULT_CELL, KITTY_FORGET};
+use hint::hint_keymap::Keymap;
+use hint::hint_log::{init_debug_log, LogConfig};
+use hint::hint_positions::ReadingPositions;
+use hint::hint_prefetch::{Prefetch, PREFETCH_DELAY};
+use hint::hint_scores::{self, ScoreHistory};
+use hint::hint_search::{self, SeenResults};
+use hint::hint_seen::SeenComments;
+use hint::hint_session::Session;
+use hint::hint_snooze::{self, Snoozed};
+use hint::hint_store::Store;
+use hint::hint_terminal::{ImageProtocol, TitleMode, KEYBOARD_FLAGS, POP_TITLE, PUSH_TITLE};
+use hint::hint_user;
+use hint::hnreader::HnClient;
+use hint::hint_log::log_debug_info;
+use hint::hint_view::view;
+
+/// Timers such as snoozes count in ticks; animations have their own, configurable, frames
+const TICK_INTERVAL: Duration = Duration::from_millis(100);
+const STORY_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+/// How long the key reader waits for input before checking whether it should stand aside
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The feed showing and the ranks of its stories on or near the screen, see `Model::fetch_window`
+type FetchWindow = (Feed, Range<usize>);
+
+/// Fetches the stories of the feed showing, and only of that one
+struct FeedFetcher {
+    window: watch::Receiver<FetchWindow>,
+    task: FetchTask,
+    sizes: StoriesPerFeed,
+    /// Fetches ahead for the selected story; a new selection drops the one before
+    prefetch: FetchTask,
+    /// Articles downloaded at once for the disk cache
+    article_downloads: usize,
+    /// Bytes each disk cache may take, for those the config limits
+    cache_limits: Vec<(DiskCache, u64)>,
+}
+
+impl FeedFetcher {
+    fn cache_limit(&self, cache: DiskCache) -> Option<u64> {
+        self.cache_limits.iter().find(|(limited, _)| *limited == cache).map(|(_, max)| *max)
+    }
+}
+
+/// A terminal reader for Hacker News
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Cli {
+    /// Show a bundled, deterministic set of stories and comments instead of fetching from HN
+    #[arg(long, conflicts_with_all = ["record", "replay"])]
+    demo: bool,
+
+    /// Never touch the network: show feeds, stories, comments and articles as last saved
+    #[arg(long, conflicts_with_all = ["demo", "record", "replay"])]
+    offline: bool,
+
+    /// Save every HN API response under DIR, for replaying the session later
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    record: Option<PathBuf>,
+
+    /// Serve HN API responses from a directory written by --record instead of the network
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Write the debug log to PATH instead of ./hint.log
+    #[arg(long, value_name = "PATH")]
+    log_file: Option<PathBuf>,
+
+    /// Rotate the debug log once it reaches this many KiB
+    #[arg(long, value_name = "KIB")]
+    log_max_kb: Option<u64>,
+
+    /// Number of rotated debug logs to keep
+    #[arg(long, value_name = "N")]
+    log_keep: Option<usize>,
+
+    /// List this many stories of every feed, over `stories_per_feed` in the config
+    #[arg(long, value_name = "N")]
+    stories_per_feed: Option<usize>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Print a ranked summary of recent top stories and exit, e.g. from cron
+    Digest(DigestArgs),
+}
+
+#[derive(Args, Debug)]
+struct DigestArgs {
+    /// Only stories posted within this long, like 12h or 2d
+    #[arg(long, default_value = "24h", value_parser = parse_since)]
+    since: String,
+
+    /// Leave out stories with fewer points
+    #[arg(long, default_value_t = 100)]
+    min_score: u32,
+
+    /// Keep stories whose titles contain WORD whatever their score; repeatable
+    #[arg(long = "watch", value_name = "WORD")]
+    watches: Vec<String>,
+
+    /// Most stories to list
+    #[arg(long, default_value_t = 30)]
+    limit: usize,
+
+    /// markdown, json or csv
+    #[arg(long, default_value = "markdown", value_parser = parse_format)]
+    format: ExportFormat,
+
+    /// Write to PATH instead of standard output
+    #[arg(long, value_name = "PATH")]
+    output: Option<PathBuf>,
+}
+
+fn parse_since(since: &str) -> Result<String, String> {
+    match hint_snooze::parse_duration(since) {
+        Some(_) => Ok(since.to_string()),
+        None => Err("expected a duration like 30m, 24h or 7d".to_string()),
+    }
+}
+
+fn parse_format(format: &str) -> Result<ExportFormat, String> {
+    ExportFormat::from_name(format).ok_or_else(|| "expected markdown, json or csv".to_string())
+}
+
+impl Cli {
+    fn log_config(&self) -> LogConfig {
+        let mut config = LogConfig::default();
+        if let Some(path) = &self.log_file {
+            config.path = path.clone();
+        }
+        if let Some(kb) = self.log_max_kb {
+            config.max_bytes = kb * 1024;
+        }
+        if let Some(keep) = self.log_keep {
+            config.keep = keep;
+        }
+        config
+    }
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    init_debug_log();
+    let cli = Cli::parse();
+    init_debug_log(cli.log_config());
     color_eyre::install()?;
 
-    let mut terminal = ratatui::init();
-    let mut hintapp = App::default();
+    let store = Store::open_default();
+    let mut config = Config::load(&store);
+    if let Some(count) = cli.stories_per_feed {
+        config.stories_per_feed = StoriesPerFeed::Every(count);
+    }
+    let client = if cli.demo {
+        HnClient::demo()
+    } else if let Some(dir) = cli.replay {
+        HnClient::replay(dir)
+    } else if cli.offline {
+        HnClient::replay(api_cache_dir(&store))
+    } else if let Some(dir) = cli.record {
+        HnClient::new().with_recording(dir)
+    } else {
+        // Saved for `--offline`
+        HnClient::new().with_recording(api_cache_dir(&store))
+    };
+    let client = client.with_timeout(config.request_timeout());
 
-    // Create a new HnStoryList wrapped in Arc<Mutex<>>
-    let story_list = Arc::new(Mutex::new(hint_hackernews::HnStoryList::new().await));
+    if let Some(Command::Digest(args)) = cli.command {
+        return digest(&client, args).await;
+    }
 
-    // Create an mpsc channel for communication
-    let (tx, mut rx) = mpsc::channel::<HnStory>(100);
+    let session = Session::load(&store);
 
-    for story in story_list.lock().await.iter() {
-        hintapp
-            .storylist
-            .append_item(DisplayListItem::from_hnstory(story.clone()));
+    let mut terminal = ratatui::init();
+    // Pasted text arrives whole instead of as keys, which would run as commands in the list
+    execute!(io::stdout(), EnableBracketedPaste)?;
+    // Asked before the key reader starts, as the answer comes in like a key would; terminals
+    // without the protocol keep sending the legacy keys
+    let kitty_keyboard = config.kitty_keyboard() && supports_keyboard_enhancement().unwrap_or(false);
+    if kitty_keyboard {
+        execute!(io::stdout(), PushKeyboardEnhancementFlags(KEYBOARD_FLAGS))?;
     }
+    let mut model = Model::restore(&session);
+    model.hidden = HiddenStories::load(&store);
+    model.ignored = IgnoredUsers::load(&store);
+    model.seen = SeenComments::load(&store);
+    model.seen_results = SeenResults::load(&store);
+    model.bookmarks = Bookmarks::load(&store);
+    model.snoozed = Snoozed::load(&store);
+    model.history = History::load(&store);
+    model.positions = ReadingPositions::load(&store);
+    model.scores = ScoreHistory::load(&store);
+    model.story_cache = StoryCache::load(&store);
+    model.feed_defaults = config.feeds.clone();
+    model.use_feed_defaults();
+    // Last time's front page shows at once, until the fresh one replaces it
+    model.show_front_page(&FrontPage::load(&store));
+    let animation_interval = config.animation_interval();
+    let startup_deadline = config.startup_deadline();
+    let article_downloads = config.prefetch_concurrency();
+    let cache_limits: Vec<_> =
+        DiskCache::ALL.into_iter().filter_map(|cache| Some((cache, config.cache_limit(cache)?))).collect();
+    model.hyperlinks = config.hyperlinks();
+    model.inline_images = config.image_protocol();
+    model.title_mode = config.title;
+    model.details_layout = config.details;
+    model.ignore_mode = config.ignored_comments;
+    // A split picked with `<` and `>` last time wins over the configured one
+    model.details_split = model.details_split.or(config.details_split);
+    model.hooks = config.hooks;
+    model.open_with = config.open_with;
+    model.saved_searches = config.searches;
+    model.max_stories = config.max_stories;
+    model.wrap_titles = config.wrap_titles;
+    model.reading_time_column = config.reading_time_column;
+    model.plain_code = config.plain_code;
+    model.prefetch = config.prefetch;
+    model.prefetch_articles = config.prefetch_articles;
+    model.offline = cli.offline;
+    model.keymap = Keymap::with_keys(&config.keys);
+    model.metrics = client.metrics().clone();
+    model.refresh_queue();
+    model.refresh_history();
+    model.hn_session = tokio::task::spawn_blocking(hint_auth::load_session)
+        .await
+        .unwrap_or(None);
+    let web = HnWeb::new();
 
-    // Start the update thread
-    {
-        let story_list_clone = Arc::clone(&story_list);
-        tokio::spawn(async move {
-            let mut locked_list = story_list_clone.lock().await;
-            locked_list.start_update_thread_with_callback(tx.clone());
+    // Every source of change (input, background fetches, timers) feeds this one channel
+    let (tx, mut rx) = mpsc::channel::<Msg>(100);
+    // Tells the story fetchers what is on screen, so they fetch that first
+    let (window_tx, window) = watch::channel((model.feed, 0..0));
+    let mut fetcher = FeedFetcher {
+        window,
+        task: FetchTask::default(),
+        sizes: config.stories_per_feed.clone(),
+        prefetch: FetchTask::default(),
+        article_downloads,
+        cache_limits,
+    };
+    // What earlier runs saved is brought back under the limits, out of the way of the first feed
+    for &(cache, max) in &fetcher.cache_limits {
+        tokio::task::spawn_blocking({
+            let store = store.clone();
+            move || trim_cache(&store, cache, max)
         });
     }
+    // The screen comes up right away and the first feed streams into it, unless the network
+    // keeps it waiting past the deadline
+    model.loading = true;
+    let first_load = FeedLoad {
+        session: model.hn_session.clone(),
+        deadline: Some(startup_deadline),
+        ..fetcher.load(model.feed)
+    };
+    fetcher.task.start(load_feed(client.clone(), web.clone(), first_load, fetcher.window.clone(), tx.clone()));
+    // Set while a child program such as `less` owns the terminal
+    let input_paused = Arc::new(AtomicBool::new(false));
+    spawn_event_reader(tx.clone(), input_paused.clone());
+    spawn_ticker(tx.clone(), animation_interval);
 
-    // Main TUI loop
-    loop {
-        // Process received updates
-        if let Some(updated_story) = rx.recv().await {
-            // Add the received story to the display list
-            hintapp.storylist.append_item(DisplayListItem::from_hnstory(updated_story));
-        }
-
-        terminal.draw(|frame| {
-            let size = frame.area();
-            hintapp.render(size, frame.buffer_mut());
-        })?;
+    if model.title_mode != TitleMode::Off {
+        print!("{}", PUSH_TITLE);
+    }
+    // Last title sent to the terminal, so it is only written when it changes
+    let mut window_title = None;
+    let mut images = model.inline_images.map(|protocol| (ImageWriter::new(protocol), Vec::new()));
 
-        if let Event::Key(key) = event::read()? {
-            hintapp.handle_key(key);
-        };
+    // Main TUI loop: draw the model if it changed, then block until something changes it
+    while !model.should_exit {
+        if model.needs_redraw() {
+            terminal.draw(|frame| view(&mut model, frame))?;
+            if let Some((writer, shown)) = images.as_mut() {
+                draw_images(&mut terminal, &mut model, writer, shown)?;
+            }
+            model.mark_drawn();
+        }
+        let rows = terminal.size()?.height as usize;
+        let fetch_window = (model.feed, model.fetch_window(rows));
+        window_tx.send_if_modified(|window| {
+            let changed = *window != fetch_window;
+            *window = fetch_window;
+            changed
+        });
+        let title = model.window_title();
+        if title != window_title {
+            if let Some(title) = &title {
+                execute!(io::stdout(), SetTitle(title))?;
+            }
+            window_title = title;
+        }
 
-        // Check if the app should exit
-        if hintapp.should_exit {
+        let Some(msg) = rx.recv().await else {
             break;
+        };
+        let feed = model.feed;
+        let cmd = update(&mut model, msg);
+        // Stories of a feed no longer showing would only use up bandwidth and rate limits; a
+        // feed that is fetched gets a new task from its `LoadFeed`
+        if model.feed != feed {
+            fetcher.task.stop();
+        }
+        match cmd {
+            Some(cmd @ (Cmd::Pipe { .. } | Cmd::ViewText { .. } | Cmd::Suspend)) => {
+                let run = || run_on_terminal(cmd);
+                if let Some(msg) = with_terminal_suspended(&mut terminal, &input_paused, kitty_keyboard, run)? {
+                    update(&mut model, msg);
+                }
+                // The child may have set a title of its own, and left its output on the screen
+                window_title = None;
+                model.force_redraw();
+            }
+            Some(cmd) => run_cmd(cmd, &client, &web, &store, &mut fetcher, &tx),
+            None => {}
         }
-
-        // Short delay to prevent excessive CPU usage
-        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
     }
 
+    fetcher.task.stop();
+    if model.inline_images == Some(ImageProtocol::Kitty) {
+        print!("{}", KITTY_FORGET);
+    }
+    if kitty_keyboard {
+        execute!(io::stdout(), PopKeyboardEnhancementFlags)?;
+    }
+    execute!(io::stdout(), DisableBracketedPaste)?;
     ratatui::restore();
+    if model.title_mode != TitleMode::Off {
+        print!("{}", POP_TITLE);
+        io::stdout().flush()?;
+    }
+    if let Err(err) = model.session().save(&store) {
+        log_debug_info("Failed to save session: ", format_args!("{}", err));
+    }
+    if let Some(page) = model.front_page() {
+        if let Err(err) = page.save(&store) {
+            log_debug_info("Failed to save front page: ", format_args!("{}", err));
+        }
+    }
+    // Like the command history, visits are written once on the way out rather than on every open
+    if let Err(err) = model.history.save(&store) {
+        log_debug_info("Failed to save history: ", format_args!("{}", err));
+    }
+    // Reading positions too
+    model.remember_positions();
+    if let Err(err) = model.positions.save(&store) {
+        log_debug_info("Failed to save reading positions: ", format_args!("{}", err));
+    }
+    if config.log_metrics {
+        for line in model.metrics.report() {
+            log_debug_info("API requests: ", format_args!("{}", line));
+        }
+    }
     Ok(())
 }
 
-/// This struct holds the current state of the app. In particular, it has the `list` field
-/// which is a wrapper around `ListState`. Keeping track of the state lets us render the
-/// associated widget with its state and have access to features such as natural scrolling.
-///
-/// Check the event handling at the bottom to see how to change the state on incoming events. Check
-/// the drawing logic for items on how to specify the highlighting style for selected items.
-struct App {
-    should_exit: bool,
-    show_details: bool,
-    storylist: DisplayList,
-    tick_count: u32,
-}
-
-struct DisplayList {
-    items: Vec<DisplayListItem>,
-    state: ListState,
-}
-
-#[derive(Debug)]
-struct DisplayListItem {
-    title: String,
-    details: String,
-    status: Status,
+/// Headless mode: print the digest and exit without touching the terminal
+async fn digest(client: &HnClient, args: DigestArgs) -> Result<()> {
+    let options = DigestOptions {
+        since: hint_snooze::parse_duration(&args.since).expect("checked by clap"),
+        min_score: args.min_score,
+        watches: args.watches,
+        limit: args.limit,
+    };
+    let stories = hint_digest::fetch_candidates(client).await?;
+    let stories = hint_digest::select(stories, &options, hint_snooze::now());
+    let contents = hint_digest::render(args.format, &args.since, &stories);
+    match args.output {
+        Some(path) => std::fs::write(path, contents)?,
+        None => print!("{}", contents),
+    }
+    Ok(())
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-enum Status {
-    Unread,
-    Read,
+/// Give the terminal to a child program: stop reading keys, leave raw mode and the alternate
+/// screen, and take all of it back once `run` returns. The child gets the legacy keys, as it may
+/// not know the kitty keyboard protocol.
+fn with_terminal_suspended<T>(
+    terminal: &mut DefaultTerminal,
+    input_paused: &AtomicBool,
+    kitty_keyboard: bool,
+    run: impl FnOnce() -> T,
+) -> io::Result<T> {
+    input_paused.store(true, Ordering::SeqCst);
+    // Let a poll that is already waiting run out, so it cannot swallow the child's first key
+    std::thread::sleep(INPUT_POLL_INTERVAL);
+    if kitty_keyboard {
+        execute!(io::stdout(), PopKeyboardEnhancementFlags)?;
+    }
+    execute!(io::stdout(), DisableBracketedPaste)?;
+    ratatui::restore();
+    let result = run();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableBracketedPaste)?;
+    if kitty_keyboard {
+        execute!(io::stdout(), PushKeyboardEnhancementFlags(KEYBOARD_FLAGS))?;
+    }
+    terminal.clear()?;
+    input_paused.store(false, Ordering::SeqCst);
+    Ok(result)
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self {
-            show_details: false,
-            should_exit: false,
-            storylist: DisplayList::from_iter([]),
-            tick_count: 0,
+/// Carry out a side effect that takes over the terminal, see `with_terminal_suspended`
+fn run_on_terminal(cmd: Cmd) -> Option<Msg> {
+    match cmd {
+        Cmd::Pipe { command, input } => pipe_through(&command, &input),
+        Cmd::ViewText { text, editor } => view_text(&text, editor),
+        Cmd::Suspend => {
+            suspend();
+            None
         }
+        _ => None,
     }
 }
 
-impl DisplayList {
-    fn from_iter<I: IntoIterator<Item = (Status, &'static str, &'static str)>>(iter: I) -> Self {
-        let items = iter
-            .into_iter()
-            .map(|(status, title, details)| DisplayListItem::new(status, title, details))
-            .collect();
-        let state = ListState::default();
-        Self { items, state }
+/// Stop the whole process group, returning once the shell's `fg` continues it
+#[cfg(unix)]
+fn suspend() {
+    // SAFETY: `kill` only sends a signal; SIGTSTP keeps its default action of stopping us
+    unsafe {
+        libc::kill(0, libc::SIGTSTP);
     }
+}
+
+/// No job control outside of Unix, so there is nothing to suspend to
+#[cfg(not(unix))]
+fn suspend() {}
 
-    fn append_item(&mut self, item: DisplayListItem) {
-        self.items.push(item);
+/// Run a `|` command and wait for Enter, so output of commands like `wc` stays readable
+fn pipe_through(command: &str, input: &str) -> Option<Msg> {
+    let result = hint_hooks::pipe(command, input);
+    print!("\nPress Enter to return to hint");
+    let _ = io::stdout().flush();
+    let _ = io::stdin().lock().read_line(&mut String::new());
+    match result {
+        Ok(status) if status.success() => None,
+        Ok(status) => Some(Msg::Notify(format!("{} exited with {}", command, status))),
+        Err(err) => Some(Msg::Notify(format!("Could not run {}: {}", command, err))),
     }
 }
 
-impl DisplayListItem {
-    fn new(status: Status, title: &str, details: &str) -> Self {
-        Self {
-            status,
-            title:title.to_string(),
-            details: details.to_string(),
-        }
+/// Show text in `$PAGER` (default `less`) or `$EDITOR` (default `vi`) through a temporary file
+fn view_text(text: &str, editor: bool) -> Option<Msg> {
+    let (var, fallback) = if editor { ("EDITOR", "vi") } else { ("PAGER", "less") };
+    let program = std::env::var(var).ok().filter(|program| !program.trim().is_empty());
+    let program = program.unwrap_or_else(|| fallback.to_string());
+    let path = std::env::temp_dir().join(format!("hint-{}.md", std::process::id()));
+    if let Err(err) = std::fs::write(&path, text) {
+        return Some(Msg::Notify(format!("Could not write {}: {}", path.display(), err)));
     }
-
-    #[allow(dead_code)]
-    fn from_hnstory(story: HnStory) -> Self {
-        Self {
-            status: Status::Unread,
-            title: story.title().to_string(),
-            details: story.details(),
-        }
+    let command = format!("{} {}", program, hint_hooks::shell_quote(&path.to_string_lossy()));
+    let result = hint_hooks::run_foreground(&command);
+    let _ = std::fs::remove_file(&path);
+    match result {
+        Ok(status) if status.success() => None,
+        Ok(status) => Some(Msg::Notify(format!("{} exited with {}", program, status))),
+        Err(err) => Some(Msg::Notify(format!("Could not run {}: {}", program, err))),
     }
 }
 
-impl App {
-    fn handle_key(&mut self, key: KeyEvent) {
-        if key.kind != KeyEventKind::Press {
-            return;
+/// Carry out a side effect requested by `update`
+fn run_cmd(
+    cmd: Cmd,
+    client: &HnClient,
+    web: &HnWeb,
+    store: &Store,
+    fetcher: &mut FeedFetcher,
+    tx: &mpsc::Sender<Msg>,
+) {
+    if client.is_offline() && cmd.needs_network() {
+        refuse_offline(&cmd, tx);
+        return;
+    }
+    match cmd {
+        Cmd::Batch(cmds) => {
+            for cmd in cmds {
+                run_cmd(cmd, client, web, store, fetcher, tx);
+            }
+        }
+        Cmd::SaveHidden(hidden) => {
+            if let Err(err) = hidden.save(store) {
+                log_debug_info("Failed to save hidden stories: ", format_args!("{}", err));
+            }
+        }
+        Cmd::SaveIgnored(ignored) => {
+            if let Err(err) = ignored.save(store) {
+                log_debug_info("Failed to save ignored users: ", format_args!("{}", err));
+            }
+        }
+        Cmd::SaveSeen(seen) => {
+            if let Err(err) = seen.save(store) {
+                log_debug_info("Failed to save seen comments: ", format_args!("{}", err));
+            }
+        }
+        Cmd::SaveSeenResults(seen) => {
+            if let Err(err) = seen.save(store) {
+                log_debug_info("Failed to save seen search results: ", format_args!("{}", err));
+            }
+        }
+        Cmd::SaveScores(scores) => {
+            if let Err(err) = scores.save(store) {
+                log_debug_info("Failed to save scores: ", format_args!("{}", err));
+            }
+        }
+        Cmd::SaveStoryCache(cache) => {
+            if let Err(err) = cache.save(store) {
+                log_debug_info("Failed to save the story cache: ", format_args!("{}", err));
+            }
+        }
+        Cmd::SaveBookmarks(bookmarks) => {
+            if let Err(err) = bookmarks.save(store) {
+                log_debug_info("Failed to save bookmarks: ", format_args!("{}", err));
+            }
+        }
+        Cmd::SaveSnoozed(snoozed) => {
+            if let Err(err) = snoozed.save(store) {
+                log_debug_info("Failed to save snoozed stories: ", format_args!("{}", err));
+            }
         }
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => self.should_exit = true,
-            KeyCode::Char('h') | KeyCode::Left => self.select_none(),
-            KeyCode::Char('j') | KeyCode::Down => self.select_next(),
-            KeyCode::Char('k') | KeyCode::Up => self.select_previous(),
-            KeyCode::Char('g') | KeyCode::Home => self.select_first(),
-            KeyCode::Char('G') | KeyCode::End => self.select_last(),
-            KeyCode::Char('l') | KeyCode::Right | KeyCode::Enter => {
-                self.toggle_status();
+        Cmd::OpenUrls(urls) => {
+            for url in urls {
+                if let Err(err) = hint_browser::open(&url) {
+                    let message = format!("Could not open a browser: {}", err);
+                    let _ = tx.try_send(Msg::Notify(message));
+                    break;
+                }
             }
-            _ => {}
+        }
+        Cmd::Export { path, contents } => {
+            let message = match std::fs::write(&path, contents) {
+                Ok(()) => format!("Exported to {}", path.display()),
+                Err(err) => format!("Export to {} failed: {}", path.display(), err),
+            };
+            let _ = tx.try_send(Msg::Notify(message));
+        }
+        // Handled by the main loop, which owns the terminal these need
+        Cmd::Pipe { .. } | Cmd::ViewText { .. } | Cmd::Suspend => {
+            log_debug_info("Terminal command must not be batched, dropped: ", format_args!("{:?}", cmd));
+        }
+        Cmd::FetchArticle { url, editor, archived } => {
+            let (articles, offline, tx) = (ArticleCache::new(store), client.is_offline(), tx.clone());
+            tokio::spawn(async move {
+                // Saved for reading offline, and articles rarely change once posted
+                let saved = if archived { None } else { articles.get(&url) };
+                let fetched = match saved {
+                    Some(article) => Ok(article),
+                    None if offline => {
+                        let _ = tx.send(Msg::Notify("Offline, and this article was not saved".to_string())).await;
+                        return;
+                    }
+                    None => hint_article::fetch(&url, archived, hint_article::WAYBACK_API).await,
+                };
+                let msg = match fetched {
+                    Ok(article) => Msg::ArticleLoaded { article, editor },
+                    Err(err) => Msg::Notify(format!("Could not fetch the article: {}", err)),
+                };
+                let _ = tx.send(msg).await;
+            });
+        }
+        Cmd::RunSearch { name, query } => {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                match hint_search::search(&query, hint_search::ALGOLIA_API).await {
+                    Ok(stories) => {
+                        let _ = tx.send(Msg::SearchResultsLoaded { name, stories }).await;
+                    }
+                    // Runs every few minutes, so a failure waits for the next round quietly
+                    Err(err) => log_debug_info("Saved search failed: ", format_args!("{}: {}", name, err)),
+                }
+            });
+        }
+        Cmd::LoadPastFrontPage(day) => {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let msg = match hint_search::front_page(day, hint_search::ALGOLIA_API).await {
+                    Ok(stories) => Msg::PastFrontPageLoaded { day, stories },
+                    Err(err) => {
+                        let _ = tx.send(Msg::Notify(format!("Failed to load the front page of {}: {}", day, err))).await;
+                        Msg::FeedLoaded(Feed::Past)
+                    }
+                };
+                let _ = tx.send(msg).await;
+            });
+        }
+        Cmd::LoadBetween { start, end } => {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let msg = match hint_search::posted_between(start, end, hint_search::ALGOLIA_API).await {
+                    Ok(stories) => Msg::BetweenLoaded { start, end, stories },
+                    Err(err) => {
+                        let _ = tx.send(Msg::Notify(format!("Failed to load the stories of then: {}", err))).await;
+                        Msg::FeedLoaded(Feed::Between)
+                    }
+                };
+                let _ = tx.send(msg).await;
+            });
+        }
+        Cmd::LoadDiscussions(url) => {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let msg = match hint_search::discussions(&url, hint_search::ALGOLIA_API).await {
+                    Ok(stories) => Msg::DiscussionsLoaded { url, stories },
+                    Err(err) => {
+                        let _ = tx.send(Msg::Notify(format!("Failed to look up the discussions of the link: {}", err))).await;
+                        Msg::FeedLoaded(Feed::Discussions)
+                    }
+                };
+                let _ = tx.send(msg).await;
+            });
+        }
+        Cmd::LoadSubmissions(user) => {
+            let (client, tx) = (client.clone(), tx.clone());
+            tokio::spawn(async move {
+                let failure = match hint_user::fetch_submissions(&client, &user).await {
+                    Ok(Some(stories)) => {
+                        let _ = tx.send(Msg::SubmissionsLoaded { user, stories }).await;
+                        return;
+                    }
+                    Ok(None) => format!("HN has no user {}", user),
+                    Err(err) => format!("Failed to load the stories of {}: {}", user, err),
+                };
+                let _ = tx.send(Msg::Notify(failure)).await;
+                let _ = tx.send(Msg::FeedLoaded(Feed::Submissions)).await;
+            });
+        }
+        Cmd::FetchAuthor(name) => {
+            let (client, tx) = (client.clone(), tx.clone());
+            tokio::spawn(async move {
+                match client.fetch_user(&name).await {
+                    Ok(Some(user)) => {
+                        let _ = tx.send(Msg::AuthorLoaded(user)).await;
+                    }
+                    // The details pane just goes without the karma
+                    Ok(None) => log::warn!("HN has no user {}", name),
+                    Err(err) => log::warn!("Failed to fetch the account of {}: {}", name, err),
+                }
+            });
+        }
+        Cmd::FindRelated { id, title } => {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                match hint_search::related(&title, hint_search::ALGOLIA_API).await {
+                    Ok(stories) => {
+                        let _ = tx.send(Msg::RelatedLoaded { id, stories }).await;
+                    }
+                    // The details pane just goes without them
+                    Err(err) => log::warn!("Failed to find stories like {}: {}", id, err),
+                }
+            });
+        }
+        Cmd::FetchScores(ids) => {
+            let (client, tx) = (client.clone(), tx.clone());
+            tokio::spawn(async move {
+                let scores = hint_scores::fetch_scores(&client, &ids).await;
+                let _ = tx.send(Msg::ScoresFetched(scores)).await;
+            });
+        }
+        Cmd::PollUpdates => {
+            let (client, tx) = (client.clone(), tx.clone());
+            tokio::spawn(async move {
+                match client.fetch_updates().await {
+                    Ok(updates) => {
+                        let _ = tx.send(Msg::UpdatesLoaded(updates.items)).await;
+                    }
+                    // Polled every half minute, so a failure waits for the next round quietly
+                    Err(err) => log_debug_info("Polling updates failed: ", format_args!("{}", err)),
+                }
+            });
+        }
+        Cmd::RefreshCounts(ids) => {
+            let (client, tx) = (client.clone(), tx.clone());
+            tokio::spawn(async move {
+                let counts = hint_scores::fetch_counts(&client, &ids).await;
+                let _ = tx.send(Msg::CountsRefreshed(counts)).await;
+            });
+        }
+        Cmd::Copy { text, what } => {
+            let message = match hint_clipboard::copy(&text) {
+                Ok(how) => format!("Copied {} with {}", what, how),
+                Err(err) => format!("Could not copy {}: {}", what, err),
+            };
+            let _ = tx.try_send(Msg::Notify(message));
+        }
+        Cmd::RunCommands(commands) => {
+            for command in commands {
+                if let Err(err) = hint_hooks::run(&command) {
+                    let _ = tx.try_send(Msg::Notify(format!("Hook failed: {}", err)));
+                    break;
+                }
+            }
+        }
+        Cmd::SyncHide { session, id, hide } => {
+            let (web, tx) = (web.clone(), tx.clone());
+            tokio::spawn(async move {
+                if let Err(err) = web.hide(&session, id, hide).await {
+                    let _ = tx.send(Msg::Notify(format!("Hiding on HN failed: {}", err))).await;
+                }
+            });
+        }
+        Cmd::LoadFeed(feed) => {
+            let (client, web, window) = (client.clone(), web.clone(), fetcher.window.clone());
+            fetcher.task.start(load_feed(client, web, fetcher.load(feed), window, tx.clone()));
+        }
+        Cmd::RefreshFeed { feed, known } => {
+            let (client, web, window) = (client.clone(), web.clone(), fetcher.window.clone());
+            let load = FeedLoad { known, ..fetcher.load(feed) };
+            fetcher.task.start(load_feed(client, web, load, window, tx.clone()));
+        }
+        Cmd::LoadFavorites(session) => {
+            let (client, web, window) = (client.clone(), web.clone(), fetcher.window.clone());
+            let load = FeedLoad { session: Some(session), ..fetcher.load(Feed::Favorites) };
+            fetcher.task.start(load_feed(client, web, load, window, tx.clone()));
+        }
+        Cmd::Login { username, password } => {
+            tokio::spawn(login(web.clone(), username, password, tx.clone()));
+        }
+        Cmd::Vote { session, id, up } => {
+            tokio::spawn(vote(web.clone(), session, id, up, tx.clone()));
+        }
+        Cmd::Favorite { session, id, add } => {
+            tokio::spawn(favorite(web.clone(), session, id, add, tx.clone()));
+        }
+        Cmd::LoadMore { feed, ids } => {
+            tokio::spawn(load_more(HnStoryList::unfetched(client.clone(), feed, ids), tx.clone()));
+        }
+        Cmd::LoadComments(id) => {
+            tokio::spawn(load_comments(client.clone(), id, tx.clone()));
+        }
+        Cmd::SaveArticles(urls) => {
+            let limit = fetcher.cache_limit(DiskCache::Articles);
+            tokio::spawn(save_articles(store.clone(), urls, fetcher.article_downloads, limit, tx.clone()));
+        }
+        Cmd::FetchImages(urls) => {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                // One at a time, in the order they come in the article
+                for url in urls {
+                    let image = match hint_images::download(&url).await {
+                        Ok(bytes) => tokio::task::spawn_blocking(move || hint_images::decode(&bytes)).await,
+                        Err(err) => Ok(Err(err)),
+                    };
+                    match image {
+                        Ok(Ok(image)) => {
+                            let _ = tx.send(Msg::ImageLoaded { url, image }).await;
+                        }
+                        // The placeholder stays, which says as much as an error would
+                        Ok(Err(err)) => log_debug_info("Image not shown: ", format_args!("{}: {}", url, err)),
+                        Err(err) => log_debug_info("Image not shown: ", format_args!("{}: {}", url, err)),
+                    }
+                }
+            });
+        }
+        Cmd::MeasureCache => {
+            tokio::spawn(measure_cache(store.clone(), tx.clone()));
+        }
+        Cmd::ClearCache(caches) => {
+            let (store, tx) = (store.clone(), tx.clone());
+            tokio::spawn(async move {
+                let cleared = tokio::task::spawn_blocking({
+                    let store = store.clone();
+                    move || caches.iter().map(|cache| cache.clear(&store)).sum::<io::Result<CacheUsage>>()
+                });
+                let notice = match cleared.await {
+                    Ok(Ok(usage)) => format!("Deleted {} from the disk cache", usage),
+                    Ok(Err(err)) => format!("Clearing the cache failed: {}", err),
+                    Err(_) => return,
+                };
+                let _ = tx.send(Msg::Notify(notice)).await;
+                measure_cache(store, tx).await;
+            });
+        }
+        Cmd::Prefetch(mut prefetch) => {
+            if client.is_offline() {
+                prefetch.url = None;
+            }
+            fetcher.prefetch.start(prefetch_story(client.clone(), prefetch, tx.clone()));
+        }
+        Cmd::LoadReplies { story_id, parent, depth, ids } => {
+            let (client, tx) = (client.clone(), tx.clone());
+            tokio::spawn(async move {
+                let replies = hint_comments::fetch_replies(&client, &ids, depth).await;
+                let _ = tx.send(Msg::RepliesLoaded { story_id, parent, replies }).await;
+            });
+        }
+        Cmd::Post { session, target, text } => {
+            tokio::spawn(post(web.clone(), session, target, text, tx.clone()));
+        }
+        Cmd::Logout => {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let forgotten = tokio::task::spawn_blocking(hint_auth::forget_session).await;
+                if let Ok(Err(err)) = forgotten {
+                    log_debug_info("Failed to remove stored session: ", format_args!("{}", err));
+                }
+                let _ = tx.send(Msg::LoggedOut).await;
+            });
         }
     }
+}
 
-    fn select_none(&mut self) {
-        self.storylist.state.select(None);
-    }
+async fn vote(web: HnWeb, session: HnSession, id: u64, up: bool, tx: mpsc::Sender<Msg>) {
+    let msg = match web.vote(&session, id, up).await {
+        Ok(()) => Msg::Notify(if up { "Upvoted" } else { "Vote removed" }.to_string()),
+        Err(err) => Msg::VoteFailed { id, up, error: err.to_string() },
+    };
+    let _ = tx.send(msg).await;
+}
 
-    fn select_next(&mut self) {
-        self.storylist.state.select_next();
+async fn favorite(web: HnWeb, session: HnSession, id: u64, add: bool, tx: mpsc::Sender<Msg>) {
+    let msg = match web.favorite(&session, id, add).await {
+        Ok(()) if add => Msg::Notify("Added to favorites".to_string()),
+        Ok(()) => Msg::Notify("Removed from favorites".to_string()),
+        Err(err) => Msg::FavoriteFailed { id, add, error: err.to_string() },
+    };
+    let _ = tx.send(msg).await;
+}
+
+async fn load_comments(client: HnClient, id: u64, tx: mpsc::Sender<Msg>) {
+    let msg = match hint_comments::fetch_thread(&client, id).await {
+        Ok(thread) => Msg::CommentsLoaded(thread),
+        Err(err) => Msg::CommentsFailed(err.to_string()),
+    };
+    let _ = tx.send(msg).await;
+}
+
+/// Draw the reader's images over the rows the frame left for them. Images drawn into the cells
+/// stay until something is drawn over them, so when they move the whole screen is drawn again.
+fn draw_images(
+    terminal: &mut DefaultTerminal,
+    model: &mut Model,
+    writer: &mut ImageWriter,
+    shown: &mut Vec<(String, Rect)>,
+) -> io::Result<()> {
+    let loaded = |model: &Model| -> Vec<(String, Rect)> {
+        model.image_slots.iter().filter(|(url, _)| model.images.contains_key(url)).cloned().collect()
+    };
+    if writer.in_cells() && !shown.is_empty() && loaded(model) != *shown {
+        terminal.clear()?;
+        terminal.draw(|frame| view(model, frame))?;
     }
-    fn select_previous(&mut self) {
-        self.storylist.state.select_previous();
+    *shown = loaded(model);
+    let out = writer.frame(shown, &model.images, cell_size());
+    if !out.is_empty() {
+        let mut stdout = io::stdout();
+        stdout.write_all(out.as_bytes())?;
+        stdout.flush()?;
     }
+    Ok(())
+}
 
-    fn select_first(&mut self) {
-        self.storylist.state.select_first();
+/// Pixels of a terminal cell, across and down, as the terminal reports its size
+fn cell_size() -> (u16, u16) {
+    match ratatui::crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.height > 0 && size.columns > 0 && size.rows > 0 => {
+            (size.width / size.columns, size.height / size.rows)
+        }
+        _ => DEFAULT_CELL,
     }
+}
 
-    fn select_last(&mut self) {
-        self.storylist.state.select_last();
+/// Tell why a command was not carried out, and stop the spinner of a feed it was to load
+fn refuse_offline(cmd: &Cmd, tx: &mpsc::Sender<Msg>) {
+    let feed = match cmd {
+        Cmd::LoadFavorites(_) => Some(Feed::Favorites),
+        Cmd::LoadPastFrontPage(_) => Some(Feed::Past),
+        Cmd::LoadBetween { .. } => Some(Feed::Between),
+        Cmd::LoadDiscussions(_) => Some(Feed::Discussions),
+        // Background work waits for the next run online without a word
+        Cmd::RunSearch { .. } | Cmd::SyncHide { .. } | Cmd::SaveArticles(_) | Cmd::FetchImages(_) | Cmd::FindRelated { .. } => return,
+        _ => None,
+    };
+    let _ = tx.try_send(Msg::Notify("Offline, that needs the network".to_string()));
+    if let Some(feed) = feed {
+        let _ = tx.try_send(Msg::FeedLoaded(feed));
     }
+}
 
-    /// Changes the status of the selected list item
-    fn toggle_status(&mut self) {
-        if let Some(i) = self.storylist.state.selected() {
-            self.storylist.items[i].status = match self.storylist.items[i].status {
-                Status::Read => Status::Unread,
-                Status::Unread => Status::Read,
-            };
-            self.show_details = match self.show_details {
-                true => false,
-                false => true,
+/// Fetch the thread and article of the selected story once the selection has rested on it, one
+/// request after the other so the feed's own fetching keeps most of the rate limit
+async fn prefetch_story(client: HnClient, prefetch: Prefetch, tx: mpsc::Sender<Msg>) {
+    tokio::time::sleep(PREFETCH_DELAY).await;
+    if let Some(id) = prefetch.thread {
+        match hint_comments::fetch_thread(&client, id).await {
+            Ok(thread) => {
+                if tx.send(Msg::ThreadPrefetched(thread)).await.is_err() {
+                    return;
+                }
+            }
+            Err(err) => log_debug_info("Prefetching comments failed: ", format_args!("{}: {}", id, err)),
+        }
+    }
+    if let Some(url) = prefetch.url {
+        match hint_article::fetch(&url, false, hint_article::WAYBACK_API).await {
+            Ok(article) => {
+                let _ = tx.send(Msg::ArticlePrefetched(article)).await;
             }
+            Err(err) => log_debug_info("Prefetching the article failed: ", format_args!("{}: {}", url, err)),
         }
     }
 }
 
-impl Widget for &mut App {
-    fn render(self, area: Rect, buf: &mut Buffer) {
-        let [main_area, footer_area] = Layout::vertical([
-            Constraint::Fill(1),
-            Constraint::Length(1),
-        ])
-        .areas(area);
-
-        let (list_area, item_area);
-
-        if self.show_details {
-            let areas: [Rect; 2] = Layout::vertical([Constraint::Fill(1), Constraint::Fill(1)]).areas(main_area);
-            list_area = areas[0];
-            item_area = areas[1];
-        } else {
-            let areas: [Rect; 1] = Layout::vertical([Constraint::Fill(1)]).areas(main_area);
-            list_area = areas[0];
-            item_area = Rect::default(); // Use a default value when not needed
+/// Download the articles not saved yet, `downloads` at a time, into the disk cache
+async fn save_articles(store: Store, urls: Vec<String>, downloads: usize, limit: Option<u64>, tx: mpsc::Sender<Msg>) {
+    let articles = ArticleCache::new(&store);
+    let permits = Arc::new(Semaphore::new(downloads));
+    let mut reading_times = Vec::new();
+    let mut saves = JoinSet::new();
+    for url in urls {
+        // Saved by an earlier feed or run, which tells the reading time as well
+        if let Some(article) = articles.get(&url) {
+            reading_times.push((url, article.reading_minutes()));
+            continue;
         }
-
-        App::render_footer(footer_area, buf);
-        self.render_list(list_area, buf);
-        if self.show_details == true {
-            self.render_selected_item(item_area, buf);
+        let (articles, permits) = (articles.clone(), permits.clone());
+        saves.spawn(async move {
+            let _permit = permits.acquire_owned().await.ok()?;
+            match hint_article::fetch(&url, false, hint_article::WAYBACK_API).await {
+                Ok(article) => {
+                    articles.put(&article).ok()?;
+                    Some((url, article.reading_minutes()))
+                }
+                Err(err) => {
+                    log_debug_info("Saving an article failed: ", format_args!("{}: {}", url, err));
+                    None
+                }
+            }
+        });
+    }
+    let mut saved = 0;
+    while let Some(result) = saves.join_next().await {
+        if let Ok(Some(reading_time)) = result {
+            saved += 1;
+            reading_times.push(reading_time);
         }
-        self.tick_count += 1;
+    }
+    if !reading_times.is_empty() && tx.send(Msg::ReadingTimes(reading_times)).await.is_err() {
+        return;
+    }
+    if saved > 0 {
+        let _ = tx.send(Msg::Notify(format!("Saved {} articles for reading offline", saved))).await;
+    }
+    if let Some(max) = limit.filter(|_| saved > 0) {
+        let _ = tokio::task::spawn_blocking(move || trim_cache(&store, DiskCache::Articles, max)).await;
     }
 }
 
-/// Rendering logic for the app
-impl App {
-    fn render_footer(area: Rect, buf: &mut Buffer) {
-        Paragraph::new("Use ↓↑ to move, ← to unselect, → to change status, g/G to go top/bottom.")
-            .centered()
-            .render(area, buf);
+/// Delete the oldest of what a disk cache holds past its limit
+fn trim_cache(store: &Store, cache: DiskCache, max: u64) {
+    match cache.trim(store, max) {
+        Ok(deleted) if deleted.files > 0 => {
+            log_debug_info("Trimmed the disk cache: ", format_args!("{} {}", cache, deleted));
+        }
+        Ok(_) => {}
+        Err(err) => log_debug_info("Trimming the disk cache failed: ", format_args!("{}: {}", cache, err)),
     }
+}
 
-    fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
-        let block = Block::new()
-            .title(Line::raw("HackerNews").centered())
-            .borders(Borders::TOP)
-            .border_set(symbols::border::EMPTY)
-            .border_style(HEADER_STYLE)
-            .bg(NORMAL_ROW_BG);
+/// Tell what each disk cache holds, for the debug overlay
+async fn measure_cache(store: Store, tx: mpsc::Sender<Msg>) {
+    let measured = tokio::task::spawn_blocking(move || DiskCache::ALL.map(|cache| (cache, cache.usage(&store))));
+    if let Ok(usage) = measured.await {
+        let _ = tx.send(Msg::CacheMeasured(usage.to_vec())).await;
+    }
+}
 
-        // Iterate through all elements in the `items` and stylize them.
-        let mut items: Vec<ListItem> = self
-            .storylist
-            .items
-            .iter()
-            .enumerate()
-            .map(|(i, storyitem)| {
-                let color = alternate_colors(i);
-                ListItem::from(storyitem).bg(color)
+async fn post(
+    web: HnWeb,
+    session: HnSession,
+    target: ComposeTarget,
+    text: String,
+    tx: mpsc::Sender<Msg>,
+) {
+    let posted = match &target {
+        ComposeTarget::Reply { parent, .. } => {
+            web.reply(&session, *parent, &text).await.map(|()| Msg::ReplyPosted)
+        }
+        ComposeTarget::Submission { title, url } => {
+            let url = url.as_deref().unwrap_or("");
+            web.submit(&session, title, url, &text).await.map(|id| {
+                let story = HnStory::new(
+                    id.to_string(),
+                    session.username.clone(),
+                    title.clone(),
+                    Some(url.to_string()).filter(|url| !url.is_empty()),
+                    "story".to_string(),
+                );
+                Msg::Submitted(story)
             })
-            .collect();
+        }
+    };
+    let msg = posted.unwrap_or_else(|err| Msg::PostFailed { target, text, error: err.to_string() });
+    let _ = tx.send(msg).await;
+}
 
-        // Define the spinner frames
-        let spinner_frames = vec!["|", "/", "-", "\\"];
-        let tick = self.tick_count; // Or you can use a counter from your app logic to track ticks
+async fn login(web: HnWeb, username: String, password: String, tx: mpsc::Sender<Msg>) {
+    let msg = match web.login(&username, &password).await {
+        Ok(session) => {
+            let stored = session.clone();
+            let saved = tokio::task::spawn_blocking(move || hint_auth::save_session(&stored)).await;
+            if let Ok(Err(err)) = saved {
+                // Still usable for this run, just not remembered
+                log_debug_info("Failed to store session: ", format_args!("{}", err));
+            }
+            Msg::LoggedIn(session)
+        }
+        Err(err) => Msg::Notify(format!("Login failed: {}", err)),
+    };
+    let _ = tx.send(msg).await;
+}
 
-        // Get the current spinner frame
-        let frame = spinner_frames[tick  as usize % (spinner_frames.len() as usize)];
+/// Favorites are scraped from the website with the account, everything else is an API feed
+async fn fetch_feed_ids(client: &HnClient, web: &HnWeb, feed: Feed, session: Option<&HnSession>) -> Result<Vec<u64>, String> {
+    match (feed, session) {
+        (Feed::Favorites, Some(session)) => web.favorite_ids(session).await.map_err(|err| err.to_string()),
+        _ => feed.fetch_ids(client).await.map_err(|err| err.to_string()),
+    }
+}
 
-        // Add the spinner as the last item
-        items.push(ListItem::from(format!("  Updating... {}", frame)));
+/// A feed to fetch, and what the fetching starts from
+struct FeedLoad {
+    feed: Feed,
+    /// The account to scrape the favorites with
+    session: Option<HnSession>,
+    /// How long the list may take before the load is given up with a notice rather than left
+    /// spinning
+    deadline: Option<Duration>,
+    /// Stories already in the list, which a refresh puts in their new places without fetching
+    known: HashSet<u64>,
+    /// Stories to list out of the feed's
+    sizes: StoriesPerFeed,
+}
 
-        // Create a List from all list items and highlight the currently selected one
-        let list = List::new(items)
-            .block(block)
-            .highlight_style(SELECTED_STYLE)
-            .highlight_symbol(">")
-            .highlight_spacing(HighlightSpacing::Always);
+impl FeedFetcher {
+    fn load(&self, feed: Feed) -> FeedLoad {
+        FeedLoad { feed, session: None, deadline: None, known: HashSet::new(), sizes: self.sizes.clone() }
+    }
+}
 
-        // We need to disambiguate this trait method as both `Widget` and `StatefulWidget` share the
-        // same method name `render`.
-        StatefulWidget::render(list, area, buf, &mut self.storylist.state);
+/// Fetch the feed's list, then its stories one by one, each handed to the main loop as it comes
+/// so the list fills in on screen
+async fn load_feed(
+    client: HnClient,
+    web: HnWeb,
+    load: FeedLoad,
+    window: watch::Receiver<FetchWindow>,
+    tx: mpsc::Sender<Msg>,
+) {
+    let FeedLoad { feed, session, deadline, known, sizes } = load;
+    let fetched = fetch_feed_ids(&client, &web, feed, session.as_ref());
+    let ids = match deadline {
+        Some(deadline) => match tokio::time::timeout(deadline, fetched).await {
+            Ok(ids) => ids,
+            Err(_) => {
+                let notice = format!("Gave up on {} stories after {}s, is the network down?", feed, deadline.as_secs());
+                let _ = tx.send(Msg::Notify(notice)).await;
+                let _ = tx.send(Msg::FeedLoaded(feed)).await;
+                return;
+            }
+        },
+        None => fetched.await,
+    };
+    let mut ids = match ids {
+        Ok(ids) => ids,
+        Err(err) => {
+            log_debug_info("Failed to fetch story ids: ", format_args!("{}", err));
+            let _ = tx.send(Msg::Notify(format!("Failed to load {} stories: {}", feed, err))).await;
+            Vec::new()
+        }
+    };
+    let more = sizes.apply(feed, &mut ids);
+    if tx.send(Msg::FeedOrder(feed, ids.clone())).await.is_err() {
+        return;
     }
+    if tx.send(Msg::FeedMore(feed, more)).await.is_err() {
+        return;
+    }
+    if let Some(at) = feed.api_path().and_then(|path| client.saved_at(path)) {
+        let _ = tx.send(Msg::FeedSavedAt(feed, at)).await;
+    }
+    run_story_updater(HnStoryList::unfetched(client, feed, ids), &known, window, tx).await;
+}
 
-    fn render_selected_item(&self, area: Rect, buf: &mut Buffer) {
-        if self.show_details == false {
-            return;
+/// Fetch the remaining stories one by one, handing each one to the main loop. Stories on or near
+/// the screen come first and one right after the other; the rest trickle in, reordered as the
+/// screen moves.
+async fn run_story_updater(
+    story_list: HnStoryList,
+    known: &HashSet<u64>,
+    mut window: watch::Receiver<FetchWindow>,
+    tx: mpsc::Sender<Msg>,
+) {
+    let feed = story_list.feed();
+    let mut queue = FetchQueue::new(story_list.ids().to_vec(), story_list.fetched());
+    queue.skip(known);
+    loop {
+        // Windows of another feed mean this one is no longer showing, so its order hardly matters
+        let (showing, ranks) = window.borrow_and_update().clone();
+        if showing == feed {
+            queue.set_window(ranks);
         }
-        // We get the info depending on the item's state.
-        let info = if let Some(i) = self.storylist.state.selected() {
-            match self.storylist.items[i].status {
-                Status::Read => format!("✓ DONE: {}", self.storylist.items[i].details),
-                Status::Unread => format!("☐ TOREAD: {}", self.storylist.items[i].details),
-            }
-        } else {
-            "Nothing selected...".to_string()
+        let urgent = queue.is_urgent();
+        let Some((_, id)) = queue.pop() else {
+            break;
         };
-
-        // We show the list item's info under the list in this paragraph
-        let block = Block::new()
-            .title(Line::raw("Story Details").centered())
-            .borders(Borders::TOP)
-            .border_set(symbols::border::EMPTY)
-            .border_style(HEADER_STYLE)
-            .bg(NORMAL_ROW_BG)
-            .padding(Padding::horizontal(1));
-
-        // We can now render the item info
-        Paragraph::new(info)
-            .block(block)
-            .fg(TEXT_FG_COLOR)
-            .wrap(Wrap { trim: false })
-            .render(area, buf);
+        match story_list.fetch_story(id).await {
+            Ok(story) => {
+                if tx.send(Msg::StoryLoaded(feed, story)).await.is_err() {
+                    break;
+                }
+            }
+            Err(err) => log_debug_info("Story updater skipped a story: ", format_args!("{}", err)),
+        }
+        if queue.is_empty() {
+            break;
+        }
+        if !urgent {
+            // A scroll may bring missing stories on screen, which should not wait
+            tokio::select! {
+                _ = tokio::time::sleep(STORY_UPDATE_INTERVAL) => {}
+                _ = window.changed() => {}
+            }
+        }
     }
+    let _ = tx.send(Msg::FeedLoaded(feed)).await;
 }
 
-const fn alternate_colors(i: usize) -> Color {
-    if i % 2 == 0 {
-        NORMAL_ROW_BG
-    } else {
-        ALT_ROW_BG_COLOR
+/// Fetch the stories past the end of the list, in their order
+async fn load_more(story_list: HnStoryList, tx: mpsc::Sender<Msg>) {
+    let feed = story_list.feed();
+    for id in story_list.ids() {
+        match story_list.fetch_story(*id).await {
+            Ok(story) => {
+                if tx.send(Msg::StoryLoaded(feed, story)).await.is_err() {
+                    return;
+                }
+            }
+            Err(err) => log_debug_info("Loading more skipped a story: ", format_args!("{}", err)),
+        }
     }
+    let _ = tx.send(Msg::MoreLoaded(feed)).await;
 }
 
-impl From<&DisplayListItem> for ListItem<'_> {
-    fn from(value: &DisplayListItem) -> Self {
-        let line = match value.status {
-            Status::Unread => Line::styled(format!(" ☐ {}", value.title), TEXT_FG_COLOR),
-            Status::Read => {
-                Line::styled(format!(" ✓ {}", value.title), COMPLETED_TEXT_FG_COLOR)
+/// Read terminal events on a dedicated thread, as crossterm's `read` blocks
+fn spawn_event_reader(tx: mpsc::Sender<Msg>, paused: Arc<AtomicBool>) {
+    std::thread::spawn(move || loop {
+        if paused.load(Ordering::SeqCst) {
+            std::thread::sleep(INPUT_POLL_INTERVAL);
+            continue;
+        }
+        match event::poll(INPUT_POLL_INTERVAL) {
+            // Keys typed after the pause began belong to the child program
+            Ok(true) if !paused.load(Ordering::SeqCst) => {}
+            Ok(_) => continue,
+            Err(err) => {
+                log_debug_info("Event reader stopped: ", format_args!("{}", err));
+                break;
             }
-        };
-        ListItem::new(line)
-    }
+        }
+        match event::read() {
+            Ok(Event::Key(key)) => {
+                if tx.blocking_send(Msg::Key(key)).is_err() {
+                    break;
+                }
+            }
+            Ok(Event::Paste(text)) => {
+                if tx.blocking_send(Msg::Paste(text)).is_err() {
+                    break;
+                }
+            }
+            Ok(Event::Resize(width, height)) => {
+                if tx.blocking_send(Msg::Resize { width, height }).is_err() {
+                    break;
+                }
+            }
+            Ok(_) => {}
+            Err(err) => {
+                log_debug_info("Event reader stopped: ", format_args!("{}", err));
+                break;
+            }
+        }
+    });
+}
+
+/// Send the timer ticks and the animation frames. Both skip a beat rather than queue up behind
+/// keys the main loop is still busy with, so input never waits on them.
+fn spawn_ticker(tx: mpsc::Sender<Msg>, animation_interval: Duration) {
+    tokio::spawn(async move {
+        let mut ticks = tokio::time::interval(TICK_INTERVAL);
+        let mut frames = tokio::time::interval(animation_interval);
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        frames.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        loop {
+            let msg = tokio::select! {
+                _ = ticks.tick() => Msg::Tick,
+                _ = frames.tick() => Msg::Frame,
+            };
+            if let Err(TrySendError::Closed(_)) = tx.try_send(msg) {
+                break;
+            }
+        }
+    });
 }
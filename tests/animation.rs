@@ -0,0 +1,43 @@
+//! Animation frames come at their own configurable pace, apart from the timer ticks.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_config::Config;
+use hint::hint_view::view;
+use ratatui::{backend::TestBackend, Terminal};
+use std::time::Duration;
+
+fn spinner(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(40, 6)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    let start = screen.find("... ").expect("spinner on screen") + "... ".len();
+    screen[start..start + 1].to_string()
+}
+
+#[test]
+fn spinner_turns_with_frames_not_ticks() {
+    let mut model = Model::default();
+    model.loading = true;
+    let first = spinner(&mut model);
+    for _ in 0..7 {
+        update(&mut model, Msg::Tick);
+    }
+    assert_eq!(spinner(&mut model), first);
+    update(&mut model, Msg::Frame);
+    assert_ne!(spinner(&mut model), first);
+    assert_eq!(model.frame_count, 1);
+    assert_eq!(model.tick_count, 7);
+}
+
+#[test]
+fn frames_default_to_a_quarter_second() {
+    assert_eq!(Config::default().animation_interval(), Duration::from_millis(250));
+}
+
+#[test]
+fn frame_pace_comes_from_the_config() {
+    let config: Config = serde_json::from_str(r#"{"animation_ms": 100}"#).unwrap();
+    assert_eq!(config.animation_interval(), Duration::from_millis(100));
+    let config: Config = serde_json::from_str(r#"{"animation_ms": 0}"#).unwrap();
+    assert_eq!(config.animation_interval(), Duration::from_millis(16), "never a busy loop");
+}
@@ -0,0 +1,146 @@
+//! Article text extraction and reading it in the pager or editor.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_article::{extract_text, fetch, looks_paywalled, raw_snapshot_url, Article, ArticleError};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::KeyCode;
+use wiremock::matchers::{path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const PAGE: &str = r#"<html><head><title>Plain &amp; simple</title>
+<style>body { color: red }</style></head>
+<body><nav><a href="/">Home</a></nav>
+<article><h1>Plain text</h1><p>It is   <b>everywhere</b>.</p>
+<script>track()</script>
+<ul><li>Greppable</li><li>Diffable</li></ul><pre>code</pre></article>
+<footer>Copyright</footer></body></html>"#;
+
+#[test]
+fn article_text_skips_page_furniture() {
+    assert_eq!(
+        extract_text(PAGE),
+        "Plain & simple\n==============\n\nPlain text\n\nIt is everywhere.\n\n- Greppable\n\n- Diffable\n\n```\ncode\n```\n"
+    );
+}
+
+#[test]
+fn body_is_used_without_an_article() {
+    let text = extract_text("<body><div>One</div><div>Two</div><footer>x</footer></body>");
+    assert_eq!(text, "One\n\nTwo\n");
+}
+
+#[tokio::test]
+async fn fetch_extracts_the_downloaded_page() {
+    let server = MockServer::start().await;
+    Mock::given(path("/post"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(PAGE))
+        .mount(&server)
+        .await;
+    let api = format!("{}/wayback/available", server.uri());
+    let article = fetch(&format!("{}/post", server.uri()), false, &api).await.unwrap();
+    assert!(article.text.starts_with("Plain & simple\n"));
+    assert_eq!(article.archived_from, None);
+}
+
+/// A site at `/site` and a Wayback Machine at `/web` with a copy of `/site/dead`
+async fn site_with_archive() -> MockServer {
+    let server = MockServer::start().await;
+    let snapshot = format!("{}/web/20240101000000/{}/site/dead", server.uri(), server.uri());
+    Mock::given(path("/wayback/available"))
+        .and(query_param("url", format!("{}/site/dead", server.uri())))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "archived_snapshots": { "closest": { "available": true, "url": snapshot, "status": "200" } }
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(path("/wayback/available"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"archived_snapshots": {}}"#))
+        .mount(&server)
+        .await;
+    let raw = format!("/web/20240101000000id_/{}/site/dead", server.uri());
+    Mock::given(path(raw.as_str()))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<body><p>Archived</p></body>"))
+        .mount(&server)
+        .await;
+    Mock::given(path("/site/dead")).respond_with(ResponseTemplate::new(404)).mount(&server).await;
+    Mock::given(path("/site/gone")).respond_with(ResponseTemplate::new(410)).mount(&server).await;
+    Mock::given(path("/site/paywall"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<body><div class="paywall">Subscribe to continue reading</div><p>Teaser</p></body>"#,
+        ))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn dead_links_fall_back_to_the_archive() {
+    let server = site_with_archive().await;
+    let api = format!("{}/wayback/available", server.uri());
+    let article = fetch(&format!("{}/site/dead", server.uri()), false, &api).await.unwrap();
+    assert_eq!(article.text, "Archived\n");
+    assert!(article.archived_from.unwrap().contains("/web/20240101000000/"));
+
+    let err = fetch(&format!("{}/site/gone", server.uri()), false, &api).await.unwrap_err();
+    assert!(matches!(err, ArticleError::Status(410)));
+}
+
+#[tokio::test]
+async fn paywalled_page_without_a_copy_keeps_the_teaser() {
+    let server = site_with_archive().await;
+    let api = format!("{}/wayback/available", server.uri());
+    let article = fetch(&format!("{}/site/paywall", server.uri()), false, &api).await.unwrap();
+    assert_eq!(article.archived_from, None);
+    assert!(article.text.contains("Teaser"));
+
+    let err = fetch(&format!("{}/site/paywall", server.uri()), true, &api).await.unwrap_err();
+    assert!(matches!(err, ArticleError::NotArchived));
+}
+
+#[test]
+fn snapshots_are_fetched_without_the_toolbar() {
+    assert_eq!(
+        raw_snapshot_url("http://web.archive.org/web/20240101000000/https://example.com/"),
+        "http://web.archive.org/web/20240101000000id_/https://example.com/"
+    );
+    assert!(looks_paywalled(r#"{"isAccessibleForFree": false}"#));
+    assert!(!looks_paywalled("<p>Free to read</p>"));
+}
+
+#[test]
+fn p_fetches_the_article_then_pages_it() {
+    let mut model = Model::default();
+    let url = Some("https://example.com/post".to_string());
+    let story = HnStory::new("1".into(), "pg".into(), "Story".into(), url, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    let text_post = HnStory::new("2".into(), "pg".into(), "Ask HN".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, text_post));
+    press(&mut model, KeyCode::Down);
+
+    let cmd = press(&mut model, KeyCode::Char('e'));
+    let url = "https://example.com/post".to_string();
+    assert_eq!(cmd, Some(Cmd::FetchArticle { url: url.clone(), editor: true, archived: false }));
+    let article = Article {
+        url: url.clone(),
+        text: "Text".to_string(),
+        links: Vec::new(),
+        archived_from: Some("snapshot".to_string()),
+    };
+    let cmd = update(&mut model, Msg::ArticleLoaded { article, editor: true });
+    assert_eq!(cmd, Some(Cmd::ViewText { text: "Text".to_string(), editor: true }));
+    assert_eq!(
+        model.notice.as_deref(),
+        Some("The live page was unavailable, this was the archived copy at snapshot")
+    );
+
+    press(&mut model, KeyCode::Char('A'));
+    let cmd = press(&mut model, KeyCode::Char('p'));
+    assert_eq!(cmd, Some(Cmd::FetchArticle { url, editor: false, archived: true }));
+
+    press(&mut model, KeyCode::Down);
+    assert_eq!(press(&mut model, KeyCode::Char('p')), None);
+    assert_eq!(model.notice.as_deref(), Some("Text posts have no article, open the comments with c"));
+}
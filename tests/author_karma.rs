@@ -0,0 +1,103 @@
+//! The details pane shows the karma and account age of the selected story's author, fetched
+//! from the user API the first time the pane shows one of their stories.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use hint::hnreader::{HnClient, User};
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const YEAR: u64 = 365 * 86_400;
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for (id, author) in [(1, "pg"), (2, "dang"), (3, "pg")] {
+        let story = HnStory::new(id.to_string(), author.into(), format!("Show HN: Story {}", id), None, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+/// The accounts a command asks for, leaving the rest of what the details pane fetches
+fn authors_asked(cmd: Option<Cmd>) -> Vec<String> {
+    let cmds = match cmd {
+        Some(Cmd::Batch(cmds)) => cmds,
+        cmd => cmd.into_iter().collect(),
+    };
+    cmds.into_iter()
+        .filter_map(|cmd| match cmd {
+            Cmd::FetchAuthor(name) => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..24).map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n").collect()
+}
+
+#[test]
+fn authors_are_fetched_once_the_details_pane_shows_them() {
+    let mut model = model_with_stories();
+    // Moving about the list alone fetches nothing
+    press(&mut model, KeyCode::Down);
+    assert!(model.authors.is_empty());
+
+    assert_eq!(authors_asked(press(&mut model, KeyCode::Tab)), vec!["pg"]);
+    assert_eq!(authors_asked(update(&mut model, Msg::SelectNext)), vec!["dang"]);
+    // pg's account is on its way already
+    assert_eq!(authors_asked(update(&mut model, Msg::SelectNext)), Vec::<String>::new());
+
+    // Nor are they asked for again, even if the first answer never comes
+    update(&mut model, Msg::SelectPrevious);
+    assert_eq!(update(&mut model, Msg::SelectPrevious), None);
+}
+
+#[test]
+fn nothing_is_fetched_offline() {
+    let mut model = model_with_stories();
+    model.offline = true;
+    press(&mut model, KeyCode::Down);
+    assert_eq!(press(&mut model, KeyCode::Tab), None);
+}
+
+#[test]
+fn the_details_pane_shows_karma_and_account_age() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Tab);
+    assert!(!screen(&mut model).contains("karma"));
+
+    let now = chrono::Utc::now().timestamp() as u64;
+    let created = now - 19 * YEAR - 86_400;
+    update(&mut model, Msg::AuthorLoaded(User { id: "pg".into(), created, karma: 157316, submitted: Vec::new() }));
+    assert!(screen(&mut model).contains("pg: 157316 karma · account 19 years old"));
+
+    // A week-old account shows its days
+    update(&mut model, Msg::SelectNext);
+    update(&mut model, Msg::AuthorLoaded(User { id: "dang".into(), created: now - 7 * 86_400, karma: 1, ..User::default() }));
+    assert!(screen(&mut model).contains("dang: 1 karma · account 7 days old"));
+}
+
+#[tokio::test]
+async fn users_carry_their_karma_and_creation_time() {
+    let server = MockServer::start().await;
+    let body = json!({"id": "pg", "created": 1160418092, "karma": 157316, "about": "Bug fixer."});
+    let response = ResponseTemplate::new(200).set_body_json(body);
+    Mock::given(method("GET")).and(path("/v0/user/pg.json")).respond_with(response).mount(&server).await;
+
+    let client = HnClient::with_base_url(&format!("{}/v0", server.uri()));
+    let user = client.fetch_user("pg").await.unwrap().unwrap();
+    assert_eq!((user.created, user.karma), (1160418092, 157316));
+    assert!(user.submitted.is_empty());
+}
@@ -0,0 +1,112 @@
+//! From the details pane, `w` keeps the loaded stories by the selected story's author and `W`
+//! lists their latest submissions, fetched from the user API.
+
+mod common;
+
+use common::{ids, press};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_user::fetch_submissions;
+use hint::hnreader::HnClient;
+use ratatui::crossterm::event::KeyCode;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn story(id: u64, author: &str) -> HnStory {
+    HnStory::new(id.to_string(), author.into(), format!("Story {}", id), None, "story".into())
+}
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for (id, author) in [(1, "dang"), (2, "pg"), (3, "tptacek"), (4, "pg")] {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id, author)));
+    }
+    model
+}
+
+/// Select the second story, by pg, and move to its details
+fn details_of_pg(model: &mut Model) {
+    press(model, KeyCode::Down);
+    press(model, KeyCode::Down);
+    press(model, KeyCode::Tab);
+    assert_eq!(model.storylist.selected_item().unwrap().story.author(), "pg");
+}
+
+#[test]
+fn w_keeps_the_stories_by_the_same_author() {
+    let mut model = model_with_stories();
+    details_of_pg(&mut model);
+    press(&mut model, KeyCode::Char('w'));
+    assert_eq!(ids(&model), vec![2, 4]);
+    assert_eq!(model.only_author.as_deref(), Some("pg"));
+    assert_eq!(model.notice.as_deref(), Some("Only stories by pg, w again for all, W for their latest"));
+
+    // Stories still arriving are filtered too
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(5, "dang")));
+    assert_eq!(ids(&model), vec![2, 4]);
+
+    // Pressing it again brings everyone back by loading the feed again
+    press(&mut model, KeyCode::Tab);
+    press(&mut model, KeyCode::Tab);
+    assert_eq!(press(&mut model, KeyCode::Char('w')), Some(Cmd::LoadFeed(Feed::Top)));
+    assert_eq!(model.only_author, None);
+}
+
+#[test]
+fn the_author_keys_belong_to_the_details_pane() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    assert_eq!(press(&mut model, KeyCode::Char('W')), None);
+    press(&mut model, KeyCode::Char('w'));
+    assert_eq!(model.only_author, None);
+    assert_eq!(ids(&model), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn capital_w_lists_the_authors_latest_stories() {
+    let mut model = model_with_stories();
+    details_of_pg(&mut model);
+    assert_eq!(press(&mut model, KeyCode::Char('W')), Some(Cmd::LoadSubmissions("pg".to_string())));
+    assert_eq!(model.feed, Feed::Submissions);
+    assert_eq!(model.feed_title(), "Submissions: pg");
+    assert!(model.loading);
+
+    // Stories of someone picked before are dropped
+    update(&mut model, Msg::SubmissionsLoaded { user: "dang".into(), stories: vec![story(1, "dang")] });
+    assert!(model.storylist.items.is_empty());
+
+    update(&mut model, Msg::SubmissionsLoaded { user: "pg".into(), stories: vec![story(9, "pg"), story(4, "pg")] });
+    assert_eq!(ids(&model), vec![9, 4]);
+    assert!(!model.loading);
+
+    // A refresh asks for them again
+    assert_eq!(press(&mut model, KeyCode::Char('R')), Some(Cmd::LoadSubmissions("pg".to_string())));
+}
+
+#[tokio::test]
+async fn submissions_are_the_users_stories_newest_first() {
+    let server = MockServer::start().await;
+    let items = [
+        json!({"id": 15, "by": "pg", "type": "comment", "text": "Indeed", "parent": 1}),
+        json!({"id": 14, "by": "pg", "type": "story", "title": "Startups in 13 sentences", "score": 300}),
+        json!({"id": 13, "by": "pg", "type": "story", "title": "Flagged", "dead": true}),
+        json!({"id": 12, "by": "pg", "type": "job", "title": "YC is hiring"}),
+    ];
+    let mut responses = vec![
+        ("/v0/user/pg.json".to_string(), json!({"id": "pg", "karma": 157000, "submitted": [15, 14, 13, 12]})),
+        ("/v0/user/nobody.json".to_string(), serde_json::Value::Null),
+    ];
+    responses.extend(items.into_iter().map(|item| (format!("/v0/item/{}.json", item["id"]), item)));
+    for (route, body) in responses {
+        let response = ResponseTemplate::new(200).set_body_json(body);
+        Mock::given(method("GET")).and(path(route)).respond_with(response).mount(&server).await;
+    }
+
+    let client = HnClient::with_base_url(&format!("{}/v0", server.uri()));
+    let stories = fetch_submissions(&client, "pg").await.unwrap().unwrap();
+    let titles: Vec<&str> = stories.iter().map(|story| story.title()).collect();
+    assert_eq!(titles, vec!["Startups in 13 sentences", "YC is hiring"]);
+    assert_eq!(stories[0].score(), Some(300));
+    assert_eq!(fetch_submissions(&client, "nobody").await.unwrap(), None);
+}
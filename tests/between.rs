@@ -0,0 +1,76 @@
+//! `:between` lists the stories posted within a stretch of time, such as overnight, from HN
+//! Search rather than the live New feed.
+
+mod common;
+
+use common::run;
+use chrono::{TimeZone, Utc};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_search::{parse_moment, posted_between};
+use wiremock::matchers::{path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[test]
+fn moments_are_named_like_the_prompt_takes_them() {
+    // Wednesday 2024-01-17 08:15 UTC
+    let now = Utc.with_ymd_and_hms(2024, 1, 17, 8, 15, 0).unwrap();
+    let at = |y, mo, d, h, mi| Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap().timestamp();
+    assert_eq!(parse_moment("now", &now), Some(now.timestamp()));
+    assert_eq!(parse_moment("8h", &now), Some(at(2024, 1, 17, 0, 15)));
+    assert_eq!(parse_moment("07:00", &now), Some(at(2024, 1, 17, 7, 0)));
+    assert_eq!(parse_moment("23:30", &now), Some(at(2024, 1, 16, 23, 30)), "the last time the clock read it");
+    assert_eq!(parse_moment("2024-01-01", &now), Some(at(2024, 1, 1, 0, 0)));
+    assert_eq!(parse_moment("2024-01-01T12:45", &now), Some(at(2024, 1, 1, 12, 45)));
+    assert_eq!(parse_moment("soon", &now), None);
+}
+
+#[tokio::test]
+async fn asks_for_stories_posted_in_the_stretch() {
+    let server = MockServer::start().await;
+    let body = r#"{"hits": [
+        {"objectID": "9", "title": "Late", "author": "pg", "points": 3, "created_at_i": 1705400000},
+        {"objectID": "8", "title": "Early", "author": "dang", "points": 40, "created_at_i": 1705390000}
+    ]}"#;
+    Mock::given(path("/search_by_date"))
+        .and(query_param("tags", "story"))
+        .and(query_param("numericFilters", "created_at_i>=1705380000,created_at_i<1705410000"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+    let stories = posted_between(1705380000, 1705410000, &server.uri()).await.unwrap();
+    let titles: Vec<&str> = stories.iter().map(HnStory::title).collect();
+    assert_eq!(titles, vec!["Late", "Early"]);
+}
+
+#[test]
+fn between_lists_what_came_in() {
+    let mut model = Model::default();
+    let (start, end) = match run(&mut model, "between 2024-01-16T23:00 2024-01-17T07:00") {
+        Some(Cmd::LoadBetween { start, end }) => (start, end),
+        other => panic!("expected the stretch to load, got {:?}", other),
+    };
+    assert_eq!(end - start, 8 * 60 * 60);
+    assert_eq!(model.feed, Feed::Between);
+    assert!(model.loading);
+
+    let stories = (1..=3).rev().map(|id| HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), None, "story".into()));
+    update(&mut model, Msg::BetweenLoaded { start, end, stories: stories.collect() });
+    let ids: Vec<u64> = model.storylist.items.iter().map(|item| item.id()).collect();
+    assert_eq!(ids, vec![3, 2, 1]);
+    assert_eq!(model.notice.as_deref(), Some("3 stories posted then"));
+    assert!(!model.loading);
+
+    // Refreshing asks for the same stretch again
+    assert_eq!(update(&mut model, Msg::Refresh), Some(Cmd::LoadBetween { start, end }));
+}
+
+#[test]
+fn the_stretch_has_to_make_sense() {
+    let mut model = Model::default();
+    run(&mut model, "between 2h 8h");
+    assert_eq!(model.notice.as_deref(), Some("The stretch has to start before it ends, and before now"));
+    run(&mut model, "between");
+    assert!(model.notice.as_deref().is_some_and(|notice| notice.starts_with("Usage: :between")));
+    assert_eq!(model.feed, Feed::Top);
+}
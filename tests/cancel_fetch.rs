@@ -0,0 +1,69 @@
+//! Fetches for a feed that is no longer showing are cancelled.
+
+use hint::hint_fetch::FetchTask;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// A task that runs until aborted, saying so when dropped
+fn endless(dropped: Arc<AtomicBool>) -> impl std::future::Future<Output = ()> {
+    struct Guard(Arc<AtomicBool>);
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+    async move {
+        let _guard = Guard(dropped);
+        std::future::pending::<()>().await;
+    }
+}
+
+async fn settle() {
+    tokio::time::sleep(Duration::from_millis(20)).await;
+}
+
+#[tokio::test]
+async fn starting_another_fetch_aborts_the_one_before() {
+    let mut task = FetchTask::default();
+    assert!(!task.is_running());
+    let first = Arc::new(AtomicBool::new(false));
+    task.start(endless(first.clone()));
+    settle().await;
+    assert!(task.is_running());
+    assert!(!first.load(Ordering::SeqCst));
+
+    let second = Arc::new(AtomicBool::new(false));
+    task.start(endless(second.clone()));
+    settle().await;
+    assert!(first.load(Ordering::SeqCst), "the old feed's fetch is dropped");
+    assert!(!second.load(Ordering::SeqCst));
+    assert!(task.is_running());
+}
+
+#[tokio::test]
+async fn stop_aborts_without_starting_anything() {
+    let mut task = FetchTask::default();
+    let dropped = Arc::new(AtomicBool::new(false));
+    task.start(endless(dropped.clone()));
+    settle().await;
+    task.stop();
+    settle().await;
+    assert!(dropped.load(Ordering::SeqCst));
+    assert!(!task.is_running());
+    // Stopping twice is fine
+    task.stop();
+}
+
+#[tokio::test]
+async fn finished_fetch_is_not_running() {
+    let mut task = FetchTask::default();
+    let (done_tx, done_rx) = oneshot::channel();
+    task.start(async move {
+        let _ = done_tx.send(());
+    });
+    done_rx.await.unwrap();
+    settle().await;
+    assert!(!task.is_running());
+}
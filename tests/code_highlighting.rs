@@ -0,0 +1,85 @@
+//! Code blocks in comments and articles coloured by their syntax, the language taken from the
+//! fence or class when there is one and guessed from the code when not.
+
+use hint::hint_article::{extract_article, Article};
+use hint::hint_highlight::{fence, highlight};
+use hint::hint_html::to_styled_text;
+use hint::hint_reader::Reader;
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+
+const TEXT: Style = Style::new().fg(Color::White);
+const CODE: Style = Style::new().fg(Color::Yellow);
+
+fn plain(lines: &[Line]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+/// How many colours the line is in
+fn colours(line: &Line) -> usize {
+    let mut colours: Vec<Option<Color>> = line.spans.iter().map(|span| span.style.fg).collect();
+    colours.dedup();
+    colours.len()
+}
+
+#[test]
+fn fences_name_their_language() {
+    assert_eq!(fence("```rust"), Some("rust"));
+    assert_eq!(fence("  ```"), Some(""));
+    assert_eq!(fence("```c++"), Some("c++"));
+    assert_eq!(fence("``` not a fence"), None);
+    assert_eq!(fence("fn main() {}"), None);
+}
+
+#[test]
+fn code_is_coloured_by_token_keeping_its_text() {
+    let lines = highlight("fn main() {\n    let x = 1;\n}", Some("rust"), CODE);
+    assert_eq!(plain(&lines), vec!["fn main() {", "    let x = 1;", "}"]);
+    assert!(colours(&lines[0]) > 1, "{:?}", lines[0]);
+}
+
+#[test]
+fn the_language_is_guessed_without_a_name() {
+    let python = highlight("def greet(name):\n    print(name)\n    return None", None, CODE);
+    assert!(colours(&python[0]) > 1, "{:?}", python[0]);
+
+    // Prose in a code block is left as it is
+    let prose = highlight("Roses are red\nviolets are blue", None, CODE);
+    assert_eq!(prose[0].spans, vec![Span::styled("Roses are red", CODE)]);
+}
+
+#[test]
+fn comments_colour_pre_blocks_and_fenced_paragraphs() {
+    let html = "Try:<p><pre><code>  fn main() {\n      let x = 1;\n  }\n</code></pre>";
+    let lines = to_styled_text(html, 40, TEXT, CODE, true).lines;
+    assert_eq!(plain(&lines), vec!["Try:", "", "  fn main() {", "      let x = 1;", "  }"]);
+    assert!(colours(&lines[2]) > 1, "{:?}", lines[2]);
+
+    // A blank line in fenced code splits it into paragraphs, put back together
+    let html = "Like so:<p>```python\ndef f(x):<p>    return None\n```\nAnd done.";
+    let lines = to_styled_text(html, 40, TEXT, CODE, true).lines;
+    assert_eq!(plain(&lines), vec!["Like so:", "", "def f(x):", "", "    return None", "", "And done."]);
+    assert!(colours(&lines[2]) > 1, "{:?}", lines[2]);
+    assert_eq!(lines[6].spans, vec![Span::styled("And done.", TEXT)]);
+}
+
+#[test]
+fn articles_fence_their_pre_blocks_with_the_language_of_the_class() {
+    let html = r#"<article><p>Example:</p><pre class="language-rust"><code>fn main() {
+    println!("&lt;hi&gt;");<br>}</code></pre><p>The end.</p></article>"#;
+    let article = extract_article(html, "https://blog.example/post");
+    assert_eq!(article.text, "Example:\n\n```rust\nfn main() {\n    println!(\"<hi>\");\n}\n```\n\nThe end.\n");
+}
+
+#[test]
+fn the_reader_keeps_code_lines_whole_and_coloured() {
+    let text = "Some words to wrap\n```rust\nfn main() { let long_name = 1; }\n```".to_string();
+    let mut reader = Reader::new(&Article { url: "https://blog.example/post".into(), text, links: Vec::new(), archived_from: None });
+    let lines = reader.lines(10, 10, CODE, true);
+    assert_eq!(plain(&lines), vec!["Some words", "to wrap", "```rust", "fn main() { let long_name = 1; }", "```"]);
+    assert_eq!(lines[2].spans, vec![Span::styled("```rust", CODE)]);
+    assert!(colours(&lines[3]) > 1, "{:?}", lines[3]);
+
+    let plain_code = reader.lines(10, 10, CODE, false);
+    assert_eq!(plain_code[3].spans, vec![Span::styled("fn main() { let long_name = 1; }", CODE)]);
+}
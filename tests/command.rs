@@ -0,0 +1,130 @@
+//! The `:` command line: commands, completion and history.
+
+mod common;
+
+use common::{press, run, titled};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_command::{complete, push_history, MAX_HISTORY};
+use hint::hint_hackernews::Feed;
+use hint::hint_session::Session;
+use hint::hint_theme::Theme;
+use ratatui::crossterm::event::KeyCode;
+
+fn model_with_titles(titles: &[&str]) -> Model {
+    let mut model = Model::default();
+    for (i, title) in titles.iter().enumerate() {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, titled(i as u64 + 1, title)));
+    }
+    model
+}
+
+#[test]
+fn command_names_and_arguments_complete() {
+    assert_eq!(complete("th").line, "theme ");
+    assert_eq!(complete("theme g").line, "theme gruvbox");
+    // "login" and "logout" share "log"
+    let completion = complete("lo");
+    assert_eq!(completion.line, "log");
+    assert_eq!(completion.candidates, vec!["login", "logout"]);
+    assert_eq!(complete("feed s").line, "feed show");
+    assert_eq!(complete("zzz").candidates, Vec::<&str>::new());
+}
+
+#[test]
+fn tab_completes_at_the_prompt() {
+    let mut model = Model::default();
+    press(&mut model, KeyCode::Char(':'));
+    press(&mut model, KeyCode::Char('f'));
+    press(&mut model, KeyCode::Char('e'));
+    press(&mut model, KeyCode::Tab);
+    assert_eq!(model.prompt.as_ref().unwrap().input, "feed ");
+    press(&mut model, KeyCode::Char('a'));
+    press(&mut model, KeyCode::Tab);
+    assert_eq!(press(&mut model, KeyCode::Enter), Some(Cmd::LoadFeed(Feed::Ask)));
+    assert_eq!(model.feed, Feed::Ask);
+}
+
+#[test]
+fn up_and_down_walk_the_history() {
+    let mut model = Model::default();
+    run(&mut model, "theme gruvbox");
+    run(&mut model, "limit 5");
+    press(&mut model, KeyCode::Char(':'));
+    press(&mut model, KeyCode::Up);
+    assert_eq!(model.prompt.as_ref().unwrap().input, "limit 5");
+    press(&mut model, KeyCode::Up);
+    press(&mut model, KeyCode::Up);
+    assert_eq!(model.prompt.as_ref().unwrap().input, "theme gruvbox");
+    press(&mut model, KeyCode::Down);
+    assert_eq!(model.prompt.as_ref().unwrap().input, "limit 5");
+    press(&mut model, KeyCode::Down);
+    assert_eq!(model.prompt.as_ref().unwrap().input, "");
+}
+
+#[test]
+fn history_skips_repeats_and_is_bounded() {
+    let mut history = Vec::new();
+    for i in 0..MAX_HISTORY + 10 {
+        push_history(&mut history, &format!("search {}", i));
+        push_history(&mut history, &format!("search {}", i));
+    }
+    assert_eq!(history.len(), MAX_HISTORY);
+    assert_eq!(history[0], "search 10");
+}
+
+#[test]
+fn theme_is_switched_and_remembered() {
+    let mut model = Model::default();
+    run(&mut model, "theme gruvbox");
+    assert_eq!(model.theme, Theme::GRUVBOX);
+    run(&mut model, "theme nope");
+    assert_eq!(model.notice.as_deref(), Some("Themes: default, gruvbox"));
+
+    let session = model.session();
+    assert_eq!(session.theme.as_deref(), Some("gruvbox"));
+    let restored = Model::restore(&session);
+    assert_eq!(restored.theme, Theme::GRUVBOX);
+    assert_eq!(restored.command_history, vec!["theme gruvbox", "theme nope"]);
+    assert_eq!(Model::restore(&Session::default()).theme, Theme::DEFAULT);
+}
+
+#[test]
+fn limit_caps_the_list() {
+    let mut model = model_with_titles(&["a", "b", "c"]);
+    update(&mut model, Msg::SelectLast);
+    run(&mut model, "limit 2");
+    assert_eq!(model.storylist.items.len(), 2);
+    assert_eq!(model.storylist.state.selected(), Some(1));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(9, "late")));
+    assert_eq!(model.storylist.items.len(), 2);
+
+    run(&mut model, "limit many");
+    assert_eq!(model.notice.as_deref(), Some("Usage: :limit [number]"));
+    run(&mut model, "limit");
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(9, "late")));
+    assert_eq!(model.storylist.items.len(), 3);
+}
+
+#[test]
+fn search_jumps_between_matching_titles() {
+    let mut model = model_with_titles(&["Rust 2024", "Go 1.22", "Why I like rust"]);
+    run(&mut model, "search rust");
+    assert_eq!(model.storylist.state.selected(), Some(0));
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(model.storylist.state.selected(), Some(2));
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(model.storylist.state.selected(), Some(0));
+
+    run(&mut model, "search haskell");
+    assert_eq!(model.notice.as_deref(), Some("No story matches \"haskell\""));
+}
+
+#[test]
+fn unknown_feed_shows_usage() {
+    let mut model = Model::default();
+    assert_eq!(run(&mut model, "feed worst"), None);
+    assert_eq!(
+        model.notice.as_deref(),
+        Some("Usage: :feed <top|new|best|ask|show|jobs|favorites|queue|history>")
+    );
+}
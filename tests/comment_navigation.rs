@@ -0,0 +1,150 @@
+//! Folding comment subtrees with Space, `zM` and `zR`, and skipping over them with `]]` and `[[`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    Terminal,
+};
+
+/// Two top-level comments, the first with a reply that has a reply of its own
+fn model_with_thread() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    let comment = |id, depth| Comment {
+        id,
+        author: format!("user{}", id),
+        text: format!("Comment {}", id),
+        time: None,
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    };
+    let comments = vec![comment(10, 0), comment(11, 1), comment(12, 2), comment(20, 0)];
+    let thread = CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: None,
+        comments,
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    model
+}
+
+fn visible_ids(model: &Model) -> Vec<u64> {
+    let view = model.comments.as_ref().unwrap();
+    let thread = view.thread.as_ref().unwrap();
+    view.visible().into_iter().map(|i| thread.comments[i].id).collect()
+}
+
+fn selected_author(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(40, 20)).unwrap();
+    let frame = terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = frame.buffer;
+    let row = (0..20)
+        .map(|y| (0..40).map(|x| buffer[(x, y)].symbol()).collect::<String>())
+        .find(|row| row.starts_with('>'))
+        .unwrap();
+    row.trim_start_matches('>').trim().to_string()
+}
+
+#[test]
+fn space_folds_the_selected_subtree() {
+    let mut model = model_with_thread();
+    // Past the story header to the first comment
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char(' '));
+    assert_eq!(visible_ids(&model), vec![10, 20]);
+
+    // The next row is now the following top-level comment
+    press(&mut model, KeyCode::Down);
+    assert_eq!(selected_author(&mut model), "user20");
+
+    press(&mut model, KeyCode::Up);
+    press(&mut model, KeyCode::Char(' '));
+    assert_eq!(visible_ids(&model), vec![10, 11, 12, 20]);
+}
+
+#[test]
+fn comments_without_replies_do_not_fold() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::End);
+    press(&mut model, KeyCode::Char(' '));
+    assert!(model.comments.as_ref().unwrap().collapsed.is_empty());
+}
+
+#[test]
+fn folded_comments_summarize_their_replies() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char(' '));
+    let mut terminal = Terminal::new(TestBackend::new(40, 20)).unwrap();
+    let frame = terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    let text: String = frame.buffer.content().iter().map(|cell| cell.symbol()).collect();
+    assert!(text.contains("[+ 2 children]"), "{}", text);
+    assert!(!text.contains("Comment 11"));
+}
+
+#[test]
+fn zm_and_zr_fold_and_unfold_everything() {
+    let mut model = model_with_thread();
+    for _ in 0..4 {
+        press(&mut model, KeyCode::Down);
+    }
+    press(&mut model, KeyCode::Char('z'));
+    press(&mut model, KeyCode::Char('M'));
+    assert_eq!(visible_ids(&model), vec![10, 20]);
+    assert_eq!(selected_author(&mut model), "user10", "the selection moves up to the folded parent");
+
+    press(&mut model, KeyCode::Char('z'));
+    press(&mut model, KeyCode::Char('R'));
+    assert_eq!(visible_ids(&model), vec![10, 11, 12, 20]);
+}
+
+#[test]
+fn unknown_sequences_are_dropped() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Char('z'));
+    press(&mut model, KeyCode::Char('j'));
+    assert_eq!(model.comments.as_ref().unwrap().state.selected(), None, "j was part of the sequence");
+    press(&mut model, KeyCode::Char('j'));
+    assert_eq!(model.comments.as_ref().unwrap().state.selected(), Some(0));
+}
+
+#[test]
+fn brackets_jump_between_top_level_comments() {
+    let mut model = model_with_thread();
+    let selected = |model: &Model| model.comments.as_ref().unwrap().state.selected();
+    press(&mut model, KeyCode::Char(']'));
+    press(&mut model, KeyCode::Char(']'));
+    assert_eq!(selected(&model), Some(1));
+    press(&mut model, KeyCode::Char(']'));
+    press(&mut model, KeyCode::Char(']'));
+    assert_eq!(selected(&model), Some(4), "the replies are skipped");
+
+    press(&mut model, KeyCode::Up);
+    press(&mut model, KeyCode::Char('['));
+    press(&mut model, KeyCode::Char('['));
+    assert_eq!(selected(&model), Some(1), "from a reply back to its top-level comment");
+
+    press(&mut model, KeyCode::Char('J'));
+    assert_eq!(selected(&model), Some(4));
+    press(&mut model, KeyCode::Char('J'));
+    assert_eq!(selected(&model), Some(4), "nothing further down");
+}
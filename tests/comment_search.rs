@@ -0,0 +1,117 @@
+//! Searching the open thread with `/`, and moving between matches with `n` and `N`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    style::Modifier,
+    Terminal,
+};
+
+fn comment(id: u64, author: &str, text: &str, depth: usize) -> Comment {
+    Comment {
+        id,
+        author: author.to_string(),
+        text: text.to_string(),
+        time: None,
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    }
+}
+
+fn model_with_thread() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    let comments = vec![
+        comment(10, "alan", "Rust is <i>fast</i>", 0),
+        comment(11, "ada", "Not as fast as C", 1),
+        comment(12, "rustacean", "Agreed", 0),
+        comment(13, "bob", "Unrelated", 0),
+    ];
+    let thread = CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: None,
+        comments,
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    model
+}
+
+fn search(model: &mut Model, text: &str) {
+    press(model, KeyCode::Char('/'));
+    assert_eq!(model.prompt.as_ref().unwrap().label, "/");
+    for c in text.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter);
+}
+
+fn selected_id(model: &Model) -> Option<u64> {
+    let view = model.comments.as_ref()?;
+    let i = view.visible()[view.state.selected()? - 1];
+    Some(view.thread.as_ref()?.comments[i].id)
+}
+
+#[test]
+fn slash_finds_text_and_authors_and_n_moves_between_matches() {
+    let mut model = model_with_thread();
+    search(&mut model, "RUST");
+    assert_eq!(selected_id(&model), Some(10));
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(selected_id(&model), Some(12), "the author matches");
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(selected_id(&model), Some(10), "wraps around to the top");
+    press(&mut model, KeyCode::Char('N'));
+    assert_eq!(selected_id(&model), Some(12), "and back to the bottom");
+}
+
+#[test]
+fn failed_search_says_so() {
+    let mut model = model_with_thread();
+    search(&mut model, "haskell");
+    assert_eq!(selected_id(&model), None);
+    assert_eq!(model.notice.as_deref(), Some("No comment matches \"haskell\""));
+}
+
+#[test]
+fn matches_are_highlighted_and_counted() {
+    let mut model = model_with_thread();
+    search(&mut model, "fast");
+    let mut terminal = Terminal::new(TestBackend::new(50, 16)).unwrap();
+    let frame = terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    let buffer = frame.buffer;
+    let rows: Vec<String> = (0..16).map(|y| (0..50).map(|x| buffer[(x, y)].symbol()).collect()).collect();
+    assert!(rows.iter().any(|row| row.contains("Comments (/fast: 2 matches, n to jump)")), "{:#?}", rows);
+
+    let y = rows.iter().position(|row| row.contains("Not as fast")).unwrap();
+    let x = rows[y].find("fast").unwrap() as u16;
+    assert!(buffer[(x, y as u16)].modifier.contains(Modifier::REVERSED));
+    assert!(buffer[(x + 3, y as u16)].modifier.contains(Modifier::REVERSED));
+    assert!(!buffer[(x - 2, y as u16)].modifier.contains(Modifier::REVERSED));
+}
+
+#[test]
+fn an_empty_search_gives_n_back_to_new_comments() {
+    let mut model = model_with_thread();
+    search(&mut model, "rust");
+    search(&mut model, "");
+    assert_eq!(model.comments.as_ref().unwrap().search, None);
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(model.notice.as_deref(), Some("No new comments since the last visit"));
+}
@@ -0,0 +1,179 @@
+//! Comment threads: fetching, text handling and the reply flow.
+
+use hint::hint_app::{update, Cmd, ComposeTarget, Model, Msg};
+use hint::hint_auth::HnSession;
+use hint::hint_comments::{fetch_thread, format_age, wrap_text, Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_html::to_plain_text;
+use hint::hnreader::HnClient;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[tokio::test]
+async fn demo_thread_is_flattened_in_reading_order() {
+    let thread = fetch_thread(&HnClient::demo(), 39000000).await.unwrap();
+    assert_eq!(thread.story_id, 39000000);
+    let order: Vec<(u64, usize)> = thread.comments.iter().map(|c| (c.id, c.depth)).collect();
+    assert_eq!(order, vec![(40000000, 0), (40000001, 1)]);
+    assert_eq!(thread.comments[0].author, "alan");
+}
+
+#[test]
+fn html_is_reduced_to_plain_text() {
+    assert_eq!(
+        to_plain_text("I&#x27;ve <i>wanted</i> this.<p>Does it &amp; &quot;that&quot;?"),
+        "I've wanted this.\n\nDoes it & \"that\"?"
+    );
+    assert_eq!(to_plain_text("a &bogus; b < c"), "a &bogus; b < c");
+}
+
+#[test]
+fn text_wraps_on_words_and_keeps_line_breaks() {
+    assert_eq!(wrap_text("one two three", 8), vec!["one two", "three"]);
+    assert_eq!(wrap_text("a\n\nb", 8), vec!["a", "", "b"]);
+    assert_eq!(wrap_text("abcdefghij", 4), vec!["abcd", "efgh", "ij"]);
+}
+
+#[test]
+fn ages_are_rounded_down() {
+    assert_eq!(format_age(1000, 1030), "just now");
+    assert_eq!(format_age(0, 7200), "2h ago");
+    assert_eq!(format_age(0, 3 * 86_400 + 5), "3d ago");
+}
+
+fn press(model: &mut Model, key: KeyEvent) -> Option<Cmd> {
+    update(model, Msg::Key(key))
+}
+
+fn thread() -> CommentThread {
+    CommentThread {
+        story_id: 1,
+        title: "A story".to_string(),
+        author: "pg".to_string(),
+        url: None,
+        text: None,
+        comments: vec![Comment {
+            id: 2,
+            author: "alan".to_string(),
+            text: "First!".to_string(),
+            time: None,
+            depth: 0,
+            dead: false,
+            deleted: false,
+            voted: false,
+            unloaded: Vec::new(),
+        }],
+        unloaded: Vec::new(),
+    }
+}
+
+fn model_with_open_thread() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+    assert_eq!(press(&mut model, KeyEvent::from(KeyCode::Char('c'))), Some(Cmd::LoadComments(1)));
+    update(&mut model, Msg::CommentsLoaded(thread()));
+    model
+}
+
+#[test]
+fn reply_needs_login() {
+    let mut model = model_with_open_thread();
+    press(&mut model, KeyEvent::from(KeyCode::Char('R')));
+    assert!(model.compose.is_none());
+    assert_eq!(model.notice.as_deref(), Some("Log in with :login to reply"));
+}
+
+#[test]
+fn reply_is_previewed_then_posted() {
+    let mut model = model_with_open_thread();
+    let session = HnSession::new("alice".to_string(), "alice&abc".to_string());
+    model.hn_session = Some(session.clone());
+
+    // Second row is the first comment
+    press(&mut model, KeyEvent::from(KeyCode::Char('j')));
+    press(&mut model, KeyEvent::from(KeyCode::Char('j')));
+    press(&mut model, KeyEvent::from(KeyCode::Char('R')));
+    let target = ComposeTarget::Reply { parent: 2, replying_to: "alan".to_string() };
+    assert_eq!(model.compose.as_ref().unwrap().target, target);
+    for code in [KeyCode::Char('h'), KeyCode::Char('i'), KeyCode::Enter, KeyCode::Char('!')] {
+        press(&mut model, KeyEvent::from(code));
+    }
+    assert_eq!(model.compose.as_ref().unwrap().text, "hi\n!");
+
+    press(&mut model, KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+    assert!(model.compose.as_ref().unwrap().preview);
+    // Back to editing and into the preview again
+    press(&mut model, KeyEvent::from(KeyCode::Char('n')));
+    assert!(!model.compose.as_ref().unwrap().preview);
+    press(&mut model, KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+
+    let cmd = press(&mut model, KeyEvent::from(KeyCode::Char('y')));
+    assert_eq!(cmd, Some(Cmd::Post { session, target, text: "hi\n!".to_string() }));
+    assert!(model.compose.is_none());
+
+    assert_eq!(update(&mut model, Msg::ReplyPosted), Some(Cmd::LoadComments(1)));
+    assert_eq!(model.notice.as_deref(), Some("Reply posted"));
+}
+
+#[test]
+fn failed_reply_gives_the_text_back() {
+    let mut model = model_with_open_thread();
+    update(
+        &mut model,
+        Msg::PostFailed {
+            target: ComposeTarget::Reply { parent: 1, replying_to: "pg".to_string() },
+            text: "lost?".to_string(),
+            error: "HN said: You're posting too fast.".to_string(),
+        },
+    );
+    let compose = model.compose.as_ref().unwrap();
+    assert_eq!(compose.text, "lost?");
+    assert!(!compose.preview);
+    assert_eq!(model.notice.as_deref(), Some("Reply failed: HN said: You're posting too fast."));
+}
+
+fn type_line(model: &mut Model, line: &str) -> Option<Cmd> {
+    for c in line.chars() {
+        press(model, KeyEvent::from(KeyCode::Char(c)));
+    }
+    press(model, KeyEvent::from(KeyCode::Enter))
+}
+
+#[test]
+fn submit_asks_for_title_url_and_text() {
+    let mut model = Model::default();
+    type_line(&mut model, ":submit");
+    assert_eq!(model.notice.as_deref(), Some("Log in with :login to submit"));
+
+    let session = HnSession::new("alice".to_string(), "alice&abc".to_string());
+    model.hn_session = Some(session.clone());
+    type_line(&mut model, ":submit");
+    type_line(&mut model, "Ask HN: Favourite terminal apps?");
+    type_line(&mut model, "ftp://nope");
+    assert_eq!(model.notice.as_deref(), Some("The URL must start with http:// or https://"));
+    // Empty URL makes it a text post
+    type_line(&mut model, "");
+    let target = ComposeTarget::Submission {
+        title: "Ask HN: Favourite terminal apps?".to_string(),
+        url: None,
+    };
+    assert_eq!(model.compose.as_ref().unwrap().target, target);
+
+    for c in "Mine is hint".chars() {
+        press(&mut model, KeyEvent::from(KeyCode::Char(c)));
+    }
+    press(&mut model, KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL));
+    let cmd = press(&mut model, KeyEvent::from(KeyCode::Enter));
+    assert_eq!(cmd, Some(Cmd::Post { session, target, text: "Mine is hint".to_string() }));
+}
+
+#[test]
+fn submitted_story_is_selected_at_the_top() {
+    let mut model = model_with_open_thread();
+    let story = HnStory::new("99".into(), "alice".into(), "Mine".into(), None, "story".into());
+    update(&mut model, Msg::Submitted(story));
+    assert!(model.comments.is_none());
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 99);
+    assert_eq!(model.notice.as_deref(), Some("Submitted as item 99"));
+}
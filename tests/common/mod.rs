@@ -0,0 +1,68 @@
+//! Helpers shared by the integration tests: stories to load, keys to press and what the list then
+//! holds. Each test crate uses its own share of them.
+#![allow(dead_code)]
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+/// Story `id`, titled "Story <id>", with no link
+pub fn story(id: u64) -> HnStory {
+    HnStory::new(id.to_string(), "author".into(), format!("Story {}", id), None, "story".into())
+}
+
+/// Story `id` under `title`, with no link
+pub fn titled(id: u64, title: &str) -> HnStory {
+    HnStory::new(id.to_string(), "author".into(), title.into(), None, "story".into())
+}
+
+/// Points, time and comment count for the stories these helpers make, set builder-style
+pub trait StoryBuilder {
+    fn with_score(self, points: u32) -> Self;
+    fn with_time(self, time: u64) -> Self;
+    fn with_descendants(self, comments: u32) -> Self;
+}
+
+impl StoryBuilder for HnStory {
+    fn with_score(mut self, points: u32) -> Self {
+        self.set_score(points);
+        self
+    }
+
+    fn with_time(mut self, time: u64) -> Self {
+        self.set_time(time);
+        self
+    }
+
+    fn with_descendants(mut self, comments: u32) -> Self {
+        self.set_descendants(comments);
+        self
+    }
+}
+
+/// The Top feed with stories 1 to `n`, nothing selected
+pub fn model_with_stories(n: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=n {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    model
+}
+
+pub fn press(model: &mut Model, code: KeyCode) -> Option<Cmd> {
+    update(model, Msg::Key(KeyEvent::from(code)))
+}
+
+/// Type `line` at the `:` prompt and run it
+pub fn run(model: &mut Model, line: &str) -> Option<Cmd> {
+    update(model, Msg::OpenCommandLine);
+    for c in line.chars() {
+        update(model, Msg::PromptInput(c));
+    }
+    update(model, Msg::PromptSubmit)
+}
+
+/// The ids of the list's stories, top to bottom
+pub fn ids(model: &Model) -> Vec<u64> {
+    model.storylist.items.iter().map(|item| item.id()).collect()
+}
@@ -0,0 +1,89 @@
+//! Questions asked before actions that are hard to take back: hiding a marked range,
+//! `:readall`, `:clearhistory` and posting a reply.
+
+mod common;
+
+use common::{press, run};
+use hint::hint_app::{update, Model, Msg, Pane, Status};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::Terminal;
+
+fn model_with_stories(n: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=n {
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), None, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    update(&mut model, Msg::SelectFirst);
+    model
+}
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..10).map(|y| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n").collect()
+}
+
+#[test]
+fn hiding_a_marked_range_asks_first() {
+    let mut model = model_with_stories(3);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('x'));
+    assert_eq!(model.focused_pane(), Pane::Confirm);
+    assert!(screen(&mut model).contains("Hide 2 stories?"));
+
+    // Other keys wait for an answer
+    press(&mut model, KeyCode::Char('j'));
+    assert_eq!(model.storylist.items.len(), 3);
+    press(&mut model, KeyCode::Esc);
+    assert!(model.confirm.is_none());
+    assert_eq!(model.storylist.items.len(), 3);
+
+    press(&mut model, KeyCode::Char('x'));
+    press(&mut model, KeyCode::Char('y'));
+    assert_eq!(model.storylist.items.len(), 1);
+}
+
+#[test]
+fn a_single_story_hides_at_once() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Char('x'));
+    assert!(model.confirm.is_none());
+    assert!(model.hidden.contains(1));
+}
+
+#[test]
+fn read_all_marks_every_story_after_a_yes() {
+    let mut model = model_with_stories(3);
+    run(&mut model, "readall");
+    assert_eq!(model.confirm.as_ref().map(|confirm| confirm.question.as_str()), Some("Mark 3 stories as read?"));
+    press(&mut model, KeyCode::Enter);
+    assert!(model.storylist.items.iter().all(|item| item.status == Status::Read));
+
+    run(&mut model, "readall");
+    assert!(model.confirm.is_none());
+    assert_eq!(model.notice.as_deref(), Some("Every story is read already"));
+    press(&mut model, KeyCode::Char('u'));
+    assert!(model.storylist.items.iter().all(|item| item.status == Status::Unread));
+}
+
+#[test]
+fn clearing_the_history_can_be_called_off() {
+    let mut model = model_with_stories(2);
+    run(&mut model, "clearhistory");
+    assert_eq!(model.notice.as_deref(), Some("Nothing opened yet, the history is empty"));
+
+    press(&mut model, KeyCode::Char('o'));
+    assert_eq!(model.history.len(), 1);
+    run(&mut model, "clearhistory");
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(model.history.len(), 1);
+    run(&mut model, "clearhistory");
+    press(&mut model, KeyCode::Char('y'));
+    assert!(model.history.is_empty());
+}
@@ -0,0 +1,121 @@
+//! The `[OP]` badge, and dead and deleted comments: left out by default, greyed out with `d`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_theme::Theme;
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    crossterm::event::KeyCode,
+    Terminal,
+};
+
+fn comment(id: u64, author: &str, depth: usize) -> Comment {
+    Comment {
+        id,
+        author: author.to_string(),
+        text: format!("Comment {}", id),
+        time: None,
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    }
+}
+
+/// A reply by the story's author, a dead comment, and a deleted one that still has a live reply
+fn model_with_thread() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    let dead = Comment { dead: true, ..comment(20, "spammer", 0) };
+    let dead_reply = Comment { dead: true, ..comment(21, "troll", 1) };
+    let deleted = Comment { deleted: true, text: String::new(), ..comment(30, "[deleted]", 0) };
+    let comments = vec![comment(10, "alan", 0), comment(11, "pg", 1), dead, dead_reply, deleted, comment(31, "ada", 1)];
+    let thread = CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: None,
+        comments,
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    model
+}
+
+fn visible_ids(model: &Model) -> Vec<u64> {
+    let view = model.comments.as_ref().unwrap();
+    let thread = view.thread.as_ref().unwrap();
+    view.visible().into_iter().map(|i| thread.comments[i].id).collect()
+}
+
+fn draw(model: &mut Model) -> Buffer {
+    let mut terminal = Terminal::new(TestBackend::new(40, 30)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap().buffer.clone()
+}
+
+/// The row starting with `text`, and the colour of its first letter
+fn find_row(buffer: &Buffer, text: &str) -> Option<(String, ratatui::style::Color)> {
+    (0..buffer.area.height).find_map(|y| {
+        let row: String = (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect();
+        let x = row.find(text)?;
+        let column = row[..x].chars().count() as u16;
+        Some((row.trim().to_string(), buffer[(column, y)].fg))
+    })
+}
+
+#[test]
+fn dead_comments_are_left_out_unless_they_have_live_replies() {
+    let model = model_with_thread();
+    assert_eq!(visible_ids(&model), vec![10, 11, 30, 31]);
+}
+
+#[test]
+fn d_shows_dead_comments_greyed_out() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Char('d'));
+    assert_eq!(visible_ids(&model), vec![10, 11, 20, 21, 30, 31]);
+    assert_eq!(model.notice.as_deref(), Some("Showing dead and deleted comments"));
+
+    let buffer = draw(&mut model);
+    let theme = Theme::DEFAULT;
+    assert_eq!(find_row(&buffer, "Comment 10").unwrap().1, theme.text);
+    assert_eq!(find_row(&buffer, "Comment 20").unwrap().1, theme.read_text);
+    assert!(find_row(&buffer, "spammer").unwrap().0.ends_with("[dead]"));
+    assert_eq!(find_row(&buffer, "[deleted]").unwrap().1, theme.read_text);
+
+    // The setting carries over to the next thread
+    press(&mut model, KeyCode::Left);
+    press(&mut model, KeyCode::Char('c'));
+    assert!(model.comments.as_ref().unwrap().show_dead);
+}
+
+#[test]
+fn hiding_dead_comments_moves_the_selection_to_the_comment_before() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Char('d'));
+    for _ in 0..4 {
+        press(&mut model, KeyCode::Down);
+    }
+    assert_eq!(model.comments.as_ref().unwrap().state.selected(), Some(3), "on the dead comment");
+    press(&mut model, KeyCode::Char('d'));
+    assert_eq!(model.comments.as_ref().unwrap().state.selected(), Some(2), "on the reply before it");
+}
+
+#[test]
+fn comments_by_the_story_author_get_a_badge() {
+    let mut model = model_with_thread();
+    let buffer = draw(&mut model);
+    assert_eq!(find_row(&buffer, "pg [OP]").map(|(row, _)| row), Some("pg [OP]".to_string()));
+    assert!(!find_row(&buffer, "alan").unwrap().0.contains("[OP]"));
+}
@@ -0,0 +1,35 @@
+//! A story arriving again updates its row instead of adding a duplicate.
+
+mod common;
+
+use common::{ids, titled, StoryBuilder};
+use hint::hint_app::{update, Model, Msg, Status};
+use hint::hint_hackernews::Feed;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+#[test]
+fn refreshed_story_updates_its_row() {
+    let mut model = Model::default();
+    for id in 1..=3 {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, titled(id, "Old").with_score(10)));
+    }
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    update(&mut model, Msg::ToggleStatus);
+
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(2, "New title").with_score(99)));
+    assert_eq!(ids(&model), vec![1, 2, 3]);
+    let item = &model.storylist.items[1];
+    assert_eq!((item.story.title(), item.story.score()), ("New title", Some(99)));
+    assert_eq!(item.status, Status::Read, "what the user did to the row stays");
+    assert_eq!(model.storylist.state.selected(), Some(1));
+}
+
+#[test]
+fn story_without_a_score_keeps_the_old_one() {
+    let mut model = Model::default();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(1, "Story").with_score(42)));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(1, "Story")));
+    assert_eq!(ids(&model), vec![1]);
+    assert_eq!(model.storylist.items[0].story.score(), Some(42));
+}
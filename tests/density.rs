@@ -0,0 +1,63 @@
+//! Compact and comfortable list densities, switched with `i` and kept across sessions.
+
+use hint::hint_app::{update, Density, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_session::Session;
+use hint::hint_view::view;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn story(id: u64, score: u32, age: u64) -> HnStory {
+    let url = Some("https://github.com/codervijo/hint".to_string());
+    let mut story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), url, "story".into());
+    story.set_score(score);
+    story.set_time(chrono::Utc::now().timestamp() as u64 - age);
+    story
+}
+
+fn rows(model: &mut Model) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..8).map(|y| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string()).collect()
+}
+
+fn loaded() -> Model {
+    let mut model = Model::default();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1, 120, 3 * 3600 + 60)));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(2, 7, 300)));
+    model
+}
+
+#[test]
+fn compact_is_a_line_per_story() {
+    let mut model = loaded();
+    let rows = rows(&mut model);
+    assert_eq!(rows[1], "  ☐ Story 1 (github.com)");
+    assert_eq!(rows[2], "  ☐ Story 2 (github.com)");
+}
+
+#[test]
+fn comfortable_adds_a_line_of_details() {
+    let mut model = loaded();
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('i'))));
+    assert_eq!(model.density, Density::Comfortable);
+    let rows = rows(&mut model);
+    assert_eq!(rows[1], "  ☐ Story 1");
+    assert_eq!(rows[2], "    ▲ 120 · by pg · 3h ago · github.com");
+    assert_eq!(rows[3], "  ☐ Story 2");
+    assert_eq!(rows[4], "    ▲ 7 · by pg · 5m ago · github.com");
+
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('i'))));
+    assert_eq!(model.density, Density::Compact);
+}
+
+#[test]
+fn density_is_kept_across_sessions() {
+    let mut model = loaded();
+    update(&mut model, Msg::ToggleDensity);
+    let session = model.session();
+    assert_eq!(session.density, Density::Comfortable);
+    assert_eq!(Model::restore(&session).density, Density::Comfortable);
+    assert_eq!(Model::restore(&Session::default()).density, Density::Compact);
+}
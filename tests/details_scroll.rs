@@ -0,0 +1,74 @@
+//! Scrolling the details pane once Tab has moved the focus to it.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, DetailsLayout, Model, Msg, Pane};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    Terminal,
+};
+
+fn model_with_stories(n: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=n {
+        let url = format!("https://example.com/{}", "long/path/".repeat(8));
+        let story = HnStory::new(id.to_string(), "author".into(), format!("Story {}", id), Some(url), "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+fn draw(model: &mut Model) {
+    let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+}
+
+#[test]
+fn tab_opens_and_focuses_the_details() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Tab);
+    assert!(model.show_details);
+    assert_eq!(model.focus, Pane::Details);
+
+    press(&mut model, KeyCode::Down);
+    assert_eq!(model.details_scroll, 1);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 1, "the list keeps its selection");
+
+    press(&mut model, KeyCode::Tab);
+    assert_eq!(model.focus, Pane::List);
+    press(&mut model, KeyCode::Down);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 2);
+    assert_eq!(model.details_scroll, 0, "a new story starts from the top");
+}
+
+#[test]
+fn scrolling_stops_at_the_last_line() {
+    let mut model = model_with_stories(1);
+    model.details_layout = DetailsLayout::Right;
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Tab);
+    press(&mut model, KeyCode::End);
+    draw(&mut model);
+    let last = model.details_scroll;
+    assert!(last > 0 && last < 20, "clamped to the wrapped text, got {}", last);
+
+    press(&mut model, KeyCode::PageUp);
+    assert_eq!(model.details_scroll, last.saturating_sub(10));
+    press(&mut model, KeyCode::Home);
+    assert_eq!(model.details_scroll, 0);
+}
+
+#[test]
+fn closing_the_details_gives_the_focus_back_to_the_list() {
+    let mut model = model_with_stories(1);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Tab);
+    press(&mut model, KeyCode::Enter);
+    assert!(!model.show_details);
+    assert_eq!(model.focus, Pane::List);
+}
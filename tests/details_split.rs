@@ -0,0 +1,40 @@
+//! Moving the split between the list and the details pane with `<` and `>`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::Model;
+use hint::hint_session::Session;
+use ratatui::crossterm::event::KeyCode;
+
+#[test]
+fn split_moves_in_steps_within_bounds() {
+    let mut model = Model::default();
+    assert_eq!(model.details_split(), 50);
+    press(&mut model, KeyCode::Char('>'));
+    assert_eq!(model.details_split(), 55);
+    for _ in 0..10 {
+        press(&mut model, KeyCode::Char('>'));
+    }
+    assert_eq!(model.details_split(), 80);
+    for _ in 0..20 {
+        press(&mut model, KeyCode::Char('<'));
+    }
+    assert_eq!(model.details_split(), 20);
+}
+
+#[test]
+fn split_is_kept_in_the_session() {
+    let mut model = Model::default();
+    press(&mut model, KeyCode::Char('<'));
+    let session = model.session();
+    assert_eq!(session.details_split, Some(45));
+    assert_eq!(Model::restore(&session).details_split(), 45);
+}
+
+#[test]
+fn out_of_range_splits_from_the_config_are_clamped() {
+    let mut model = Model::restore(&Session::default());
+    model.details_split = Some(95);
+    assert_eq!(model.details_split(), 80);
+}
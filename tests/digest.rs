@@ -0,0 +1,61 @@
+//! Headless digests of recent top stories.
+
+use hint::hint_digest::{fetch_candidates, render, select, DigestOptions};
+use hint::hint_export::ExportFormat;
+use hint::hnreader::{HnClient, Story};
+
+const NOW: i64 = 1_760_600_000;
+
+fn story(id: u64, title: &str, score: u32, age: i64) -> Story {
+    serde_json::from_value(serde_json::json!({
+        "id": id,
+        "by": "pg",
+        "title": title,
+        "score": score,
+        "time": NOW - age,
+        "type": "story",
+    }))
+    .unwrap()
+}
+
+fn options() -> DigestOptions {
+    DigestOptions { since: 86_400, min_score: 100, watches: Vec::new(), limit: 30 }
+}
+
+fn ids(stories: &[Story]) -> Vec<u64> {
+    stories.iter().map(|story| story.id).collect()
+}
+
+#[test]
+fn digest_ranks_recent_high_scoring_stories() {
+    let stories = vec![
+        story(1, "Old news", 900, 2 * 86_400),
+        story(2, "Good", 150, 3600),
+        story(3, "Better", 300, 7200),
+        story(4, "Quiet", 20, 60),
+    ];
+    assert_eq!(ids(&select(stories, &options(), NOW)), vec![3, 2]);
+}
+
+#[test]
+fn watched_words_get_in_below_the_score() {
+    let stories = vec![story(1, "Rust 2.0 released", 20, 60), story(2, "Quiet", 20, 60)];
+    let options = DigestOptions { watches: vec!["rust".to_string()], ..options() };
+    assert_eq!(ids(&select(stories, &options, NOW)), vec![1]);
+}
+
+#[test]
+fn digest_is_cut_to_the_limit() {
+    let stories = (1..=5).map(|id| story(id, "Story", 100 + id as u32, 60)).collect();
+    let options = DigestOptions { limit: 2, ..options() };
+    assert_eq!(ids(&select(stories, &options, NOW)), vec![5, 4]);
+}
+
+#[tokio::test]
+async fn demo_digest_renders_as_markdown() {
+    let stories = fetch_candidates(&HnClient::demo()).await.unwrap();
+    let options = DigestOptions { since: i64::MAX / 2, limit: 2, ..options() };
+    let markdown = render(ExportFormat::Markdown, "24h", &select(stories, &options, NOW));
+    assert!(markdown.starts_with("# HackerNews digest | last 24h\n\n"));
+    assert_eq!(markdown.lines().filter(|line| line.starts_with("- ")).count(), 2);
+}
@@ -0,0 +1,118 @@
+//! `P` in the details pane lists the other submissions of the selected story's link, looked up on
+//! HN Search, with when each was posted and how many comments it drew.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_search::discussions;
+use hint::hint_view::view;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+use wiremock::matchers::{path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const LINK: &str = "https://www.example.com/post";
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    let stories = [
+        HnStory::new("1".into(), "pg".into(), "Post".into(), Some(LINK.to_string()), "story".into()),
+        HnStory::new("2".into(), "dang".into(), "Ask HN: Anything?".into(), None, "story".into()),
+    ];
+    for story in stories {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..12).map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n").collect()
+}
+
+/// Two earlier submissions of the link, one of another page of the site, and the story itself
+async fn search_server() -> MockServer {
+    let server = MockServer::start().await;
+    let body = r#"{"hits": [
+        {"objectID": "1", "title": "Post", "author": "pg", "url": "https://www.example.com/post", "created_at_i": 1705400000},
+        {"objectID": "7", "title": "Post (2019)", "url": "http://example.com/post/", "num_comments": 120, "created_at_i": 1551700000},
+        {"objectID": "6", "title": "Other post", "url": "https://example.com/post-two", "num_comments": 3},
+        {"objectID": "5", "title": "Post", "author": "tptacek", "url": "https://example.com/post#top", "num_comments": 1, "created_at_i": 1300000000}
+    ]}"#;
+    Mock::given(path("/search_by_date"))
+        .and(query_param("query", LINK))
+        .and(query_param("restrictSearchableAttributes", "url"))
+        .and(query_param("tags", "story"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn only_submissions_of_the_same_page_are_kept() {
+    let server = search_server().await;
+    let stories = discussions(LINK, &server.uri()).await.unwrap();
+    let ids: Vec<u64> = stories.iter().map(HnStory::id).collect();
+    assert_eq!(ids, vec![1, 7, 5]);
+    assert_eq!(stories[1].descendants(), Some(120));
+}
+
+#[tokio::test]
+async fn capital_p_lists_the_other_discussions_of_the_link() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Tab);
+    let cmd = press(&mut model, KeyCode::Char('P'));
+    assert_eq!(cmd, Some(Cmd::LoadDiscussions(LINK.to_string())));
+    assert_eq!(model.feed, Feed::Discussions);
+    assert_eq!(model.feed_title(), "Discussions: www.example.com/post");
+    assert!(model.loading);
+
+    let server = search_server().await;
+    let stories = discussions(LINK, &server.uri()).await.unwrap();
+    // Results of a link looked up before are dropped
+    update(&mut model, Msg::DiscussionsLoaded { url: "https://example.org".into(), stories: stories.clone() });
+    assert!(model.storylist.items.is_empty());
+
+    update(&mut model, Msg::DiscussionsLoaded { url: LINK.into(), stories });
+    let ids: Vec<u64> = model.storylist.items.iter().map(|item| item.id()).collect();
+    assert_eq!(ids, vec![7, 5], "the story they were looked up from is left out");
+    assert!(!model.loading);
+
+    // Each row says when it was and how much was said
+    let screen = screen(&mut model);
+    assert!(screen.contains("Post (2019) (2019-03-0"), "{}", screen);
+    assert!(screen.contains("120 comments)"), "{}", screen);
+    assert!(screen.contains("1 comment)"), "{}", screen);
+
+    // A refresh asks again
+    assert_eq!(press(&mut model, KeyCode::Char('R')), Some(Cmd::LoadDiscussions(LINK.to_string())));
+}
+
+#[test]
+fn a_link_never_posted_again_says_so() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Tab);
+    press(&mut model, KeyCode::Char('P'));
+    let story = HnStory::new("1".into(), "pg".into(), "Post".into(), Some(LINK.to_string()), "story".into());
+    update(&mut model, Msg::DiscussionsLoaded { url: LINK.into(), stories: vec![story] });
+    assert!(model.storylist.items.is_empty());
+    assert_eq!(model.notice.as_deref(), Some("No other discussions of this link"));
+}
+
+#[test]
+fn text_posts_have_no_link_to_look_up() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Tab);
+    assert_eq!(press(&mut model, KeyCode::Char('P')), None);
+    assert_eq!(model.feed, Feed::Top);
+    assert_eq!(model.notice.as_deref(), Some("A text post has no link to look up"));
+}
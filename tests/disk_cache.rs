@@ -0,0 +1,92 @@
+//! `:cache stats` shows what the disk caches of API items and articles hold, `:cache clear`
+//! empties them, and the config's limits keep them from growing without end.
+
+mod common;
+
+use common::run;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_cache::{format_size, CacheUsage, DiskCache};
+use hint::hint_config::Config;
+use hint::hint_store::Store;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+/// A file of `bytes` under the cache, written `age` seconds ago
+fn write(store: &Store, cache: DiskCache, name: &str, bytes: usize, age: u64) {
+    let path = cache.dir(store).join(name);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, vec![b'x'; bytes]).unwrap();
+    let modified = SystemTime::now() - Duration::from_secs(age);
+    fs::File::options().write(true).open(&path).unwrap().set_modified(modified).unwrap();
+}
+
+#[test]
+fn usage_counts_every_file_however_deep() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    assert_eq!(DiskCache::Items.usage(&store), CacheUsage::default(), "nothing saved yet");
+
+    write(&store, DiskCache::Items, "topstories.json", 100, 0);
+    write(&store, DiskCache::Items, "item/1.json", 50, 0);
+    write(&store, DiskCache::Articles, "a.json", 7, 0);
+    assert_eq!(DiskCache::Items.usage(&store), CacheUsage { files: 2, bytes: 150 });
+    assert_eq!(DiskCache::Articles.usage(&store).to_string(), "1 file, 7 B");
+
+    assert_eq!(DiskCache::Items.clear(&store).unwrap(), CacheUsage { files: 2, bytes: 150 });
+    assert_eq!(DiskCache::Items.usage(&store), CacheUsage::default());
+    assert_eq!(DiskCache::Articles.usage(&store).files, 1, "the other cache is kept");
+}
+
+#[test]
+fn trimming_drops_what_was_written_longest_ago() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    write(&store, DiskCache::Articles, "old.json", 40, 300);
+    write(&store, DiskCache::Articles, "older.json", 40, 600);
+    write(&store, DiskCache::Articles, "new.json", 40, 0);
+
+    assert_eq!(DiskCache::Articles.trim(&store, 90).unwrap(), CacheUsage { files: 1, bytes: 40 });
+    let articles = DiskCache::Articles.dir(&store);
+    assert!(!articles.join("older.json").exists());
+    assert!(articles.join("old.json").exists() && articles.join("new.json").exists());
+}
+
+#[test]
+fn sizes_read_like_a_file_manager_shows_them() {
+    assert_eq!(format_size(512), "512 B");
+    assert_eq!(format_size(1536), "1.5 KB");
+    assert_eq!(format_size(20 * 1024 * 1024), "20 MB");
+}
+
+#[test]
+fn limits_come_in_megabytes() {
+    let config: Config = serde_json::from_str(r#"{"article_cache_mb": 2}"#).unwrap();
+    assert_eq!(config.cache_limit(DiskCache::Articles), Some(2 * 1024 * 1024));
+    assert_eq!(config.cache_limit(DiskCache::Items), None);
+}
+
+#[test]
+fn stats_show_in_the_debug_overlay() {
+    let mut model = Model::default();
+    assert_eq!(run(&mut model, "cache stats"), Some(Cmd::MeasureCache));
+    assert!(model.show_metrics);
+
+    let usage = vec![(DiskCache::Items, CacheUsage { files: 3, bytes: 2048 })];
+    update(&mut model, Msg::CacheMeasured(usage.clone()));
+    assert_eq!(model.cache_usage, usage);
+}
+
+#[test]
+fn clearing_asks_first() {
+    let mut model = Model::default();
+    assert_eq!(run(&mut model, "cache clear articles"), None);
+    assert_eq!(model.confirm.as_ref().map(|confirm| confirm.question.as_str()), Some("Delete the cached articles?"));
+    assert_eq!(update(&mut model, Msg::Answer(true)), Some(Cmd::ClearCache(vec![DiskCache::Articles])));
+
+    run(&mut model, "cache clear");
+    assert_eq!(update(&mut model, Msg::Answer(true)), Some(Cmd::ClearCache(DiskCache::ALL.to_vec())));
+
+    run(&mut model, "cache clear comments");
+    assert_eq!(model.notice.as_deref(), Some("Usage: :cache <stats|clear [items|articles]>"));
+    assert!(model.confirm.is_none());
+}
@@ -0,0 +1,95 @@
+//! Story domains and filtering the list by site.
+
+mod common;
+
+use common::{ids, press};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_browser::{domain, on_site};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::KeyCode;
+
+const URLS: [Option<&str>; 4] = [
+    Some("https://github.com/a"),
+    Some("https://www.example.com/b"),
+    Some("https://gist.github.com/c"),
+    None,
+];
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for (i, url) in URLS.iter().enumerate() {
+        let id = i as u64 + 1;
+        let url = url.map(str::to_string);
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), url, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+#[test]
+fn domains_drop_www_and_cover_subdomains() {
+    assert_eq!(domain("https://www.Example.com:8080/x?y").as_deref(), Some("example.com"));
+    assert_eq!(domain("not a url"), None);
+    assert!(on_site("gist.github.com", "github.com"));
+    assert!(!on_site("notgithub.com", "github.com"));
+}
+
+#[test]
+fn only_this_domain_keeps_the_site_and_its_subdomains() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('d'));
+    assert_eq!(ids(&model), vec![1, 3]);
+    assert_eq!(model.only_domain.as_deref(), Some("github.com"));
+
+    // Stories still arriving are filtered too
+    let late = HnStory::new("5".into(), "pg".into(), "Late".into(), Some("https://lwn.net".into()), "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, late));
+    assert_eq!(ids(&model), vec![1, 3]);
+
+    // Pressing it again brings everything back by loading the feed again
+    assert_eq!(press(&mut model, KeyCode::Char('d')), Some(Cmd::LoadFeed(Feed::Top)));
+    assert_eq!(model.only_domain, None);
+}
+
+#[test]
+fn never_this_domain_adds_up() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('D'));
+    assert_eq!(ids(&model), vec![2, 4]);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 2);
+    press(&mut model, KeyCode::Char('D'));
+    assert_eq!(ids(&model), vec![4]);
+    assert_eq!(model.never_domains, vec!["github.com".to_string(), "example.com".to_string()]);
+}
+
+#[test]
+fn text_posts_have_no_domain() {
+    let mut model = model_with_stories();
+    for _ in 0..4 {
+        press(&mut model, KeyCode::Down);
+    }
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 4);
+    press(&mut model, KeyCode::Char('d'));
+    assert_eq!(ids(&model).len(), 4);
+    assert_eq!(model.notice.as_deref(), Some("Text posts are not from any site"));
+}
+
+#[test]
+fn domain_command_sets_and_clears_filters() {
+    let mut model = model_with_stories();
+    let command = |model: &mut Model, line: &str| {
+        press(model, KeyCode::Char(':'));
+        for c in line.chars() {
+            press(model, KeyCode::Char(c));
+        }
+        press(model, KeyCode::Enter)
+    };
+    command(&mut model, "domain never GitHub.com");
+    assert_eq!(ids(&model), vec![2, 4]);
+    assert_eq!(command(&mut model, "domain"), Some(Cmd::LoadFeed(Feed::Top)));
+    assert!(model.never_domains.is_empty());
+    command(&mut model, "domain sometimes x");
+    assert_eq!(model.notice.as_deref(), Some("Usage: :domain [only|never <site>]"));
+}
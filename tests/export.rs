@@ -0,0 +1,166 @@
+//! Writing lists out with `:export`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_export::{thread_markdown, ExportFormat, ExportedStory};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::KeyCode;
+use std::path::PathBuf;
+
+fn model_with_stories(n: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=n {
+        let url = Some(format!("https://example.com/{}", id));
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), url, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+fn command(model: &mut Model, line: &str) -> Option<Cmd> {
+    press(model, KeyCode::Char(':'));
+    for c in line.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter)
+}
+
+fn story(id: u64, title: &str, score: Option<u32>, read: bool) -> ExportedStory {
+    ExportedStory {
+        id,
+        title: title.to_string(),
+        url: format!("https://example.com/{}", id),
+        score,
+        author: "pg".to_string(),
+        read,
+    }
+}
+
+#[test]
+fn markdown_is_a_task_list() {
+    let stories = [story(1, "Show HN: [beta]", Some(42), true), story(2, "Ask", None, false)];
+    let markdown = ExportFormat::Markdown.render("HackerNews | Top", &stories);
+    assert_eq!(
+        markdown,
+        "# HackerNews | Top\n\n\
+         - [x] [Show HN: \\[beta\\]](https://example.com/1) (42 points by pg)\n\
+         - [ ] [Ask](https://example.com/2) (by pg)\n"
+    );
+}
+
+#[test]
+fn csv_quotes_awkward_fields() {
+    let csv = ExportFormat::Csv.render("", &[story(1, "Rust, \"fast\"", Some(7), false)]);
+    assert_eq!(
+        csv,
+        "id,title,url,score,author,read\n1,\"Rust, \"\"fast\"\"\",https://example.com/1,7,pg,false\n"
+    );
+}
+
+#[test]
+fn json_round_trips() {
+    let json = ExportFormat::Json.render("", &[story(1, "Story", None, true)]);
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value[0]["title"], "Story");
+    assert_eq!(value[0]["read"], true);
+    assert!(value[0]["score"].is_null());
+}
+
+#[test]
+fn export_writes_the_whole_list_by_default() {
+    let mut model = model_with_stories(3);
+    let Some(Cmd::Export { path, contents }) = command(&mut model, "export csv") else {
+        panic!("expected an export");
+    };
+    assert_eq!(path, PathBuf::from("hint-top.csv"));
+    assert_eq!(contents.lines().count(), 4);
+}
+
+#[test]
+fn export_writes_only_the_visual_range() {
+    let mut model = model_with_stories(3);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Enter);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    let Some(Cmd::Export { path, contents }) = command(&mut model, "export md digest.md") else {
+        panic!("expected an export");
+    };
+    assert_eq!(path, PathBuf::from("digest.md"));
+    assert!(contents.contains("- [x] [Story 1]"));
+    assert!(contents.contains("- [ ] [Story 2]"));
+    assert!(!contents.contains("Story 3"));
+    assert_eq!(model.storylist.visual_anchor, None);
+}
+
+#[test]
+fn export_needs_a_known_format() {
+    let mut model = model_with_stories(1);
+    assert_eq!(command(&mut model, "export pdf"), None);
+    assert_eq!(model.notice.as_deref(), Some("Usage: :export <markdown|json|csv> [file]"));
+}
+
+fn comment(id: u64, author: &str, text: &str, depth: usize) -> Comment {
+    Comment {
+        id,
+        author: author.to_string(),
+        text: text.to_string(),
+        time: Some(1_700_000_000),
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    }
+}
+
+fn thread() -> CommentThread {
+    CommentThread {
+        story_id: 1,
+        title: "Story 1".to_string(),
+        author: "pg".to_string(),
+        url: Some("https://example.com/1".to_string()),
+        text: None,
+        comments: vec![
+            comment(2, "alan", "Two points:<p>one &amp; two", 0),
+            comment(3, "grace", "Agreed.", 1),
+        ],
+        unloaded: Vec::new(),
+    }
+}
+
+#[test]
+fn thread_is_written_as_nested_list() {
+    assert_eq!(
+        thread_markdown(&thread()),
+        "# Story 1\n\n\
+         <https://example.com/1>\n\n\
+         by pg | [discussion](https://news.ycombinator.com/item?id=1)\n\n\
+         ---\n\n\
+         - **alan** · 2023-11-14 22:13 UTC\n  Two points:\n\n  one & two\n\
+         \x20\x20- **grace** · 2023-11-14 22:13 UTC\n    Agreed.\n"
+    );
+}
+
+#[test]
+fn export_from_the_comment_viewer_writes_the_thread() {
+    let mut model = model_with_stories(1);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    assert_eq!(command(&mut model, "export"), None, "nothing to write while loading");
+    update(&mut model, Msg::CommentsLoaded(thread()));
+
+    let Some(Cmd::Export { path, contents }) = command(&mut model, "export") else {
+        panic!("expected an export");
+    };
+    assert_eq!(path, PathBuf::from("hint-1.md"));
+    assert_eq!(contents, thread_markdown(&thread()));
+    let Some(Cmd::Export { path, .. }) = command(&mut model, "export markdown story.md") else {
+        panic!("expected an export");
+    };
+    assert_eq!(path, PathBuf::from("story.md"));
+    assert_eq!(command(&mut model, "export csv"), None);
+}
@@ -0,0 +1,112 @@
+//! The config's `feeds` gives each feed a sort and filters of its own, put in place as the feed is
+//! switched to; `:sort` and `:minscore` change them for the feed showing.
+
+mod common;
+
+use common::{ids, run, story, StoryBuilder};
+use hint::hint_app::{update, Cmd, FeedDefaults, Model, Msg, StorySort};
+use hint::hint_config::Config;
+use hint::hint_hackernews::Feed;
+use hint::hint_session::Session;
+use hint::hint_store::Store;
+
+/// Stories 1 to 4 in the feed's order, the later ones posted more recently
+fn load(model: &mut Model, feed: Feed) {
+    update(model, Msg::FeedOrder(feed, vec![1, 2, 3, 4]));
+    for (id, points) in [(1, 50), (2, 5), (3, 30), (4, 80)] {
+        update(model, Msg::StoryLoaded(feed, story(id).with_score(points).with_time(1_700_000_000 + id * 60)));
+    }
+}
+
+fn model_with_ask_defaults() -> Model {
+    let config: Config = serde_json::from_str(r#"{"feeds": {"ask": {"min_score": 20, "sort": "new"}}}"#).unwrap();
+    let mut model = Model::default();
+    model.feed_defaults = config.feeds;
+    model
+}
+
+#[test]
+fn each_feed_starts_with_its_own_sort_and_filters() {
+    let mut model = model_with_ask_defaults();
+    assert_eq!(run(&mut model, "feed ask"), Some(Cmd::LoadFeed(Feed::Ask)));
+    assert_eq!((model.sort, model.min_score), (StorySort::New, Some(20)));
+    load(&mut model, Feed::Ask);
+    assert_eq!(ids(&model), vec![4, 3, 1], "newest first, without the story under 20 points");
+
+    // A feed the config says nothing about is back to its own order, unfiltered
+    run(&mut model, "feed top");
+    assert_eq!(model.feed_defaults.get(&Feed::Top), None);
+    load(&mut model, Feed::Top);
+    assert_eq!(ids(&model), vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn startup_puts_the_first_feeds_defaults_in_place() {
+    let mut model = Model::default();
+    model.feed_defaults.insert(Feed::Top, FeedDefaults { sort: StorySort::Score, min_score: None });
+    model.use_feed_defaults();
+    load(&mut model, Feed::Top);
+    assert_eq!(ids(&model), vec![4, 1, 3, 2]);
+}
+
+#[test]
+fn sort_and_minscore_change_the_feed_showing() {
+    let mut model = Model::default();
+    load(&mut model, Feed::Top);
+    assert_eq!(run(&mut model, "sort score"), None);
+    assert_eq!(ids(&model), vec![4, 1, 3, 2]);
+    run(&mut model, "sort");
+    assert_eq!(ids(&model), vec![1, 2, 3, 4], "back in the feed's order");
+
+    assert_eq!(run(&mut model, "minscore 40"), None);
+    assert_eq!(ids(&model), vec![1, 4]);
+    // Stories already dropped come back only with the feed loaded again
+    assert_eq!(run(&mut model, "minscore 10"), Some(Cmd::LoadFeed(Feed::Top)));
+    assert_eq!(model.min_score, Some(10));
+    assert_eq!(run(&mut model, "minscore"), Some(Cmd::LoadFeed(Feed::Top)));
+    assert_eq!(model.min_score, None);
+
+    run(&mut model, "sort hot");
+    assert_eq!(model.notice.as_deref(), Some("Usage: :sort [rank|new|score|comments]"));
+}
+
+/// Start the way `main` does: the session, then the config's defaults for the feed it is on
+fn start(store: &Store, config: &str) -> Model {
+    let config: Config = serde_json::from_str(config).unwrap();
+    let mut model = Model::restore(&Session::load(store));
+    model.feed_defaults = config.feeds;
+    model.use_feed_defaults();
+    model
+}
+
+#[test]
+fn a_first_start_keeps_the_configured_sort() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let model = start(&store, r#"{"feeds": {"top": {"sort": "score"}}}"#);
+    assert_eq!(model.sort, StorySort::Score);
+
+    // Nor does quitting without a `:sort` tie the next start to it
+    model.session().save(&store).unwrap();
+    let model = start(&store, r#"{"feeds": {"top": {"sort": "new"}}}"#);
+    assert_eq!(model.sort, StorySort::New);
+}
+
+#[test]
+fn a_sort_picked_for_the_feed_outlasts_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let config = r#"{"feeds": {"top": {"sort": "score"}, "ask": {"sort": "new"}}}"#;
+    let mut model = start(&store, config);
+    run(&mut model, "sort comments");
+    model.session().save(&store).unwrap();
+    let mut model = start(&store, config);
+    assert_eq!(model.sort, StorySort::Comments);
+
+    // Another feed has its own configured order, and the pick is gone on coming back
+    run(&mut model, "feed ask");
+    assert_eq!(model.sort, StorySort::New);
+    run(&mut model, "feed top");
+    assert_eq!(model.sort, StorySort::Score);
+    assert_eq!(model.session().sort, None);
+}
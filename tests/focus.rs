@@ -0,0 +1,63 @@
+//! Keys go to the focused pane, and the focused pane is marked while it shares the screen.
+
+mod common;
+
+use common::{model_with_stories, press};
+use hint::hint_app::{Model, Pane};
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    style::Modifier,
+    Terminal,
+};
+
+/// Whether the pane with this title has it drawn in reverse
+fn title_reversed(model: &mut Model, title: &str) -> bool {
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    let frame = terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = frame.buffer;
+    let row = |y| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>();
+    let y = (0..12).find(|&y| row(y).contains(title)).expect("pane on screen");
+    (0..60).any(|x| buffer[(x, y)].modifier.contains(Modifier::REVERSED))
+}
+
+#[test]
+fn prompts_take_the_keys_over_any_pane() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Tab);
+    assert_eq!(model.focused_pane(), Pane::Details);
+    press(&mut model, KeyCode::Char(':'));
+    assert_eq!(model.focused_pane(), Pane::Prompt);
+
+    // j is typed rather than scrolling the details
+    press(&mut model, KeyCode::Char('j'));
+    assert_eq!(model.prompt.as_ref().unwrap().input, "j");
+    press(&mut model, KeyCode::Esc);
+    assert_eq!(model.focused_pane(), Pane::Details);
+}
+
+#[test]
+fn comments_take_the_keys_until_closed() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    assert_eq!(model.focused_pane(), Pane::Comments);
+    press(&mut model, KeyCode::Esc);
+    assert_eq!(model.focused_pane(), Pane::List);
+    assert!(!model.should_exit);
+}
+
+#[test]
+fn focused_pane_title_is_reversed_when_split() {
+    let mut model = model_with_stories(2);
+    assert!(!title_reversed(&mut model, "HackerNews"), "nothing to tell apart with the list alone");
+
+    press(&mut model, KeyCode::Tab);
+    assert!(!title_reversed(&mut model, "HackerNews"));
+    assert!(title_reversed(&mut model, "Story Details"));
+
+    press(&mut model, KeyCode::Tab);
+    assert!(title_reversed(&mut model, "HackerNews"));
+    assert!(!title_reversed(&mut model, "Story Details"));
+}
@@ -0,0 +1,81 @@
+//! The front page showing at the last quit comes back at once on the next launch, flagged as
+//! cached, and gives way to the fresh one as it loads.
+
+mod common;
+
+use common::{ids, story, StoryBuilder};
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_cache::FrontPage;
+use hint::hint_hackernews::Feed;
+use hint::hint_session::Session;
+use hint::hint_store::Store;
+use hint::hint_view::view;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+fn header(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 6)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..80).map(|x| buffer[(x, 0)].symbol()).collect()
+}
+
+fn cached_model() -> Model {
+    let stories = [story(1).with_score(10), story(2).with_score(20), story(3).with_score(30)];
+    let mut model = Model::restore(&Session { selected_story: Some(2), ..Session::default() });
+    model.show_front_page(&FrontPage::new(Feed::Top, hint::hint_snooze::now() - 7200, &stories));
+    model
+}
+
+#[test]
+fn cached_rows_show_at_once_with_the_selection() {
+    let mut model = cached_model();
+    assert_eq!(ids(&model), vec![1, 2, 3]);
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(2));
+    assert_eq!(model.storylist.items[0].story.score(), Some(10));
+    assert!(header(&mut model).contains("| cached 2h ago"), "{}", header(&mut model));
+}
+
+#[test]
+fn fresh_order_drops_stale_rows_and_clears_the_flag() {
+    let mut model = cached_model();
+    update(&mut model, Msg::FeedOrder(Feed::Top, vec![4, 3, 2]));
+    assert_eq!(ids(&model), vec![3, 2], "story 1 fell off the front page");
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(2));
+
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(3).with_score(999)));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(4)));
+    assert_eq!(ids(&model), vec![4, 3, 2]);
+    assert_eq!(model.storylist.items[1].story.score(), Some(999));
+    assert!(model.cached_at.is_some());
+
+    update(&mut model, Msg::FeedLoaded(Feed::Top));
+    assert_eq!(model.cached_at, None);
+    assert!(!header(&mut model).contains("cached"));
+}
+
+#[test]
+fn page_of_another_feed_is_not_shown() {
+    let mut model = Model::default();
+    model.feed = Feed::New;
+    model.show_front_page(&FrontPage::new(Feed::Top, 0, &[story(1)]));
+    assert!(model.storylist.items.is_empty());
+    assert_eq!(model.cached_at, None);
+}
+
+#[test]
+fn front_page_round_trips_through_the_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut model = Model::default();
+    assert_eq!(model.front_page(), None, "nothing loaded yet");
+
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(5).with_score(50)));
+    update(&mut model, Msg::FeedLoaded(Feed::Top));
+    model.front_page().unwrap().save(&store).unwrap();
+
+    let mut next = Model::default();
+    next.show_front_page(&FrontPage::load(&store));
+    assert_eq!(ids(&next), vec![5]);
+    assert_eq!(next.storylist.items[0].story.score(), Some(50));
+}
@@ -0,0 +1,124 @@
+//! `:group` gathers the list's stories by site or author under headers that Space folds.
+
+mod common;
+
+use common::{ids, press};
+use hint::hint_app::{update, Grouping, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+
+const STORIES: [(&str, Option<&str>); 6] = [
+    ("pg", Some("https://lwn.net/a")),
+    ("dang", Some("https://github.com/b")),
+    ("pg", Some("https://www.example.com/c")),
+    ("tptacek", Some("https://github.com/d")),
+    ("dang", None),
+    ("pg", Some("https://github.com/f")),
+];
+
+fn story(id: u64) -> HnStory {
+    let (author, url) = STORIES[id as usize - 1];
+    HnStory::new(id.to_string(), author.into(), format!("Story {}", id), url.map(str::to_string), "story".into())
+}
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::Top, (1..=6).collect()));
+    for id in 1..=5 {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    model
+}
+
+fn command(model: &mut Model, line: &str) {
+    press(model, KeyCode::Char(':'));
+    for c in line.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter);
+}
+
+fn rows(model: &mut Model) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..12).map(|y| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string()).collect()
+}
+
+#[test]
+fn the_biggest_group_comes_first_keeping_each_groups_order() {
+    let mut model = model_with_stories();
+    command(&mut model, "group domain");
+    assert_eq!(model.group, Some(Grouping::Domain));
+    assert_eq!(ids(&model), vec![2, 4, 1, 3, 5]);
+
+    let rows = rows(&mut model);
+    assert!(rows[0].contains("grouped by site"), "{}", rows[0]);
+    assert_eq!(rows[1], "  ▾ github.com · 2 stories");
+    assert_eq!(rows[2], "  ☐ Story 2 (github.com)");
+    assert_eq!(rows[3], "  ☐ Story 4 (github.com)");
+    assert_eq!(rows[4], "  ▾ lwn.net · 1 story");
+    assert_eq!(rows[8], "  ▾ text posts · 1 story");
+
+    // Stories still arriving join their group
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(6)));
+    assert_eq!(ids(&model), vec![2, 4, 6, 1, 3, 5]);
+}
+
+#[test]
+fn stories_group_by_author_too() {
+    let mut model = model_with_stories();
+    command(&mut model, "group author");
+    assert_eq!(ids(&model), vec![1, 3, 2, 5, 4]);
+    command(&mut model, "group");
+    assert_eq!(model.group, None);
+    assert_eq!(ids(&model), vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn space_folds_a_group_under_its_first_story() {
+    let mut model = model_with_stories();
+    command(&mut model, "group author");
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 3);
+
+    press(&mut model, KeyCode::Char(' '));
+    assert_eq!(ids(&model), vec![1, 2, 5, 4]);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 1);
+    assert_eq!(rows(&mut model)[1], "> ▸ pg · 2 stories, 1 folded");
+
+    // A new story of a folded group is folded with the others
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(6)));
+    assert_eq!(ids(&model), vec![1, 2, 5, 4]);
+    assert_eq!(rows(&mut model)[1], "> ▸ pg · 3 stories, 2 folded");
+
+    press(&mut model, KeyCode::Char(' '));
+    assert_eq!(ids(&model), vec![1, 3, 6, 2, 5, 4]);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 1);
+}
+
+#[test]
+fn ungrouping_unfolds_everything() {
+    let mut model = model_with_stories();
+    command(&mut model, "group domain");
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char(' '));
+    assert_eq!(ids(&model), vec![2, 1, 3, 5]);
+    command(&mut model, "group");
+    assert_eq!(ids(&model), vec![1, 2, 3, 4, 5]);
+    assert!(model.storylist.folded.is_empty());
+}
+
+#[test]
+fn space_only_folds_a_grouped_list() {
+    let mut model = model_with_stories();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char(' '));
+    assert_eq!(ids(&model), vec![1, 2, 3, 4, 5]);
+    assert_eq!(model.notice.as_deref(), Some("Group the list with :group domain or :group author to fold it"));
+    command(&mut model, "group site");
+    assert_eq!(model.notice.as_deref(), Some("Usage: :group [domain|author]"));
+}
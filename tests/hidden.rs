@@ -0,0 +1,81 @@
+//! Hiding stories with `x`.
+
+mod common;
+
+use common::{press, story};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_auth::HnSession;
+use hint::hint_hackernews::Feed;
+use hint::hint_hidden::HiddenStories;
+use hint::hint_store::Store;
+use ratatui::crossterm::event::KeyCode;
+
+fn model_with_stories(ids: &[u64]) -> Model {
+    let mut model = Model::default();
+    for id in ids {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(*id)));
+    }
+    model
+}
+
+#[test]
+fn hidden_story_leaves_the_list_and_stays_out() {
+    let mut model = model_with_stories(&[1, 2, 3]);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    let cmd = press(&mut model, KeyCode::Char('x'));
+
+    assert!(model.hidden.contains(2));
+    assert_eq!(cmd, Some(Cmd::SaveHidden(model.hidden.clone())));
+    let ids: Vec<u64> = model.storylist.items.iter().map(|item| item.id()).collect();
+    assert_eq!(ids, vec![1, 3]);
+    // The row below moves up into the selection
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 3);
+
+    // Other feeds and reloads skip it too
+    update(&mut model, Msg::NextFeed);
+    update(&mut model, Msg::StoryLoaded(Feed::New, story(2)));
+    assert!(model.storylist.items.is_empty());
+}
+
+#[test]
+fn hiding_syncs_with_hn_when_logged_in() {
+    let mut model = model_with_stories(&[1]);
+    let session = HnSession::new("alice".to_string(), "alice&abc".to_string());
+    model.hn_session = Some(session.clone());
+    update(&mut model, Msg::SelectFirst);
+    let cmd = press(&mut model, KeyCode::Char('x'));
+    let expected = Cmd::Batch(vec![
+        Cmd::SaveHidden(model.hidden.clone()),
+        Cmd::SyncHide { session, id: 1, hide: true },
+    ]);
+    assert_eq!(cmd, Some(expected));
+}
+
+#[test]
+fn unhide_brings_back_the_last_one() {
+    let mut model = model_with_stories(&[1, 2]);
+    update(&mut model, Msg::SelectFirst);
+    press(&mut model, KeyCode::Char('x'));
+    press(&mut model, KeyCode::Char('x'));
+    assert_eq!(model.hidden.len(), 2);
+
+    for c in ":unhide".chars() {
+        press(&mut model, KeyCode::Char(c));
+    }
+    press(&mut model, KeyCode::Enter);
+    assert!(!model.hidden.contains(2));
+    assert!(model.hidden.contains(1));
+    assert_eq!(model.notice.as_deref(), Some("Story 2 is back in its feeds"));
+}
+
+#[test]
+fn hidden_stories_round_trip_through_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut hidden = HiddenStories::default();
+    hidden.hide(5);
+    hidden.hide(9);
+    hidden.save(&store).unwrap();
+    assert_eq!(HiddenStories::load(&store), hidden);
+}
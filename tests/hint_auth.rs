@@ -0,0 +1,402 @@
+//! Login against a mocked news.ycombinator.com.
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_auth::{AuthError, HnSession, HnWeb};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use wiremock::matchers::{body_string_contains, header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn login_returns_session_from_user_cookie() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .and(body_string_contains("acct=alice"))
+        .and(body_string_contains("pw=hunter2"))
+        .respond_with(
+            ResponseTemplate::new(302)
+                .insert_header("Location", "news")
+                .insert_header("Set-Cookie", "user=alice&abc123; Path=/; Secure; HttpOnly"),
+        )
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let session = web.login("alice", "hunter2").await.unwrap();
+    assert_eq!(session.username, "alice");
+    assert_eq!(session.cookie_header(), "user=alice&abc123");
+    assert!(!format!("{:?}", session).contains("abc123"), "cookie stays out of Debug output");
+}
+
+#[tokio::test]
+async fn login_without_cookie_is_bad_login() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/login"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("Bad login."))
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let err = web.login("alice", "wrong").await.unwrap_err();
+    assert!(matches!(err, AuthError::BadLogin));
+}
+
+#[tokio::test]
+async fn authenticated_pages_send_the_cookie() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/favorites"))
+        .and(header("Cookie", "user=alice&abc123"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<html></html>"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let session = HnSession::new("alice".to_string(), "alice&abc123".to_string());
+    assert_eq!(web.get_page(&session, "favorites").await.unwrap(), "<html></html>");
+}
+
+fn type_text(model: &mut Model, text: &str) -> Option<Cmd> {
+    let mut cmd = None;
+    for c in text.chars() {
+        let code = if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) };
+        cmd = update(model, Msg::Key(KeyEvent::from(code)));
+    }
+    cmd
+}
+
+#[test]
+fn login_command_prompts_for_credentials() {
+    let mut model = Model::default();
+    assert_eq!(type_text(&mut model, ":login\n"), None);
+    assert_eq!(model.prompt.as_ref().unwrap().label, "Username: ");
+
+    type_text(&mut model, "alice\nsecret");
+    let prompt = model.prompt.as_ref().unwrap();
+    assert!(prompt.secret);
+    assert_eq!(prompt.display_input(), "******");
+
+    let cmd = type_text(&mut model, "\n");
+    assert_eq!(
+        cmd,
+        Some(Cmd::Login { username: "alice".to_string(), password: "secret".to_string() })
+    );
+    assert!(model.prompt.is_none());
+
+    let session = HnSession::new("alice".to_string(), "alice&abc".to_string());
+    update(&mut model, Msg::LoggedIn(session.clone()));
+    assert_eq!(model.hn_session, Some(session));
+}
+
+#[test]
+fn login_with_username_skips_username_prompt() {
+    let mut model = Model::default();
+    type_text(&mut model, ":login bob\n");
+    assert_eq!(model.prompt.as_ref().unwrap().label, "Password: ");
+}
+
+#[test]
+fn unknown_command_is_reported() {
+    let mut model = Model::default();
+    type_text(&mut model, ":frobnicate\n");
+    assert_eq!(model.notice.as_deref(), Some("Unknown command: frobnicate"));
+    assert_eq!(type_text(&mut model, ":logout\n"), Some(Cmd::Logout));
+}
+
+const ITEM_PAGE: &str = r#"<td class="votelinks"><center>
+<a id='up_8863' class='clicky' href='vote?id=8863&amp;how=up&amp;auth=f00dcafe&amp;goto=item%3Fid%3D8863'>
+<div class='votearrow' title='upvote'></div></a></center></td>
+<a href="hide?id=8863&amp;auth=beef1234&amp;goto=item%3Fid%3D8863">hide</a>"#;
+
+#[test]
+fn auth_tokens_are_found_per_action() {
+    use hint::hint_auth::auth_token;
+    assert_eq!(auth_token(ITEM_PAGE, "vote", 8863).as_deref(), Some("f00dcafe"));
+    assert_eq!(auth_token(ITEM_PAGE, "hide", 8863).as_deref(), Some("beef1234"));
+    assert_eq!(auth_token(ITEM_PAGE, "vote", 1), None);
+    assert_eq!(auth_token(ITEM_PAGE, "fave", 8863), None);
+}
+
+#[tokio::test]
+async fn vote_follows_the_item_page_link() {
+    use wiremock::matchers::query_param;
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/item"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(ITEM_PAGE))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/vote"))
+        .and(query_param("id", "8863"))
+        .and(query_param("how", "up"))
+        .and(query_param("auth", "f00dcafe"))
+        .and(header("Cookie", "user=alice&abc123"))
+        .respond_with(ResponseTemplate::new(302))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let session = HnSession::new("alice".to_string(), "alice&abc123".to_string());
+    web.vote(&session, 8863, true).await.unwrap();
+
+    let err = web.vote(&session, 1, true).await.unwrap_err();
+    assert!(matches!(err, AuthError::Unavailable("vote")));
+}
+
+#[test]
+fn vote_is_optimistic_and_reverted_on_failure() {
+    use hint::hint_hackernews::{Feed, HnStory};
+
+    let mut model = Model::default();
+    let mut story = HnStory::new(
+        "8863".to_string(),
+        "dhouston".to_string(),
+        "Dropbox".to_string(),
+        None,
+        "story".to_string(),
+    );
+    story.set_score(111);
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+
+    assert_eq!(update(&mut model, Msg::ToggleVote), None, "needs a login first");
+    assert_eq!(model.notice.as_deref(), Some("Log in with :login to vote"));
+
+    let session = HnSession::new("alice".to_string(), "alice&abc".to_string());
+    model.hn_session = Some(session.clone());
+    let cmd = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('v'))));
+    assert_eq!(cmd, Some(Cmd::Vote { session, id: 8863, up: true }));
+    let item = &model.storylist.items[0];
+    assert!(item.voted);
+    assert_eq!(item.story.score(), Some(112));
+
+    update(
+        &mut model,
+        Msg::VoteFailed { id: 8863, up: true, error: "bad login".to_string() },
+    );
+    let item = &model.storylist.items[0];
+    assert!(!item.voted);
+    assert_eq!(item.story.score(), Some(111));
+    assert_eq!(model.notice.as_deref(), Some("Vote failed: bad login"));
+}
+
+#[test]
+fn v_in_a_thread_votes_on_the_selected_comment() {
+    use hint::hint_comments::{Comment, CommentThread};
+    use hint::hint_hackernews::{Feed, HnStory};
+
+    let mut model = Model::default();
+    let story = HnStory::new("8863".into(), "dhouston".into(), "Dropbox".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('c'))));
+    let comment = Comment {
+        id: 9224,
+        author: "BrandonM".into(),
+        text: "For a Linux user...".into(),
+        time: None,
+        depth: 0,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    };
+    let thread = CommentThread {
+        story_id: 8863,
+        title: "Dropbox".into(),
+        author: "dhouston".into(),
+        url: None,
+        text: None,
+        comments: vec![comment],
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    // Past the story's own row to the comment
+    update(&mut model, Msg::SelectNext);
+    update(&mut model, Msg::SelectNext);
+    let session = HnSession::new("alice".to_string(), "alice&abc".to_string());
+    model.hn_session = Some(session.clone());
+    let voted = |model: &Model| model.comments.as_ref().unwrap().thread.as_ref().unwrap().comments[0].voted;
+
+    let cmd = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('v'))));
+    assert_eq!(cmd, Some(Cmd::Vote { session: session.clone(), id: 9224, up: true }));
+    assert!(voted(&model));
+    assert!(!model.storylist.items[0].voted, "the story keeps its own vote");
+
+    update(&mut model, Msg::VoteFailed { id: 9224, up: true, error: "bad login".to_string() });
+    assert!(!voted(&model));
+    assert_eq!(model.notice.as_deref(), Some("Vote failed: bad login"));
+
+    // The row of the story itself votes on the story
+    update(&mut model, Msg::SelectFirst);
+    let cmd = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('v'))));
+    assert_eq!(cmd, Some(Cmd::Vote { session, id: 8863, up: true }));
+    assert!(model.storylist.items[0].voted);
+}
+
+#[test]
+fn item_ids_are_scraped_from_listing_rows() {
+    use hint::hint_auth::item_ids;
+    let page = r#"<tr class='athing submission' id='111'>
+        <td>..</td></tr><tr class="athing" id="222"><td class="title">athing</td></tr>"#;
+    assert_eq!(item_ids(page), vec![111, 222]);
+}
+
+#[tokio::test]
+async fn favorites_follow_more_links_and_fave_uses_token() {
+    use wiremock::matchers::query_param;
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/favorites"))
+        .and(query_param("id", "alice"))
+        .and(query_param("p", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "<tr class='athing' id='1'></tr><a href='favorites?id=alice&amp;p=2' class='morelink'>",
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/favorites"))
+        .and(query_param("p", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("<tr class='athing' id='2'>"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/item"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            "<a href=\"fave?id=8863&amp;auth=abcd&amp;goto=item%3Fid%3D8863\">favorite</a>",
+        ))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fave"))
+        .and(query_param("auth", "abcd"))
+        .and(query_param("un", "t"))
+        .respond_with(ResponseTemplate::new(302))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let session = HnSession::new("alice".to_string(), "alice&abc123".to_string());
+    assert_eq!(web.favorite_ids(&session).await.unwrap(), vec![1, 2]);
+    web.favorite(&session, 8863, false).await.unwrap();
+}
+
+#[test]
+fn favorites_command_needs_login_and_switches_list() {
+    use hint::hint_hackernews::Feed;
+
+    let mut model = Model::default();
+    assert_eq!(type_text(&mut model, ":favorites\n"), None);
+    assert_eq!(model.feed, Feed::Top);
+
+    let session = HnSession::new("alice".to_string(), "alice&abc".to_string());
+    model.hn_session = Some(session.clone());
+    assert_eq!(type_text(&mut model, ":favorites\n"), Some(Cmd::LoadFavorites(session)));
+    assert_eq!(model.feed, Feed::Favorites);
+    assert_eq!(Feed::Favorites.next(), Feed::Top);
+}
+
+#[test]
+fn hidden_inputs_are_found_by_name() {
+    use hint::hint_auth::hidden_input;
+
+    let page = r#"<form method="post" action="comment"><input type="hidden" name="parent" value="7"><input type="hidden" name="hmac" value="f00d"><textarea name="text"></textarea>"#;
+    assert_eq!(hidden_input(page, "hmac").as_deref(), Some("f00d"));
+    assert_eq!(hidden_input(page, "text"), None);
+}
+
+async fn mount_reply_form(server: &MockServer) {
+    use wiremock::matchers::query_param;
+
+    Mock::given(method("GET"))
+        .and(path("/reply"))
+        .and(query_param("id", "7"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<form method="post" action="comment"><input type="hidden" name="hmac" value="f00d">"#,
+        ))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn reply_posts_the_form_with_its_hmac() {
+    let server = MockServer::start().await;
+    mount_reply_form(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/comment"))
+        .and(header("Cookie", "user=alice&abc123"))
+        .and(body_string_contains("parent=7"))
+        .and(body_string_contains("hmac=f00d"))
+        .and(body_string_contains("text=Nice+work"))
+        .respond_with(ResponseTemplate::new(302).insert_header("Location", "item?id=7"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let session = HnSession::new("alice".to_string(), "alice&abc123".to_string());
+    web.reply(&session, 7, "Nice work").await.unwrap();
+}
+
+#[tokio::test]
+async fn reply_error_page_is_reported() {
+    let server = MockServer::start().await;
+    mount_reply_form(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/comment"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string("<html><body>You&#x27;re posting too fast.</body></html>"),
+        )
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let session = HnSession::new("alice".to_string(), "alice&abc123".to_string());
+    let err = web.reply(&session, 7, "again").await.unwrap_err();
+    assert_eq!(err.to_string(), "HN said: You're posting too fast.");
+}
+
+#[tokio::test]
+async fn submit_finds_the_new_item_under_submitted() {
+    use wiremock::matchers::query_param;
+
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/submit"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"<form action="r"><input type="hidden" name="fnid" value="abc">"#),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/r"))
+        .and(body_string_contains("fnid=abc"))
+        .and(body_string_contains("title=Show+HN%3A+hint"))
+        .and(body_string_contains("url=https%3A%2F%2Fexample.com"))
+        .respond_with(ResponseTemplate::new(302).insert_header("Location", "newest"))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/submitted"))
+        .and(query_param("id", "alice"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"<tr class="athing" id="4242"></tr><tr class="athing" id="17"></tr>"#),
+        )
+        .mount(&server)
+        .await;
+
+    let web = HnWeb::with_web_url(&server.uri());
+    let session = HnSession::new("alice".to_string(), "alice&abc123".to_string());
+    let id = web.submit(&session, "Show HN: hint", "https://example.com", "").await.unwrap();
+    assert_eq!(id, 4242);
+}
@@ -0,0 +1,65 @@
+//! Size-based rotation of the debug log.
+
+use hint::hint_log::{rotated_path, LogConfig, RotatingFile};
+use std::fs;
+
+fn config(dir: &std::path::Path, max_bytes: u64, keep: usize) -> LogConfig {
+    LogConfig {
+        path: dir.join("logs").join("hint.log"),
+        max_bytes,
+        keep,
+    }
+}
+
+#[test]
+fn rotates_when_size_is_exceeded() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(dir.path(), 20, 2);
+    let mut file = RotatingFile::open(config.clone()).unwrap();
+
+    file.write_entry("first entry 0123\n").unwrap();
+    file.write_entry("second entry 012\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&config.path).unwrap(), "second entry 012\n");
+    assert_eq!(
+        fs::read_to_string(rotated_path(&config.path, 1)).unwrap(),
+        "first entry 0123\n"
+    );
+}
+
+#[test]
+fn keeps_only_the_configured_number_of_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(dir.path(), 10, 2);
+    let mut file = RotatingFile::open(config.clone()).unwrap();
+
+    for i in 0..5 {
+        file.write_entry(&format!("entry {:03}\n", i)).unwrap();
+    }
+
+    assert_eq!(fs::read_to_string(&config.path).unwrap(), "entry 004\n");
+    assert_eq!(fs::read_to_string(rotated_path(&config.path, 1)).unwrap(), "entry 003\n");
+    assert_eq!(fs::read_to_string(rotated_path(&config.path, 2)).unwrap(), "entry 002\n");
+    assert!(!rotated_path(&config.path, 3).exists());
+}
+
+#[test]
+fn oversized_entry_still_gets_written() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(dir.path(), 4, 0);
+    let mut file = RotatingFile::open(config.clone()).unwrap();
+
+    file.write_entry("longer than the cap\n").unwrap();
+    assert_eq!(fs::read_to_string(&config.path).unwrap(), "longer than the cap\n");
+    assert!(!rotated_path(&config.path, 1).exists());
+}
+
+#[test]
+fn appends_to_existing_log_from_today() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = config(dir.path(), 1024, 1);
+    RotatingFile::open(config.clone()).unwrap().write_entry("one\n").unwrap();
+    RotatingFile::open(config.clone()).unwrap().write_entry("two\n").unwrap();
+
+    assert_eq!(fs::read_to_string(&config.path).unwrap(), "one\ntwo\n");
+}
@@ -0,0 +1,130 @@
+//! The History feed: every story opened, searchable by words and by day.
+
+mod common;
+
+use common::{ids, press};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_history::History;
+use hint::hint_store::Store;
+use ratatui::crossterm::event::KeyCode;
+
+fn story(id: u64, title: &str, url: Option<&str>) -> HnStory {
+    HnStory::new(id.to_string(), "author".into(), title.into(), url.map(str::to_string), "story".into())
+}
+
+fn fixture_model() -> Model {
+    let mut model = Model::default();
+    let stories = [
+        story(1, "Rust 2024 edition", Some("https://blog.rust-lang.org/")),
+        story(2, "Ask HN: What are you reading?", None),
+        story(3, "SQLite internals", Some("https://sqlite.org/arch.html")),
+    ];
+    for story in stories {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+fn type_text(model: &mut Model, text: &str) {
+    for c in text.chars() {
+        press(model, KeyCode::Char(c));
+    }
+}
+
+#[test]
+fn opening_and_reading_record_visits() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::Down);
+    assert!(matches!(press(&mut model, KeyCode::Char('o')), Some(Cmd::OpenUrls(_))));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    assert!(matches!(press(&mut model, KeyCode::Char('p')), Some(Cmd::FetchArticle { .. })));
+    assert_eq!(model.history.len(), 2);
+
+    assert_eq!(press(&mut model, KeyCode::Char('H')), None, "the history is not fetched");
+    assert_eq!(model.feed, Feed::History);
+    assert_eq!(ids(&model), vec![3, 1], "most recent first");
+    assert_eq!(model.storylist.state.selected(), Some(0));
+    assert!(model.storylist.items[0].visited.is_some());
+}
+
+#[test]
+fn stories_opened_twice_are_listed_once() {
+    let mut model = Model::default();
+    model.history.record(&story(1, "One", None), 100);
+    model.history.record(&story(2, "Two", None), 200);
+    model.history.record(&story(1, "One", None), 300);
+    press(&mut model, KeyCode::Char('H'));
+    assert_eq!(ids(&model), vec![1, 2]);
+    assert_eq!(model.storylist.items[0].visited, Some(300));
+}
+
+#[test]
+fn slash_narrows_the_history_to_matching_words() {
+    let mut model = Model::default();
+    model.history.record(&story(1, "Rust 2024 edition", Some("https://blog.rust-lang.org/")), 100);
+    model.history.record(&story(2, "SQLite internals", Some("https://sqlite.org/")), 200);
+    press(&mut model, KeyCode::Char('H'));
+
+    press(&mut model, KeyCode::Char('/'));
+    type_text(&mut model, "RUST blog");
+    press(&mut model, KeyCode::Enter);
+    assert_eq!(ids(&model), vec![1]);
+    assert_eq!(model.feed_title(), "History: RUST blog");
+
+    press(&mut model, KeyCode::Char('/'));
+    type_text(&mut model, "haskell");
+    press(&mut model, KeyCode::Enter);
+    assert!(ids(&model).is_empty());
+    assert_eq!(model.notice.as_deref(), Some("Nothing opened before matches \"haskell\""));
+
+    // An empty search lists everything again
+    press(&mut model, KeyCode::Char('/'));
+    press(&mut model, KeyCode::Enter);
+    assert_eq!(ids(&model), vec![2, 1]);
+    assert_eq!(model.feed_title(), "History");
+}
+
+#[test]
+fn history_command_searches_by_day() {
+    let mut model = Model::default();
+    // Noon UTC keeps the day the same in every time zone but the most extreme ones
+    let tuesday = 1_710_244_800; // 2024-03-12
+    let friday = 1_710_504_000; // 2024-03-15
+    model.history.record(&story(1, "Read on Tuesday", None), tuesday);
+    model.history.record(&story(2, "Read on Friday", None), friday);
+    assert_eq!(model.history.search("tuesday")[0].day(), "2024-03-12 Tuesday");
+
+    type_text(&mut model, ":history tuesday");
+    press(&mut model, KeyCode::Enter);
+    assert_eq!(model.feed, Feed::History);
+    assert_eq!(ids(&model), vec![1]);
+
+    type_text(&mut model, ":history 2024-03-15");
+    press(&mut model, KeyCode::Enter);
+    assert_eq!(ids(&model), vec![2]);
+
+    // Other feeds forget the search
+    press(&mut model, KeyCode::Char(']'));
+    assert_eq!(model.history_query, None);
+}
+
+#[test]
+fn empty_history_says_how_to_fill_it() {
+    let mut model = Model::default();
+    press(&mut model, KeyCode::Char('H'));
+    assert!(model.notice.as_deref().unwrap().starts_with("Nothing opened yet"));
+}
+
+#[test]
+fn history_survives_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path().to_path_buf());
+    let mut history = History::default();
+    history.record(&story(7, "Saved", Some("https://example.com/")), 1_700_000_000);
+    history.save(&store).unwrap();
+    let loaded = History::load(&store);
+    assert_eq!(loaded, history);
+    assert_eq!(loaded.iter().next().unwrap().story.url.as_deref(), Some("https://example.com/"));
+}
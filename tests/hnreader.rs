@@ -0,0 +1,377 @@
+//! End-to-end tests for the HN API client against a local wiremock server.
+
+use hint::hint_config::Config;
+use hint::hint_hackernews::{Feed, HnStoryList};
+use hint::hnreader::{HnClient, HnError};
+use reqwest::StatusCode;
+use serde_json::json;
+use std::time::Duration;
+use wiremock::matchers::{header, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_json(server: &MockServer, route: &str, body: serde_json::Value) {
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(ResponseTemplate::new(200).set_body_json(body))
+        .mount(server)
+        .await;
+}
+
+fn client(server: &MockServer) -> HnClient {
+    HnClient::with_base_url(&format!("{}/v0", server.uri()))
+}
+
+#[test]
+fn base_url_gets_trailing_slash() {
+    let client = HnClient::with_base_url("http://localhost:1234/v0");
+    assert_eq!(client.base_url(), "http://localhost:1234/v0/");
+}
+
+#[tokio::test]
+async fn fetches_top_story_ids() {
+    let server = MockServer::start().await;
+    mount_json(&server, "/v0/topstories.json", json!([3, 1, 2])).await;
+
+    let ids = client(&server).fetch_top_stories().await.unwrap();
+    assert_eq!(ids, vec![3, 1, 2]);
+}
+
+#[tokio::test]
+async fn best_feed_fetches_best_story_ids() {
+    let server = MockServer::start().await;
+    mount_json(&server, "/v0/beststories.json", json!([7, 4])).await;
+
+    assert_eq!(Feed::from_name("best"), Some(Feed::Best));
+    assert_eq!(Feed::New.next(), Feed::Best);
+    let ids = Feed::Best.fetch_ids(&client(&server)).await.unwrap();
+    assert_eq!(ids, vec![7, 4]);
+}
+
+#[tokio::test]
+async fn parses_full_item() {
+    let server = MockServer::start().await;
+    mount_json(
+        &server,
+        "/v0/item/8863.json",
+        json!({
+            "by": "dhouston",
+            "descendants": 71,
+            "id": 8863,
+            "kids": [8952, 9224],
+            "score": 111,
+            "time": 1175714200,
+            "title": "My YC app: Dropbox - Throw away your USB drive",
+            "type": "story",
+            "url": "http://www.getdropbox.com/u/2/screencast.html"
+        }),
+    )
+    .await;
+
+    let story = client(&server).fetch_story_details(8863).await.unwrap();
+    assert_eq!(story.id, 8863);
+    assert_eq!(story.by.as_deref(), Some("dhouston"));
+    assert_eq!(story.score, Some(111));
+    assert_eq!(story.descendants, Some(71));
+    assert_eq!(story.url.as_deref(), Some("http://www.getdropbox.com/u/2/screencast.html"));
+}
+
+#[tokio::test]
+async fn missing_optional_fields_are_none() {
+    let server = MockServer::start().await;
+    mount_json(&server, "/v0/item/42.json", json!({ "id": 42, "type": "job" })).await;
+
+    let story = client(&server).fetch_story_details(42).await.unwrap();
+    assert_eq!(story.id, 42);
+    assert!(story.by.is_none());
+    assert!(story.title.is_none());
+    assert!(story.url.is_none());
+    assert!(story.score.is_none());
+}
+
+#[tokio::test]
+async fn malformed_json_is_an_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("[1, 2,"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client(&server).fetch_top_stories().await.unwrap_err();
+    assert!(matches!(err, HnError::Json(_)));
+}
+
+#[tokio::test]
+async fn null_item_is_an_error() {
+    let server = MockServer::start().await;
+    mount_json(&server, "/v0/item/7.json", serde_json::Value::Null).await;
+
+    assert!(client(&server).fetch_story_details(7).await.is_err());
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .expect(2)
+        .mount(&server)
+        .await;
+    mount_json(&server, "/v0/topstories.json", json!([1])).await;
+
+    let ids = client(&server).with_retries(2).fetch_top_stories().await.unwrap();
+    assert_eq!(ids, vec![1]);
+}
+
+#[tokio::test]
+async fn retries_are_bounded() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .respond_with(ResponseTemplate::new(500))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let err = client(&server).with_retries(1).fetch_top_stories().await.unwrap_err();
+    assert_eq!(err.status().map(|s| s.as_u16()), Some(500));
+}
+
+#[tokio::test]
+async fn hung_requests_time_out_and_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([1])).set_delay(Duration::from_secs(5)))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let started = std::time::Instant::now();
+    let client = client(&server).with_timeout(Duration::from_millis(100)).with_retries(1);
+    let err = client.fetch_top_stories().await.unwrap_err();
+    assert!(matches!(err, HnError::Http(ref err) if err.is_timeout()), "{:?}", err);
+    assert!(started.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn timeouts_come_from_the_config() {
+    assert_eq!(Config::default().request_timeout(), Duration::from_secs(10));
+    assert_eq!(Config::default().startup_deadline(), Duration::from_secs(30));
+    let config: Config = serde_json::from_str(r#"{"request_timeout_secs": 3, "startup_deadline_secs": 0}"#).unwrap();
+    assert_eq!(config.request_timeout(), Duration::from_secs(3));
+    assert_eq!(config.startup_deadline(), Duration::from_secs(1), "never gives up before trying");
+}
+
+#[tokio::test]
+async fn client_errors_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/item/1.json"))
+        .respond_with(ResponseTemplate::new(404))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let err = client(&server).with_retries(3).fetch_story_details(1).await.unwrap_err();
+    assert_eq!(err.status().map(|s| s.as_u16()), Some(404));
+}
+
+#[tokio::test]
+async fn story_list_loads_from_server() {
+    let server = MockServer::start().await;
+    mount_json(&server, "/v0/topstories.json", json!([10, 20])).await;
+    mount_json(
+        &server,
+        "/v0/item/10.json",
+        json!({ "id": 10, "by": "alice", "title": "First", "url": "https://a.example/" }),
+    )
+    .await;
+    mount_json(&server, "/v0/item/20.json", json!({ "id": 20, "by": "bob", "title": "Second" }))
+        .await;
+
+    let list = HnStoryList::new(client(&server), Feed::Top).await;
+    let titles: Vec<&str> = list.iter().map(|s| s.title()).collect();
+    assert_eq!(titles, vec!["First", "Second"]);
+    assert_eq!(list.iter().next().unwrap().author(), "alice");
+    assert!(list.is_filled());
+}
+
+#[tokio::test]
+async fn story_list_is_empty_when_feed_fails() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .respond_with(ResponseTemplate::new(404))
+        .mount(&server)
+        .await;
+
+    let list = HnStoryList::new(client(&server), Feed::Top).await;
+    assert_eq!(list.iter().count(), 0);
+}
+
+#[tokio::test]
+async fn demo_client_serves_bundled_stories_and_comments() {
+    let client = HnClient::demo();
+    assert!(client.is_demo());
+
+    let ids = client.fetch_top_stories().await.unwrap();
+    assert_eq!(ids.len(), 15);
+    assert_eq!(client.fetch_top_stories().await.unwrap(), ids, "demo data is deterministic");
+
+    let list = HnStoryList::new(client.clone(), Feed::Top).await;
+    let first = list.iter().next().unwrap();
+    assert_eq!(first.title(), "Show HN: Hint – a fast Hacker News reader for the terminal");
+
+    // Comments are plain items too
+    let comment = client.fetch_story_details(40000000).await.unwrap();
+    assert_eq!(comment.by.as_deref(), Some("alan"));
+}
+
+#[tokio::test]
+async fn demo_client_reports_missing_items() {
+    let err = HnClient::demo().fetch_story_details(1).await.unwrap_err();
+    assert!(matches!(err, HnError::NotFound(path) if path == "item/1.json"));
+}
+
+#[tokio::test]
+async fn recorded_responses_replay_without_network() {
+    let server = MockServer::start().await;
+    mount_json(&server, "/v0/topstories.json", json!([10])).await;
+    mount_json(&server, "/v0/item/10.json", json!({ "id": 10, "by": "alice", "title": "First" }))
+        .await;
+    let captures = tempfile::tempdir().unwrap();
+
+    let recorder = client(&server).with_recording(captures.path());
+    let recorded = HnStoryList::new(recorder, Feed::Top).await;
+    assert!(captures.path().join("topstories.json").is_file());
+    assert!(captures.path().join("item/10.json").is_file());
+    drop(server);
+
+    let replayed = HnStoryList::new(HnClient::replay(captures.path()), Feed::Top).await;
+    let titles = |list: &HnStoryList| list.iter().map(|s| s.title().to_string()).collect::<Vec<_>>();
+    assert_eq!(titles(&replayed), titles(&recorded));
+}
+
+#[tokio::test]
+async fn replay_reports_missing_captures() {
+    let captures = tempfile::tempdir().unwrap();
+    let err = HnClient::replay(captures.path()).fetch_story_details(5).await.unwrap_err();
+    assert!(matches!(err, HnError::NotFound(path) if path == "item/5.json"));
+}
+
+#[tokio::test]
+async fn unchanged_feed_list_is_a_conditional_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .and(header("If-None-Match", "\"v1\""))
+        .respond_with(ResponseTemplate::new(304))
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .and(header("X-Firebase-ETag", "true"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([3, 1, 2])).insert_header("ETag", "\"v1\""))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    assert_eq!(client.fetch_top_stories().await.unwrap(), vec![3, 1, 2]);
+    // Clones share what was fetched, as each feed load gets one
+    assert_eq!(client.clone().fetch_top_stories().await.unwrap(), vec![3, 1, 2], "from the 304");
+}
+
+#[tokio::test]
+async fn items_are_not_kept_for_conditional_requests() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/item/1.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 1 })).insert_header("ETag", "\"v1\""))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    client.fetch_story_details(1).await.unwrap();
+    client.fetch_story_details(1).await.unwrap();
+    let requests = server.received_requests().await.unwrap();
+    assert!(requests.iter().all(|request| !request.headers.contains_key("If-None-Match")));
+}
+
+#[tokio::test]
+async fn simultaneous_requests_for_an_item_share_one() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/item/7.json"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "id": 7, "title": "Seven" }))
+                .set_delay(Duration::from_millis(100)),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    // Each subsystem has a clone of its own
+    let (other, another) = (client.clone(), client.clone());
+    let (list, comments, scores) = tokio::join!(
+        client.fetch_story_details(7),
+        other.fetch_story_details(7),
+        another.fetch_story_details(7),
+    );
+    for story in [list, comments, scores] {
+        assert_eq!(story.unwrap().title.as_deref(), Some("Seven"));
+    }
+}
+
+#[tokio::test]
+async fn waiting_requests_share_the_error_too() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/item/7.json"))
+        .respond_with(ResponseTemplate::new(404).set_delay(Duration::from_millis(100)))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let (first, second) = tokio::join!(client.fetch_story_details(7), client.fetch_story_details(7));
+    assert_eq!(first.unwrap_err().status(), Some(StatusCode::NOT_FOUND));
+    let second = second.unwrap_err();
+    assert!(matches!(second, HnError::Coalesced(_)));
+    assert!(second.to_string().contains("404"), "{}", second);
+}
+
+#[tokio::test]
+async fn aborted_request_leaves_the_others_to_go_again() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/item/7.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 7 })).set_delay(Duration::from_millis(100)))
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let aborted = tokio::spawn({
+        let client = client.clone();
+        async move { client.fetch_story_details(7).await.map(|_| ()) }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    let waiting = tokio::spawn({
+        let client = client.clone();
+        async move { client.fetch_story_details(7).await.map(|story| story.id) }
+    });
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    aborted.abort();
+    assert_eq!(waiting.await.unwrap().unwrap(), 7);
+    // And nothing is left in flight for later ones
+    assert_eq!(client.fetch_story_details(7).await.unwrap().id, 7);
+}
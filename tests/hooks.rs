@@ -0,0 +1,92 @@
+//! Shell command hooks on story actions.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_config::Config;
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_hooks::{expand, HookEvent, Hooks};
+use hint::hint_store::Store;
+use ratatui::crossterm::event::KeyCode;
+
+fn story(id: u64, title: &str) -> HnStory {
+    let url = Some(format!("https://example.com/{}", id));
+    HnStory::new(id.to_string(), "pg".into(), title.into(), url, "story".into())
+}
+
+fn model_with_hooks(hooks: Hooks) -> Model {
+    let mut model = Model::default();
+    model.hooks = hooks;
+    for id in 1..=2 {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id, &format!("Story {}", id))));
+    }
+    model
+}
+
+#[test]
+fn placeholders_are_quoted_for_the_shell() {
+    let story = story(7, "It's; rm -rf $HOME");
+    assert_eq!(
+        expand("notify {id} {title} {url}", &story),
+        "notify 7 'It'\\''s; rm -rf $HOME' 'https://example.com/7'"
+    );
+}
+
+#[test]
+fn config_file_sets_the_hooks() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("config.json"),
+        r#"{ "hooks": { "on_open": "wget -P ~/articles {url}", "on_read": "" } }"#,
+    )
+    .unwrap();
+    let hooks = Config::load(&Store::new(dir.path())).hooks;
+    assert_eq!(hooks.command(HookEvent::Open), Some("wget -P ~/articles {url}"));
+    assert_eq!(hooks.command(HookEvent::Read), None, "empty commands are ignored");
+}
+
+#[test]
+fn opening_runs_the_open_hook_per_story() {
+    let hooks = Hooks { on_open: Some("echo {id}".to_string()), ..Hooks::default() };
+    let mut model = model_with_hooks(hooks);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    let cmd = press(&mut model, KeyCode::Char('o'));
+    let Some(Cmd::Batch(cmds)) = cmd else {
+        panic!("expected the browser and the hooks, got {:?}", cmd);
+    };
+    assert_eq!(cmds[1], Cmd::RunCommands(vec!["echo 1".to_string(), "echo 2".to_string()]));
+}
+
+#[test]
+fn bookmark_hook_only_fires_when_adding() {
+    let hooks = Hooks { on_bookmark: Some("echo {id}".to_string()), ..Hooks::default() };
+    let mut model = model_with_hooks(hooks);
+    press(&mut model, KeyCode::Down);
+    let Some(Cmd::Batch(cmds)) = press(&mut model, KeyCode::Char('b')) else {
+        panic!("expected a save and the hook");
+    };
+    assert_eq!(cmds[1], Cmd::RunCommands(vec!["echo 1".to_string()]));
+    assert!(matches!(press(&mut model, KeyCode::Char('b')), Some(Cmd::SaveBookmarks(_))));
+}
+
+#[test]
+fn read_hook_fires_when_a_story_becomes_read() {
+    let hooks = Hooks { on_read: Some("echo {id}".to_string()), ..Hooks::default() };
+    let mut model = model_with_hooks(hooks);
+    press(&mut model, KeyCode::Down);
+    // Enter also opens the details pane, which fetches the author's account and related stories
+    let hook = Cmd::RunCommands(vec!["echo 1".to_string()]);
+    let related = Cmd::FindRelated { id: 1, title: "Story 1".to_string() };
+    assert_eq!(press(&mut model, KeyCode::Enter), Some(Cmd::Batch(vec![Cmd::FetchAuthor("pg".to_string()), related, hook])));
+    assert_eq!(press(&mut model, KeyCode::Enter), None, "marking unread runs nothing");
+}
+
+#[test]
+fn no_hooks_configured_changes_nothing() {
+    let mut model = model_with_hooks(Hooks::default());
+    press(&mut model, KeyCode::Down);
+    assert!(matches!(press(&mut model, KeyCode::Char('o')), Some(Cmd::OpenUrls(_))));
+}
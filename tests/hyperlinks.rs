@@ -0,0 +1,72 @@
+//! OSC 8 hyperlinks on story titles, and guessing whether the terminal supports them.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_terminal::{hyperlink, supports_hyperlinks};
+use hint::hint_view::view;
+use ratatui::{backend::TestBackend, Terminal};
+use std::collections::HashMap;
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> =
+        vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+    move |name| vars.get(name).cloned()
+}
+
+fn model_with_story(url: Option<&str>) -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("7".into(), "pg".into(), "Linked title".into(), url.map(String::from), "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    model
+}
+
+/// The symbols of one row, with the cells a link covers left out like the terminal backend does
+fn drawn_row(model: &mut Model, y: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+    let frame = terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = frame.buffer;
+    (0..buffer.area.width)
+        .map(|x| &buffer[(x, y)])
+        .filter(|cell| !cell.skip)
+        .map(|cell| cell.symbol())
+        .collect()
+}
+
+#[test]
+fn known_terminals_support_hyperlinks() {
+    assert!(supports_hyperlinks(env(&[("TERM_PROGRAM", "WezTerm")])));
+    assert!(supports_hyperlinks(env(&[("TERM", "xterm-kitty")])));
+    assert!(supports_hyperlinks(env(&[("VTE_VERSION", "7600"), ("TERM", "xterm-256color")])));
+    assert!(!supports_hyperlinks(env(&[("VTE_VERSION", "4600"), ("TERM", "xterm-256color")])));
+    assert!(!supports_hyperlinks(env(&[("TERM", "xterm-256color")])));
+    assert!(!supports_hyperlinks(env(&[("TERM", "dumb"), ("WT_SESSION", "1")])));
+}
+
+#[test]
+fn force_hyperlink_overrides_the_guess() {
+    assert!(supports_hyperlinks(env(&[("FORCE_HYPERLINK", "1"), ("TERM", "dumb")])));
+    assert!(!supports_hyperlinks(env(&[("FORCE_HYPERLINK", "0"), ("TERM_PROGRAM", "iTerm.app")])));
+}
+
+#[test]
+fn titles_link_to_the_story() {
+    let mut model = model_with_story(Some("https://example.com/post"));
+    model.hyperlinks = true;
+    let row = drawn_row(&mut model, 1);
+    let link = hyperlink("https://example.com/post", "Linked title");
+    assert!(row.starts_with(&format!("  ☐ {} (example.com)", link)), "{:?}", row);
+}
+
+#[test]
+fn text_posts_link_to_the_discussion() {
+    let mut model = model_with_story(None);
+    model.hyperlinks = true;
+    let row = drawn_row(&mut model, 1);
+    assert!(row.contains(&hyperlink("https://news.ycombinator.com/item?id=7", "Linked title")), "{:?}", row);
+}
+
+#[test]
+fn no_escapes_without_hyperlinks() {
+    let mut model = model_with_story(Some("https://example.com/post"));
+    assert!(drawn_row(&mut model, 1).starts_with("  ☐ Linked title (example.com)"));
+}
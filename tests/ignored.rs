@@ -0,0 +1,124 @@
+//! Ignoring commenters with `I`: their comments fold away, or leave the thread entirely.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_ignored::{IgnoreMode, IgnoredUsers};
+use hint::hint_store::Store;
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    Terminal,
+};
+
+fn comment(id: u64, author: &str, depth: usize) -> Comment {
+    Comment {
+        id,
+        author: author.to_string(),
+        text: format!("Comment {}", id),
+        time: None,
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    }
+}
+
+fn thread() -> CommentThread {
+    CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: None,
+        comments: vec![comment(10, "alan", 0), comment(11, "troll", 0), comment(12, "ada", 1), comment(13, "ada", 0)],
+        unloaded: Vec::new(),
+    }
+}
+
+fn open_thread(model: &mut Model) {
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(model, Msg::StoryLoaded(Feed::Top, story));
+    press(model, KeyCode::Down);
+    press(model, KeyCode::Char('c'));
+    update(model, Msg::CommentsLoaded(thread()));
+}
+
+fn visible_ids(model: &Model) -> Vec<u64> {
+    let view = model.comments.as_ref().unwrap();
+    let thread = view.thread.as_ref().unwrap();
+    view.visible().into_iter().map(|i| thread.comments[i].id).collect()
+}
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(60, 20)).unwrap();
+    let frame = terminal.draw(|frame| view(model, frame)).unwrap();
+    frame.buffer.content().iter().map(|cell| cell.symbol()).collect()
+}
+
+#[test]
+fn ignored_users_persist() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut ignored = IgnoredUsers::default();
+    ignored.ignore("troll");
+    ignored.ignore("troll");
+    ignored.save(&store).unwrap();
+    assert_eq!(IgnoredUsers::load(&store), ignored);
+    assert_eq!(ignored.len(), 1);
+}
+
+#[test]
+fn i_folds_the_authors_comments() {
+    let mut model = Model::default();
+    open_thread(&mut model);
+    for _ in 0..3 {
+        press(&mut model, KeyCode::Down);
+    }
+    let cmd = press(&mut model, KeyCode::Char('I'));
+    assert!(model.ignored.contains("troll"));
+    assert_eq!(cmd, Some(Cmd::SaveIgnored(model.ignored.clone())));
+    assert_eq!(visible_ids(&model), vec![10, 11, 13]);
+    let text = screen(&mut model);
+    assert!(text.contains("[ignored, Space to show it and 1 reply]"), "{}", text);
+    assert!(!text.contains("Comment 11"));
+
+    // Space reads it anyway, I again stops ignoring
+    press(&mut model, KeyCode::Char(' '));
+    assert!(screen(&mut model).contains("Comment 11"));
+    press(&mut model, KeyCode::Char(' '));
+    press(&mut model, KeyCode::Char('I'));
+    assert!(!model.ignored.contains("troll"));
+    assert_eq!(visible_ids(&model), vec![10, 11, 12, 13]);
+}
+
+#[test]
+fn threads_open_with_ignored_comments_folded() {
+    let mut model = Model::default();
+    model.ignored.ignore("alan");
+    open_thread(&mut model);
+    assert_eq!(visible_ids(&model), vec![10, 11, 12, 13]);
+    assert!(screen(&mut model).contains("[ignored, Space to show]"));
+}
+
+#[test]
+fn hide_mode_leaves_ignored_comments_out() {
+    let mut model = Model::default();
+    model.ignore_mode = IgnoreMode::Hide;
+    open_thread(&mut model);
+    update(&mut model, Msg::SetIgnored { user: "troll".into(), ignored: true });
+    assert_eq!(visible_ids(&model), vec![10, 13]);
+    assert_eq!(model.notice.as_deref(), Some("Ignoring troll, :unignore troll brings their comments back"));
+
+    press(&mut model, KeyCode::Char(':'));
+    for c in "unignore troll".chars() {
+        press(&mut model, KeyCode::Char(c));
+    }
+    press(&mut model, KeyCode::Enter);
+    assert_eq!(visible_ids(&model), vec![10, 11, 12, 13]);
+}
@@ -0,0 +1,126 @@
+//! Images in the reader: a placeholder with the alt text, and on terminals that can show them
+//! the image itself in rows left under it.
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_article::{extract_article, Article};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_images::{kitty_transmit, sixel, Image, ImageWriter, IMAGE_ROWS};
+use hint::hint_terminal::{image_protocol, ImageProtocol};
+use hint::hint_view::view;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::layout::Rect;
+use ratatui::{backend::TestBackend, Terminal};
+use std::collections::HashMap;
+
+const URL: &str = "https://blog.example/post";
+const CHART: &str = "https://blog.example/chart.png";
+
+fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> =
+        vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+    move |name| vars.get(name).cloned()
+}
+
+/// Two pixels across, one red and one see-through
+fn image() -> Image {
+    Image { width: 2, height: 1, rgba: vec![255, 0, 0, 255, 0, 0, 0, 0] }
+}
+
+fn model_reading(images: Option<ImageProtocol>) -> (Model, Option<Cmd>) {
+    let mut model = Model::default();
+    model.inline_images = images;
+    let story = HnStory::new("1".into(), "pg".into(), "Post".into(), Some(URL.to_string()), "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+    let text = "Before\n[image: A chart][1]\nAfter\n".to_string();
+    let article = Article { url: URL.to_string(), text, links: vec![CHART.to_string()], archived_from: None };
+    update(&mut model, Msg::ArticlePrefetched(article));
+    let cmd = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('a'))));
+    (model, cmd)
+}
+
+fn draw(model: &mut Model) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(40, 60)).unwrap();
+    let frame = terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = frame.buffer;
+    (0..60).map(|y| (0..40).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string()).collect()
+}
+
+#[test]
+fn images_become_placeholders_numbered_as_links() {
+    let html = r#"<article><p>Look:</p><img src="/chart.png" alt="A  chart"><img src="photo.jpg">
+        <img src="data:image/png;base64,AAAA" alt="Inline"></article>"#;
+    let article = extract_article(html, URL);
+    assert_eq!(article.text, "Look:\n\n[image: A chart][1]\n\n[image][2]\n\n[image: Inline]\n\n[1] https://blog.example/chart.png\n[2] https://blog.example/photo.jpg\n");
+    assert_eq!(article.links, vec![CHART.to_string(), "https://blog.example/photo.jpg".to_string()]);
+}
+
+#[test]
+fn terminals_that_show_images_are_known_from_their_environment() {
+    assert_eq!(image_protocol(env(&[("TERM", "xterm-kitty")])), Some(ImageProtocol::Kitty));
+    assert_eq!(image_protocol(env(&[("TERM_PROGRAM", "iTerm.app")])), Some(ImageProtocol::Iterm));
+    assert_eq!(image_protocol(env(&[("TERM", "foot")])), Some(ImageProtocol::Sixel));
+    assert_eq!(image_protocol(env(&[("TERM", "xterm-256color")])), None);
+    assert_eq!(image_protocol(env(&[("TERM", "xterm-kitty"), ("TMUX", "/tmp/tmux")])), None);
+}
+
+#[test]
+fn without_images_the_reader_shows_only_the_placeholder() {
+    let (mut model, cmd) = model_reading(None);
+    assert_eq!(cmd, None);
+    let rows = draw(&mut model);
+    let placeholder = rows.iter().position(|row| row.contains("[image: A chart][1]")).unwrap();
+    assert!(rows[placeholder + 1].contains("After"));
+    assert!(model.image_slots.is_empty());
+}
+
+#[test]
+fn the_reader_fetches_images_and_leaves_room_for_them() {
+    let (mut model, cmd) = model_reading(Some(ImageProtocol::Kitty));
+    assert_eq!(cmd, Some(Cmd::FetchImages(vec![CHART.to_string()])));
+    update(&mut model, Msg::ImageLoaded { url: CHART.to_string(), image: image() });
+
+    let rows = draw(&mut model);
+    let placeholder = rows.iter().position(|row| row.contains("[image: A chart][1]")).unwrap();
+    assert!(rows[placeholder + IMAGE_ROWS + 1].contains("After"), "{:#?}", rows);
+    let slots: Vec<(String, u16, u16)> = model.image_slots.iter().map(|(url, area)| (url.clone(), area.y, area.height)).collect();
+    assert_eq!(slots, vec![(CHART.to_string(), placeholder as u16 + 1, IMAGE_ROWS as u16)]);
+
+    // Anything on top of the reader would be drawn over, so the images wait for it to go
+    update(&mut model, Msg::Confirm { question: "Sure?".into(), then: Box::new(Msg::Quit) });
+    draw(&mut model);
+    assert!(model.image_slots.is_empty());
+}
+
+#[test]
+fn images_fit_their_rows_keeping_their_shape() {
+    let wide = Image { width: 1600, height: 800, rgba: Vec::new() };
+    assert_eq!(wide.fit(40, 12, (10, 20)), (40, 10));
+    // Small images are not blown up
+    assert_eq!(image().fit(40, 12, (10, 20)), (1, 1));
+}
+
+#[test]
+fn kitty_is_sent_each_image_once_then_only_told_where_to_put_it() {
+    let mut writer = ImageWriter::new(ImageProtocol::Kitty);
+    let images = HashMap::from([(CHART.to_string(), image())]);
+    let slots = vec![(CHART.to_string(), Rect::new(2, 5, 40, 12))];
+    let first = writer.frame(&slots, &images, (10, 20));
+    assert!(first.starts_with("\x1b7"), "the cursor is saved");
+    assert!(first.contains(&kitty_transmit(1, &image())));
+    assert!(first.contains("\x1b[6;3H"), "at the top left of the slot");
+    assert!(first.contains("\x1b_Ga=p,i=1,c=1,r=1,C=1,q=2\x1b\\"));
+
+    let again = writer.frame(&slots, &images, (10, 20));
+    assert!(!again.contains("a=t"));
+    assert!(again.contains("a=p,i=1"));
+}
+
+#[test]
+fn sixels_skip_see_through_pixels() {
+    let drawn = sixel(&image(), 1, 1, (10, 20));
+    assert!(drawn.starts_with("\x1bPq\"1;1;2;1"));
+    assert!(drawn.ends_with("\x1b\\"));
+    // Pure red is the last red of the colour cube, set in the first pixel's top row only
+    assert!(drawn.contains("#180@?$-"), "{:?}", &drawn[drawn.len() - 20..]);
+}
@@ -0,0 +1,85 @@
+//! The text input behind the prompts and the reply box: a movable cursor, readline keys and
+//! history.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_comments::wrap_text;
+use hint::hint_input::{Edit, TextInput};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn edited(text: &str, edits: impl IntoIterator<Item = Edit>) -> TextInput {
+    let mut input = TextInput::new(text);
+    for edit in edits {
+        input.apply(edit);
+    }
+    input
+}
+
+#[test]
+fn typing_goes_in_at_the_cursor() {
+    let typed = "new ".chars().map(Edit::Insert);
+    let input = edited("hello world", std::iter::once(Edit::WordLeft).chain(typed));
+    assert_eq!(input, "hello new world");
+    assert_eq!(input.before_cursor(), "hello new ");
+
+    let input = edited("héllo", [Edit::Home, Edit::Right, Edit::Right, Edit::Backspace, Edit::Delete]);
+    assert_eq!(input, "hlo");
+}
+
+#[test]
+fn words_and_lines_are_deleted_whole() {
+    assert_eq!(edited("feed  show ", [Edit::DeleteWord]), "feed  ");
+    assert_eq!(edited("feed show", [Edit::WordLeft, Edit::DeleteToEnd]), "feed ");
+    assert_eq!(edited("feed show", [Edit::WordLeft, Edit::DeleteToStart]), "show");
+    assert_eq!(edited("one\ntwo", [Edit::DeleteToStart]), "one\n");
+}
+
+#[test]
+fn up_and_down_move_between_lines() {
+    let mut input = edited("first line\nab\nthird", [Edit::Up, Edit::Up]);
+    assert_eq!(input.before_cursor(), "fi", "as far as the short line in between allowed");
+    input.apply(Edit::WordRight);
+    input.apply(Edit::Down);
+    assert_eq!(input.before_cursor(), "first line\nab");
+    input.apply(Edit::Down);
+    assert_eq!(input.before_cursor(), "first line\nab\nth");
+}
+
+#[test]
+fn browsing_the_history_keeps_what_was_typed() {
+    let history = vec!["theme gruvbox".to_string(), "limit 5".to_string()];
+    let mut input = TextInput::new("fe");
+    input.browse(&history, true);
+    input.browse(&history, true);
+    assert_eq!(input, "theme gruvbox");
+    input.browse(&history, false);
+    input.browse(&history, false);
+    assert_eq!(input, "fe");
+}
+
+#[test]
+fn cursor_follows_the_wrapped_text() {
+    let mut input = TextInput::new("the quick brown fox");
+    let lines = wrap_text(input.text(), 10);
+    assert_eq!(input.wrapped_cursor(&lines), (1, 9));
+    input.apply(Edit::Home);
+    input.apply(Edit::WordRight);
+    input.apply(Edit::WordRight);
+    assert_eq!(input.wrapped_cursor(&lines), (0, 9));
+    input.apply(Edit::Right);
+    assert_eq!(input.wrapped_cursor(&lines), (1, 0));
+}
+
+#[test]
+fn readline_keys_work_at_the_prompt() {
+    let mut model = Model::default();
+    update(&mut model, Msg::OpenCommandLine);
+    for c in "feed top".chars() {
+        update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char(c))));
+    }
+    update(&mut model, Msg::Key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL)));
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Left)));
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('s'))));
+    let prompt = model.prompt.as_ref().unwrap();
+    assert_eq!(prompt.input, "feeds ");
+    assert_eq!(prompt.cursor_column(), 6);
+}
@@ -0,0 +1,96 @@
+//! Job ads: tagged and set apart in the list, with their text in the details and no comments.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory, HnStoryList};
+use hint::hint_view::view;
+use hint::hnreader::HnClient;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::style::Modifier;
+use ratatui::Terminal;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn job() -> HnStory {
+    let mut job = HnStory::new("7".into(), "acme".into(), "Acme (YC W24) is hiring".into(), None, "job".into());
+    job.set_text("We build <i>rockets</i>.<p>Apply within.".to_string());
+    job
+}
+
+fn draw(model: &mut Model, width: u16, height: u16) -> Terminal<TestBackend> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    terminal
+}
+
+fn line(terminal: &Terminal<TestBackend>, y: u16) -> String {
+    let buffer = terminal.backend().buffer();
+    (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string()
+}
+
+#[test]
+fn jobs_are_tagged_and_set_apart() {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, job()));
+    let terminal = draw(&mut model, 60, 6);
+    assert_eq!(line(&terminal, 1), "  ☐ A story");
+    assert_eq!(line(&terminal, 2), "  ☐ [job] Acme (YC W24) is hiring");
+    let buffer = terminal.backend().buffer();
+    assert!(buffer[(12, 2)].modifier.contains(Modifier::ITALIC));
+    assert!(!buffer[(6, 1)].modifier.contains(Modifier::ITALIC));
+}
+
+#[test]
+fn details_show_the_job_text() {
+    let mut model = Model::default();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, job()));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Enter);
+    let terminal = draw(&mut model, 60, 14);
+    let text: Vec<String> = (0..14).map(|y| line(&terminal, y)).collect();
+    assert!(text.iter().any(|line| line.trim() == "We build rockets."), "{:#?}", text);
+    assert!(text.iter().any(|line| line.trim() == "Apply within."), "{:#?}", text);
+}
+
+#[test]
+fn jobs_without_comments_do_not_open_a_thread() {
+    let mut model = Model::default();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, job()));
+    press(&mut model, KeyCode::Down);
+    let footer = line(&draw(&mut model, 100, 6), 5);
+    assert!(!footer.contains("comments"), "{}", footer);
+    assert_eq!(press(&mut model, KeyCode::Char('c')), None);
+    assert!(model.comments.is_none());
+    assert_eq!(model.notice.as_deref(), Some("Job ads take no comments"));
+
+    // The odd job that takes comments says how many it has
+    let mut open = job();
+    open.set_descendants(3);
+    assert!(open.has_comments());
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    assert!(story.has_comments());
+}
+
+#[tokio::test]
+async fn fetched_jobs_keep_their_type_and_text() {
+    let server = MockServer::start().await;
+    let mount = |route: &str, body: serde_json::Value| {
+        Mock::given(method("GET")).and(path(route.to_string())).respond_with(ResponseTemplate::new(200).set_body_json(body))
+    };
+    mount("/v0/jobstories.json", json!([5])).mount(&server).await;
+    let item = json!({ "id": 5, "by": "acme", "title": "Acme is hiring", "type": "job", "text": "Apply", "time": 1 });
+    mount("/v0/item/5.json", item).mount(&server).await;
+
+    let list = HnStoryList::new(HnClient::with_base_url(&format!("{}/v0", server.uri())), Feed::Jobs).await;
+    let job = list.iter().next().unwrap();
+    assert!(job.is_job());
+    assert_eq!(job.text(), Some("Apply"));
+    assert_eq!(job.descendants(), None);
+    assert!(!job.has_comments());
+}
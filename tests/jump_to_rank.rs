@@ -0,0 +1,85 @@
+//! Going straight to a story by its rank, with a number and Enter or `:42`.
+
+mod common;
+
+use common::{press, story};
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::Feed;
+use ratatui::crossterm::event::KeyCode;
+
+fn type_keys(model: &mut Model, keys: &str) {
+    for c in keys.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter);
+}
+
+fn selected(model: &Model) -> Option<u64> {
+    model.storylist.selected_item().map(|item| item.id())
+}
+
+/// Top stories 100 to 129 ranked in order, the first 20 of them loaded
+fn model_with_feed() -> Model {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::Top, (100..130).collect()));
+    for id in 100..120 {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    model
+}
+
+#[test]
+fn number_and_enter_select_that_rank() {
+    let mut model = model_with_feed();
+    press(&mut model, KeyCode::Char('1'));
+    assert_eq!(model.prompt.as_ref().unwrap().display_input(), "1");
+    type_keys(&mut model, "7");
+    assert!(model.prompt.is_none());
+    assert_eq!(selected(&model), Some(116), "#17 is the 17th story");
+}
+
+#[test]
+fn colon_number_jumps_too() {
+    let mut model = model_with_feed();
+    type_keys(&mut model, ":5");
+    assert_eq!(selected(&model), Some(104));
+    assert!(model.notice.is_none());
+}
+
+#[test]
+fn rank_follows_the_feed_not_the_rows() {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::Top, vec![7, 8, 9]));
+    // Rank 3 arrives first and sits in the first row until the others come
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(9)));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(7)));
+    type_keys(&mut model, ":2");
+    assert_eq!(model.notice.as_deref(), Some("Going to #2 once it loads"));
+    assert_eq!(selected(&model), None);
+
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(8)));
+    assert_eq!(selected(&model), Some(8), "selected when it arrives");
+}
+
+#[test]
+fn unranked_lists_go_by_row() {
+    let mut model = Model::default();
+    for id in [5, 3, 9] {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    type_keys(&mut model, ":2");
+    assert_eq!(selected(&model), Some(3));
+}
+
+#[test]
+fn out_of_range_ranks_say_so() {
+    let mut model = model_with_feed();
+    type_keys(&mut model, ":31");
+    assert_eq!(model.notice.as_deref(), Some("There are only 30 stories"));
+    type_keys(&mut model, ":0");
+    assert_eq!(model.notice.as_deref(), Some("Stories are numbered from 1"));
+
+    press(&mut model, KeyCode::Char('4'));
+    type_keys(&mut model, "x");
+    assert_eq!(model.notice.as_deref(), Some("Type the number of a story, as in #17"));
+}
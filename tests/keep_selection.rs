@@ -0,0 +1,70 @@
+//! The selected story stays selected, and on screen where it was, as rows come and go around it.
+
+mod common;
+
+use common::story;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::Feed;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+fn selected(model: &Model) -> Option<u64> {
+    model.storylist.selected_item().map(|item| item.id())
+}
+
+/// Stories ranked `1..=100` in the feed, of which only the even ones are in
+fn half_loaded() -> Model {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::Top, (1..=100).collect()));
+    for id in (2..=100).step_by(2) {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    model
+}
+
+#[test]
+fn story_arriving_above_the_screen_leaves_it_be() {
+    let mut model = half_loaded();
+    model.storylist.state.select(Some(25));
+    *model.storylist.state.offset_mut() = 20;
+    assert_eq!(selected(&model), Some(52));
+
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(3)));
+    assert_eq!(selected(&model), Some(52));
+    assert_eq!(model.storylist.state.offset(), 21, "the same story stays at the top");
+    assert_eq!(model.storylist.items[21].id(), 42);
+}
+
+#[test]
+fn story_arriving_at_the_top_comes_into_view() {
+    let mut model = half_loaded();
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1)));
+    assert_eq!(model.storylist.state.offset(), 0);
+    assert_eq!(selected(&model), Some(2));
+    assert_eq!(model.storylist.state.selected(), Some(1));
+}
+
+#[test]
+fn reordered_feed_keeps_the_selected_and_top_stories() {
+    let mut model = half_loaded();
+    model.storylist.state.select(Some(10));
+    *model.storylist.state.offset_mut() = 5;
+    let top = model.storylist.items[5].id();
+
+    update(&mut model, Msg::FeedOrder(Feed::Top, (1..=100).rev().collect()));
+    assert_eq!(selected(&model), Some(22));
+    assert_eq!(model.storylist.items[model.storylist.state.offset()].id(), top);
+}
+
+#[test]
+fn marked_range_follows_its_stories() {
+    let mut model = half_loaded();
+    for _ in 0..3 {
+        update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    }
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('V'))));
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    assert_eq!(model.storylist.marked_range(), Some(2..=3));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1)));
+    assert_eq!(model.storylist.marked_range(), Some(3..=4));
+}
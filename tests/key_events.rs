@@ -0,0 +1,44 @@
+//! Terminals with the kitty keyboard protocol also report held keys repeating and keys being let
+//! go; repeats act like presses and releases are ignored.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for id in 1..=4 {
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), None, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    update(&mut model, Msg::SelectFirst);
+    model
+}
+
+fn key(model: &mut Model, c: char, kind: KeyEventKind) {
+    let event = KeyEvent::new_with_kind_and_state(KeyCode::Char(c), KeyModifiers::NONE, kind, KeyEventState::NONE);
+    update(model, Msg::Key(event));
+}
+
+#[test]
+fn held_keys_repeat_and_releases_do_nothing() {
+    let mut model = model_with_stories();
+    key(&mut model, 'j', KeyEventKind::Press);
+    key(&mut model, 'j', KeyEventKind::Repeat);
+    key(&mut model, 'j', KeyEventKind::Repeat);
+    key(&mut model, 'j', KeyEventKind::Release);
+    assert_eq!(model.storylist.state.selected(), Some(3));
+}
+
+#[test]
+fn a_release_between_the_keys_of_a_sequence_keeps_it_going() {
+    let mut model = model_with_stories();
+    key(&mut model, 'c', KeyEventKind::Press);
+    update(&mut model, Msg::NewTab);
+    key(&mut model, 'G', KeyEventKind::Press);
+    key(&mut model, 'g', KeyEventKind::Press);
+    key(&mut model, 'g', KeyEventKind::Release);
+    key(&mut model, 'T', KeyEventKind::Press);
+    assert_eq!(model.current_tab(), 0);
+    assert!(model.comments.is_some());
+}
@@ -0,0 +1,101 @@
+//! Keys come from a keymap the config can change, and the footer shows whatever it says.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_keymap::{Action, Key, Keymap, Mode};
+use hint::hint_view::view;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::Terminal;
+use std::collections::BTreeMap;
+
+fn keys(mode: &str, action: &str, names: &[&str]) -> BTreeMap<String, BTreeMap<String, Vec<String>>> {
+    let actions = BTreeMap::from([(action.to_string(), names.iter().map(|name| name.to_string()).collect())]);
+    BTreeMap::from([(mode.to_string(), actions)])
+}
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::from(code)
+}
+
+fn footer(model: &mut Model, width: u16) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, 6)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..width).map(|x| buffer[(x, 5)].symbol()).collect::<String>().trim().to_string()
+}
+
+#[test]
+fn key_names_parse() {
+    assert_eq!(Key::parse("PageDown").unwrap().to_string(), "PgDn");
+    assert_eq!(Key::parse("Space").unwrap().to_string(), "Space");
+    assert_eq!(Key::parse("Ctrl-d").unwrap().to_string(), "Ctrl-d");
+    assert_eq!(Key::parse("Down").unwrap().to_string(), "↓");
+    assert_eq!(Key::parse("B").unwrap().to_string(), "B");
+    assert_eq!(Key::parse("Bogus"), None);
+    assert_eq!(Key::parse(""), None);
+}
+
+#[test]
+fn defaults_match_the_usual_keys() {
+    let keymap = Keymap::default();
+    assert_eq!(keymap.action(Mode::List, &key(KeyCode::Char('b'))), Some(Action::Bookmark));
+    assert_eq!(keymap.action(Mode::List, &key(KeyCode::Enter)), Some(Action::ToggleStatus));
+    assert_eq!(keymap.action(Mode::Details, &key(KeyCode::Char(' '))), Some(Action::PageDown));
+    assert_eq!(keymap.action(Mode::Comments, &key(KeyCode::Char(' '))), Some(Action::Fold));
+    assert_eq!(keymap.action(Mode::List, &key(KeyCode::Char('Q'))), None);
+}
+
+#[test]
+fn remapped_key_takes_over_and_frees_the_old_one() {
+    let keymap = Keymap::with_keys(&keys("list", "bookmark", &["B"]));
+    assert_eq!(keymap.action(Mode::List, &key(KeyCode::Char('B'))), Some(Action::Bookmark));
+    assert_eq!(keymap.action(Mode::List, &key(KeyCode::Char('b'))), None);
+    // Only in that mode
+    assert_eq!(keymap.action(Mode::Comments, &key(KeyCode::Char('B'))), None);
+
+    // A key taken from another action leaves that action with its others
+    let keymap = Keymap::with_keys(&keys("list", "bookmark", &["j"]));
+    assert_eq!(keymap.action(Mode::List, &key(KeyCode::Char('j'))), Some(Action::Bookmark));
+    assert_eq!(keymap.keys(Mode::List, Action::Down), &[Key::parse("Down").unwrap()]);
+}
+
+#[test]
+fn unknown_names_are_skipped() {
+    let mut config = keys("list", "fly", &["f"]);
+    config.extend(keys("nowhere", "quit", &["x"]));
+    assert_eq!(Keymap::with_keys(&config), Keymap::default());
+}
+
+#[test]
+fn ctrl_binding_wins_over_plain_one() {
+    let keymap = Keymap::with_keys(&keys("list", "bottom", &["Ctrl-d"]));
+    let ctrl_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+    assert_eq!(keymap.action(Mode::List, &ctrl_d), Some(Action::Bottom));
+    assert_eq!(keymap.action(Mode::List, &key(KeyCode::Char('d'))), Some(Action::OnlyDomain));
+}
+
+#[test]
+fn remapped_key_works_and_shows_in_the_footer() {
+    let mut model = Model::default();
+    model.keymap = Keymap::with_keys(&keys("list", "open", &["O"]));
+    let story = HnStory::new("1".to_string(), "pg".to_string(), "A".to_string(), None, "story".to_string());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    assert!(footer(&mut model, 100).contains("O open"));
+
+    update(&mut model, Msg::Key(key(KeyCode::Down)));
+    assert!(update(&mut model, Msg::Key(key(KeyCode::Char('o')))).is_none(), "the old key does nothing now");
+    assert!(update(&mut model, Msg::Key(key(KeyCode::Char('O')))).is_some());
+}
+
+#[test]
+fn footer_drops_hints_that_do_not_fit() {
+    let mut model = Model::default();
+    assert_eq!(footer(&mut model, 30), "↓/↑ move · ← unselect");
+    let wide = footer(&mut model, 100);
+    assert!(wide.ends_with("q quit"), "{}", wide);
+
+    // An action left without keys has no hint
+    model.keymap = Keymap::with_keys(&keys("list", "unselect", &[]));
+    assert_eq!(footer(&mut model, 30), "↓/↑ move · → status");
+}
@@ -0,0 +1,129 @@
+//! Big threads open with the first comments only; the rest is fetched as the reader gets to it.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_comments::{fetch_replies, fetch_thread, Comment, CommentThread, TOP_LEVEL_BATCH};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hnreader::HnClient;
+use ratatui::crossterm::event::KeyCode;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+async fn mount_item(server: &MockServer, id: u64, kids: &[u64]) {
+    let item = json!({ "id": id, "by": format!("user{}", id), "text": "Hi", "type": "comment", "kids": kids });
+    Mock::given(method("GET"))
+        .and(path(format!("/v0/item/{}.json", id)))
+        .respond_with(ResponseTemplate::new(200).set_body_json(item))
+        .mount(server)
+        .await;
+}
+
+fn comment(id: u64, depth: usize, unloaded: Vec<u64>) -> Comment {
+    Comment {
+        id,
+        author: format!("user{}", id),
+        text: "Hi".to_string(),
+        time: None,
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded,
+    }
+}
+
+/// A thread with one comment whose replies are not fetched, and two top-level comments to come
+fn model_with_partial_thread() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    let thread = CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: None,
+        comments: vec![comment(2, 0, vec![20, 21]), comment(3, 0, Vec::new())],
+        unloaded: vec![4, 5],
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    model
+}
+
+fn ids(model: &Model) -> Vec<(u64, usize)> {
+    let thread = model.comments.as_ref().unwrap().thread.as_ref().unwrap();
+    thread.comments.iter().map(|comment| (comment.id, comment.depth)).collect()
+}
+
+#[tokio::test]
+async fn threads_open_with_the_first_comments_and_their_replies() {
+    let server = MockServer::start().await;
+    let top_level: Vec<u64> = (2..TOP_LEVEL_BATCH as u64 + 4).collect();
+    let story = json!({ "id": 1, "title": "A story", "by": "pg", "type": "story", "kids": top_level });
+    Mock::given(method("GET"))
+        .and(path("/v0/item/1.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(story))
+        .mount(&server)
+        .await;
+    mount_item(&server, 2, &[100]).await;
+    for id in 3..TOP_LEVEL_BATCH as u64 + 2 {
+        mount_item(&server, id, &[]).await;
+    }
+    mount_item(&server, 100, &[200]).await;
+
+    let client = HnClient::with_base_url(&format!("{}/v0", server.uri()));
+    let thread = fetch_thread(&client, 1).await.unwrap();
+    assert_eq!(thread.comments.len(), TOP_LEVEL_BATCH + 1);
+    assert_eq!((thread.comments[1].id, thread.comments[1].depth), (100, 1));
+    assert_eq!(thread.comments[1].unloaded, vec![200], "replies to replies wait");
+    assert_eq!(thread.unloaded, vec![TOP_LEVEL_BATCH as u64 + 2, TOP_LEVEL_BATCH as u64 + 3]);
+
+    mount_item(&server, 200, &[]).await;
+    let replies = fetch_replies(&client, &[200], 2).await;
+    assert_eq!(replies, vec![comment(200, 2, Vec::new())]);
+}
+
+#[test]
+fn selecting_a_comment_loads_its_replies() {
+    let mut model = model_with_partial_thread();
+    // The thread is short enough that reaching the header already asks for more
+    let cmd = press(&mut model, KeyCode::Down);
+    assert_eq!(cmd, Some(Cmd::LoadReplies { story_id: 1, parent: None, depth: 0, ids: vec![4, 5] }));
+    let cmd = press(&mut model, KeyCode::Down);
+    assert_eq!(cmd, Some(Cmd::LoadReplies { story_id: 1, parent: Some(2), depth: 1, ids: vec![20, 21] }));
+    let view = model.comments.as_ref().unwrap();
+    assert_eq!(view.loading_replies.get(&2), Some(&2));
+    assert_eq!(view.loading_more, 2);
+
+    // Moving on does not ask again while the fetches are out
+    assert_eq!(press(&mut model, KeyCode::Down), None);
+}
+
+#[test]
+fn loaded_replies_go_under_their_parent_and_keep_the_selection() {
+    let mut model = model_with_partial_thread();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+
+    let replies = vec![comment(20, 1, vec![30]), comment(21, 1, Vec::new())];
+    update(&mut model, Msg::RepliesLoaded { story_id: 1, parent: Some(2), replies });
+    update(&mut model, Msg::RepliesLoaded { story_id: 1, parent: None, replies: vec![comment(4, 0, Vec::new())] });
+    assert_eq!(ids(&model), vec![(2, 0), (20, 1), (21, 1), (3, 0), (4, 0)]);
+
+    let view = model.comments.as_ref().unwrap();
+    assert!(view.loading_replies.is_empty() && view.loading_more == 0);
+    assert_eq!(view.state.selected(), Some(4), "comment 3 is still selected");
+}
+
+#[test]
+fn replies_for_another_thread_are_ignored() {
+    let mut model = model_with_partial_thread();
+    update(&mut model, Msg::RepliesLoaded { story_id: 9, parent: None, replies: vec![comment(4, 0, Vec::new())] });
+    assert_eq!(ids(&model), vec![(2, 0), (3, 0)]);
+}
@@ -0,0 +1,117 @@
+//! Numbered links in comments and articles, opened with the number keys or `L`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_article::{extract_article, resolve_link, Article};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::KeyCode;
+
+fn open(url: &str) -> Option<Cmd> {
+    Some(Cmd::OpenUrls(vec![url.to_string()]))
+}
+
+fn model_with_story() -> Model {
+    let mut model = Model::default();
+    let url = Some("https://example.com/blog/post".to_string());
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), url, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    press(&mut model, KeyCode::Down);
+    model
+}
+
+/// The story's text links to the docs; the only comment links to two places
+fn model_with_thread() -> Model {
+    let mut model = model_with_story();
+    press(&mut model, KeyCode::Char('c'));
+    let comment = Comment {
+        id: 2,
+        author: "alan".into(),
+        text: "<a href=\"https://a.example\">one</a> and <a href=\"https://b.example\">two</a>".into(),
+        time: None,
+        depth: 0,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    };
+    let thread = CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: Some("See <a href=\"https:&#x2F;&#x2F;docs.example\">the docs</a>".into()),
+        comments: vec![comment],
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    model
+}
+
+#[test]
+fn number_keys_open_the_links_of_the_selected_comment() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Down);
+    assert_eq!(press(&mut model, KeyCode::Char('1')), open("https://docs.example"), "the story's text");
+    press(&mut model, KeyCode::Down);
+    assert_eq!(press(&mut model, KeyCode::Char('2')), open("https://b.example"));
+    assert_eq!(press(&mut model, KeyCode::Char('3')), None);
+    assert_eq!(model.notice.as_deref(), Some("There are only 2 links"));
+}
+
+#[test]
+fn l_asks_which_link_to_follow() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('L'));
+    assert_eq!(model.prompt.as_ref().unwrap().label, "Open link 1-2: ");
+
+    press(&mut model, KeyCode::Char('7'));
+    assert_eq!(press(&mut model, KeyCode::Enter), None);
+    assert_eq!(model.notice.as_deref(), Some("Pick a link from 1 to 2"));
+    assert!(model.prompt.is_some(), "asked again");
+
+    press(&mut model, KeyCode::Char('1'));
+    assert_eq!(press(&mut model, KeyCode::Enter), open("https://a.example"));
+}
+
+#[test]
+fn article_links_open_while_their_story_is_selected() {
+    let mut model = model_with_story();
+    assert_eq!(press(&mut model, KeyCode::Char('1')), None);
+    assert_eq!(model.prompt.as_ref().unwrap().label, "Go to #", "no links to number, so a rank");
+    press(&mut model, KeyCode::Esc);
+    assert_eq!(press(&mut model, KeyCode::Char('L')), None);
+    assert_eq!(model.notice.as_deref(), Some("Read the article with p to number its links"));
+
+    let page = "<article><p>Read <a href=\"/about\">this</a> and <a href=\"#top\">skip this</a>.</p></article>";
+    let article = extract_article(page, "https://example.com/blog/post");
+    assert_eq!(article.text, "Read this[1] and skip this.\n\n[1] https://example.com/about\n");
+    update(&mut model, Msg::ArticleLoaded { article, editor: false });
+    assert_eq!(press(&mut model, KeyCode::Char('1')), open("https://example.com/about"));
+
+    // Another story's article does not count
+    let article = Article {
+        url: "https://elsewhere.example".into(),
+        text: String::new(),
+        links: vec!["https://x.example".into()],
+        archived_from: None,
+    };
+    update(&mut model, Msg::ArticleLoaded { article, editor: false });
+    assert_eq!(press(&mut model, KeyCode::Char('1')), None);
+}
+
+#[test]
+fn relative_links_resolve_against_the_page() {
+    let page = "https://example.com/blog/post?page=2";
+    assert_eq!(resolve_link(page, "next").as_deref(), Some("https://example.com/blog/next"));
+    assert_eq!(resolve_link(page, "/").as_deref(), Some("https://example.com/"));
+    assert_eq!(resolve_link(page, "//cdn.example/x").as_deref(), Some("https://cdn.example/x"));
+    assert_eq!(resolve_link("https://example.com", "a").as_deref(), Some("https://example.com/a"));
+    assert_eq!(resolve_link(page, "http://other.example").as_deref(), Some("http://other.example"));
+    assert_eq!(resolve_link(page, "mailto:me@example.com"), None);
+    assert_eq!(resolve_link(page, "#section"), None);
+}
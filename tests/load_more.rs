@@ -0,0 +1,63 @@
+//! Scrolling to the end of a feed cut short by `stories_per_feed` fetches its next stories.
+
+mod common;
+
+use common::story;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::Feed;
+
+/// The first `listed` of `total` stories in, the rest left for later
+fn model_with_feed(listed: u64, total: u64) -> Model {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::Top, (1..=listed).collect()));
+    update(&mut model, Msg::FeedMore(Feed::Top, (listed + 1..=total).collect()));
+    for id in 1..=listed {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    update(&mut model, Msg::FeedLoaded(Feed::Top));
+    update(&mut model, Msg::SelectFirst);
+    model
+}
+
+#[test]
+fn nearing_the_end_fetches_the_next_stories() {
+    let mut model = model_with_feed(10, 100);
+    for _ in 0..6 {
+        assert_eq!(update(&mut model, Msg::SelectNext), None);
+    }
+    let ids = match update(&mut model, Msg::SelectNext) {
+        Some(Cmd::LoadMore { feed: Feed::Top, ids }) => ids,
+        other => panic!("expected more stories to load, got {:?}", other),
+    };
+    assert_eq!(ids, (11..=40).collect::<Vec<u64>>());
+    assert_eq!(model.notice.as_deref(), Some("Loading 30 more stories, 60 left"));
+
+    // Only one lot at a time
+    assert_eq!(update(&mut model, Msg::SelectNext), None);
+
+    // They line up after the others whatever order they come in
+    for id in ids.into_iter().rev() {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    update(&mut model, Msg::MoreLoaded(Feed::Top));
+    let order: Vec<u64> = model.storylist.items.iter().map(|item| item.id()).collect();
+    assert_eq!(order, (1..=40).collect::<Vec<u64>>());
+    assert!(!model.loading);
+}
+
+#[test]
+fn the_bottom_key_loads_more_too() {
+    let mut model = model_with_feed(10, 15);
+    match update(&mut model, Msg::SelectLast) {
+        Some(Cmd::LoadMore { ids, .. }) => assert_eq!(ids, (11..=15).collect::<Vec<u64>>()),
+        other => panic!("expected more stories to load, got {:?}", other),
+    }
+    update(&mut model, Msg::MoreLoaded(Feed::Top));
+    assert_eq!(update(&mut model, Msg::SelectLast), None, "nothing is left");
+}
+
+#[test]
+fn feeds_listed_whole_load_nothing_more() {
+    let mut model = model_with_feed(10, 10);
+    assert_eq!(update(&mut model, Msg::SelectLast), None);
+}
@@ -0,0 +1,80 @@
+//! API requests are counted per endpoint, with their error rate and latency percentiles, and
+//! the figures show in a debug overlay.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_metrics::{endpoint, Metrics};
+use hint::hint_view::view;
+use hint::hnreader::HnClient;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Terminal;
+use std::time::Duration;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..20).map(|y| (0..100).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n").collect()
+}
+
+#[test]
+fn endpoints_group_items_and_name_lists() {
+    assert_eq!(endpoint("item/8863.json"), "item");
+    assert_eq!(endpoint("topstories.json"), "topstories");
+    assert_eq!(endpoint("user/pg.json"), "user");
+}
+
+#[test]
+fn error_rate_and_percentiles() {
+    let metrics = Metrics::default();
+    for ms in 1..=100 {
+        metrics.record("item", Duration::from_millis(ms), ms % 10 != 0);
+    }
+    let stats = &metrics.snapshot()["item"];
+    assert_eq!(stats.requests, 100);
+    assert_eq!(stats.errors, 10);
+    assert_eq!(stats.error_rate(), 0.1);
+    assert_eq!(stats.percentile(50), Some(Duration::from_millis(50)));
+    assert_eq!(stats.percentile(99), Some(Duration::from_millis(99)));
+    assert_eq!(metrics.report(), ["item  100 req  10.0% err  p50 50ms  p90 90ms  p99 99ms"]);
+
+    // Clones record into the same figures
+    metrics.clone().record("user", Duration::from_millis(7), true);
+    assert_eq!(metrics.snapshot()["user"].percentile(90), Some(Duration::from_millis(7)));
+}
+
+#[tokio::test]
+async fn client_records_requests_and_failures() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/item/1.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"id":1,"by":"pg","title":"A","type":"story"}"#))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET")).and(path("/item/2.json")).respond_with(ResponseTemplate::new(404)).mount(&server).await;
+    let client = HnClient::with_base_url(&server.uri());
+
+    client.fetch_story_details(1).await.unwrap();
+    assert!(client.fetch_story_details(2).await.is_err());
+
+    let stats = &client.metrics().snapshot()["item"];
+    assert_eq!(stats.requests, 2);
+    assert_eq!(stats.errors, 1);
+}
+
+#[test]
+fn overlay_toggles_with_m() {
+    let mut model = Model::default();
+    model.metrics.record("topstories", Duration::from_millis(120), true);
+    assert!(!screen(&mut model).contains("API requests"));
+
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('M'))));
+    let shown = screen(&mut model);
+    assert!(shown.contains("API requests"), "{}", shown);
+    assert!(shown.contains("topstories  1 req  0.0% err  p50 120ms"), "{}", shown);
+
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('M'))));
+    assert!(!screen(&mut model).contains("API requests"));
+}
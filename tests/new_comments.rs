@@ -0,0 +1,141 @@
+//! Comments posted since the last visit to a thread: remembered, badged and jumped to with `n`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_seen::SeenComments;
+use hint::hint_store::Store;
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    Terminal,
+};
+
+fn comment(id: u64, depth: usize) -> Comment {
+    Comment {
+        id,
+        author: format!("user{}", id),
+        text: format!("Comment {}", id),
+        time: None,
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    }
+}
+
+fn thread(ids: &[(u64, usize)]) -> CommentThread {
+    CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: None,
+        comments: ids.iter().map(|(id, depth)| comment(*id, *depth)).collect(),
+        unloaded: Vec::new(),
+    }
+}
+
+/// Open the story's comments and load `ids` as the thread
+fn visit(model: &mut Model, ids: &[(u64, usize)]) -> Option<Cmd> {
+    if model.storylist.items.is_empty() {
+        let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+        update(model, Msg::StoryLoaded(Feed::Top, story));
+        press(model, KeyCode::Down);
+    }
+    if model.comments.is_some() {
+        press(model, KeyCode::Left);
+    }
+    press(model, KeyCode::Char('c'));
+    update(model, Msg::CommentsLoaded(thread(ids)))
+}
+
+fn selected_id(model: &Model) -> Option<u64> {
+    let view = model.comments.as_ref()?;
+    let row = view.state.selected()?;
+    let i = view.visible()[row - 1];
+    Some(view.thread.as_ref()?.comments[i].id)
+}
+
+#[test]
+fn seen_comments_persist_and_forget_old_threads() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut seen = SeenComments::default();
+    seen.mark(1, [10, 11]);
+    seen.mark(1, [11, 12]);
+    seen.save(&store).unwrap();
+    let loaded = SeenComments::load(&store);
+    assert_eq!(loaded, seen);
+    assert_eq!(loaded.get(1).unwrap().len(), 3);
+    assert_eq!(loaded.get(2), None);
+
+    for story in 2..=501 {
+        seen.mark(story, [story]);
+    }
+    assert_eq!(seen.len(), 500);
+    assert_eq!(seen.get(1), None, "the least recently opened thread goes first");
+}
+
+#[test]
+fn nothing_is_new_on_the_first_visit() {
+    let mut model = Model::default();
+    let cmd = visit(&mut model, &[(10, 0), (11, 1)]);
+    assert_eq!(cmd, Some(Cmd::SaveSeen(model.seen.clone())));
+    assert_eq!(model.comments.as_ref().unwrap().new_count(), 0);
+    assert!(model.seen.get(1).unwrap().contains(&11));
+}
+
+#[test]
+fn comments_added_since_the_last_visit_are_badged() {
+    let mut model = Model::default();
+    visit(&mut model, &[(10, 0), (20, 0)]);
+    visit(&mut model, &[(10, 0), (11, 1), (20, 0), (21, 1)]);
+    assert_eq!(model.comments.as_ref().unwrap().new_count(), 2);
+
+    let mut terminal = Terminal::new(TestBackend::new(50, 16)).unwrap();
+    let frame = terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    let rows: Vec<String> = (0..16)
+        .map(|y| (0..50).map(|x| frame.buffer[(x, y)].symbol()).collect::<String>().trim().to_string())
+        .collect();
+    assert!(rows.contains(&"Comments (2 new, n to jump)".to_string()), "{:#?}", rows);
+    assert!(rows.contains(&"user11  new".to_string()), "{:#?}", rows);
+    assert!(rows.contains(&"user10".to_string()), "{:#?}", rows);
+}
+
+#[test]
+fn n_and_shift_n_jump_between_new_comments() {
+    let mut model = Model::default();
+    visit(&mut model, &[(10, 0), (20, 0)]);
+    visit(&mut model, &[(10, 0), (11, 1), (20, 0), (21, 1)]);
+
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(selected_id(&model), Some(11));
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(selected_id(&model), Some(21));
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(selected_id(&model), Some(21));
+    assert_eq!(model.notice.as_deref(), Some("No more new comments below"));
+    press(&mut model, KeyCode::Char('N'));
+    assert_eq!(selected_id(&model), Some(11));
+
+    // Seen now, so the third visit has nothing new
+    visit(&mut model, &[(10, 0), (11, 1), (20, 0), (21, 1)]);
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(model.notice.as_deref(), Some("No new comments since the last visit"));
+}
+
+#[test]
+fn replies_loaded_later_are_remembered_too() {
+    let mut model = Model::default();
+    visit(&mut model, &[(10, 0)]);
+    let replies = vec![comment(11, 1)];
+    let cmd = update(&mut model, Msg::RepliesLoaded { story_id: 1, parent: Some(10), replies });
+    assert_eq!(cmd, Some(Cmd::SaveSeen(model.seen.clone())));
+    assert!(model.seen.get(1).unwrap().contains(&11));
+}
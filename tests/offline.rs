@@ -0,0 +1,56 @@
+//! `--offline` reads feeds, stories and comments from the API responses saved by earlier runs,
+//! refuses what needs the network, and says how old the list is.
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_cache::api_cache_dir;
+use hint::hint_hackernews::{Feed, HnStoryList};
+use hint::hint_store::Store;
+use hint::hnreader::HnClient;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+#[tokio::test]
+async fn what_one_run_fetched_the_next_reads_offline() {
+    let server = MockServer::start().await;
+    for (route, body) in [("/v0/topstories.json", json!([10])), ("/v0/item/10.json", json!({ "id": 10, "by": "alice", "title": "First" }))] {
+        Mock::given(method("GET")).and(path(route)).respond_with(ResponseTemplate::new(200).set_body_json(body)).mount(&server).await;
+    }
+    let dir = tempfile::tempdir().unwrap();
+    let saved = api_cache_dir(&Store::new(dir.path()));
+
+    let online = HnClient::with_base_url(&format!("{}/v0", server.uri())).with_recording(&saved);
+    assert!(!online.is_offline());
+    HnStoryList::new(online, Feed::Top).await;
+    drop(server);
+
+    let offline = HnClient::replay(&saved);
+    assert!(offline.is_offline());
+    assert!(offline.saved_at(Feed::Top.api_path().unwrap()).is_some());
+    assert_eq!(offline.saved_at(Feed::New.api_path().unwrap()), None, "never fetched");
+    let list = HnStoryList::new(offline, Feed::Top).await;
+    let titles: Vec<String> = list.iter().map(|story| story.title().to_string()).collect();
+    assert_eq!(titles, vec!["First"]);
+}
+
+#[test]
+fn only_the_hn_api_is_answered_from_disk() {
+    assert!(!Cmd::LoadComments(1).needs_network());
+    assert!(Cmd::LoadBetween { start: 0, end: 1 }.needs_network());
+    assert!(Cmd::SaveArticles(vec!["https://example.com".to_string()]).needs_network());
+    assert_eq!(Feed::Favorites.api_path(), None);
+}
+
+#[test]
+fn an_offline_feed_stays_marked_with_its_age() {
+    let mut model = Model::default();
+    model.offline = true;
+    update(&mut model, Msg::FeedSavedAt(Feed::Top, 1_700_000_000));
+    update(&mut model, Msg::FeedLoaded(Feed::Top));
+    assert_eq!(model.cached_at, Some(1_700_000_000));
+    assert!(!model.loading);
+
+    // Another feed's age is not this one's
+    update(&mut model, Msg::FeedSavedAt(Feed::New, 1_600_000_000));
+    assert_eq!(model.cached_at, Some(1_700_000_000));
+}
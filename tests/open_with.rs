@@ -0,0 +1,63 @@
+//! Opening links with per-domain and per-file-type commands.
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_browser::{expand_opener, opener};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use std::collections::BTreeMap;
+
+fn open_with() -> BTreeMap<String, String> {
+    BTreeMap::from([
+        ("youtube.com".to_string(), "mpv {url}".to_string()),
+        ("github.com".to_string(), "$BROWSER {url}".to_string()),
+        ("gist.github.com".to_string(), "gist-viewer".to_string()),
+        (".pdf".to_string(), "zathura".to_string()),
+    ])
+}
+
+fn story(id: u64, url: &str) -> HnStory {
+    HnStory::new(id.to_string(), "pg".into(), "Story".into(), Some(url.to_string()), "story".into())
+}
+
+#[test]
+fn domains_match_their_subdomains() {
+    let open_with = open_with();
+    assert_eq!(opener(&open_with, "https://www.youtube.com/watch?v=1"), Some("mpv {url}"));
+    assert_eq!(opener(&open_with, "https://YouTube.com:443/"), Some("mpv {url}"));
+    assert_eq!(opener(&open_with, "https://notyoutube.com/"), None);
+    assert_eq!(opener(&open_with, "https://example.com/"), None);
+}
+
+#[test]
+fn longest_domain_and_extensions_win() {
+    let open_with = open_with();
+    assert_eq!(opener(&open_with, "https://gist.github.com/a/b"), Some("gist-viewer"));
+    assert_eq!(opener(&open_with, "https://github.com/a/b"), Some("$BROWSER {url}"));
+    assert_eq!(opener(&open_with, "https://github.com/a/paper.PDF?raw=1"), Some("zathura"));
+}
+
+#[test]
+fn url_is_appended_when_the_command_has_no_placeholder() {
+    let story = story(1, "https://example.com/paper.pdf");
+    assert_eq!(expand_opener("zathura", &story), "zathura 'https://example.com/paper.pdf'");
+    assert_eq!(expand_opener("mpv {url} --fs", &story), "mpv 'https://example.com/paper.pdf' --fs");
+}
+
+#[test]
+fn open_dispatches_each_story_to_its_opener() {
+    let mut model = Model::default();
+    model.open_with = open_with();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1, "https://youtube.com/watch?v=1")));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(2, "https://example.com/")));
+    update(&mut model, Msg::SelectFirst);
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('V'))));
+    update(&mut model, Msg::SelectNext);
+    let cmd = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('o'))));
+    assert_eq!(
+        cmd,
+        Some(Cmd::Batch(vec![
+            Cmd::OpenUrls(vec!["https://example.com/".to_string()]),
+            Cmd::RunCommands(vec!["mpv 'https://youtube.com/watch?v=1'".to_string()]),
+        ]))
+    );
+}
@@ -0,0 +1,81 @@
+//! `:past` shows what made the front page on a given day, from HN Search, with `[` and `]`
+//! stepping to the days around it.
+
+mod common;
+
+use common::{run, story, StoryBuilder};
+use chrono::NaiveDate;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_search::{front_page, parse_day};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use wiremock::matchers::{path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn day(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+#[test]
+fn days_are_named_like_the_prompt_takes_them() {
+    let today = day("2024-03-01");
+    assert_eq!(parse_day("", today), Some(day("2024-02-29")));
+    assert_eq!(parse_day("yesterday", today), Some(day("2024-02-29")));
+    assert_eq!(parse_day("today", today), Some(today));
+    assert_eq!(parse_day("-7", today), Some(day("2024-02-23")));
+    assert_eq!(parse_day("2023-12-25", today), Some(day("2023-12-25")));
+    assert_eq!(parse_day("last week", today), None);
+}
+
+#[tokio::test]
+async fn front_page_asks_for_the_day_and_ranks_by_points() {
+    let server = MockServer::start().await;
+    let body = r#"{"hits": [
+        {"objectID": "1", "title": "Less", "author": "pg", "points": 90, "num_comments": 12, "created_at_i": 1705320000},
+        {"objectID": "2", "title": "More", "author": "dang", "points": 300, "num_comments": 80, "created_at_i": 1705330000}
+    ]}"#;
+    Mock::given(path("/search"))
+        .and(query_param("tags", "front_page"))
+        .and(query_param("numericFilters", "created_at_i>=1705276800,created_at_i<1705363200"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+    let stories = front_page(day("2024-01-15"), &server.uri()).await.unwrap();
+    let titles: Vec<&str> = stories.iter().map(HnStory::title).collect();
+    assert_eq!(titles, vec!["More", "Less"]);
+    assert_eq!((stories[0].descendants(), stories[0].time()), (Some(80), Some(1705330000)));
+}
+
+#[test]
+fn past_command_shows_the_day_and_brackets_step_through_days() {
+    let mut model = Model::default();
+    assert_eq!(run(&mut model, "past 2024-01-15"), Some(Cmd::LoadPastFrontPage(day("2024-01-15"))));
+    assert_eq!(model.feed, Feed::Past);
+    assert!(model.loading);
+    assert_eq!(model.feed_title(), "Past: Mon 2024-01-15");
+
+    let stories = vec![story(2).with_score(300), story(1).with_score(90)];
+    update(&mut model, Msg::PastFrontPageLoaded { day: day("2024-01-14"), stories: stories.clone() });
+    assert!(model.storylist.items.is_empty(), "another day's page is dropped");
+    update(&mut model, Msg::PastFrontPageLoaded { day: day("2024-01-15"), stories });
+    let ids: Vec<u64> = model.storylist.items.iter().map(|item| item.id()).collect();
+    assert_eq!(ids, vec![2, 1]);
+    assert!(!model.loading);
+
+    let previous = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('['))));
+    assert_eq!(previous, Some(Cmd::LoadPastFrontPage(day("2024-01-14"))));
+    assert!(model.storylist.items.is_empty());
+    let next = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char(']'))));
+    assert_eq!(next, Some(Cmd::LoadPastFrontPage(day("2024-01-15"))));
+}
+
+#[test]
+fn the_future_has_no_front_page() {
+    let mut model = Model::default();
+    assert_eq!(run(&mut model, "past 2999-01-01"), None);
+    assert_eq!(model.notice.as_deref(), Some("2999-01-01 has not happened yet"));
+    assert_eq!(model.feed, Feed::Top);
+
+    run(&mut model, "past someday");
+    assert_eq!(model.notice.as_deref(), Some("Usage: :past [yesterday|today|-days|YYYY-MM-DD]"));
+}
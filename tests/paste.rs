@@ -0,0 +1,61 @@
+//! Bracketed paste: text pasted into a prompt or the reply box goes in whole, rather than as keys.
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_auth::HnSession;
+use hint::hint_comments::CommentThread;
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for id in 1..=3 {
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), None, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    update(&mut model, Msg::SelectFirst);
+    model
+}
+
+#[test]
+fn paste_goes_in_at_the_prompt_cursor_on_one_line() {
+    let mut model = model_with_stories();
+    update(&mut model, Msg::OpenCommandLine);
+    for c in "search ".chars() {
+        update(&mut model, Msg::PromptInput(c));
+    }
+    update(&mut model, Msg::Paste("rust\r\nasync\n".to_string()));
+    assert_eq!(model.prompt.as_ref().unwrap().input, "search rust async");
+}
+
+#[test]
+fn paste_keeps_its_lines_in_a_reply() {
+    let mut model = model_with_stories();
+    model.hn_session = Some(HnSession::new("alice".to_string(), "alice&abc".to_string()));
+    update(&mut model, Msg::OpenComments);
+    let thread = CommentThread {
+        story_id: 1,
+        title: "Story 1".to_string(),
+        author: "pg".to_string(),
+        url: None,
+        text: None,
+        comments: Vec::new(),
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    update(&mut model, Msg::Reply);
+    update(&mut model, Msg::ComposeInput('>'));
+    update(&mut model, Msg::Paste("quoted\r\ntext with q and x".to_string()));
+    assert_eq!(model.compose.as_ref().unwrap().text, ">quoted\ntext with q and x");
+    assert!(!model.should_exit);
+}
+
+#[test]
+fn paste_in_the_list_does_not_run_keys() {
+    let mut model = model_with_stories();
+    let cmd = update(&mut model, Msg::Paste("xqo".to_string()));
+    assert_eq!(cmd, None::<Cmd>);
+    assert!(!model.should_exit);
+    assert_eq!(model.storylist.items.len(), 3);
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('j'))));
+    assert_eq!(model.storylist.state.selected(), Some(1));
+}
@@ -0,0 +1,105 @@
+//! Piping stories and threads to other programs with `|`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_article::Article;
+use hint::hint_comments::CommentThread;
+use hint::hint_export::thread_markdown;
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::KeyCode;
+
+fn model_with_stories(n: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=n {
+        let url = Some(format!("https://example.com/{}", id));
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), url, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+fn pipe(model: &mut Model, command: &str) -> Option<Cmd> {
+    press(model, KeyCode::Char('|'));
+    assert_eq!(model.prompt.as_ref().map(|prompt| prompt.label.as_str()), Some("| "));
+    for c in command.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter)
+}
+
+#[test]
+fn selected_story_details_are_piped() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    assert_eq!(
+        pipe(&mut model, "less"),
+        Some(Cmd::Pipe {
+            command: "less".to_string(),
+            input: "Title: Story 1\nURL: https://example.com/1\nAuthor: pg\n\
+                    Comments: https://news.ycombinator.com/item?id=1\n"
+                .to_string(),
+        })
+    );
+}
+
+#[test]
+fn marked_stories_are_piped_as_blocks() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    let Some(Cmd::Pipe { input, .. }) = pipe(&mut model, "wc -l") else {
+        panic!("expected a pipe");
+    };
+    assert_eq!(input.split("\n\n").count(), 2);
+    assert_eq!(model.storylist.visual_anchor, None);
+}
+
+#[test]
+fn empty_command_does_nothing() {
+    let mut model = model_with_stories(1);
+    press(&mut model, KeyCode::Down);
+    assert_eq!(pipe(&mut model, "  "), None);
+}
+
+#[test]
+fn open_thread_is_piped_as_markdown() {
+    let mut model = model_with_stories(1);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    press(&mut model, KeyCode::Char('|'));
+    assert!(model.prompt.is_none(), "nothing to pipe while loading");
+
+    let thread = CommentThread {
+        story_id: 1,
+        title: "Story 1".to_string(),
+        author: "pg".to_string(),
+        url: None,
+        text: None,
+        comments: Vec::new(),
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread.clone()));
+    let Some(Cmd::Pipe { input, .. }) = pipe(&mut model, "pandoc") else {
+        panic!("expected a pipe");
+    };
+    assert_eq!(input, thread_markdown(&thread));
+}
+
+#[test]
+fn article_in_the_reader_is_piped_as_text() {
+    let mut model = model_with_stories(1);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('a'));
+    let text = "Lede\n\nSecond paragraph".to_string();
+    let article = Article { url: "https://example.com/1".to_string(), text: text.clone(), links: Vec::new(), archived_from: None };
+    update(&mut model, Msg::ArticleLoaded { article, editor: false });
+    assert!(model.reader.is_some());
+
+    let Some(Cmd::Pipe { input, .. }) = pipe(&mut model, "wc -w") else {
+        panic!("expected a pipe");
+    };
+    assert_eq!(input, text);
+}
@@ -0,0 +1,92 @@
+//! With `prefetch` on, the selected story's thread and article are fetched ahead, so `c` and
+//! the pager show them without waiting.
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_article::Article;
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_prefetch::Prefetch;
+
+fn url(id: u64) -> String {
+    format!("https://example.com/{}", id)
+}
+
+fn model_with_stories(prefetch: bool) -> Model {
+    let mut model = Model::default();
+    model.prefetch = prefetch;
+    for id in 1..=3 {
+        let mut story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), Some(url(id)), "story".into());
+        // The third story has no comments to fetch
+        story.set_descendants(if id == 3 { 0 } else { 4 });
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+fn thread(story_id: u64) -> CommentThread {
+    let comment = Comment {
+        id: story_id * 10,
+        author: "alan".to_string(),
+        text: "First!".to_string(),
+        time: None,
+        depth: 0,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    };
+    CommentThread {
+        story_id,
+        title: format!("Story {}", story_id),
+        author: "pg".to_string(),
+        url: Some(url(story_id)),
+        text: None,
+        comments: vec![comment],
+        unloaded: Vec::new(),
+    }
+}
+
+fn article(id: u64) -> Article {
+    Article { url: url(id), text: "Body".to_string(), links: Vec::new(), archived_from: None }
+}
+
+#[test]
+fn selecting_a_story_fetches_ahead_for_it() {
+    let mut model = model_with_stories(true);
+    let cmd = update(&mut model, Msg::SelectFirst);
+    assert_eq!(cmd, Some(Cmd::Prefetch(Prefetch { thread: Some(1), url: Some(url(1)) })));
+
+    let cmd = update(&mut model, Msg::SelectNext);
+    assert_eq!(cmd, Some(Cmd::Prefetch(Prefetch { thread: Some(2), url: Some(url(2)) })));
+    let cmd = update(&mut model, Msg::SelectLast);
+    assert_eq!(cmd, Some(Cmd::Prefetch(Prefetch { thread: None, url: Some(url(3)) })), "no comments to fetch");
+}
+
+#[test]
+fn prefetched_thread_and_article_show_at_once() {
+    let mut model = model_with_stories(true);
+    update(&mut model, Msg::SelectFirst);
+    update(&mut model, Msg::ThreadPrefetched(thread(1)));
+    update(&mut model, Msg::ArticlePrefetched(article(1)));
+
+    // Both are in, so coming back fetches nothing more
+    update(&mut model, Msg::SelectNext);
+    assert_eq!(update(&mut model, Msg::SelectPrevious), None);
+
+    assert_eq!(update(&mut model, Msg::ViewText { editor: false }), Some(Cmd::ViewText { text: "Body".to_string(), editor: false }));
+
+    let cmd = update(&mut model, Msg::OpenComments);
+    assert!(!matches!(cmd, Some(Cmd::LoadComments(_))), "got {:?}", cmd);
+    assert!(model.comments.as_ref().is_some_and(|view| view.thread.is_some()));
+
+    // The thread is used once, the next visit gets it fresh
+    update(&mut model, Msg::CloseComments);
+    assert_eq!(update(&mut model, Msg::OpenComments), Some(Cmd::LoadComments(1)));
+}
+
+#[test]
+fn nothing_is_fetched_ahead_unless_the_config_asks() {
+    let mut model = model_with_stories(false);
+    assert_eq!(update(&mut model, Msg::SelectFirst), None);
+    assert_eq!(update(&mut model, Msg::SelectNext), None);
+}
@@ -0,0 +1,121 @@
+//! Fetching the stories on screen first, and keeping the list in feed order when they arrive.
+
+mod common;
+
+use common::{ids, story};
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_fetch::{FetchQueue, FETCH_AHEAD};
+use hint::hint_hackernews::Feed;
+use hint::hint_session::Session;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+fn drain(queue: &mut FetchQueue) -> Vec<usize> {
+    std::iter::from_fn(|| queue.pop().map(|(rank, _)| rank)).collect()
+}
+
+fn drain_n(queue: &mut FetchQueue, n: usize) -> Vec<usize> {
+    (0..n).filter_map(|_| queue.pop().map(|(rank, _)| rank)).collect()
+}
+
+#[test]
+fn queue_goes_in_order_without_a_window() {
+    let mut queue = FetchQueue::new(vec![10, 11, 12, 13], 1);
+    assert_eq!(queue.len(), 3);
+    assert_eq!(queue.pop(), Some((1, 11)));
+    assert_eq!(drain(&mut queue), vec![2, 3]);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn queue_fetches_the_window_then_below_then_above() {
+    let mut queue = FetchQueue::new((0..10).collect(), 2);
+    queue.set_window(5..7);
+    assert!(queue.is_urgent());
+    assert_eq!(queue.pop(), Some((5, 5)));
+    assert_eq!(queue.pop(), Some((6, 6)));
+    assert!(!queue.is_urgent());
+    assert_eq!(drain(&mut queue), vec![7, 8, 9, 4, 3, 2]);
+}
+
+#[test]
+fn moving_the_window_reprioritizes_what_is_pending() {
+    let mut queue = FetchQueue::new((0..100).collect(), 0);
+    queue.set_window(0..3);
+    assert_eq!(drain_n(&mut queue, 3), vec![0, 1, 2]);
+    queue.set_window(50..52);
+    assert_eq!(drain_n(&mut queue, 3), vec![50, 51, 52]);
+    queue.set_window(3..5);
+    assert_eq!(drain_n(&mut queue, 2), vec![3, 4]);
+}
+
+#[test]
+fn stories_arriving_out_of_order_take_their_feed_place() {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::Top, vec![1, 2, 3, 4, 5]));
+    for id in [1, 2, 5] {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    for _ in 0..3 {
+        update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    }
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(5));
+
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(4)));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(3)));
+    assert_eq!(ids(&model), vec![1, 2, 3, 4, 5]);
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(5), "selection stays put");
+}
+
+#[test]
+fn order_of_another_feed_is_ignored() {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::New, vec![2, 1]));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1)));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(2)));
+    assert_eq!(ids(&model), vec![1, 2]);
+}
+
+#[test]
+fn window_follows_the_top_row() {
+    let mut model = Model::default();
+    assert_eq!(model.fetch_window(20), 0..20 + FETCH_AHEAD);
+    update(&mut model, Msg::FeedOrder(Feed::Top, (100..200).collect()));
+    for id in 100..130 {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    *model.storylist.state.offset_mut() = 12;
+    assert_eq!(model.fetch_window(20), 12..32 + FETCH_AHEAD);
+}
+
+#[test]
+fn window_centers_on_the_story_restored_from_the_last_session() {
+    let session = Session { feed: Feed::Top, selected_story: Some(180), ..Session::default() };
+    let mut model = Model::restore(&session);
+    update(&mut model, Msg::FeedOrder(Feed::Top, (100..200).collect()));
+    assert_eq!(model.fetch_window(20), 70..90 + FETCH_AHEAD);
+
+    // Once it is in, the list leads again
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(180)));
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(180));
+    assert_eq!(model.fetch_window(20).start, 80);
+}
+
+#[test]
+fn refreshed_order_moves_rows_to_their_new_rank() {
+    let mut model = Model::default();
+    update(&mut model, Msg::FeedOrder(Feed::Top, vec![1, 2, 3, 4]));
+    for id in 1..=4 {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(1));
+
+    // Story 2 fell off the front page and 5 is new
+    update(&mut model, Msg::FeedOrder(Feed::Top, vec![4, 5, 1, 3]));
+    assert_eq!(ids(&model), vec![4, 2, 1, 3], "the unranked story keeps its row");
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(1), "selection follows the story");
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(5)));
+    assert_eq!(ids(&model), vec![4, 2, 5, 1, 3]);
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(4)));
+    assert_eq!(ids(&model), vec![4, 2, 5, 1, 3], "stories arriving again keep their rank");
+}
@@ -0,0 +1,111 @@
+//! The reading queue: bookmarks in a hand-picked order.
+
+mod common;
+
+use common::{ids, press};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_bookmarks::SavedStory;
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::KeyCode;
+
+fn model_with_queue(n: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=n {
+        let story =
+            HnStory::new(id.to_string(), "author".into(), format!("Story {}", id), None, "story".into());
+        model.bookmarks.add(SavedStory::from_story(&story));
+    }
+    for c in ":queue".chars() {
+        press(&mut model, KeyCode::Char(c));
+    }
+    assert_eq!(press(&mut model, KeyCode::Enter), None, "the queue is not fetched");
+    model
+}
+
+fn queued(model: &Model) -> Vec<u64> {
+    model.bookmarks.iter().map(|bookmark| bookmark.id).collect()
+}
+
+#[test]
+fn queue_lists_bookmarks_in_order() {
+    let model = model_with_queue(3);
+    assert_eq!(model.feed, Feed::Queue);
+    assert_eq!(ids(&model), vec![1, 2, 3]);
+}
+
+#[test]
+fn shift_j_and_k_reorder_the_queue() {
+    let mut model = model_with_queue(3);
+    press(&mut model, KeyCode::Down);
+    let cmd = press(&mut model, KeyCode::Char('J'));
+    assert_eq!(ids(&model), vec![2, 1, 3]);
+    assert_eq!(queued(&model), vec![2, 1, 3]);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 1);
+    assert_eq!(cmd, Some(Cmd::SaveBookmarks(model.bookmarks.clone())));
+
+    press(&mut model, KeyCode::Char('K'));
+    press(&mut model, KeyCode::Char('K'));
+    assert_eq!(queued(&model), vec![1, 2, 3]);
+    assert_eq!(press(&mut model, KeyCode::Char('K')), None, "already at the top");
+}
+
+#[test]
+fn reading_pops_the_story_off_the_queue() {
+    let mut model = model_with_queue(3);
+    press(&mut model, KeyCode::Down);
+    let cmd = press(&mut model, KeyCode::Char('r'));
+    assert_eq!(ids(&model), vec![2, 3]);
+    assert_eq!(cmd, Some(Cmd::SaveBookmarks(model.bookmarks.clone())));
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 2);
+
+    press(&mut model, KeyCode::Char('u'));
+    assert_eq!(ids(&model), vec![1, 2, 3]);
+}
+
+#[test]
+fn opening_pops_and_saves() {
+    let mut model = model_with_queue(2);
+    press(&mut model, KeyCode::Down);
+    let Some(Cmd::Batch(cmds)) = press(&mut model, KeyCode::Char('o')) else {
+        panic!("expected the browser and a save");
+    };
+    assert_eq!(cmds[1], Cmd::SaveBookmarks(model.bookmarks.clone()));
+    assert_eq!(queued(&model), vec![2]);
+}
+
+#[test]
+fn reordering_is_only_for_the_queue() {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "author".into(), "Story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    press(&mut model, KeyCode::Down);
+    assert_eq!(press(&mut model, KeyCode::Char('J')), None);
+}
+
+#[test]
+fn reordering_goes_by_the_bookmarks_not_the_rows_left_by_filters() {
+    let mut model = Model::default();
+    for (id, author) in [(1, "pg"), (2, "author"), (3, "author")] {
+        let story = HnStory::new(id.to_string(), author.into(), format!("Story {}", id), None, "story".into());
+        model.bookmarks.add(SavedStory::from_story(&story));
+    }
+    model.only_author = Some("author".to_string());
+    for c in ":queue".chars() {
+        press(&mut model, KeyCode::Char(c));
+    }
+    press(&mut model, KeyCode::Enter);
+    assert_eq!(ids(&model), vec![2, 3]);
+
+    // The top row moves above the bookmark filtered out
+    press(&mut model, KeyCode::Down);
+    assert!(press(&mut model, KeyCode::Char('K')).is_some());
+    assert_eq!(queued(&model), vec![2, 1, 3]);
+    assert_eq!(ids(&model), vec![2, 3]);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 2);
+
+    press(&mut model, KeyCode::Char('J'));
+    press(&mut model, KeyCode::Char('J'));
+    assert_eq!(queued(&model), vec![1, 3, 2]);
+    assert_eq!(ids(&model), vec![3, 2]);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 2);
+}
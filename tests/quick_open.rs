@@ -0,0 +1,105 @@
+//! Quick open: `o` with nothing selected, or `O`, numbers the top rows and a number key opens one.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::Terminal;
+
+fn story(id: u64) -> HnStory {
+    let url = Some(format!("https://example.com/{}", id));
+    HnStory::new(id.to_string(), "author".into(), format!("Story {}", id), url, "story".into())
+}
+
+fn model_with_stories(count: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=count {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    model
+}
+
+/// Whether `cmd` opens exactly `url` in the browser, whatever else comes with it
+fn opens(cmd: &Option<Cmd>, url: &str) -> bool {
+    match cmd {
+        Some(Cmd::OpenUrls(urls)) => urls == &[url.to_string()],
+        Some(Cmd::Batch(cmds)) => cmds.iter().any(|cmd| opens(&Some(cmd.clone()), url)),
+        _ => false,
+    }
+}
+
+fn screen(model: &mut Model, height: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(60, height)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..height).map(|y| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string()).collect()
+}
+
+#[test]
+fn o_with_nothing_selected_numbers_the_rows() {
+    let mut model = model_with_stories(3);
+    assert_eq!(press(&mut model, KeyCode::Char('o')), None);
+    assert!(model.quick_open);
+    let lines = screen(&mut model, 8);
+    assert_eq!(lines[1], "  1 Story 1 (example.com)");
+    assert_eq!(lines[3], "  3 Story 3 (example.com)");
+    assert_eq!(lines[7].trim(), "1-3 open · any other key cancels");
+
+    let cmd = press(&mut model, KeyCode::Char('2'));
+    assert!(opens(&cmd, "https://example.com/2"), "{:?}", cmd);
+    assert!(!model.quick_open);
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(2));
+    assert_eq!(screen(&mut model, 8)[1], "  ☐ Story 1 (example.com)");
+}
+
+#[test]
+fn o_still_opens_the_selected_story() {
+    let mut model = model_with_stories(3);
+    press(&mut model, KeyCode::Down);
+    assert!(opens(&press(&mut model, KeyCode::Char('o')), "https://example.com/1"));
+    assert!(!model.quick_open);
+
+    // Its own key works with a selection too
+    press(&mut model, KeyCode::Char('O'));
+    assert!(model.quick_open);
+}
+
+#[test]
+fn other_keys_cancel_without_acting() {
+    let mut model = model_with_stories(3);
+    press(&mut model, KeyCode::Char('O'));
+    assert_eq!(press(&mut model, KeyCode::Esc), None);
+    assert!(!model.quick_open && !model.should_exit, "Esc only leaves quick open");
+
+    press(&mut model, KeyCode::Char('O'));
+    assert_eq!(press(&mut model, KeyCode::Char('j')), None);
+    assert_eq!(model.storylist.state.selected(), None);
+
+    // A number past the last row opens nothing
+    press(&mut model, KeyCode::Char('O'));
+    assert_eq!(press(&mut model, KeyCode::Char('7')), None);
+    assert!(!model.quick_open);
+}
+
+#[test]
+fn numbers_start_from_the_top_of_the_screen() {
+    let mut model = model_with_stories(20);
+    *model.storylist.state.offset_mut() = 5;
+    press(&mut model, KeyCode::Char('O'));
+    assert_eq!(model.quick_open_hint(4), None);
+    assert_eq!(model.quick_open_hint(5), Some(1));
+    assert_eq!(model.quick_open_hint(13), Some(9));
+    assert_eq!(model.quick_open_hint(14), None);
+    assert!(opens(&press(&mut model, KeyCode::Char('1')), "https://example.com/6"));
+}
+
+#[test]
+fn empty_list_has_nothing_to_open() {
+    let mut model = Model::default();
+    press(&mut model, KeyCode::Char('O'));
+    assert!(!model.quick_open);
+}
@@ -0,0 +1,105 @@
+//! `a` reads the selected story's article in the details pane, wrapped again whenever the pane
+//! changes width, staying on the paragraph that was being read.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg, Pane};
+use hint::hint_article::Article;
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_reader::Reader;
+use hint::hint_view::view;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+
+const URL: &str = "https://blog.example/post";
+
+/// Paragraphs of twelve words, each word naming its paragraph
+fn article(paragraphs: usize) -> Article {
+    let text = (1..=paragraphs).map(|n| vec![format!("p{}", n); 12].join(" ")).collect::<Vec<_>>().join("\n");
+    Article { url: URL.to_string(), text, links: Vec::new(), archived_from: None }
+}
+
+fn rows(model: &mut Model, width: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, 12)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..12).map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string()).collect()
+}
+
+fn model_with_story() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "Post".into(), Some(URL.to_string()), "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+    model
+}
+
+#[test]
+fn rewrapping_keeps_the_paragraph_at_the_top() {
+    let mut reader = Reader::new(&article(4));
+    // Twelve words of two characters take four rows at 9 columns, three words a row
+    assert_eq!(reader.rows(9, 2), vec!["p1 p1 p1", "p1 p1 p1"]);
+    reader.scroll(6);
+    assert_eq!(reader.position(), (1, 2));
+    assert_eq!(reader.rows(9, 1), vec!["p2 p2 p2"]);
+
+    // Twice as wide, the paragraph takes half the rows and the place in it moves along
+    assert_eq!(reader.rows(18, 1), vec!["p2 p2 p2 p2 p2 p2"]);
+    assert_eq!(reader.position(), (1, 1));
+
+    // Scrolling stops with the last row at the top
+    reader.scroll(100);
+    assert_eq!(reader.rows(18, 3), vec!["p4 p4 p4 p4 p4 p4"]);
+    reader.scroll(-100);
+    assert_eq!(reader.position(), (0, 0));
+}
+
+#[test]
+fn a_reads_the_article_in_the_details_pane() {
+    let mut model = model_with_story();
+    let cmd = press(&mut model, KeyCode::Char('a'));
+    assert_eq!(cmd, Some(Cmd::FetchArticle { url: URL.to_string(), editor: false, archived: false }));
+    assert_eq!(update(&mut model, Msg::ArticleLoaded { article: article(30), editor: false }), None, "not the pager");
+    assert!(model.reader.is_some());
+    assert_eq!(model.focus, Pane::Details);
+
+    // Each paragraph takes two rows in the 18 columns the pane has
+    rows(&mut model, 20);
+    press(&mut model, KeyCode::Char('j'));
+    assert_eq!(model.reader.as_ref().map(Reader::position), Some((0, 1)));
+
+    press(&mut model, KeyCode::Esc);
+    assert!(model.reader.is_none());
+    assert_eq!(model.focus, Pane::List);
+    assert!(!model.should_exit);
+}
+
+#[test]
+fn resizing_keeps_the_place_read_to() {
+    let mut model = model_with_story();
+    update(&mut model, Msg::ArticlePrefetched(article(30)));
+    press(&mut model, KeyCode::Char('a'));
+    rows(&mut model, 40);
+    for _ in 0..10 {
+        press(&mut model, KeyCode::Char('j'));
+    }
+    let paragraph = model.reader.as_ref().unwrap().position().0;
+    let reading = |rows: Vec<String>| rows.into_iter().find(|row| row.contains(&format!("p{} ", paragraph + 1)));
+    assert!(reading(rows(&mut model, 40)).is_some());
+
+    // A wider terminal, and a wider pane with `<`
+    assert!(reading(rows(&mut model, 100)).is_some());
+    press(&mut model, KeyCode::Char('<'));
+    assert!(reading(rows(&mut model, 100)).is_some());
+    assert_eq!(model.reader.as_ref().unwrap().position().0, paragraph);
+}
+
+#[test]
+fn moving_the_selection_closes_the_reader() {
+    let mut model = model_with_story();
+    update(&mut model, Msg::ArticlePrefetched(article(3)));
+    assert_eq!(press(&mut model, KeyCode::Char('a')), None);
+    update(&mut model, Msg::SelectNext);
+    assert!(model.reader.is_none());
+}
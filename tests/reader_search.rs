@@ -0,0 +1,119 @@
+//! Searching the article in the reader with `/`, and moving between matches with `n` and `N`,
+//! apart from the list's `:search`.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_article::Article;
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    style::Modifier,
+    Terminal,
+};
+
+const URL: &str = "https://blog.example/post";
+
+fn search(model: &mut Model, text: &str) {
+    press(model, KeyCode::Char('/'));
+    assert_eq!(model.prompt.as_ref().unwrap().label, "/");
+    for c in text.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter);
+}
+
+fn model_reading() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "Post".into(), Some(URL.to_string()), "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+    let text = "Alpha and beta\nGamma\nBeta again\nDelta".to_string();
+    update(&mut model, Msg::ArticlePrefetched(Article { url: URL.to_string(), text, links: Vec::new(), archived_from: None }));
+    press(&mut model, KeyCode::Char('a'));
+    model
+}
+
+fn position(model: &Model) -> Option<(usize, usize)> {
+    model.reader.as_ref().map(|reader| reader.position())
+}
+
+#[test]
+fn slash_finds_text_and_n_moves_between_matches() {
+    let mut model = model_reading();
+    search(&mut model, "BETA");
+    assert_eq!(position(&model), Some((2, 0)), "the next match after the top");
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(position(&model), Some((0, 0)), "wraps around to the start");
+    press(&mut model, KeyCode::Char('N'));
+    assert_eq!(position(&model), Some((2, 0)), "and back to the end");
+
+    // The list's search is a separate thing
+    assert_eq!(model.search, None);
+}
+
+#[test]
+fn failed_search_says_so() {
+    let mut model = model_reading();
+    search(&mut model, "epsilon");
+    assert_eq!(position(&model), Some((0, 0)));
+    assert_eq!(model.notice.as_deref(), Some("No match for \"epsilon\" in the article"));
+
+    search(&mut model, "");
+    assert_eq!(model.reader.as_ref().unwrap().search, None);
+    press(&mut model, KeyCode::Char('n'));
+    assert_eq!(model.notice.as_deref(), Some("Search the article with /"));
+}
+
+#[test]
+fn matches_are_highlighted_and_counted() {
+    let mut model = model_reading();
+    search(&mut model, "beta");
+    press(&mut model, KeyCode::Char('g'));
+    let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+    let frame = terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    let buffer = frame.buffer;
+    let rows: Vec<String> = (0..12).map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect()).collect();
+    assert!(rows.iter().any(|row| row.contains("/beta: 2 matches, n to jump")), "{:#?}", rows);
+
+    let y = rows.iter().position(|row| row.contains("Alpha and beta")).unwrap();
+    let x = rows[y].find("beta").unwrap() as u16;
+    assert!(buffer[(x, y as u16)].modifier.contains(Modifier::REVERSED));
+    assert!(buffer[(x + 3, y as u16)].modifier.contains(Modifier::REVERSED));
+    assert!(!buffer[(x - 2, y as u16)].modifier.contains(Modifier::REVERSED));
+}
+
+#[test]
+fn the_keys_stay_with_the_list_without_the_reader() {
+    let mut model = model_reading();
+    press(&mut model, KeyCode::Esc);
+    press(&mut model, KeyCode::Char('/'));
+    assert!(model.prompt.is_none());
+}
+
+#[test]
+fn letters_that_lowercase_to_two_are_highlighted_too() {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "Post".into(), Some(URL.to_string()), "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+    let text = "Flights to İstanbul".to_string();
+    update(&mut model, Msg::ArticlePrefetched(Article { url: URL.to_string(), text, links: Vec::new(), archived_from: None }));
+    press(&mut model, KeyCode::Char('a'));
+    // `İ` lowercases to an `i` and a combining dot
+    search(&mut model, "İstanbul");
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+    let frame = terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    let buffer = frame.buffer;
+    let rows: Vec<String> = (0..12).map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect()).collect();
+    let y = rows.iter().position(|row| row.contains("Flights to")).unwrap();
+    let x = rows[y].find("Flights to ").unwrap() as u16 + 11;
+    assert_eq!(buffer[(x, y as u16)].symbol(), "İ");
+    assert!(buffer[(x, y as u16)].modifier.contains(Modifier::REVERSED));
+    assert!(buffer[(x + 7, y as u16)].modifier.contains(Modifier::REVERSED));
+    assert!(!buffer[(x - 1, y as u16)].modifier.contains(Modifier::REVERSED));
+}
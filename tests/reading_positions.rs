@@ -0,0 +1,128 @@
+//! Reading positions: the paragraph an article was left at and the comment a thread was left
+//! at are remembered across sessions and gone back to on reopening.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_article::Article;
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_positions::ReadingPositions;
+use hint::hint_store::Store;
+use hint::hint_view::view;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+
+const URL: &str = "https://blog.example/post";
+
+fn article() -> Article {
+    let text = (1..=5).map(|n| format!("Paragraph {}", n)).collect::<Vec<_>>().join("\n");
+    Article { url: URL.to_string(), text, links: Vec::new(), archived_from: None }
+}
+
+fn comment(id: u64) -> Comment {
+    Comment {
+        id,
+        author: "alan".to_string(),
+        text: format!("Comment {}", id),
+        time: None,
+        depth: 0,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    }
+}
+
+fn thread() -> CommentThread {
+    CommentThread {
+        story_id: 1,
+        title: "Post".to_string(),
+        author: "pg".to_string(),
+        url: Some(URL.to_string()),
+        text: None,
+        comments: (10..=13).map(comment).collect(),
+        unloaded: Vec::new(),
+    }
+}
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..12).map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>()).collect::<Vec<_>>().join("\n")
+}
+
+fn model_with_story() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "Post".into(), Some(URL.to_string()), "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    update(&mut model, Msg::SelectFirst);
+    model
+}
+
+#[test]
+fn positions_are_kept_on_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut positions = ReadingPositions::default();
+    positions.set_article(URL, 3);
+    positions.set_thread(1, Some(12));
+    positions.save(&store).unwrap();
+
+    let mut again = ReadingPositions::load(&store);
+    assert_eq!(again.article(URL), Some(3));
+    assert_eq!(again.thread(1), Some(12));
+
+    // Leaving at the start forgets the place rather than keeping it
+    again.set_article(URL, 0);
+    again.set_thread(1, None);
+    assert!(again.is_empty());
+}
+
+#[test]
+fn reopening_an_article_goes_back_to_the_paragraph_left() {
+    let mut model = model_with_story();
+    update(&mut model, Msg::ArticlePrefetched(article()));
+    update(&mut model, Msg::ToggleReader);
+    assert!(screen(&mut model).contains("Reader: blog.example | 0%"));
+
+    press(&mut model, KeyCode::Char('j'));
+    press(&mut model, KeyCode::Char('j'));
+    assert!(screen(&mut model).contains("| 50%"));
+    press(&mut model, KeyCode::Esc);
+    assert_eq!(model.positions.article(URL), Some(2));
+
+    update(&mut model, Msg::ToggleReader);
+    assert_eq!(model.reader.as_ref().map(|reader| reader.position()), Some((2, 0)));
+    assert!(screen(&mut model).contains("Paragraph 3"));
+}
+
+#[test]
+fn reopening_a_thread_selects_the_comment_left_at() {
+    let mut model = model_with_story();
+    update(&mut model, Msg::OpenComments);
+    update(&mut model, Msg::CommentsLoaded(thread()));
+    // The first press selects the story at the head of the thread
+    press(&mut model, KeyCode::Char('j'));
+    press(&mut model, KeyCode::Char('j'));
+    press(&mut model, KeyCode::Char('j'));
+    update(&mut model, Msg::CloseComments);
+    assert_eq!(model.positions.thread(1), Some(11));
+
+    update(&mut model, Msg::OpenComments);
+    update(&mut model, Msg::CommentsLoaded(thread()));
+    let selected = model.comments.as_ref().and_then(|view| view.state.selected());
+    assert_eq!(selected, Some(2), "the story then comments 10 and 11");
+}
+
+#[test]
+fn quitting_notes_the_places_still_open() {
+    let mut model = model_with_story();
+    update(&mut model, Msg::ArticlePrefetched(article()));
+    update(&mut model, Msg::ToggleReader);
+    press(&mut model, KeyCode::Char('j'));
+    model.remember_positions();
+    assert_eq!(model.positions.article(URL), Some(1));
+}
@@ -0,0 +1,70 @@
+//! Once a story's article has been fetched, by prefetching or by opening it, the details pane
+//! says how long it takes to read, and so does the list with `reading_time_column`.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_article::Article;
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn article(url: &str, words: usize, links: usize) -> Article {
+    let mut text = vec!["word"; words].join(" ");
+    let links: Vec<String> = (1..=links).map(|n| format!("https://example.com/{}", n)).collect();
+    for (n, link) in links.iter().enumerate() {
+        text.push_str(&format!(" [{}]\n[{}] {}", n + 1, n + 1, link));
+    }
+    Article { url: url.to_string(), text, links, archived_from: None }
+}
+
+fn rows(model: &mut Model) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..12).map(|y| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string()).collect()
+}
+
+fn loaded() -> Model {
+    let mut model = Model::default();
+    for id in 1..=2 {
+        let url = Some(format!("https://blog.example/{}", id));
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), url, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+#[test]
+fn reading_time_counts_the_words_but_not_the_links() {
+    assert_eq!(article("https://a.example", 10, 0).reading_minutes(), 1, "never under a minute");
+    assert_eq!(article("https://a.example", 1600, 0).reading_minutes(), 7);
+    assert_eq!(article("https://a.example", 1600, 200).reading_minutes(), 7);
+}
+
+#[test]
+fn fetched_articles_tell_their_reading_time() {
+    let mut model = loaded();
+    let first = model.storylist.items[0].story.clone();
+    assert_eq!(model.reading_minutes(&first), None);
+
+    update(&mut model, Msg::ArticlePrefetched(article("https://blog.example/1", 1600, 0)));
+    assert_eq!(model.reading_minutes(&first), Some(7));
+    update(&mut model, Msg::ReadingTimes(vec![("https://blog.example/2".to_string(), 3)]));
+    assert_eq!(model.reading_minutes(&model.storylist.items[1].story), Some(3));
+
+    update(&mut model, Msg::SelectFirst);
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Tab)));
+    assert!(rows(&mut model).join("\n").contains("Reading time: 7 min"));
+}
+
+#[test]
+fn the_list_shows_reading_times_when_the_config_asks() {
+    let mut model = loaded();
+    update(&mut model, Msg::ArticlePrefetched(article("https://blog.example/1", 1600, 0)));
+    assert_eq!(rows(&mut model)[1], "  ☐ Story 1 (blog.example)");
+
+    model.reading_time_column = true;
+    let rows = rows(&mut model);
+    assert_eq!(rows[1], "  ☐ Story 1 (blog.example) 7 min");
+    assert_eq!(rows[2], "  ☐ Story 2 (blog.example)", "not fetched yet");
+}
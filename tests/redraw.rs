@@ -0,0 +1,89 @@
+//! Frames are only drawn when something on screen changed.
+
+mod common;
+
+use common::story;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::Feed;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+
+/// A model whose screen is up to date
+fn drawn(mut model: Model) -> Model {
+    assert!(model.needs_redraw());
+    model.mark_drawn();
+    model
+}
+
+#[test]
+fn first_frame_is_drawn() {
+    assert!(Model::default().needs_redraw());
+}
+
+#[test]
+fn idle_ticks_draw_nothing() {
+    let mut model = drawn(Model::default());
+    for _ in 0..100 {
+        update(&mut model, Msg::Tick);
+        assert!(!model.needs_redraw());
+    }
+}
+
+#[test]
+fn input_and_data_draw_a_frame() {
+    let mut model = drawn(Model::default());
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    let mut model = drawn(model);
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1)));
+    let mut model = drawn(model);
+    update(&mut model, Msg::Resize { width: 80, height: 24 });
+    assert!(model.needs_redraw());
+}
+
+#[test]
+fn spinner_turns_only_while_loading() {
+    let mut model = Model::default();
+    update(&mut model, Msg::NextFeed);
+    assert!(model.loading);
+    let mut model = drawn(model);
+    update(&mut model, Msg::Tick);
+    assert!(!model.needs_redraw(), "timer ticks leave the spinner be");
+    update(&mut model, Msg::Frame);
+    assert!(model.needs_redraw(), "each frame turns the spinner");
+
+    let mut model = drawn(model);
+    let feed = model.feed;
+    update(&mut model, Msg::FeedLoaded(feed.previous()));
+    assert!(model.loading, "another feed finishing changes nothing");
+    assert_eq!(model.refreshed_at, None);
+    update(&mut model, Msg::FeedLoaded(feed));
+    assert!(!model.loading);
+    assert!(model.refreshed_at.is_some());
+    let mut model = drawn(model);
+    update(&mut model, Msg::Tick);
+    update(&mut model, Msg::Frame);
+    assert!(!model.needs_redraw());
+
+    update(&mut model, Msg::NextFeed);
+    assert_eq!(model.refreshed_at, None, "the next feed has yet to load");
+}
+
+#[test]
+fn ages_are_redrawn_every_minute() {
+    let mut model = drawn(Model::default());
+    let redraws = (0..1800)
+        .filter(|_| {
+            update(&mut model, Msg::Tick);
+            let redraw = model.needs_redraw();
+            model.mark_drawn();
+            redraw
+        })
+        .count();
+    assert_eq!(redraws, 3);
+}
+
+#[test]
+fn forced_redraw() {
+    let mut model = drawn(Model::default());
+    model.force_redraw();
+    assert!(model.needs_redraw());
+}
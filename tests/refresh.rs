@@ -0,0 +1,57 @@
+//! Refreshing a feed fetches its order again but only the stories the list does not have yet,
+//! moving the others to their new ranks.
+
+mod common;
+
+use common::{ids, story};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_fetch::FetchQueue;
+use hint::hint_hackernews::Feed;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use std::collections::HashSet;
+
+fn loaded(feed: Feed, order: &[u64]) -> Model {
+    let mut model = Model::default();
+    model.feed = feed;
+    update(&mut model, Msg::FeedOrder(feed, order.to_vec()));
+    for id in order {
+        update(&mut model, Msg::StoryLoaded(feed, story(*id)));
+    }
+    update(&mut model, Msg::FeedLoaded(feed));
+    model
+}
+
+#[test]
+fn refresh_asks_only_for_new_stories() {
+    let mut model = loaded(Feed::Top, &[1, 2, 3]);
+    model.hidden.hide(9);
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+
+    let cmd = update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('R'))));
+    assert_eq!(cmd, Some(Cmd::RefreshFeed { feed: Feed::Top, known: HashSet::from([1, 2, 3, 9]) }));
+    assert_eq!(ids(&model), vec![1, 2, 3], "the rows stay while the refresh runs");
+    assert!(model.loading);
+
+    // The fresh order moves the known rows, the selection going with its story
+    update(&mut model, Msg::FeedOrder(Feed::Top, vec![3, 4, 1, 2]));
+    assert_eq!(ids(&model), vec![3, 1, 2]);
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(2));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(4)));
+    assert_eq!(ids(&model), vec![3, 4, 1, 2]);
+}
+
+#[test]
+fn queue_skips_known_stories() {
+    let mut queue = FetchQueue::new(vec![3, 4, 1, 2, 5], 0);
+    queue.skip(&HashSet::from([1, 2, 3]));
+    let fetched: Vec<u64> = std::iter::from_fn(|| queue.pop().map(|(_, id)| id)).collect();
+    assert_eq!(fetched, vec![4, 5]);
+}
+
+#[test]
+fn local_feeds_are_rebuilt_instead() {
+    let mut model = Model::default();
+    model.feed = Feed::Queue;
+    assert_eq!(update(&mut model, Msg::Refresh), None);
+}
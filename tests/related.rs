@@ -0,0 +1,93 @@
+//! The details pane lists stories with titles like the selected one's, found on HN Search, and
+//! the number keys open their threads.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_search::related;
+use hint::hint_view::view;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::{backend::TestBackend, Terminal};
+use wiremock::matchers::{path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn story(id: u64, title: &str) -> HnStory {
+    HnStory::new(id.to_string(), "pg".into(), title.into(), None, "story".into())
+}
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for (id, title) in [(1, "Show HN: A tiny Lisp in Rust"), (2, "Why SQLite is so fast")] {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id, title)));
+    }
+    model
+}
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 30)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..30).map(|y| (0..80).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n").collect()
+}
+
+/// Select the first story and move to its details, where its related stories are asked for
+fn details_of_lisp(model: &mut Model) {
+    press(model, KeyCode::Down);
+    let find = Cmd::FindRelated { id: 1, title: "Show HN: A tiny Lisp in Rust".to_string() };
+    assert_eq!(press(model, KeyCode::Tab), Some(Cmd::Batch(vec![Cmd::FetchAuthor("pg".to_string()), find])));
+}
+
+#[tokio::test]
+async fn titles_are_searched_for_without_their_kind() {
+    let server = MockServer::start().await;
+    let body = r#"{"hits": [
+        {"objectID": "9", "title": "Lisp in Rust, part 2", "author": "pg", "num_comments": 40, "created_at_i": 1705400000}
+    ]}"#;
+    Mock::given(path("/search"))
+        .and(query_param("similarQuery", "A tiny Lisp in Rust"))
+        .and(query_param("tags", "story"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+    let stories = related("Show HN: A tiny Lisp in Rust", &server.uri()).await.unwrap();
+    let titles: Vec<&str> = stories.iter().map(HnStory::title).collect();
+    assert_eq!(titles, vec!["Lisp in Rust, part 2"]);
+}
+
+#[test]
+fn related_stories_are_asked_for_once() {
+    let mut model = model_with_stories();
+    details_of_lisp(&mut model);
+    update(&mut model, Msg::SelectNext);
+    let cmd = update(&mut model, Msg::SelectPrevious);
+    assert_eq!(cmd, None);
+}
+
+#[test]
+fn the_details_list_related_discussions_to_open() {
+    let mut model = model_with_stories();
+    details_of_lisp(&mut model);
+    // A digit with nothing related yet is left to the list, which goes to that rank
+    press(&mut model, KeyCode::Char('1'));
+    assert_eq!(model.prompt.as_ref().map(|prompt| prompt.label.as_str()), Some("Go to #"));
+    press(&mut model, KeyCode::Esc);
+
+    let mut found: Vec<HnStory> = (3..=9).map(|id| story(id, &format!("Lisp {}", id))).collect();
+    found.insert(1, story(1, "Show HN: A tiny Lisp in Rust"));
+    found[0].set_descendants(40);
+    update(&mut model, Msg::RelatedLoaded { id: 1, stories: found });
+
+    let screen = screen(&mut model);
+    assert!(screen.contains("Related discussions, 1-5 to read:"), "{}", screen);
+    assert!(screen.contains("1. Lisp 3 (40 comments)"), "{}", screen);
+    // The story itself is left out, and only five are listed
+    assert!(screen.contains("2. Lisp 4"), "{}", screen);
+    assert!(screen.contains("5. Lisp 7"), "{}", screen);
+    assert!(!screen.contains("Lisp 8"), "{}", screen);
+
+    assert_eq!(press(&mut model, KeyCode::Char('2')), Some(Cmd::LoadComments(4)));
+    assert_eq!(model.comments.as_ref().map(|view| view.story_id), Some(4));
+    assert_eq!(model.notice.as_deref(), Some("Comments of Lisp 4"));
+}
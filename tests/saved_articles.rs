@@ -0,0 +1,54 @@
+//! `prefetch_articles` saves the articles of the unread stories at the top of a feed to disk,
+//! for the reader to show later without the network.
+
+use hint::hint_app::{update, Cmd, Model, Msg, Status};
+use hint::hint_article::Article;
+use hint::hint_cache::ArticleCache;
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_store::Store;
+
+fn article(url: &str) -> Article {
+    Article { url: url.to_string(), text: "Body [1]".to_string(), links: vec!["https://b.example".to_string()], archived_from: None }
+}
+
+fn model_with_stories(prefetch_articles: usize) -> Model {
+    let mut model = Model::default();
+    model.prefetch_articles = prefetch_articles;
+    for id in 1..=4 {
+        // The second story is a text post, with no article to save
+        let url = (id != 2).then(|| format!("https://example.com/{}", id));
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), url, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+#[test]
+fn articles_are_kept_on_disk_by_address() {
+    let dir = tempfile::tempdir().unwrap();
+    let articles = ArticleCache::new(&Store::new(dir.path()));
+    assert_eq!(articles.get("https://a.example/post"), None);
+
+    articles.put(&article("https://a.example/post")).unwrap();
+    assert_eq!(articles.get("https://a.example/post"), Some(article("https://a.example/post")));
+    assert!(!articles.contains("https://a.example/other"));
+
+    // Another run finds them where this one left them
+    let again = ArticleCache::new(&Store::new(dir.path()));
+    assert!(again.contains("https://a.example/post"));
+}
+
+#[test]
+fn a_loaded_feed_saves_the_articles_of_its_top_unread_stories() {
+    let mut model = model_with_stories(2);
+    model.storylist.items[0].status = Status::Read;
+    let cmd = update(&mut model, Msg::FeedLoaded(Feed::Top));
+    let urls = vec!["https://example.com/3".to_string(), "https://example.com/4".to_string()];
+    assert_eq!(cmd, Some(Cmd::SaveArticles(urls)));
+}
+
+#[test]
+fn nothing_is_saved_unless_the_config_asks() {
+    let mut model = model_with_stories(0);
+    assert_eq!(update(&mut model, Msg::FeedLoaded(Feed::Top)), None);
+}
@@ -0,0 +1,136 @@
+//! Saved searches: tabs above the list with a count of results not seen yet.
+
+mod common;
+
+use common::{ids, press, titled};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::Feed;
+use hint::hint_search::{search, SavedSearch, SeenResults};
+use hint::hint_session::Session;
+use ratatui::crossterm::event::KeyCode;
+use wiremock::matchers::{path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn saved(name: &str, query: &str, local: bool) -> SavedSearch {
+    SavedSearch { name: name.into(), query: query.into(), local }
+}
+
+fn model_with_searches() -> Model {
+    let mut model = Model::default();
+    model.saved_searches = vec![saved("Rust", "rust", false), saved("Zig", "zig lang", true)];
+    model
+}
+
+#[test]
+fn searches_run_on_the_first_tick_and_every_few_minutes() {
+    let mut model = model_with_searches();
+    let run = Cmd::RunSearch { name: "Rust".into(), query: "rust".into() };
+    assert_eq!(update(&mut model, Msg::Tick), Some(run.clone()), "local searches are not run");
+    for _ in 1..2999 {
+        assert_eq!(update(&mut model, Msg::Tick), None);
+    }
+    assert_eq!(update(&mut model, Msg::Tick), None);
+    assert_eq!(update(&mut model, Msg::Tick), Some(run));
+}
+
+#[test]
+fn tabs_count_results_until_the_search_is_shown() {
+    let mut model = model_with_searches();
+    let stories = vec![titled(1, "Rust 2.0"), titled(2, "Why Rust")];
+    update(&mut model, Msg::SearchResultsLoaded { name: "Rust".into(), stories });
+    assert_eq!(model.unseen_results(0), 2);
+
+    let Some(Cmd::SaveSeenResults(seen)) = press(&mut model, KeyCode::Char('s')) else {
+        panic!("showing the results should remember them as seen");
+    };
+    assert_eq!(model.feed, Feed::Search);
+    assert_eq!(model.feed_title(), "Search: Rust");
+    assert_eq!(ids(&model), vec![1, 2]);
+    assert_eq!(model.unseen_results(0), 0);
+    assert_eq!(seen.unseen("Rust", &[titled(1, "Rust 2.0"), titled(3, "Rust again")]), 1);
+
+    // A refresh while away brings a new result
+    press(&mut model, KeyCode::Char(']'));
+    let stories = vec![titled(3, "Rust again"), titled(1, "Rust 2.0")];
+    update(&mut model, Msg::SearchResultsLoaded { name: "Rust".into(), stories });
+    assert_eq!(model.unseen_results(0), 1);
+}
+
+#[test]
+fn results_refresh_the_list_while_showing() {
+    let mut model = model_with_searches();
+    let run = press(&mut model, KeyCode::Char('s'));
+    assert_eq!(run, Some(Cmd::RunSearch { name: "Rust".into(), query: "rust".into() }));
+    assert!(model.storylist.items.is_empty());
+
+    let stories = vec![titled(1, "Rust 2.0")];
+    let saved = update(&mut model, Msg::SearchResultsLoaded { name: "Rust".into(), stories });
+    assert!(matches!(saved, Some(Cmd::SaveSeenResults(_))));
+    assert_eq!(ids(&model), vec![1]);
+}
+
+#[test]
+fn local_searches_collect_matching_stories_from_any_feed() {
+    let mut model = model_with_searches();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(1, "Zig lang 1.0")));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(2, "Zig the band")));
+    assert_eq!(model.unseen_results(1), 1);
+
+    press(&mut model, KeyCode::Char('s'));
+    press(&mut model, KeyCode::Char('s'));
+    assert_eq!(model.feed_title(), "Search: Zig");
+    assert_eq!(ids(&model), vec![1]);
+
+    // The old feed's stories still arrive and keep matching
+    let cmd = update(&mut model, Msg::StoryLoaded(Feed::Top, titled(3, "Learning the Zig Lang")));
+    assert!(matches!(cmd, Some(Cmd::SaveSeenResults(_))));
+    assert_eq!(ids(&model), vec![1, 3]);
+    assert_eq!(model.unseen_results(1), 0);
+}
+
+#[test]
+fn s_without_saved_searches_explains() {
+    let mut model = Model::default();
+    assert_eq!(press(&mut model, KeyCode::Char('s')), None);
+    assert_eq!(model.feed, Feed::Top);
+    assert!(model.notice.as_deref().unwrap().contains("config.json"));
+}
+
+#[test]
+fn session_does_not_restore_search_results() {
+    let mut model = model_with_searches();
+    press(&mut model, KeyCode::Char('s'));
+    assert_eq!(model.session().feed, Feed::Top);
+    assert_eq!(Model::restore(&Session::default()).feed, Feed::Top);
+}
+
+#[test]
+fn seen_results_are_kept_per_search() {
+    let mut seen = SeenResults::default();
+    assert!(seen.mark("Rust", [1, 2]));
+    assert!(!seen.mark("Rust", [2]));
+    assert_eq!(seen.unseen("Rust", &[titled(1, "a"), titled(3, "b")]), 1);
+    assert_eq!(seen.unseen("Zig", &[titled(1, "a")]), 1);
+    assert_eq!(seen.len(), 1);
+}
+
+#[tokio::test]
+async fn search_reads_algolia_hits() {
+    let server = MockServer::start().await;
+    let body = r#"{"hits": [
+        {"objectID": "42", "title": "Rust 2.0", "url": "https://rust-lang.org", "author": "pg", "points": 120},
+        {"objectID": "43", "title": "Ask HN: Rust?", "url": null, "author": "dang", "points": null},
+        {"objectID": "not-a-story", "title": "Broken"}
+    ]}"#;
+    Mock::given(path("/search_by_date"))
+        .and(query_param("query", "rust"))
+        .and(query_param("tags", "story"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(body))
+        .mount(&server)
+        .await;
+    let stories = search("rust", &server.uri()).await.unwrap();
+    assert_eq!(stories.len(), 2);
+    assert_eq!((stories[0].id(), stories[0].author(), stories[0].score()), (42, "pg", Some(120)));
+    assert_eq!(stories[0].url().as_deref(), Some("https://rust-lang.org"));
+    assert_eq!((stories[1].title(), stories[1].url()), ("Ask HN: Rust?", &None));
+}
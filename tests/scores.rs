@@ -0,0 +1,87 @@
+//! Score sparklines: bookmarked stories' scores sampled over time.
+
+mod common;
+
+use common::story;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_bookmarks::SavedStory;
+use hint::hint_hackernews::Feed;
+use hint::hint_scores::{fetch_scores, sparkline, trend, ScoreHistory, ScoreSample};
+use hint::hint_store::Store;
+use hint::hint_view::view;
+use hint::hnreader::HnClient;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn samples(scores: &[(i64, u32)]) -> Vec<ScoreSample> {
+    scores.iter().map(|&(at, score)| ScoreSample { at, score }).collect()
+}
+
+#[test]
+fn sparkline_scales_between_lowest_and_highest() {
+    assert_eq!(sparkline(&[0, 10, 20, 30, 40, 50, 60, 70], 8), "▁▂▃▄▅▆▇█");
+    assert_eq!(sparkline(&[5, 5, 5], 8), "▁▁▁");
+    assert_eq!(sparkline(&[1, 2, 100, 200], 2), "▁█", "only the latest fit");
+    assert_eq!(sparkline(&[], 8), "");
+}
+
+#[test]
+fn trend_needs_two_samples() {
+    assert_eq!(trend(&samples(&[(0, 12)]), 10), None);
+    let line = trend(&samples(&[(0, 12), (3600, 80), (5 * 3600, 340)]), 10);
+    assert_eq!(line.as_deref(), Some("Score ▁▂█ 12 → 340 in 5h"));
+    let line = trend(&samples(&[(0, 1), (600, 2)]), 10);
+    assert_eq!(line.as_deref(), Some("Score ▁█ 1 → 2 in 10m"));
+}
+
+#[test]
+fn bookmarks_are_sampled_on_the_first_tick_and_every_fifteen_minutes() {
+    let mut model = Model::default();
+    assert_eq!(update(&mut model, Msg::Tick), None, "nothing to sample without bookmarks");
+
+    let mut model = Model::default();
+    model.bookmarks.add(SavedStory::from_story(&story(1)));
+    model.bookmarks.add(SavedStory::from_story(&story(2)));
+    assert_eq!(update(&mut model, Msg::Tick), Some(Cmd::FetchScores(vec![1, 2])));
+    for _ in 1..9000 {
+        assert_eq!(update(&mut model, Msg::Tick), None);
+    }
+    assert_eq!(update(&mut model, Msg::Tick), Some(Cmd::FetchScores(vec![1, 2])));
+}
+
+#[test]
+fn fetched_scores_are_recorded_and_shown_in_the_details() {
+    let mut model = Model::default();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1)));
+    for score in [10, 25, 90] {
+        let cmd = update(&mut model, Msg::ScoresFetched(vec![(1, score)]));
+        assert!(matches!(cmd, Some(Cmd::SaveScores(_))));
+    }
+    assert_eq!(model.scores.samples(1).iter().map(|sample| sample.score).collect::<Vec<_>>(), vec![10, 25, 90]);
+    assert_eq!(model.storylist.items[0].story.score(), Some(90));
+
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Enter)));
+    let mut terminal = Terminal::new(TestBackend::new(60, 12)).unwrap();
+    terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    let screen: String = terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect();
+    assert!(screen.contains("Score ▁▂█ 10 → 90 in 0m"));
+}
+
+#[test]
+fn score_history_survives_a_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path().to_path_buf());
+    let mut scores = ScoreHistory::default();
+    scores.record(7, 3, 100);
+    scores.record(7, 9, 200);
+    scores.save(&store).unwrap();
+    assert_eq!(ScoreHistory::load(&store).samples(7), samples(&[(100, 3), (200, 9)]).as_slice());
+    assert!(ScoreHistory::load(&store).samples(8).is_empty());
+}
+
+#[tokio::test]
+async fn scores_are_fetched_for_each_story() {
+    let scores = fetch_scores(&HnClient::demo(), &[39000000, 1, 39000010]).await;
+    assert_eq!(scores, vec![(39000000, 412), (39000010, 389)]);
+}
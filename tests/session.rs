@@ -0,0 +1,91 @@
+//! Session persistence and restore.
+
+mod common;
+
+use common::{run, story};
+use hint::hint_app::{update, Cmd, Density, Model, Msg, StorySort};
+use hint::hint_hackernews::Feed;
+use hint::hint_session::Session;
+use hint::hint_store::Store;
+
+#[test]
+fn session_round_trips_through_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let session = Session {
+        feed: Feed::Ask,
+        selected_story: Some(42),
+        scroll_offset: 7,
+        theme: Some("gruvbox".to_string()),
+        command_history: vec!["feed ask".to_string()],
+        details_split: Some(65),
+        density: Density::Comfortable,
+        sort: Some(StorySort::Score),
+    };
+    session.save(&store).unwrap();
+    assert_eq!(Session::load(&store), session);
+}
+
+#[test]
+fn sort_is_saved_with_the_session() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut model = Model::default();
+    run(&mut model, "sort comments");
+
+    model.session().save(&store).unwrap();
+    assert_eq!(Session::load(&store).sort, Some(StorySort::Comments));
+}
+
+#[test]
+fn missing_or_corrupt_session_is_default() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    assert_eq!(Session::load(&store), Session::default());
+
+    std::fs::write(dir.path().join("session.json"), "{not json").unwrap();
+    assert_eq!(Session::load(&store), Session::default());
+}
+
+#[test]
+fn restored_selection_waits_for_its_story() {
+    let session = Session {
+        feed: Feed::Show,
+        selected_story: Some(3),
+        scroll_offset: 1,
+        theme: Some("default".to_string()),
+        command_history: Vec::new(),
+        details_split: None,
+        density: Density::Compact,
+        sort: None,
+    };
+    let mut model = Model::restore(&session);
+    assert_eq!(model.feed, Feed::Show);
+
+    update(&mut model, Msg::StoryLoaded(Feed::Show, story(1)));
+    update(&mut model, Msg::StoryLoaded(Feed::Show, story(2)));
+    assert_eq!(model.storylist.state.selected(), None);
+    assert_eq!(model.session(), session, "unresolved selection is kept for the next run");
+
+    update(&mut model, Msg::StoryLoaded(Feed::Show, story(3)));
+    assert_eq!(model.storylist.state.selected(), Some(2));
+    assert_eq!(model.session().selected_story, Some(3));
+}
+
+#[test]
+fn switching_feed_clears_list_and_drops_stale_stories() {
+    let mut model = Model::default();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1)));
+
+    let cmd = update(&mut model, Msg::NextFeed);
+    assert_eq!(cmd, Some(Cmd::LoadFeed(Feed::New)));
+    assert!(model.storylist.items.is_empty());
+
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(2)));
+    assert!(model.storylist.items.is_empty());
+    update(&mut model, Msg::StoryLoaded(Feed::New, story(3)));
+    assert_eq!(model.storylist.items.len(), 1);
+
+    assert_eq!(update(&mut model, Msg::PreviousFeed), Some(Cmd::LoadFeed(Feed::Top)));
+    assert_eq!(Feed::Top.previous(), Feed::Jobs);
+}
@@ -0,0 +1,63 @@
+//! Each site's name in the list is drawn in an accent colour of its own, the same on every run.
+
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_theme::site_colour;
+use hint::hint_view::view;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::style::Color;
+use ratatui::{backend::TestBackend, Terminal};
+
+fn loaded() -> Model {
+    let mut model = Model::default();
+    for (id, url) in [(1, "https://github.com/a"), (2, "https://lwn.net/b"), (3, "https://gist.github.com/c")] {
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), Some(url.into()), "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+/// The colour of the first cell of `text` on each row it shows on
+fn colours_of(model: &mut Model, text: &str) -> Vec<Color> {
+    let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..10)
+        .filter_map(|y| {
+            let row: Vec<&str> = (0..60).map(|x| buffer[(x, y)].symbol()).collect();
+            let at = (0..60).find(|x| row[*x..].concat().starts_with(text))?;
+            Some(buffer[(at as u16, y)].fg)
+        })
+        .collect()
+}
+
+#[test]
+fn a_site_always_gets_the_same_colour() {
+    assert_eq!(site_colour("github.com"), site_colour("github.com"));
+    assert_ne!(site_colour("github.com"), site_colour("lwn.net"));
+    assert_ne!(site_colour("github.com"), site_colour("gist.github.com"));
+    // Light enough to read on the dark rows of both themes
+    let Color::Rgb(r, g, b) = site_colour("example.com") else {
+        panic!("sites get RGB colours");
+    };
+    assert!(r as u32 + g as u32 + b as u32 > 3 * 0x80);
+}
+
+#[test]
+fn the_site_after_a_title_is_in_its_colour() {
+    let mut model = loaded();
+    assert_eq!(colours_of(&mut model, "(github.com)"), vec![site_colour("github.com")]);
+    assert_eq!(colours_of(&mut model, "lwn.net"), vec![site_colour("lwn.net")]);
+    // The parentheses go with the site
+    assert_eq!(colours_of(&mut model, "(gist"), vec![site_colour("gist.github.com")]);
+}
+
+#[test]
+fn the_site_on_the_details_line_is_in_its_colour() {
+    let mut model = loaded();
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Char('i'))));
+    assert_eq!(colours_of(&mut model, "lwn.net"), vec![site_colour("lwn.net")]);
+    // The rest of the line keeps the theme's colour
+    assert_eq!(colours_of(&mut model, "by pg").len(), 3);
+    assert!(!colours_of(&mut model, "by pg").contains(&site_colour("lwn.net")));
+}
@@ -0,0 +1,95 @@
+//! Snoozing stories with `z`.
+
+mod common;
+
+use common::{ids, model_with_stories, press, story};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_bookmarks::SavedStory;
+use hint::hint_hackernews::Feed;
+use hint::hint_snooze::{now, parse_duration, Snoozed};
+use hint::hint_store::Store;
+use ratatui::crossterm::event::KeyCode;
+
+fn type_line(model: &mut Model, line: &str) -> Option<Cmd> {
+    for c in line.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter)
+}
+
+#[test]
+fn durations_parse() {
+    assert_eq!(parse_duration("30m"), Some(1800));
+    assert_eq!(parse_duration("4h"), Some(4 * 3600));
+    assert_eq!(parse_duration(" 1d "), Some(86_400));
+    assert_eq!(parse_duration("0h"), None);
+    assert_eq!(parse_duration("soon"), None);
+    assert_eq!(parse_duration(""), None);
+}
+
+#[test]
+fn snoozed_story_leaves_the_list_until_due() {
+    let mut model = model_with_stories(3);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('z'));
+    let cmd = type_line(&mut model, "1h");
+    assert_eq!(cmd, Some(Cmd::SaveSnoozed(model.snoozed.clone())));
+    assert_eq!(ids(&model), vec![1, 3]);
+    assert!(model.snoozed.is_snoozed(2, now() + 3000));
+    assert!(!model.snoozed.is_snoozed(2, now() + 3700));
+
+    // A reload of the feed does not bring it back early
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(2)));
+    assert_eq!(ids(&model), vec![1, 3]);
+    // Nothing is due yet
+    assert_eq!(update(&mut model, Msg::Tick), None);
+}
+
+#[test]
+fn bad_duration_asks_again_and_empty_uses_the_default() {
+    let mut model = model_with_stories(1);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('z'));
+    type_line(&mut model, "later");
+    assert_eq!(model.notice.as_deref(), Some("Snooze for a time like 30m, 4h or 1d"));
+    assert!(model.prompt.is_some());
+    type_line(&mut model, "");
+    assert!(model.storylist.items.is_empty());
+    assert!(model.snoozed.is_snoozed(1, now() + 4 * 3600 - 60));
+}
+
+#[test]
+fn due_stories_resurface_at_the_top_with_a_badge() {
+    let mut model = model_with_stories(2);
+    model.snoozed.snooze(SavedStory::from_story(&story(9)), now() - 1);
+    update(&mut model, Msg::SelectFirst);
+
+    let cmd = update(&mut model, Msg::Tick);
+    assert_eq!(cmd, Some(Cmd::SaveSnoozed(Snoozed::default())));
+    assert_eq!(ids(&model), vec![9, 1, 2]);
+    assert!(model.storylist.items[0].resurfaced);
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 1, "selection stays put");
+    assert_eq!(model.notice.as_deref(), Some("Back from snooze: Story 9"));
+}
+
+#[test]
+fn undo_wakes_a_snoozed_story() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('z'));
+    type_line(&mut model, "1d");
+    press(&mut model, KeyCode::Char('u'));
+    assert_eq!(ids(&model), vec![1, 2]);
+    assert!(model.snoozed.is_empty());
+}
+
+#[test]
+fn snoozed_stories_round_trip_through_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut snoozed = Snoozed::default();
+    snoozed.snooze(SavedStory::from_story(&story(4)), 1_700_000_000);
+    snoozed.save(&store).unwrap();
+    assert_eq!(Snoozed::load(&store), snoozed);
+}
@@ -0,0 +1,66 @@
+//! The screen comes up before anything is fetched: a loading row first, then the feed's order
+//! and its stories one by one through the event channel.
+
+mod common;
+
+use common::story;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::{Feed, HnStoryList};
+use hint::hint_view::view;
+use hint::hnreader::HnClient;
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn screen(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..8).map(|y| (0..60).map(|x| buffer[(x, y)].symbol()).collect::<String>() + "\n").collect()
+}
+
+#[test]
+fn empty_list_shows_the_feed_loading() {
+    let mut model = Model::default();
+    model.loading = true;
+    let shown = screen(&mut model);
+    assert!(shown.contains("Loading Top stories..."), "{}", shown);
+
+    // Once stories come in, the spinner moves below them
+    update(&mut model, Msg::FeedOrder(Feed::Top, vec![1, 2]));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(2)));
+    let shown = screen(&mut model);
+    assert!(shown.contains("Story 2") && shown.contains("Updating..."), "{}", shown);
+    assert!(!shown.contains("Loading"), "{}", shown);
+
+    // Stories arriving out of order still land in the feed's order
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(1)));
+    let ids: Vec<u64> = model.storylist.items.iter().map(|item| item.id()).collect();
+    assert_eq!(ids, vec![1, 2]);
+}
+
+#[tokio::test]
+async fn ids_are_fetched_without_their_stories() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v0/topstories.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([10, 20])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v0/item/20.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 20, "by": "bob", "title": "Second" })))
+        .expect(1)
+        .mount(&server)
+        .await;
+    let client = HnClient::with_base_url(&format!("{}/v0", server.uri()));
+
+    let ids = Feed::Top.fetch_ids(&client).await.unwrap();
+    let list = HnStoryList::unfetched(client, Feed::Top, ids);
+    assert_eq!(list.ids(), &[10, 20]);
+    assert_eq!(list.fetched(), 0);
+    assert_eq!(list.iter().count(), 0);
+    assert_eq!(list.fetch_story(20).await.unwrap().title(), "Second");
+}
@@ -0,0 +1,45 @@
+//! `stories_per_feed` caps how many stories a feed lists, for every feed or one by one, and
+//! the stories past the first few are only fetched as they are needed.
+
+use hint::hint_config::Config;
+use hint::hint_hackernews::{Feed, HnStoryList, StoriesPerFeed, INITIAL_STORIES};
+use hint::hnreader::HnClient;
+
+#[test]
+fn one_number_caps_every_feed() {
+    let config: Config = serde_json::from_str(r#"{"stories_per_feed": 3}"#).unwrap();
+    let mut ids: Vec<u64> = (1..=10).collect();
+    let more = config.stories_per_feed.apply(Feed::New, &mut ids);
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(more, (4..=10).collect::<Vec<u64>>());
+    assert_eq!(config.stories_per_feed.of(Feed::Jobs), Some(3));
+}
+
+#[test]
+fn feeds_can_have_a_count_each() {
+    let config: Config = serde_json::from_str(r#"{"stories_per_feed": {"top": 2, "new": 5}}"#).unwrap();
+    let sizes = &config.stories_per_feed;
+    assert_eq!((sizes.of(Feed::Top), sizes.of(Feed::New), sizes.of(Feed::Ask)), (Some(2), Some(5), None));
+
+    let mut ids: Vec<u64> = (1..=10).collect();
+    assert!(sizes.apply(Feed::Ask, &mut ids).is_empty());
+    assert_eq!(ids.len(), 10, "feeds left out list everything");
+}
+
+#[test]
+fn left_out_lists_everything() {
+    let config: Config = serde_json::from_str("{}").unwrap();
+    assert_eq!(config.stories_per_feed, StoriesPerFeed::default());
+    assert_eq!(config.stories_per_feed.of(Feed::Top), None);
+}
+
+#[tokio::test]
+async fn only_the_first_stories_are_fetched_up_front() {
+    let mut list = HnStoryList::new(HnClient::demo(), Feed::Top).await;
+    assert!(list.ids().len() > INITIAL_STORIES);
+    assert_eq!(list.fetched(), INITIAL_STORIES);
+    assert_eq!(list.iter().count(), INITIAL_STORIES);
+
+    list.update_story_details().await.unwrap();
+    assert_eq!(list.fetched(), INITIAL_STORIES + 1);
+}
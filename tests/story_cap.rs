@@ -0,0 +1,80 @@
+//! Long-running lists keep at most `max_stories` in memory, evicting old read ones to disk.
+
+mod common;
+
+use common::{ids, story};
+use hint::hint_app::{update, Cmd, Model, Msg, Status};
+use hint::hint_bookmarks::SavedStory;
+use hint::hint_cache::StoryCache;
+use hint::hint_hackernews::Feed;
+use hint::hint_store::Store;
+
+/// A capped model holding stories `1..=count`, all of them read
+fn read_stories(max: usize, count: u64) -> Model {
+    let mut model = Model::default();
+    model.max_stories = Some(max);
+    for id in 1..=count {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+        model.storylist.items.last_mut().unwrap().status = Status::Read;
+    }
+    model
+}
+
+#[test]
+fn list_under_the_cap_evicts_nothing() {
+    let mut model = read_stories(100, 99);
+    assert_eq!(update(&mut model, Msg::StoryLoaded(Feed::Top, story(100))), None);
+    assert_eq!(model.storylist.items.len(), 100);
+    assert!(model.story_cache.is_empty());
+}
+
+#[test]
+fn oldest_read_stories_go_to_the_cache_past_the_cap() {
+    let mut model = read_stories(100, 100);
+    let cmd = update(&mut model, Msg::StoryLoaded(Feed::Top, story(101)));
+    assert!(matches!(cmd, Some(Cmd::SaveStoryCache(_))));
+    // A batch goes at once, so the cache is not written for every story after
+    assert_eq!(model.storylist.items.len(), 50);
+    assert_eq!(ids(&model)[0], 52);
+    assert_eq!(model.story_cache.len(), 51);
+    assert!(model.story_cache.contains(1) && !model.story_cache.contains(52));
+}
+
+#[test]
+fn unread_bookmarked_and_selected_stories_stay() {
+    let mut model = read_stories(10, 10);
+    model.storylist.items[0].status = Status::Unread;
+    model.bookmarks.add(SavedStory::from_story(&story(2)));
+    model.storylist.state.select(Some(2));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(11)));
+    assert_eq!(ids(&model), vec![1, 2, 3, 11]);
+    assert_eq!(model.storylist.selected_item().map(|item| item.id()), Some(3), "selection stays on its story");
+}
+
+#[test]
+fn evicted_stories_come_back_read() {
+    let mut model = read_stories(10, 11);
+    assert!(model.story_cache.contains(1));
+    update(&mut model, Msg::NextFeed);
+    let feed = model.feed;
+    update(&mut model, Msg::StoryLoaded(feed, story(1)));
+    assert_eq!(model.storylist.items[0].status, Status::Read);
+    update(&mut model, Msg::StoryLoaded(feed, story(500)));
+    assert_eq!(model.storylist.items[1].status, Status::Unread);
+}
+
+#[test]
+fn cache_survives_a_restart_and_forgets_the_oldest() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path().to_path_buf());
+    let mut cache = StoryCache::default();
+    for id in 0..5001 {
+        cache.add(SavedStory::from_story(&story(id)));
+    }
+    cache.add(SavedStory::from_story(&story(3)));
+    cache.save(&store).unwrap();
+    let cache = StoryCache::load(&store);
+    assert_eq!(cache.len(), 5000);
+    assert!(!cache.contains(0));
+    assert_eq!(cache.iter().last().map(|story| story.id), Some(3), "evicted again, so newest");
+}
@@ -0,0 +1,98 @@
+//! Badges for Ask HN, Show HN, jobs and polls, and narrowing a list to one of them.
+
+mod common;
+
+use common::{ids, press};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_command::complete;
+use hint::hint_hackernews::{Feed, HnStory, HnStoryType};
+use hint::hint_view::view;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::KeyCode;
+use ratatui::Terminal;
+
+fn story(id: u64, title: &str, kind: &str) -> HnStory {
+    HnStory::new(id.to_string(), "pg".into(), title.into(), None, kind.into())
+}
+
+fn mixed_model() -> Model {
+    let mut model = Model::default();
+    model.feed = Feed::New;
+    for story in [
+        story(1, "Ask HN: Favorite editor?", "story"),
+        story(2, "Show HN: My terminal app", "story"),
+        story(3, "Acme is hiring", "job"),
+        story(4, "Poll: Tabs or spaces?", "poll"),
+        story(5, "A plain link", "story"),
+    ] {
+        update(&mut model, Msg::StoryLoaded(Feed::New, story));
+    }
+    model
+}
+
+fn command(model: &mut Model, line: &str) -> Option<Cmd> {
+    press(model, KeyCode::Char(':'));
+    for c in line.chars() {
+        press(model, KeyCode::Char(c));
+    }
+    press(model, KeyCode::Enter)
+}
+
+#[test]
+fn kind_comes_from_the_type_or_the_title() {
+    assert_eq!(story(1, "Ask HN: Why?", "story").kind(), HnStoryType::Ask);
+    assert_eq!(story(1, "Show HN: This", "story").kind(), HnStoryType::Show);
+    assert_eq!(story(1, "Who is hiring", "job").kind(), HnStoryType::Job);
+    assert_eq!(story(1, "Which one?", "poll").kind(), HnStoryType::Poll);
+    assert_eq!(story(1, "Asking for more", "story").kind(), HnStoryType::Story);
+    assert_eq!(story(1, "Anything", "pollopt").kind(), HnStoryType::Story, "unknown types are stories");
+}
+
+#[test]
+fn rows_carry_a_badge() {
+    let mut model = mixed_model();
+    let mut terminal = Terminal::new(TestBackend::new(50, 8)).unwrap();
+    terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    let line = |y: u16| (0..50).map(|x| buffer[(x, y)].symbol()).collect::<String>().trim_end().to_string();
+    assert_eq!(line(1), "  ☐ [ask] Ask HN: Favorite editor?");
+    assert_eq!(line(2), "  ☐ [show] Show HN: My terminal app");
+    assert_eq!(line(3), "  ☐ [job] Acme is hiring");
+    assert_eq!(line(4), "  ☐ [poll] Poll: Tabs or spaces?");
+    assert_eq!(line(5), "  ☐ A plain link");
+}
+
+#[test]
+fn t_keeps_the_selected_kind() {
+    let mut model = mixed_model();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('t'));
+    assert_eq!(model.only_kind, Some(HnStoryType::Show));
+    assert_eq!(ids(&model), vec![2]);
+    assert_eq!(model.notice.as_deref(), Some("Only [show] stories, t again for all"));
+
+    // Stories arriving later go through the filter too
+    update(&mut model, Msg::StoryLoaded(Feed::New, story(6, "Show HN: Another", "story")));
+    update(&mut model, Msg::StoryLoaded(Feed::New, story(7, "Ask HN: Another", "story")));
+    assert_eq!(ids(&model), vec![2, 6]);
+
+    // Lifting it loads the feed again, as the others are gone
+    assert!(matches!(press(&mut model, KeyCode::Char('t')), Some(Cmd::LoadFeed(Feed::New))));
+    assert_eq!(model.only_kind, None);
+}
+
+#[test]
+fn type_command_picks_a_kind_by_name() {
+    let mut model = mixed_model();
+    assert_eq!(command(&mut model, "type job"), None);
+    assert_eq!(ids(&model), vec![3]);
+
+    command(&mut model, "type comment");
+    assert_eq!(model.notice.as_deref(), Some("Usage: :type [story|ask|show|job|poll]"));
+    assert_eq!(model.only_kind, Some(HnStoryType::Job));
+
+    assert!(command(&mut model, "type").is_some());
+    assert_eq!(model.only_kind, None);
+    assert_eq!(complete("type s").candidates, vec!["story", "show"]);
+}
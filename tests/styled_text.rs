@@ -0,0 +1,70 @@
+//! HN's comment HTML turned into styled lines for the terminal.
+
+use hint::hint_html::to_styled_text;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+
+const TEXT: Style = Style::new().fg(Color::White);
+const CODE: Style = Style::new().fg(Color::Yellow);
+
+fn lines(html: &str, width: usize) -> Vec<Line<'static>> {
+    to_styled_text(html, width, TEXT, CODE, false).lines
+}
+
+fn plain(lines: &[Line]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+#[test]
+fn paragraphs_are_wrapped_and_separated() {
+    let text = lines("I&#x27;ve wanted this for years.<p>Does it &amp; &quot;that&quot;?", 12);
+    assert_eq!(plain(&text), vec!["I've wanted", "this for", "years.", "", "Does it &", "\"that\"?"]);
+}
+
+#[test]
+fn italics_keep_their_style_across_wrapping() {
+    let text = lines("a <i>very important</i> point", 10);
+    let italic = TEXT.add_modifier(Modifier::ITALIC);
+    assert_eq!(text[0], Line::from(vec![Span::styled("a ", TEXT), Span::styled("very", italic)]));
+    assert_eq!(text[1], Line::from(Span::styled("important", italic)));
+    assert_eq!(text[2], Line::from(Span::styled("point", TEXT)));
+}
+
+#[test]
+fn links_are_numbered_with_footnotes() {
+    let html = "See <a href=\"https:&#x2F;&#x2F;example.com&#x2F;a?x=1&amp;y=2\" rel=\"nofollow\">the docs</a> \
+                and <a href=\"https://example.org\">this</a>.";
+    let text = lines(html, 80);
+    assert_eq!(
+        plain(&text),
+        vec!["See the docs[1] and this[2].", "", "[1] https://example.com/a?x=1&y=2", "[2] https://example.org"]
+    );
+    let underlined = TEXT.add_modifier(Modifier::UNDERLINED);
+    assert_eq!(text[0].spans[1], Span::styled("the docs", underlined));
+    assert_eq!(text[0].spans[2], Span::styled("[1] and ", TEXT));
+}
+
+#[test]
+fn code_blocks_keep_their_lines_and_are_not_wrapped() {
+    let html = "Try this:<p><pre><code>  fn main() {\n      println!(&quot;a very long line that does not fit&quot;);\n  }\n</code></pre>Done.";
+    let text = lines(html, 20);
+    assert_eq!(
+        plain(&text),
+        vec![
+            "Try this:",
+            "",
+            "  fn main() {",
+            "      println!(\"a very long line that does not fit\");",
+            "  }",
+            "",
+            "Done."
+        ]
+    );
+    assert_eq!(text[2].spans, vec![Span::styled("  fn main() {", CODE)]);
+}
+
+#[test]
+fn unknown_tags_are_dropped_and_stray_brackets_kept() {
+    assert_eq!(plain(&lines("<b>bold</b> a < b", 40)), vec!["bold a < b"]);
+    assert_eq!(plain(&lines("", 40)), Vec::<String>::new());
+}
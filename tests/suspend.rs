@@ -0,0 +1,23 @@
+//! Ctrl-Z suspends hint from anywhere, as raw mode keeps the shell from seeing it.
+
+use hint::hint_app::{update, Cmd, Model, Msg};
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+fn ctrl_z(model: &mut Model) -> Option<Cmd> {
+    update(model, Msg::Key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL)))
+}
+
+#[test]
+fn ctrl_z_suspends_from_the_list() {
+    let mut model = Model::default();
+    assert_eq!(ctrl_z(&mut model), Some(Cmd::Suspend));
+}
+
+#[test]
+fn ctrl_z_suspends_while_typing_a_command() {
+    let mut model = Model::default();
+    update(&mut model, Msg::OpenCommandLine);
+    update(&mut model, Msg::PromptInput('q'));
+    assert_eq!(ctrl_z(&mut model), Some(Cmd::Suspend));
+    assert_eq!(model.prompt.as_ref().unwrap().input, "q");
+}
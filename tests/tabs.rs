@@ -0,0 +1,124 @@
+//! Tabs keep a comment thread or the details pane open while another tab browses the list;
+//! `gt` and `gT` go around them and `:tabnew` and `:tabclose` open and close them.
+
+mod common;
+
+use common::run;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_view::view;
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Terminal;
+
+fn press(model: &mut Model, c: char) -> Option<Cmd> {
+    update(model, Msg::Key(KeyEvent::from(KeyCode::Char(c))))
+}
+
+fn thread(story_id: u64) -> CommentThread {
+    let comment = Comment {
+        id: story_id * 10,
+        author: "alan".to_string(),
+        text: "First!".to_string(),
+        time: None,
+        depth: 0,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    };
+    CommentThread {
+        story_id,
+        title: format!("Story {}", story_id),
+        author: "pg".to_string(),
+        url: None,
+        text: None,
+        comments: vec![comment],
+        unloaded: Vec::new(),
+    }
+}
+
+fn selected(model: &Model) -> Option<u64> {
+    model.storylist.selected_item().map(|item| item.id())
+}
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for id in 1..=3 {
+        let story = HnStory::new(id.to_string(), "pg".into(), format!("Story {}", id), None, "story".into());
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    update(&mut model, Msg::SelectFirst);
+    model
+}
+
+fn footer(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(100, 6)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..100).map(|x| buffer[(x, 5)].symbol()).collect()
+}
+
+#[test]
+fn thread_stays_open_in_its_tab_while_another_browses() {
+    let mut model = model_with_stories();
+    assert_eq!(press(&mut model, 'c'), Some(Cmd::LoadComments(1)));
+    run(&mut model, "tabnew");
+    assert_eq!(model.tab_count(), 2);
+    assert!(model.comments.is_none(), "the new tab shows the list");
+    assert!(footer(&mut model).contains("tab 2/2 gt/gT"));
+
+    // The thread arrives while its tab is parked, and is there when it comes back
+    update(&mut model, Msg::CommentsLoaded(thread(1)));
+    press(&mut model, 'j');
+    press(&mut model, 'j');
+    assert_eq!(selected(&model), Some(3));
+
+    press(&mut model, 'g');
+    press(&mut model, 'T');
+    let view = model.comments.as_ref().expect("the thread is back");
+    assert_eq!(view.story_id, 1);
+    assert!(view.thread.is_some());
+    assert_eq!(selected(&model), Some(1));
+
+    // Going around brings the list back where it was, not where the `g` took it
+    press(&mut model, 'g');
+    press(&mut model, 't');
+    assert!(model.comments.is_none());
+    assert_eq!(selected(&model), Some(3));
+}
+
+#[test]
+fn g_alone_still_goes_to_the_top() {
+    let mut model = model_with_stories();
+    press(&mut model, 'G');
+    press(&mut model, 'g');
+    assert_eq!(selected(&model), Some(1));
+    press(&mut model, 'j');
+    assert_eq!(selected(&model), Some(2), "the key after the g does its own thing");
+}
+
+#[test]
+fn closing_tabs() {
+    let mut model = model_with_stories();
+    press(&mut model, 'g');
+    press(&mut model, 't');
+    assert_eq!(model.notice.as_deref(), Some("Only one tab, :tabnew opens another"));
+
+    press(&mut model, 'c');
+    run(&mut model, "tabnew");
+    run(&mut model, "tabnew");
+    assert_eq!((model.current_tab(), model.tab_count()), (2, 3));
+    run(&mut model, "tabprevious");
+    run(&mut model, "tabprevious");
+    assert!(model.comments.is_some());
+
+    run(&mut model, "tabclose");
+    assert_eq!((model.current_tab(), model.tab_count()), (0, 2));
+    assert!(model.comments.is_none());
+    run(&mut model, "tabclose");
+    run(&mut model, "tabclose");
+    assert_eq!(model.tab_count(), 1);
+    assert_eq!(model.notice.as_deref(), Some("The last tab stays open"));
+}
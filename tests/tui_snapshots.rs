@@ -0,0 +1,269 @@
+//! Render the app into a `TestBackend` and compare the frames against stored snapshots.
+//!
+//! Run `INSTA_UPDATE=always cargo test` (or `cargo insta review`) after an intended layout change.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, DetailsLayout, Model, Msg};
+use hint::hint_auth::HnSession;
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_search::SavedSearch;
+use hint::hint_theme::Theme;
+use hint::hint_view::view;
+use insta::assert_snapshot;
+use ratatui::{
+    backend::TestBackend,
+    crossterm::event::KeyCode,
+    style::{palette::tailwind::BLUE, Modifier},
+    Terminal,
+};
+
+fn fixture_stories() -> Vec<HnStory> {
+    vec![
+        HnStory::new(
+            "1".to_string(),
+            "pg".to_string(),
+            "Show HN: A terminal Hacker News reader".to_string(),
+            Some("https://github.com/codervijo/hint".to_string()),
+            "story".to_string(),
+        ),
+        HnStory::new(
+            "2".to_string(),
+            "dang".to_string(),
+            "Rust 2024 edition is out".to_string(),
+            Some("https://blog.rust-lang.org/".to_string()),
+            "story".to_string(),
+        ),
+        HnStory::new(
+            "3".to_string(),
+            "tptacek".to_string(),
+            "Ask HN: What are you working on?".to_string(),
+            None,
+            "ask".to_string(),
+        ),
+    ]
+}
+
+fn fixture_model() -> Model {
+    let mut model = Model::default();
+    for story in fixture_stories() {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    }
+    model
+}
+
+fn draw(model: &mut Model, width: u16, height: u16) -> Terminal<TestBackend> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    terminal
+}
+
+#[test]
+fn empty_list_shows_only_spinner() {
+    let mut model = Model::default();
+    model.loading = true;
+    let terminal = draw(&mut model, 80, 8);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn loaded_list_says_when_it_was_refreshed() {
+    let mut model = fixture_model();
+    model.refreshed_at = Some(hint::hint_snooze::now() - 150);
+    let terminal = draw(&mut model, 80, 10);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn story_list_rows_and_footer() {
+    let mut model = fixture_model();
+    let terminal = draw(&mut model, 80, 10);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn selected_row_is_highlighted() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    let terminal = draw(&mut model, 80, 10);
+    assert_snapshot!(terminal.backend());
+
+    // Row 0 is the header, so the second story sits on row 2
+    let buffer = terminal.backend().buffer();
+    assert_eq!(buffer[(0, 2)].symbol(), ">");
+    assert_eq!(buffer[(5, 2)].bg, BLUE.c700);
+    assert!(buffer[(5, 2)].modifier.contains(Modifier::BOLD));
+    assert_ne!(buffer[(5, 1)].bg, BLUE.c700);
+}
+
+#[test]
+fn details_pane_for_read_story() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Enter);
+    let terminal = draw(&mut model, 80, 14);
+    assert_snapshot!(terminal.backend());
+
+    let buffer = terminal.backend().buffer();
+    // The header rows of both panes use the header colours
+    assert_eq!(buffer[(0, 0)].bg, BLUE.c700);
+    assert_eq!(buffer[(0, 7)].bg, BLUE.c700);
+}
+
+#[test]
+fn details_pane_closes_on_second_toggle() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Enter);
+    press(&mut model, KeyCode::Enter);
+    let terminal = draw(&mut model, 80, 10);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn narrow_terminal_truncates_rows() {
+    let mut model = fixture_model();
+    let terminal = draw(&mut model, 30, 6);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn short_terminal_drops_the_details_pane() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Enter);
+    let terminal = draw(&mut model, 80, 4);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn growing_terminal_scrolls_back_to_fill_the_list() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::End);
+    let mut terminal = draw(&mut model, 80, 4);
+    assert_eq!(model.storylist.state.offset(), 2);
+
+    update(&mut model, Msg::Resize { width: 80, height: 10 });
+    terminal.backend_mut().resize(80, 10);
+    terminal.draw(|frame| view(&mut model, frame)).unwrap();
+    assert_eq!(model.storylist.state.offset(), 0);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn details_pane_to_the_right() {
+    let mut model = fixture_model();
+    model.details_layout = DetailsLayout::Right;
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Enter);
+    press(&mut model, KeyCode::Char('>'));
+    let terminal = draw(&mut model, 80, 8);
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn full_screen_details_hide_the_list() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('f'));
+    assert!(model.show_details && model.details_full_screen);
+    let terminal = draw(&mut model, 80, 8);
+    assert_snapshot!(terminal.backend());
+
+    // Esc goes back to the split rather than quitting
+    press(&mut model, KeyCode::Esc);
+    assert!(!model.should_exit);
+    assert!(model.show_details && !model.details_full_screen);
+}
+
+#[test]
+fn saved_search_tabs_above_the_list() {
+    let mut model = fixture_model();
+    model.saved_searches = vec![
+        SavedSearch { name: "Rust".to_string(), query: "rust".to_string(), local: true },
+        SavedSearch { name: "Ask".to_string(), query: "ask hn".to_string(), local: false },
+    ];
+    let stories = fixture_stories();
+    update(&mut model, Msg::SearchResultsLoaded { name: "Ask".to_string(), stories });
+    update(&mut model, Msg::StoryLoaded(Feed::Top, fixture_stories()[1].clone()));
+    press(&mut model, KeyCode::Char('s'));
+    let terminal = draw(&mut model, 80, 6);
+    assert_snapshot!(terminal.backend());
+}
+
+fn fixture_thread() -> CommentThread {
+    let comment = |id, author: &str, text: &str, depth| Comment {
+        id,
+        author: author.to_string(),
+        text: text.to_string(),
+        time: None,
+        depth,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    };
+    CommentThread {
+        story_id: 1,
+        title: "Show HN: A terminal Hacker News reader".to_string(),
+        author: "pg".to_string(),
+        url: Some("https://github.com/codervijo/hint".to_string()),
+        text: None,
+        comments: vec![
+            comment(11, "alan", "This is great. I&#x27;ve wanted this for years.", 0),
+            comment(12, "pg", "Thanks!<p>Vim keys work too.", 1),
+            comment(13, "grace", "", 0),
+        ],
+        unloaded: Vec::new(),
+    }
+}
+
+#[test]
+fn comment_thread_with_reply_box() {
+    let mut model = fixture_model();
+    model.hn_session = Some(HnSession::new("alice".to_string(), "alice&abc".to_string()));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    update(&mut model, Msg::CommentsLoaded(fixture_thread()));
+    let terminal = draw(&mut model, 60, 16);
+    assert_snapshot!("comment_thread", terminal.backend());
+
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('R'));
+    for c in "Agreed, the\nvim keys are great".chars() {
+        press(&mut model, if c == '\n' { KeyCode::Enter } else { KeyCode::Char(c) });
+    }
+    let terminal = draw(&mut model, 60, 16);
+    assert_snapshot!("comment_reply_box", terminal.backend());
+}
+
+#[test]
+fn theme_recolors_header_and_selection() {
+    let mut model = fixture_model();
+    model.theme = Theme::GRUVBOX;
+    press(&mut model, KeyCode::Down);
+    let terminal = draw(&mut model, 80, 10);
+    let buffer = terminal.backend().buffer();
+    assert_eq!(buffer[(0, 0)].bg, Theme::GRUVBOX.header.bg.unwrap());
+    assert_eq!(buffer[(5, 1)].bg, Theme::GRUVBOX.selected.bg.unwrap());
+    assert_eq!(buffer[(5, 2)].bg, Theme::GRUVBOX.alt_row_bg);
+}
+
+#[test]
+fn visual_mode_marks_a_range() {
+    let mut model = fixture_model();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    let terminal = draw(&mut model, 80, 10);
+    assert_snapshot!(terminal.backend());
+
+    let buffer = terminal.backend().buffer();
+    assert_eq!(buffer[(5, 1)].bg, BLUE.c800);
+    assert_eq!(buffer[(5, 2)].bg, BLUE.c700);
+    assert_ne!(buffer[(5, 3)].bg, BLUE.c800);
+}
@@ -0,0 +1,73 @@
+//! Undoing list changes with `u`.
+
+mod common;
+
+use common::{ids, model_with_stories, press};
+use hint::hint_app::{update, Cmd, Model, Msg, Status};
+use hint::hint_auth::HnSession;
+use ratatui::crossterm::event::KeyCode;
+
+#[test]
+fn undo_restores_statuses_one_step_at_a_time() {
+    let mut model = model_with_stories(3);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Enter);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('r'));
+    let statuses = |model: &Model| -> Vec<Status> {
+        model.storylist.items.iter().map(|item| item.status).collect()
+    };
+    assert_eq!(statuses(&model), vec![Status::Read, Status::Read, Status::Unread]);
+
+    press(&mut model, KeyCode::Char('u'));
+    assert_eq!(statuses(&model), vec![Status::Read, Status::Unread, Status::Unread]);
+    press(&mut model, KeyCode::Char('u'));
+    assert_eq!(statuses(&model), vec![Status::Unread, Status::Unread, Status::Unread]);
+    press(&mut model, KeyCode::Char('u'));
+    assert_eq!(model.notice.as_deref(), Some("Nothing to undo"));
+}
+
+#[test]
+fn undo_puts_hidden_stories_back_in_place() {
+    let mut model = model_with_stories(4);
+    model.hn_session = Some(HnSession::new("alice".to_string(), "alice&abc".to_string()));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('x'));
+    press(&mut model, KeyCode::Char('y'));
+    assert_eq!(ids(&model), vec![1, 4]);
+
+    let cmd = press(&mut model, KeyCode::Char('u'));
+    assert_eq!(ids(&model), vec![1, 2, 3, 4]);
+    assert!(!model.hidden.contains(2) && !model.hidden.contains(3));
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 2);
+    let Some(Cmd::Batch(cmds)) = cmd else {
+        panic!("expected a save and HN syncs, got {:?}", cmd);
+    };
+    assert_eq!(cmds.len(), 3, "one save plus an un-hide per story");
+}
+
+#[test]
+fn undoing_a_hide_from_another_feed_only_unhides() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('x'));
+    update(&mut model, Msg::NextFeed);
+    press(&mut model, KeyCode::Char('u'));
+    assert!(model.storylist.items.is_empty());
+    assert!(!model.hidden.contains(1));
+}
+
+#[test]
+fn undo_restores_bookmarks() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('b'));
+    assert!(model.bookmarks.contains(1));
+    let cmd = press(&mut model, KeyCode::Char('u'));
+    assert!(model.bookmarks.is_empty());
+    assert_eq!(cmd, Some(Cmd::SaveBookmarks(model.bookmarks.clone())));
+}
@@ -0,0 +1,67 @@
+//! Titles are measured in terminal columns, so wide CJK and emoji characters fit their rows.
+
+mod common;
+
+use common::titled;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_comments::{truncate, wrap_text};
+use hint::hint_hackernews::Feed;
+use hint::hint_view::view;
+use ratatui::{backend::TestBackend, Terminal};
+use unicode_width::UnicodeWidthStr;
+
+fn rows(model: &mut Model, width: u16, height: u16) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    let buffer = terminal.backend().buffer();
+    (0..height)
+        .map(|y| {
+            // A wide character's second cell is blank
+            let mut row = String::new();
+            let mut x = 0;
+            while x < width {
+                let symbol = buffer[(x, y)].symbol();
+                row.push_str(symbol);
+                x += symbol.width().max(1) as u16;
+            }
+            row.trim_end().to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn truncate_counts_columns() {
+    assert_eq!(truncate("short", 10), "short");
+    assert_eq!(truncate("exactly 10", 10), "exactly 10");
+    assert_eq!(truncate("a bit too long", 10), "a bit too…");
+    assert_eq!(truncate("日本語のタイトル", 7), "日本語…");
+    assert_eq!(truncate("🦀🦀🦀🦀", 6), "🦀🦀…");
+    assert_eq!(truncate("anything", 0), "");
+}
+
+#[test]
+fn wrap_counts_columns() {
+    assert_eq!(wrap_text("日本語 のタイトル", 8), vec!["日本語", "のタイト", "ル"]);
+    assert_eq!(wrap_text("🦀 rust 🦀", 7), vec!["🦀 rust", "🦀"]);
+    assert_eq!(wrap_text("plain words wrap", 11), vec!["plain words", "wrap"]);
+}
+
+#[test]
+fn long_wide_title_is_cut_with_an_ellipsis() {
+    let mut model = Model::default();
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(1, "東京のスタートアップが新しいエディタを公開しました")));
+    let rows = rows(&mut model, 30, 6);
+    assert_eq!(rows[1], "  ☐ 東京のスタートアップが新…");
+}
+
+#[test]
+fn wrap_mode_gives_long_titles_a_second_line() {
+    let mut model = Model::default();
+    model.wrap_titles = true;
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(1, "A rather long title that needs two lines or more to fit")));
+    update(&mut model, Msg::StoryLoaded(Feed::Top, titled(2, "Short one")));
+    let rows = rows(&mut model, 30, 8);
+    assert_eq!(rows[1], "  ☐ A rather long title that");
+    assert_eq!(rows[2], "    needs two lines or more t…");
+    assert_eq!(rows[3], "  ☐ Short one");
+}
@@ -0,0 +1,86 @@
+//! Polling HN's `updates.json` and refreshing the points and comments of the stories in the list
+//! it names, rather than the whole feed.
+
+mod common;
+
+use common::{story, StoryBuilder};
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_hackernews::Feed;
+use hint::hint_scores::{fetch_counts, StoryCounts};
+use hint::hnreader::{HnClient, Updates};
+use serde_json::json;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn model_with_stories() -> Model {
+    let mut model = Model::default();
+    for id in 1..=3 {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id).with_score(10).with_descendants(2)));
+    }
+    model
+}
+
+/// Whether a tick's commands include polling for updates
+fn polls(cmd: Option<Cmd>) -> bool {
+    match cmd {
+        Some(Cmd::PollUpdates) => true,
+        Some(Cmd::Batch(cmds)) => cmds.contains(&Cmd::PollUpdates),
+        _ => false,
+    }
+}
+
+#[test]
+fn updates_are_polled_every_half_minute_while_stories_are_listed() {
+    let mut model = model_with_stories();
+    let polled: Vec<u32> = (1..=900).filter(|_| polls(update(&mut model, Msg::Tick))).collect();
+    assert_eq!(polled, vec![300, 600, 900]);
+
+    let mut empty = Model::default();
+    assert!(!(1..=300).any(|_| polls(update(&mut empty, Msg::Tick))));
+
+    let mut offline = model_with_stories();
+    offline.offline = true;
+    assert!(!(1..=300).any(|_| polls(update(&mut offline, Msg::Tick))));
+}
+
+#[test]
+fn only_changed_stories_in_the_list_are_fetched_again() {
+    let mut model = model_with_stories();
+    let cmd = update(&mut model, Msg::UpdatesLoaded(vec![3, 99, 1, 12345]));
+    assert_eq!(cmd, Some(Cmd::RefreshCounts(vec![3, 1])));
+    assert_eq!(update(&mut model, Msg::UpdatesLoaded(vec![99])), None);
+}
+
+#[test]
+fn refreshed_counts_update_the_rows_in_place() {
+    let mut model = model_with_stories();
+    let counts = vec![
+        StoryCounts { id: 3, score: Some(250), comments: Some(80) },
+        StoryCounts { id: 1, score: None, comments: Some(5) },
+    ];
+    assert_eq!(update(&mut model, Msg::CountsRefreshed(counts)), None);
+    let rows: Vec<(u64, Option<u32>, Option<u32>)> =
+        model.storylist.items.iter().map(|item| (item.id(), item.story.score(), item.story.descendants())).collect();
+    assert_eq!(rows, vec![(1, Some(10), Some(5)), (2, Some(10), Some(2)), (3, Some(250), Some(80))]);
+}
+
+#[tokio::test]
+async fn updates_and_counts_come_from_the_api() {
+    let server = MockServer::start().await;
+    let item = json!({"id": 8863, "by": "dhouston", "title": "My YC app", "score": 104, "descendants": 71, "type": "story"});
+    let responses = [
+        ("/v0/updates.json", ResponseTemplate::new(200).set_body_json(json!({"items": [8863, 8864], "profiles": ["pg"]}))),
+        ("/v0/item/8863.json", ResponseTemplate::new(200).set_body_json(item)),
+        // A story that fails to load is left as it is
+        ("/v0/item/8864.json", ResponseTemplate::new(404)),
+    ];
+    for (route, response) in responses {
+        Mock::given(method("GET")).and(path(route)).respond_with(response).mount(&server).await;
+    }
+
+    let client = HnClient::with_base_url(&format!("{}/v0", server.uri()));
+    let updates = client.fetch_updates().await.unwrap();
+    assert_eq!(updates, Updates { items: vec![8863, 8864], profiles: vec!["pg".to_string()] });
+    let counts = fetch_counts(&client, &updates.items).await;
+    assert_eq!(counts, vec![StoryCounts { id: 8863, score: Some(104), comments: Some(71) }]);
+}
@@ -0,0 +1,88 @@
+//! Long feeds only build the rows on screen, plus a few either side.
+
+mod common;
+
+use common::story;
+use hint::hint_app::{update, Model, Msg};
+use hint::hint_hackernews::Feed;
+use hint::hint_view::view;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{backend::TestBackend, Terminal};
+
+fn long_feed(count: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=count {
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id)));
+    }
+    model
+}
+
+fn draw(model: &mut Model) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    terminal.draw(|frame| view(model, frame)).unwrap();
+    terminal.backend().buffer().content().iter().map(|cell| cell.symbol()).collect()
+}
+
+#[test]
+fn rows_follow_the_offset_with_overscan() {
+    let mut model = long_feed(600);
+    assert_eq!(model.storylist.visible_rows(20, 5), 0..25);
+    *model.storylist.state.offset_mut() = 100;
+    assert_eq!(model.storylist.visible_rows(20, 5), 95..125);
+    // The spinner row is the last there is
+    *model.storylist.state.offset_mut() = 590;
+    assert_eq!(model.storylist.visible_rows(20, 5), 576..601);
+    assert_eq!(model.storylist.state.offset(), 581, "no blank rows below the spinner");
+}
+
+#[test]
+fn rows_scroll_to_keep_the_selection_on_screen() {
+    let mut model = long_feed(600);
+    model.storylist.state.select(Some(300));
+    assert_eq!(model.storylist.visible_rows(20, 0), 281..301);
+    model.storylist.state.select(Some(10));
+    assert_eq!(model.storylist.visible_rows(20, 0), 10..30);
+    // Select-last leaves an index past the end for the widget to clamp
+    model.storylist.state.select(Some(usize::MAX));
+    assert_eq!(model.storylist.visible_rows(20, 0), 581..601);
+}
+
+#[test]
+fn short_lists_fit_whole() {
+    let mut model = long_feed(3);
+    assert_eq!(model.storylist.visible_rows(20, 5), 0..4);
+    let mut model = Model::default();
+    assert_eq!(model.storylist.visible_rows(0, 0), 0..1);
+}
+
+#[test]
+fn jumping_to_the_end_of_a_long_feed_draws_its_last_stories() {
+    let mut model = long_feed(600);
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::End)));
+    let screen = draw(&mut model);
+    assert!(screen.contains("Story 600"));
+    assert!(!screen.contains("Story 1 "));
+    assert!(model.storylist.state.offset() > 500);
+
+    update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Home)));
+    let screen = draw(&mut model);
+    assert!(screen.contains("Story 1 "));
+    assert!(!screen.contains("Story 600"));
+    assert_eq!(model.storylist.state.offset(), 0);
+}
+
+#[test]
+fn scrolling_down_moves_the_offset_one_row_at_a_time() {
+    let mut model = long_feed(600);
+    draw(&mut model);
+    let mut last = 0;
+    for _ in 0..100 {
+        update(&mut model, Msg::Key(KeyEvent::from(KeyCode::Down)));
+        draw(&mut model);
+        let offset = model.storylist.state.offset();
+        assert!(offset == last || offset == last + 1);
+        last = offset;
+    }
+    assert_eq!(model.storylist.state.selected(), Some(99));
+    assert!(last > 0);
+}
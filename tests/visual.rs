@@ -0,0 +1,124 @@
+//! Visual mode (`V`) and the actions that work on a range of stories.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg, Status};
+use hint::hint_bookmarks::{Bookmarks, SavedStory};
+use hint::hint_hackernews::{Feed, HnStory};
+use hint::hint_store::Store;
+use ratatui::crossterm::event::KeyCode;
+
+fn story(id: u64, url: Option<&str>) -> HnStory {
+    let url = url.map(str::to_string);
+    HnStory::new(id.to_string(), "author".into(), format!("Story {}", id), url, "story".into())
+}
+
+fn model_with_stories(n: u64) -> Model {
+    let mut model = Model::default();
+    for id in 1..=n {
+        let url = format!("https://example.com/{}", id);
+        update(&mut model, Msg::StoryLoaded(Feed::Top, story(id, Some(&url))));
+    }
+    model
+}
+
+/// Select the second story and mark it and the two below
+fn mark_three(model: &mut Model) {
+    press(model, KeyCode::Down);
+    press(model, KeyCode::Down);
+    press(model, KeyCode::Char('V'));
+    press(model, KeyCode::Down);
+    press(model, KeyCode::Down);
+    assert_eq!(model.storylist.marked_range(), Some(1..=3));
+}
+
+#[test]
+fn range_follows_the_selection_both_ways() {
+    let mut model = model_with_stories(5);
+    mark_three(&mut model);
+    for _ in 0..3 {
+        press(&mut model, KeyCode::Up);
+    }
+    assert_eq!(model.storylist.marked_range(), Some(0..=1));
+    // Esc leaves visual mode instead of quitting
+    press(&mut model, KeyCode::Esc);
+    assert!(!model.should_exit);
+    assert_eq!(model.storylist.marked_range(), None);
+}
+
+#[test]
+fn range_stops_at_the_last_story() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('V'));
+    // Onto the spinner row
+    press(&mut model, KeyCode::Char('G'));
+    assert_eq!(model.storylist.marked_range(), Some(0..=1));
+}
+
+#[test]
+fn marked_stories_are_read_in_one_go() {
+    let mut model = model_with_stories(5);
+    mark_three(&mut model);
+    press(&mut model, KeyCode::Char('r'));
+    let read: Vec<bool> =
+        model.storylist.items.iter().map(|item| item.status == Status::Read).collect();
+    assert_eq!(read, vec![false, true, true, true, false]);
+    assert_eq!(model.storylist.visual_anchor, None);
+}
+
+#[test]
+fn bookmarks_toggle_for_the_whole_range() {
+    let mut model = model_with_stories(5);
+    mark_three(&mut model);
+    let cmd = press(&mut model, KeyCode::Char('b'));
+    assert_eq!(cmd, Some(Cmd::SaveBookmarks(model.bookmarks.clone())));
+    assert_eq!(model.bookmarks.len(), 3);
+    assert_eq!(model.notice.as_deref(), Some("Bookmarked 3 stories"));
+
+    // All of them are bookmarked already, so this takes the bookmarks away again
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Up);
+    press(&mut model, KeyCode::Char('b'));
+    assert_eq!(model.bookmarks.len(), 1);
+    assert!(model.bookmarks.contains(2));
+}
+
+#[test]
+fn marked_stories_are_hidden_together() {
+    let mut model = model_with_stories(5);
+    mark_three(&mut model);
+    press(&mut model, KeyCode::Char('x'));
+    press(&mut model, KeyCode::Char('y'));
+    let ids: Vec<u64> = model.storylist.items.iter().map(|item| item.id()).collect();
+    assert_eq!(ids, vec![1, 5]);
+    assert!((2..=4).all(|id| model.hidden.contains(id)));
+    assert_eq!(model.storylist.selected_item().unwrap().id(), 5);
+}
+
+#[test]
+fn open_uses_the_link_or_the_discussion() {
+    let mut model = model_with_stories(1);
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story(2, None)));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('V'));
+    press(&mut model, KeyCode::Down);
+    let cmd = press(&mut model, KeyCode::Char('o'));
+    let urls = vec![
+        "https://example.com/1".to_string(),
+        "https://news.ycombinator.com/item?id=2".to_string(),
+    ];
+    assert_eq!(cmd, Some(Cmd::OpenUrls(urls)));
+}
+
+#[test]
+fn bookmarks_round_trip_through_store() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = Store::new(dir.path());
+    let mut bookmarks = Bookmarks::default();
+    bookmarks.add(SavedStory::from_story(&story(7, Some("https://example.com/7"))));
+    bookmarks.add(SavedStory::from_story(&story(8, None)));
+    bookmarks.save(&store).unwrap();
+    assert_eq!(Bookmarks::load(&store), bookmarks);
+}
@@ -0,0 +1,42 @@
+//! The terminal title kept on the selected story or the feed.
+
+mod common;
+
+use common::{model_with_stories, press};
+use hint::hint_app::{update, Msg};
+use hint::hint_config::Config;
+use hint::hint_terminal::TitleMode;
+use ratatui::crossterm::event::KeyCode;
+
+#[test]
+fn title_is_left_alone_by_default() {
+    let mut model = model_with_stories(2);
+    press(&mut model, KeyCode::Down);
+    assert_eq!(model.window_title(), None);
+}
+
+#[test]
+fn story_mode_follows_the_selection() {
+    let mut model = model_with_stories(2);
+    model.title_mode = TitleMode::Story;
+    assert_eq!(model.window_title().as_deref(), Some("hint | Top"));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    assert_eq!(model.window_title().as_deref(), Some("Story 2 - hint | Top"));
+}
+
+#[test]
+fn feed_mode_shows_only_the_feed() {
+    let mut model = model_with_stories(2);
+    model.title_mode = TitleMode::Feed;
+    press(&mut model, KeyCode::Down);
+    update(&mut model, Msg::NextFeed);
+    assert_eq!(model.window_title().as_deref(), Some("hint | New"));
+}
+
+#[test]
+fn title_mode_is_read_from_the_config() {
+    let config: Config = serde_json::from_str(r#"{ "title": "story" }"#).unwrap();
+    assert_eq!(config.title, TitleMode::Story);
+    assert_eq!(Config::default().title, TitleMode::Off);
+}
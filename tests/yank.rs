@@ -0,0 +1,90 @@
+//! Copying a comment with `y` as a Markdown quote, through the clipboard program or OSC 52.
+
+mod common;
+
+use common::press;
+use hint::hint_app::{update, Cmd, Model, Msg};
+use hint::hint_clipboard::osc52;
+use hint::hint_comments::{Comment, CommentThread};
+use hint::hint_hackernews::{Feed, HnStory};
+use ratatui::crossterm::event::KeyCode;
+
+fn model_with_thread() -> Model {
+    let mut model = Model::default();
+    let story = HnStory::new("1".into(), "pg".into(), "A story".into(), None, "story".into());
+    update(&mut model, Msg::StoryLoaded(Feed::Top, story));
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Char('c'));
+    let comment = Comment {
+        id: 10,
+        author: "alan".into(),
+        text: "It&#x27;s <i>great</i>.<p>Really.".into(),
+        time: None,
+        depth: 0,
+        dead: false,
+        deleted: false,
+        voted: false,
+        unloaded: Vec::new(),
+    };
+    let dead = Comment { id: 11, dead: true, ..comment.clone() };
+    let thread = CommentThread {
+        story_id: 1,
+        title: "A story".into(),
+        author: "pg".into(),
+        url: None,
+        text: None,
+        comments: vec![comment, dead],
+        unloaded: Vec::new(),
+    };
+    update(&mut model, Msg::CommentsLoaded(thread));
+    model
+}
+
+#[test]
+fn y_copies_the_comment_as_a_signed_quote() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Down);
+    press(&mut model, KeyCode::Down);
+    let cmd = press(&mut model, KeyCode::Char('y'));
+    let text = "> It's great.\n>\n> Really.\n>\n> — alan, <https://news.ycombinator.com/item?id=10>\n";
+    assert_eq!(cmd, Some(Cmd::Copy { text: text.to_string(), what: "alan's comment".to_string() }));
+}
+
+#[test]
+fn the_header_row_copies_the_story() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Down);
+    let cmd = press(&mut model, KeyCode::Char('y'));
+    let text = "> A story\n>\n> — pg, <https://news.ycombinator.com/item?id=1>\n";
+    assert_eq!(cmd, Some(Cmd::Copy { text: text.to_string(), what: "the story".to_string() }));
+}
+
+#[test]
+fn dead_comments_are_not_copied() {
+    let mut model = model_with_thread();
+    press(&mut model, KeyCode::Char('d'));
+    for _ in 0..3 {
+        press(&mut model, KeyCode::Down);
+    }
+    assert_eq!(press(&mut model, KeyCode::Char('y')), None);
+    assert_eq!(model.notice.as_deref(), Some("Nothing to copy from a dead or deleted comment"));
+}
+
+#[test]
+fn osc52_carries_the_text_in_base64() {
+    assert_eq!(osc52("hi"), "\x1b]52;c;aGk=\x07");
+    assert_eq!(osc52("hint"), "\x1b]52;c;aGludA==\x07");
+    assert_eq!(osc52("hin"), "\x1b]52;c;aGlu\x07");
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+#[test]
+fn the_clipboard_program_follows_the_display_server() {
+    use hint::hint_clipboard::clipboard_command;
+    let env = |vars: &'static [(&'static str, &'static str)]| {
+        move |name: &str| vars.iter().find(|(var, _)| *var == name).map(|(_, value)| value.to_string())
+    };
+    assert_eq!(clipboard_command(env(&[("WAYLAND_DISPLAY", "wayland-0"), ("DISPLAY", ":0")])), Some(&["wl-copy"][..]));
+    assert_eq!(clipboard_command(env(&[("DISPLAY", ":0")])), Some(&["xclip", "-selection", "clipboard"][..]));
+    assert_eq!(clipboard_command(env(&[])), None, "over SSH, OSC 52 it is");
+}